@@ -0,0 +1,197 @@
+//! App Data Module
+//!
+//! Backs up and restores the user's configuration as a single ZIP, for moving
+//! to another machine: settings (which include preferences and saved
+//! searches), folder history, tags and the last session. Favorites and other
+//! state the UI keeps on its own side travel as an opaque `frontend.json`
+//! that `import_app_data` hands back for the UI to apply. Secrets such as the
+//! VirusTotal API key stay out of the backup and survive an import untouched.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use ts_rs::TS;
+use zip::write::SimpleFileOptions;
+
+/// Bumped when the layout of the archive changes incompatibly
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const SETTINGS: &str = "settings.json";
+const FRECENCY: &str = "frecency.json";
+const SESSION: &str = "session.json";
+const TAGS: &str = "tags.db";
+const FRONTEND: &str = "frontend.json";
+/// Largest entry an import reads; a tags database with many thousands of tags stays well under it
+const MAX_ENTRY_SIZE: u64 = 256 * 1024 * 1024;
+/// Most the import allocates up front from the size an entry claims to have
+const PREALLOCATE_LIMIT: u64 = 16 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    format_version: u32,
+    app_version: String,
+    /// RFC 3339
+    exported_at: String,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct ImportedAppData {
+    /// Archive entries that were restored, e.g. "settings.json"
+    pub restored: Vec<String>,
+    /// Entries that could not be restored, with the reason
+    pub errors: Vec<String>,
+    /// The UI state passed to `export_app_data`, if any
+    #[ts(type = "unknown")]
+    pub frontend_state: Option<Value>,
+}
+
+fn add_entry(zip: &mut zip::ZipWriter<File>, name: &str, data: &[u8]) -> Result<(), String> {
+    zip.start_file(name, SimpleFileOptions::default())
+        .and_then(|_| zip.write_all(data).map_err(Into::into))
+        .map_err(|e| format!("Failed to write {}: {}", name, e))
+}
+
+fn export(target: &Path, frontend_state: Option<Value>) -> Result<(), String> {
+    let file = File::create(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Local::now().to_rfc3339(),
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    add_entry(&mut zip, MANIFEST, &manifest)?;
+    add_entry(
+        &mut zip,
+        SETTINGS,
+        &crate::settings::SettingsStore::global().to_json()?,
+    )?;
+    add_entry(
+        &mut zip,
+        FRECENCY,
+        &crate::frecency::FrecencyStore::global().to_json()?,
+    )?;
+    if let Some(session) = crate::session::to_json() {
+        add_entry(&mut zip, SESSION, &session)?;
+    }
+    if let Some(state) = frontend_state {
+        let state = serde_json::to_vec_pretty(&state).map_err(|e| e.to_string())?;
+        add_entry(&mut zip, FRONTEND, &state)?;
+    }
+
+    let tags_copy = std::env::temp_dir().join(format!("qe-tags-{}.db", std::process::id()));
+    let tags = crate::tags::export_db(&tags_copy).and_then(|_| {
+        std::fs::read(&tags_copy).map_err(|e| format!("Failed to read tag backup: {}", e))
+    });
+    let _ = std::fs::remove_file(&tags_copy);
+    add_entry(&mut zip, TAGS, &tags?)?;
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish {}: {}", target.display(), e))?;
+    Ok(())
+}
+
+/// Reads `name` from the archive; None when it is missing, unreadable or larger than
+/// `MAX_ENTRY_SIZE`. The size in the entry's header is not trusted for allocating.
+fn read_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> Option<Vec<u8>> {
+    let entry = archive.by_name(name).ok()?;
+    let mut data = Vec::with_capacity(entry.size().min(PREALLOCATE_LIMIT) as usize);
+    entry.take(MAX_ENTRY_SIZE + 1).read_to_end(&mut data).ok()?;
+    if data.len() as u64 > MAX_ENTRY_SIZE {
+        log::warn!("[APP_DATA] {} is too large to import", name);
+        return None;
+    }
+    Some(data)
+}
+
+fn import(source: &Path) -> Result<ImportedAppData, String> {
+    let file =
+        File::open(source).map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a Quick Explorer backup: {}", e))?;
+    let manifest: Manifest = read_entry(&mut archive, MANIFEST)
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .ok_or("Not a Quick Explorer backup: manifest missing")?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "This backup was made by a newer version ({}); update Quick Explorer to import it",
+            manifest.app_version
+        ));
+    }
+
+    let mut restored = Vec::new();
+    let mut errors = Vec::new();
+    let mut record = |name: &str, result: Result<(), String>| match result {
+        Ok(()) => restored.push(name.to_string()),
+        Err(e) => errors.push(format!("{}: {}", name, e)),
+    };
+
+    if let Some(data) = read_entry(&mut archive, SETTINGS) {
+        record(
+            SETTINGS,
+            crate::settings::SettingsStore::global().replace_from_json(&data),
+        );
+    }
+    if let Some(data) = read_entry(&mut archive, FRECENCY) {
+        record(
+            FRECENCY,
+            crate::frecency::FrecencyStore::global().replace_from_json(&data),
+        );
+    }
+    if let Some(data) = read_entry(&mut archive, SESSION) {
+        record(SESSION, crate::session::replace_from_json(&data));
+    }
+    if let Some(data) = read_entry(&mut archive, TAGS) {
+        let tags_copy =
+            std::env::temp_dir().join(format!("qe-tags-import-{}.db", std::process::id()));
+        let result = std::fs::write(&tags_copy, data)
+            .map_err(|e| e.to_string())
+            .and_then(|_| crate::tags::import_db(&tags_copy));
+        let _ = std::fs::remove_file(&tags_copy);
+        record(TAGS, result);
+    }
+    let frontend_state =
+        read_entry(&mut archive, FRONTEND).and_then(|data| serde_json::from_slice(&data).ok());
+
+    log::info!(
+        "[APP-DATA] Imported backup from {} (made by {}): {} restored, {} failed",
+        manifest.exported_at,
+        manifest.app_version,
+        restored.len(),
+        errors.len()
+    );
+    Ok(ImportedAppData {
+        restored,
+        errors,
+        frontend_state,
+    })
+}
+
+/// Writes settings, folder history, tags and session to `target_zip`; `frontend_state` is
+/// stored alongside for the UI's own data (favorites, layout)
+#[tauri::command]
+pub async fn export_app_data(
+    target_zip: String,
+    frontend_state: Option<Value>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || export(Path::new(&target_zip), frontend_state))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Restores a backup made by `export_app_data`, replacing the current data
+#[tauri::command]
+pub async fn import_app_data(zip: String) -> Result<ImportedAppData, String> {
+    let result = tauri::async_runtime::spawn_blocking(move || import(Path::new(&zip)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+    if result.restored.iter().any(|r| r == SETTINGS) {
+        crate::settings::notify_changed();
+    }
+    Ok(result)
+}
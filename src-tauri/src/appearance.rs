@@ -0,0 +1,224 @@
+//! Appearance Module
+//!
+//! The window backdrop (Mica, Acrylic, tabbed Mica) and the system theme.
+//! Backdrops fall back to what the OS supports: Mica needs Windows 11, so on
+//! Windows 10 it becomes Acrylic, and a window without any effect keeps its
+//! solid background. The page must leave its background transparent for a
+//! backdrop to show through.
+//!
+//! Light/dark mode and the accent color are watched in the registry and
+//! pushed to the UI as `system-theme-changed`, so a "follow system" theme
+//! updates live.
+
+use crate::paths::to_wide;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use ts_rs::TS;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::ERROR_SUCCESS;
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegGetValueW, RegNotifyChangeKeyValue, RegOpenKeyExW, HKEY, HKEY_CURRENT_USER,
+    KEY_NOTIFY, REG_NOTIFY_CHANGE_LAST_SET, RRF_RT_REG_DWORD,
+};
+
+const PERSONALIZE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize";
+const DWM_KEY: &str = "Software\\Microsoft\\Windows\\DWM";
+
+#[derive(Serialize, Deserialize, Clone, Copy, TS, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum BackdropKind {
+    Mica,
+    Acrylic,
+    /// Mica tinted with the desktop wallpaper, as used by tabbed windows
+    Tabbed,
+    #[default]
+    None,
+}
+
+#[derive(Serialize, Clone, TS, Debug, PartialEq)]
+#[ts(export)]
+pub struct SystemTheme {
+    /// Apps are set to dark mode
+    pub dark: bool,
+    /// "#RRGGBB"
+    pub accent_color: Option<String>,
+}
+
+/// Backdrop currently applied, re-applied when the theme changes so Mica matches it
+static CURRENT_BACKDROP: Mutex<BackdropKind> = Mutex::new(BackdropKind::None);
+
+fn read_dword(key: &str, value: &str) -> Option<u32> {
+    let (key, value) = (to_wide(key), to_wide(value));
+    let mut data = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(value.as_ptr()),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut data as *mut u32 as *mut _),
+            Some(&mut size),
+        )
+    };
+    (status == ERROR_SUCCESS).then_some(data)
+}
+
+/// "#RRGGBB" from the 0xAABBGGRR value DWM stores
+fn accent_hex(abgr: u32) -> String {
+    let [r, g, b, _] = abgr.to_le_bytes();
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+fn system_theme() -> SystemTheme {
+    SystemTheme {
+        // Light mode unless the value says otherwise; it is missing before Windows 10 1809
+        dark: read_dword(PERSONALIZE_KEY, "AppsUseLightTheme") == Some(0),
+        accent_color: read_dword(DWM_KEY, "AccentColor").map(accent_hex),
+    }
+}
+
+/// Applies `kind`, falling back to the closest effect the OS supports; returns what was applied
+fn apply_backdrop(window: &tauri::WebviewWindow, kind: BackdropKind) -> BackdropKind {
+    use window_vibrancy::{
+        apply_acrylic, apply_mica, apply_tabbed, clear_acrylic, clear_mica, clear_tabbed,
+    };
+
+    let _ = clear_mica(window);
+    let _ = clear_tabbed(window);
+    let _ = clear_acrylic(window);
+
+    let dark = Some(system_theme().dark);
+    let mut candidates = match kind {
+        BackdropKind::Mica => vec![BackdropKind::Mica, BackdropKind::Acrylic],
+        BackdropKind::Tabbed => vec![
+            BackdropKind::Tabbed,
+            BackdropKind::Mica,
+            BackdropKind::Acrylic,
+        ],
+        BackdropKind::Acrylic => vec![BackdropKind::Acrylic],
+        BackdropKind::None => vec![],
+    }
+    .into_iter();
+    let applied = candidates
+        .find(|candidate| {
+            let result = match candidate {
+                BackdropKind::Mica => apply_mica(window, dark),
+                BackdropKind::Tabbed => apply_tabbed(window, dark),
+                BackdropKind::Acrylic => apply_acrylic(window, None),
+                BackdropKind::None => Ok(()),
+            };
+            if let Err(e) = &result {
+                log::info!("[APPEARANCE] {:?} unavailable: {}", candidate, e);
+            }
+            result.is_ok()
+        })
+        .unwrap_or(BackdropKind::None);
+
+    if let Ok(mut current) = CURRENT_BACKDROP.lock() {
+        *current = applied;
+    }
+    applied
+}
+
+/// Sets and saves the window backdrop. Returns the one actually applied, which differs
+/// from `kind` when the OS doesn't support it
+#[tauri::command]
+pub fn set_window_backdrop(
+    window: tauri::WebviewWindow,
+    kind: BackdropKind,
+) -> Result<BackdropKind, String> {
+    let applied = apply_backdrop(&window, kind);
+    // The requested kind is kept, so a later OS upgrade gets the real thing
+    let mut preferences = crate::settings::preferences();
+    if preferences.window_backdrop != kind {
+        preferences.window_backdrop = kind;
+        crate::settings::set_settings(preferences)?;
+    }
+    log::info!("[APPEARANCE] Backdrop {:?} (requested {:?})", applied, kind);
+    Ok(applied)
+}
+
+#[tauri::command]
+pub fn get_system_theme() -> SystemTheme {
+    system_theme()
+}
+
+/// Blocks until a value under `key` changes; false if the key can't be watched
+fn wait_for_change(key: &str) -> bool {
+    let key = to_wide(key);
+    let mut hkey = HKEY::default();
+    unsafe {
+        if RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key.as_ptr()),
+            None,
+            KEY_NOTIFY,
+            &mut hkey,
+        ) != ERROR_SUCCESS
+        {
+            return false;
+        }
+        let status = RegNotifyChangeKeyValue(hkey, false, REG_NOTIFY_CHANGE_LAST_SET, None, false);
+        let _ = RegCloseKey(hkey);
+        status == ERROR_SUCCESS
+    }
+}
+
+/// Applies the saved backdrop and starts watching the system theme; call once from setup
+pub fn start(app: &tauri::AppHandle) {
+    let backdrop = crate::settings::preferences().window_backdrop;
+    if backdrop != BackdropKind::None {
+        if let Some(window) = app.get_webview_window("main") {
+            apply_backdrop(&window, backdrop);
+        }
+    }
+
+    let last = std::sync::Arc::new(Mutex::new(system_theme()));
+    for key in [PERSONALIZE_KEY, DWM_KEY] {
+        let app = app.clone();
+        let last = last.clone();
+        std::thread::spawn(move || {
+            while wait_for_change(key) {
+                let theme = system_theme();
+                let Ok(mut last) = last.lock() else {
+                    return;
+                };
+                // Both keys see many unrelated writes
+                if *last == theme {
+                    continue;
+                }
+                let dark_changed = last.dark != theme.dark;
+                *last = theme.clone();
+                drop(last);
+
+                log::info!("[APPEARANCE] System theme changed: {:?}", theme);
+                let _ = app.emit("system-theme-changed", &theme);
+
+                let backdrop = CURRENT_BACKDROP.lock().map(|b| *b).unwrap_or_default();
+                if dark_changed && backdrop != BackdropKind::None {
+                    let handle = app.clone();
+                    let _ = app.run_on_main_thread(move || {
+                        if let Some(window) = handle.get_webview_window("main") {
+                            apply_backdrop(&window, backdrop);
+                        }
+                    });
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accent_hex() {
+        assert_eq!(accent_hex(0xFFD77800), "#0078D7");
+        assert_eq!(accent_hex(0x00000000), "#000000");
+    }
+}
@@ -0,0 +1,122 @@
+//! File Associations Module
+//!
+//! Answers "what would open this file?" via AssocQueryStringW so the details pane
+//! can show "Opens with: ..." and the frontend can decide how to handle a double-click.
+
+use base64::Engine;
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use ts_rs::TS;
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::UI::Shell::{
+    AssocQueryStringW, ASSOCF_INIT_IGNOREUNKNOWN, ASSOCF_NOTRUNCATE, ASSOCSTR, ASSOCSTR_EXECUTABLE,
+    ASSOCSTR_FRIENDLYAPPNAME, ASSOCSTR_FRIENDLYDOCNAME,
+};
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct FileAssociation {
+    /// Extension (with leading dot) or ProgID the lookup was made for
+    pub assoc_key: String,
+    /// False when no application is registered for this type
+    pub has_association: bool,
+    /// e.g. "Visual Studio Code"
+    pub friendly_app_name: Option<String>,
+    /// e.g. "Markdown Source File"
+    pub friendly_type_name: Option<String>,
+    /// Full path of the executable that handles the "open" verb
+    pub executable: Option<String>,
+    /// Base64 JPEG data URI of the handler's icon
+    pub icon_data: Option<String>,
+}
+
+fn query_assoc_string(assoc: &str, kind: ASSOCSTR) -> Option<String> {
+    let assoc_wide: Vec<u16> = OsStr::new(assoc)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let verb_wide: Vec<u16> = "open\0".encode_utf16().collect();
+    let flags = ASSOCF_INIT_IGNOREUNKNOWN | ASSOCF_NOTRUNCATE;
+
+    unsafe {
+        // First call: ask for the required buffer length (in characters, including the null)
+        let mut len = 0u32;
+        let _ = AssocQueryStringW(
+            flags,
+            kind,
+            PCWSTR(assoc_wide.as_ptr()),
+            PCWSTR(verb_wide.as_ptr()),
+            None,
+            &mut len,
+        );
+        if len == 0 {
+            return None;
+        }
+
+        let mut buffer = vec![0u16; len as usize];
+        let hr = AssocQueryStringW(
+            flags,
+            kind,
+            PCWSTR(assoc_wide.as_ptr()),
+            PCWSTR(verb_wide.as_ptr()),
+            Some(PWSTR(buffer.as_mut_ptr())),
+            &mut len,
+        );
+        if hr.is_err() {
+            return None;
+        }
+
+        let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        let value = String::from_utf16_lossy(&buffer[..end]);
+        if value.is_empty() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_association(path: String) -> Result<FileAssociation, String> {
+    let expanded_path = crate::expand_env_vars(&path);
+
+    tokio::task::spawn_blocking(move || {
+        let path_obj = std::path::Path::new(&expanded_path);
+        if path_obj.is_dir() {
+            return Err("Folders have no file association".to_string());
+        }
+
+        let assoc_key = path_obj
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .ok_or_else(|| "File has no extension".to_string())?;
+
+        let executable = query_assoc_string(&assoc_key, ASSOCSTR_EXECUTABLE);
+        let friendly_app_name = query_assoc_string(&assoc_key, ASSOCSTR_FRIENDLYAPPNAME);
+        let friendly_type_name = query_assoc_string(&assoc_key, ASSOCSTR_FRIENDLYDOCNAME);
+
+        // Reuse the shell image factory: for an .exe it yields the application icon
+        let icon_data = executable
+            .as_ref()
+            .filter(|exe| std::path::Path::new(exe).is_file())
+            .and_then(|exe| crate::generate_shell_thumbnail(exe, 32).ok())
+            .map(|bytes| {
+                format!(
+                    "data:image/jpeg;base64,{}",
+                    base64::engine::general_purpose::STANDARD.encode(bytes)
+                )
+            });
+
+        Ok(FileAssociation {
+            assoc_key,
+            has_association: executable.is_some() || friendly_app_name.is_some(),
+            friendly_app_name,
+            friendly_type_name,
+            executable,
+            icon_data,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,148 @@
+//! Attributes Module
+//!
+//! The Win32 attribute bits of an item, decoded for `FileEntry`, and
+//! `set_file_attributes` for the properties panel's Hidden / Read-only boxes.
+//! Only the bits `SetFileAttributesW` accepts can be changed; compression and
+//! encryption have their own APIs and are reported here but never set.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+use windows::core::HSTRING;
+use windows::Win32::Storage::FileSystem::{
+    GetFileAttributesW, SetFileAttributesW, FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_COMPRESSED,
+    FILE_ATTRIBUTE_ENCRYPTED, FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_NORMAL,
+    FILE_ATTRIBUTE_NOT_CONTENT_INDEXED, FILE_ATTRIBUTE_OFFLINE, FILE_ATTRIBUTE_READONLY,
+    FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS, FILE_ATTRIBUTE_RECALL_ON_OPEN, FILE_ATTRIBUTE_SYSTEM,
+    FILE_ATTRIBUTE_TEMPORARY, FILE_FLAGS_AND_ATTRIBUTES, INVALID_FILE_ATTRIBUTES,
+};
+
+#[derive(Serialize, Clone, Copy, TS, Debug, Default, PartialEq, Eq)]
+#[ts(export)]
+pub struct FileAttributes {
+    pub hidden: bool,
+    pub system: bool,
+    pub readonly: bool,
+    pub archive: bool,
+    pub compressed: bool,
+    pub encrypted: bool,
+    /// Data moved to offline storage
+    pub offline: bool,
+    /// Cloud placeholder whose contents are downloaded when opened
+    pub cloud: bool,
+}
+
+impl FileAttributes {
+    pub fn from_raw(raw: u32) -> Self {
+        let has = |flag: FILE_FLAGS_AND_ATTRIBUTES| raw & flag.0 != 0;
+        FileAttributes {
+            hidden: has(FILE_ATTRIBUTE_HIDDEN),
+            system: has(FILE_ATTRIBUTE_SYSTEM),
+            readonly: has(FILE_ATTRIBUTE_READONLY),
+            archive: has(FILE_ATTRIBUTE_ARCHIVE),
+            compressed: has(FILE_ATTRIBUTE_COMPRESSED),
+            encrypted: has(FILE_ATTRIBUTE_ENCRYPTED),
+            offline: has(FILE_ATTRIBUTE_OFFLINE),
+            cloud: has(FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS) || has(FILE_ATTRIBUTE_RECALL_ON_OPEN),
+        }
+    }
+}
+
+/// Attributes to change; None leaves one as it is
+#[derive(Deserialize, Clone, Copy, TS, Debug, Default)]
+#[serde(default)]
+#[ts(export)]
+pub struct AttributeUpdate {
+    pub hidden: Option<bool>,
+    pub system: Option<bool>,
+    pub readonly: Option<bool>,
+    pub archive: Option<bool>,
+}
+
+/// `raw` with `update` applied, keeping only the bits SetFileAttributesW takes
+fn apply_update(raw: u32, update: &AttributeUpdate) -> u32 {
+    let settable = FILE_ATTRIBUTE_ARCHIVE
+        | FILE_ATTRIBUTE_HIDDEN
+        | FILE_ATTRIBUTE_NOT_CONTENT_INDEXED
+        | FILE_ATTRIBUTE_OFFLINE
+        | FILE_ATTRIBUTE_READONLY
+        | FILE_ATTRIBUTE_SYSTEM
+        | FILE_ATTRIBUTE_TEMPORARY;
+    let mut attributes = raw & settable.0;
+    for (value, flag) in [
+        (update.hidden, FILE_ATTRIBUTE_HIDDEN),
+        (update.system, FILE_ATTRIBUTE_SYSTEM),
+        (update.readonly, FILE_ATTRIBUTE_READONLY),
+        (update.archive, FILE_ATTRIBUTE_ARCHIVE),
+    ] {
+        match value {
+            Some(true) => attributes |= flag.0,
+            Some(false) => attributes &= !flag.0,
+            None => {}
+        }
+    }
+    // No attributes at all has to be spelled NORMAL
+    if attributes == 0 {
+        FILE_ATTRIBUTE_NORMAL.0
+    } else {
+        attributes
+    }
+}
+
+/// Sets or clears Hidden, System, Read-only and Archive on `path` and returns the
+/// updated entry
+#[tauri::command]
+pub fn set_file_attributes(
+    path: String,
+    attrs: AttributeUpdate,
+) -> Result<crate::FileEntry, String> {
+    let path = crate::expand_env_vars(&path);
+    let path_h = HSTRING::from(crate::paths::long_path(Path::new(&path)).as_path());
+    unsafe {
+        let raw = GetFileAttributesW(&path_h);
+        if raw == INVALID_FILE_ATTRIBUTES {
+            return Err(format!(
+                "Failed to read attributes of {}: {}",
+                path,
+                windows::core::Error::from_win32()
+            ));
+        }
+        let attributes = apply_update(raw, &attrs);
+        SetFileAttributesW(&path_h, FILE_FLAGS_AND_ATTRIBUTES(attributes))
+            .map_err(|e| format!("Failed to set attributes of {}: {}", path, e))?;
+        log::info!("[ATTRIBUTES] {}: {:#x} -> {:#x}", path, raw, attributes);
+    }
+    crate::get_file_entry(Path::new(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_update() {
+        const DIRECTORY: u32 = 0x10;
+        let raw = DIRECTORY | FILE_ATTRIBUTE_HIDDEN.0 | FILE_ATTRIBUTE_ARCHIVE.0;
+        let update = AttributeUpdate {
+            hidden: Some(false),
+            readonly: Some(true),
+            ..Default::default()
+        };
+        let attributes = apply_update(raw, &update);
+        assert_eq!(
+            attributes,
+            FILE_ATTRIBUTE_ARCHIVE.0 | FILE_ATTRIBUTE_READONLY.0
+        );
+        assert!(FileAttributes::from_raw(attributes).readonly);
+        assert!(!FileAttributes::from_raw(attributes).hidden);
+
+        let cleared = AttributeUpdate {
+            archive: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            apply_update(FILE_ATTRIBUTE_ARCHIVE.0, &cleared),
+            FILE_ATTRIBUTE_NORMAL.0
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! Batch Rename Module
+//!
+//! Renames many items at once from a pattern. The pattern gives the new name
+//! and understands these tokens:
+//! - `{name}`: the current name without its extension
+//! - `{ext}`: the current extension, without the dot
+//! - `{counter}` / `{counter:03}`: a running number, optionally zero-padded
+//! - `{date}` / `{date:%Y%m%d}`: the last-modified date (`%Y-%m-%d` by default)
+//!
+//! When the pattern has no `{ext}`, the current extension is kept. Find/replace
+//! (plain or regex) and case conversion then apply to the pattern's result.
+//!
+//! Every call returns the planned old -> new names. With `dry_run` nothing is
+//! touched; otherwise the batch goes through IFileOperation on the operation
+//! queue (one undo step), and only when no item has an error.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use ts_rs::TS;
+
+use crate::operations::{self, OperationKind};
+
+/// Cap on the compiled find regex
+const REGEX_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Deserialize, Clone, Copy, TS, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CaseConversion {
+    #[default]
+    Keep,
+    Lower,
+    Upper,
+    /// First letter of every word uppercase, the rest lowercase
+    Title,
+}
+
+#[derive(Deserialize, Clone, TS, Debug)]
+#[serde(default)]
+#[ts(export)]
+pub struct BatchRenameOptions {
+    /// Text to replace in the new names; empty for none
+    pub find: String,
+    /// Replacement; in regex mode `$1` / `${name}` refer to groups
+    pub replace: String,
+    pub regex: bool,
+    pub ignore_case: bool,
+    pub case: CaseConversion,
+    #[ts(type = "number")]
+    pub counter_start: u64,
+    #[ts(type = "number")]
+    pub counter_step: u64,
+    /// Only plan the renames
+    pub dry_run: bool,
+}
+
+impl Default for BatchRenameOptions {
+    fn default() -> Self {
+        Self {
+            find: String::new(),
+            replace: String::new(),
+            regex: false,
+            ignore_case: false,
+            case: CaseConversion::Keep,
+            counter_start: 1,
+            counter_step: 1,
+            dry_run: false,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, TS, Debug, PartialEq, Eq)]
+#[ts(export)]
+pub struct RenamePlan {
+    pub old_path: String,
+    pub new_path: String,
+    pub new_name: String,
+    /// Why the item can't take its new name
+    pub error: Option<String>,
+}
+
+/// What the tokens of one item expand to
+struct TokenValues<'a> {
+    stem: &'a str,
+    ext: &'a str,
+    counter: u64,
+    modified: DateTime<Local>,
+}
+
+fn render_token(token: &str, values: &TokenValues) -> Result<String, String> {
+    let (name, arg) = match token.split_once(':') {
+        Some((name, arg)) => (name, Some(arg)),
+        None => (token, None),
+    };
+    match (name, arg) {
+        ("name", None) => Ok(values.stem.to_string()),
+        ("ext", None) => Ok(values.ext.to_string()),
+        ("counter", None) => Ok(values.counter.to_string()),
+        ("counter", Some(width))
+            if !width.is_empty() && width.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            let width: usize = width
+                .parse()
+                .map_err(|_| format!("Bad counter width: {}", width))?;
+            Ok(format!("{:0width$}", values.counter, width = width))
+        }
+        ("date", format) => {
+            let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+            // Formatting an invalid specifier panics, so check first
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                return Err(format!("Bad date format: {}", format));
+            }
+            Ok(values.modified.format(format).to_string())
+        }
+        _ => Err(format!("Unknown token {{{}}}", token)),
+    }
+}
+
+/// Expands the tokens of `pattern`
+fn render(pattern: &str, values: &TokenValues) -> Result<String, String> {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed token in {}", pattern))?;
+        out.push_str(&render_token(&rest[open + 1..open + close], values)?);
+        rest = &rest[open + close + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn convert_case(name: &str, case: CaseConversion) -> String {
+    match case {
+        CaseConversion::Keep => name.to_string(),
+        CaseConversion::Lower => name.to_lowercase(),
+        CaseConversion::Upper => name.to_uppercase(),
+        CaseConversion::Title => {
+            let mut out = String::with_capacity(name.len());
+            let mut word_start = true;
+            for c in name.chars() {
+                if word_start {
+                    out.extend(c.to_uppercase());
+                } else {
+                    out.extend(c.to_lowercase());
+                }
+                word_start = !c.is_alphanumeric() && c != '\'';
+            }
+            out
+        }
+    }
+}
+
+fn build_find(options: &BatchRenameOptions) -> Result<Option<Regex>, String> {
+    if options.find.is_empty() {
+        return Ok(None);
+    }
+    let pattern = if options.regex {
+        options.find.clone()
+    } else {
+        regex::escape(&options.find)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(options.ignore_case)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Invalid regular expression: {}", e))
+}
+
+/// Why Windows would refuse `name`, if it would
+fn invalid_name(name: &str) -> Option<&'static str> {
+    if name.is_empty() || name == "." || name == ".." {
+        return Some("The name is empty");
+    }
+    if name.chars().any(|c| {
+        matches!(c, '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control()
+    }) {
+        return Some("The name contains characters Windows does not allow");
+    }
+    if name.ends_with(['.', ' ']) {
+        return Some("The name ends with a dot or a space");
+    }
+    None
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// The new name of every item in `paths`, counted in the order given. `exists` and
+/// `modified` stand in for the file system.
+fn plan(
+    paths: &[String],
+    pattern: &str,
+    options: &BatchRenameOptions,
+    exists: impl Fn(&Path) -> bool,
+    modified: impl Fn(&Path) -> DateTime<Local>,
+) -> Result<Vec<RenamePlan>, String> {
+    let find = build_find(options)?;
+    let pattern = if pattern.is_empty() {
+        "{name}"
+    } else {
+        pattern
+    };
+    let keep_ext = !pattern.contains("{ext}");
+    let sources: HashSet<String> = paths.iter().map(|p| path_key(Path::new(p))).collect();
+    let mut taken: HashSet<String> = HashSet::new();
+
+    let mut plans = Vec::with_capacity(paths.len());
+    for (index, old_path) in paths.iter().enumerate() {
+        let old = Path::new(old_path);
+        let old_name = old
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Folders have no extension, however their name reads
+        let is_dir = old.is_dir();
+        let (stem, ext) = match old_name.rsplit_once('.') {
+            Some((stem, ext)) if !is_dir && !stem.is_empty() => (stem, ext),
+            _ => (old_name.as_str(), ""),
+        };
+        let values = TokenValues {
+            stem,
+            ext,
+            counter: options
+                .counter_start
+                .saturating_add(options.counter_step.saturating_mul(index as u64)),
+            modified: modified(old),
+        };
+
+        let mut new_name = render(pattern, &values)?;
+        if let Some(find) = &find {
+            new_name = find
+                .replace_all(&new_name, options.replace.as_str())
+                .into_owned();
+        }
+        new_name = convert_case(&new_name, options.case);
+        if keep_ext && !ext.is_empty() {
+            new_name = format!("{}.{}", new_name, ext);
+        }
+
+        let new_path: PathBuf = old.with_file_name(&new_name);
+        let key = path_key(&new_path);
+        let error = if let Some(reason) = invalid_name(&new_name) {
+            Some(reason.to_string())
+        } else if !taken.insert(key.clone()) {
+            Some("Another item in the batch gets the same name".to_string())
+        } else if key != path_key(old) && !sources.contains(&key) && exists(&new_path) {
+            Some("An item with this name already exists".to_string())
+        } else {
+            None
+        };
+        plans.push(RenamePlan {
+            old_path: old_path.clone(),
+            new_path: new_path.to_string_lossy().to_string(),
+            new_name,
+            error,
+        });
+    }
+    Ok(plans)
+}
+
+/// Renames the planned items. Names another item of the batch still holds are freed
+/// first: those items go through a temporary name, in a second shell operation.
+fn commit(
+    plans: &[RenamePlan],
+    hwnd: Option<isize>,
+    control: &std::sync::Arc<operations::OperationControl>,
+) -> Result<(), String> {
+    let changed: Vec<&RenamePlan> = plans.iter().filter(|p| p.old_path != p.new_path).collect();
+    let held: HashSet<String> = changed
+        .iter()
+        .map(|p| path_key(Path::new(&p.old_path)))
+        .collect();
+
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    for (index, plan) in changed.iter().enumerate() {
+        let new_key = path_key(Path::new(&plan.new_path));
+        let only_case = new_key == path_key(Path::new(&plan.old_path));
+        if held.contains(&new_key) && !only_case {
+            let temp = format!("{}.qe-rename-{}", plan.new_name, index);
+            let temp_path = Path::new(&plan.old_path).with_file_name(&temp);
+            first.push((plan.old_path.clone(), temp));
+            second.push((
+                temp_path.to_string_lossy().to_string(),
+                plan.new_name.clone(),
+            ));
+        } else {
+            first.push((plan.old_path.clone(), plan.new_name.clone()));
+        }
+    }
+
+    crate::sta_worker::run_file_operation(control, || {
+        crate::sta_worker::rename_items_impl(first, hwnd, control)?;
+        if !second.is_empty() {
+            crate::sta_worker::rename_items_impl(second, hwnd, control)?;
+        }
+        Ok(())
+    })
+}
+
+fn modified_time(path: &Path) -> DateTime<Local> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+        .into()
+}
+
+/// Plans new names for `paths` from `pattern` and, unless `options.dry_run` is set,
+/// renames them. Returns the plan either way; a batch with errors is not renamed.
+#[tauri::command]
+pub async fn batch_rename(
+    window: tauri::Window,
+    paths: Vec<String>,
+    pattern: String,
+    options: BatchRenameOptions,
+) -> Result<Vec<RenamePlan>, String> {
+    let paths: Vec<String> = paths.iter().map(|p| crate::expand_env_vars(p)).collect();
+    let plans = {
+        let paths = paths.clone();
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || {
+            plan(&paths, &pattern, &options, Path::exists, modified_time)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??
+    };
+    if options.dry_run {
+        return Ok(plans);
+    }
+
+    let failed = plans.iter().filter(|p| p.error.is_some()).count();
+    if failed > 0 {
+        return Err(format!("{} item(s) can't be renamed", failed));
+    }
+    log::info!("[BATCH-RENAME] Renaming {} items", plans.len());
+
+    let hwnd = {
+        let root_hwnd = crate::get_root_hwnd(&window);
+        crate::harden_focus(root_hwnd);
+        Some(root_hwnd.0 as isize)
+    };
+    let committed = plans.clone();
+    operations::run(OperationKind::Rename, paths, None, move |control| {
+        commit(&committed, hwnd, control).map(|_| Vec::new())
+    })
+    .await?;
+    Ok(plans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn plan_names(
+        paths: &[&str],
+        pattern: &str,
+        options: &BatchRenameOptions,
+        existing: &[&str],
+    ) -> Vec<(String, Option<String>)> {
+        let paths: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+        let existing: HashSet<PathBuf> = existing.iter().map(PathBuf::from).collect();
+        plan(
+            &paths,
+            pattern,
+            options,
+            |p| existing.contains(p),
+            |_| Local.with_ymd_and_hms(2024, 3, 9, 12, 0, 0).unwrap(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|p| (p.new_name, p.error))
+        .collect()
+    }
+
+    #[test]
+    fn test_tokens_find_replace_and_case() {
+        let options = BatchRenameOptions::default();
+        assert_eq!(
+            plan_names(
+                &["/p/IMG_1.JPG", "/p/IMG_2.JPG"],
+                "Trip {counter:03} {date}",
+                &options,
+                &[]
+            ),
+            vec![
+                ("Trip 001 2024-03-09.JPG".to_string(), None),
+                ("Trip 002 2024-03-09.JPG".to_string(), None),
+            ]
+        );
+
+        let options = BatchRenameOptions {
+            find: r"_(\d+)".to_string(),
+            replace: " #$1".to_string(),
+            regex: true,
+            case: CaseConversion::Title,
+            ..BatchRenameOptions::default()
+        };
+        assert_eq!(
+            plan_names(&["/p/holiday_photo_7.png"], "{name}.{ext}", &options, &[]),
+            vec![("Holiday_Photo #7.Png".to_string(), None)]
+        );
+
+        assert!(plan(
+            &["/p/a.txt".to_string()],
+            "{nope}",
+            &BatchRenameOptions::default(),
+            |_| false,
+            |_| Local::now(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_collisions() {
+        let options = BatchRenameOptions::default();
+        // Two items mapped to one name, and a name held by a file outside the batch
+        let names = plan_names(
+            &["/p/a.txt", "/p/b.txt"],
+            "same",
+            &options,
+            &["/p/same.txt"],
+        );
+        assert!(names[0].1.is_some());
+        assert!(names[1].1.is_some());
+
+        // Names held by the batch itself are freed as it goes
+        let names = plan_names(
+            &["/p/1.txt", "/p/2.txt"],
+            "{counter}",
+            &BatchRenameOptions {
+                counter_start: 2,
+                ..BatchRenameOptions::default()
+            },
+            &["/p/1.txt", "/p/2.txt"],
+        );
+        assert_eq!(names[0], ("2.txt".to_string(), None));
+        assert_eq!(names[1], ("3.txt".to_string(), None));
+
+        let names = plan_names(&["/p/a.txt"], "bad?", &options, &[]);
+        assert!(names[0].1.is_some());
+    }
+}
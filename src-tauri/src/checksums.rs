@@ -0,0 +1,432 @@
+//! Checksum Manifest Module
+//!
+//! Reads `.md5` / `.sha1` / `.sha256` / `.b3` / `.sfv` manifests and checks the
+//! files they list. Understood line formats:
+//! - GNU coreutils: `<hex>  name` or `<hex> *name`
+//! - BSD tags: `SHA256 (name) = <hex>`
+//! - SFV: `name <crc32>`, with `;` comments
+//! - a lone digest, which refers to the manifest name minus its extension
+//!   (`ubuntu.iso.sha256` -> `ubuntu.iso`)
+//!
+//! Names are resolved relative to the manifest's folder.
+//!
+//! `verify_checksum` checks one file against a digest pasted by the user, such
+//! as the SHA-256 published next to a download.
+
+use crate::hashing::{self, HashAlgorithm, ProgressReporter};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use ts_rs::TS;
+
+/// Manifests are small text files; anything bigger is not one
+const MAX_MANIFEST_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub file_name: String,
+    pub algorithm: HashAlgorithm,
+    /// Lowercase hex
+    pub expected: String,
+}
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+#[ts(export)]
+pub enum ChecksumStatus {
+    Ok,
+    Failed,
+    Missing,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct ChecksumResult {
+    pub path: String,
+    pub algorithm: HashAlgorithm,
+    pub expected: String,
+    /// None when the file is missing or could not be read
+    pub actual: Option<String>,
+    pub status: ChecksumStatus,
+    /// Why a listed file that exists could not be hashed
+    pub error: Option<String>,
+}
+
+fn algorithm_from_extension(manifest: &Path) -> Option<HashAlgorithm> {
+    let ext = manifest.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "md5" => Some(HashAlgorithm::Md5),
+        "sha1" => Some(HashAlgorithm::Sha1),
+        "sha256" => Some(HashAlgorithm::Sha256),
+        "b3" | "blake3" => Some(HashAlgorithm::Blake3),
+        "sfv" => Some(HashAlgorithm::Crc32),
+        _ => None,
+    }
+}
+
+fn algorithm_from_tag(tag: &str) -> Option<HashAlgorithm> {
+    match tag.to_uppercase().replace('-', "").as_str() {
+        "MD5" => Some(HashAlgorithm::Md5),
+        "SHA1" => Some(HashAlgorithm::Sha1),
+        "SHA256" => Some(HashAlgorithm::Sha256),
+        "CRC32" => Some(HashAlgorithm::Crc32),
+        "BLAKE3" => Some(HashAlgorithm::Blake3),
+        _ => None,
+    }
+}
+
+fn algorithm_from_digest(digest: &str) -> Option<HashAlgorithm> {
+    match digest.len() {
+        8 => Some(HashAlgorithm::Crc32),
+        32 => Some(HashAlgorithm::Md5),
+        40 => Some(HashAlgorithm::Sha1),
+        64 => Some(HashAlgorithm::Sha256),
+        _ => None,
+    }
+}
+
+/// Algorithms whose digests are as long as `digest`. SHA-256 and BLAKE3 share a length,
+/// so a bare 64-digit digest could be either.
+fn candidates_for_digest(digest: &str) -> Vec<HashAlgorithm> {
+    match digest.len() {
+        64 => vec![HashAlgorithm::Sha256, HashAlgorithm::Blake3],
+        _ => algorithm_from_digest(digest).into_iter().collect(),
+    }
+}
+
+fn is_hex(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// `SHA256 (name) = digest`
+fn parse_bsd_line(line: &str) -> Option<ManifestEntry> {
+    let (tag, rest) = line.split_once(" (")?;
+    let (name, digest) = rest.rsplit_once(") = ")?;
+    let digest = digest.trim();
+    if !is_hex(digest) {
+        return None;
+    }
+    Some(ManifestEntry {
+        file_name: name.to_string(),
+        algorithm: algorithm_from_tag(tag.trim())?,
+        expected: digest.to_lowercase(),
+    })
+}
+
+/// `digest  name` / `digest *name`
+fn parse_gnu_line(line: &str, default: Option<HashAlgorithm>) -> Option<ManifestEntry> {
+    let (digest, name) = line.split_once(char::is_whitespace)?;
+    if !is_hex(digest) {
+        return None;
+    }
+    let name = name.trim_start();
+    let name = name.strip_prefix('*').unwrap_or(name);
+    if name.is_empty() {
+        return None;
+    }
+    Some(ManifestEntry {
+        file_name: name.to_string(),
+        algorithm: default
+            .filter(|a| *a != HashAlgorithm::Crc32)
+            .or_else(|| algorithm_from_digest(digest))?,
+        expected: digest.to_lowercase(),
+    })
+}
+
+/// `name crc32` (the name may itself contain spaces)
+fn parse_sfv_line(line: &str) -> Option<ManifestEntry> {
+    let (name, digest) = line.rsplit_once(char::is_whitespace)?;
+    if digest.len() != 8 || !is_hex(digest) || name.trim().is_empty() {
+        return None;
+    }
+    Some(ManifestEntry {
+        file_name: name.trim().to_string(),
+        algorithm: HashAlgorithm::Crc32,
+        expected: digest.to_lowercase(),
+    })
+}
+
+/// Parses the manifest text. `manifest_name` is the manifest's own file name, used for
+/// its extension and for lone-digest files.
+pub fn parse_manifest(text: &str, manifest_name: &str) -> Vec<ManifestEntry> {
+    let manifest = Path::new(manifest_name);
+    let default = algorithm_from_extension(manifest);
+    let is_sfv = default == Some(HashAlgorithm::Crc32);
+
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}').trim())
+        .filter(|l| !l.is_empty() && !l.starts_with(';') && !l.starts_with('#'))
+        .collect();
+
+    // A lone digest names the file the manifest sits next to
+    if let [only] = lines.as_slice() {
+        if is_hex(only) {
+            let target = manifest
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let algorithm = default.or_else(|| algorithm_from_digest(only));
+            return algorithm
+                .map(|algorithm| ManifestEntry {
+                    file_name: target,
+                    algorithm,
+                    expected: only.to_lowercase(),
+                })
+                .into_iter()
+                .collect();
+        }
+    }
+
+    lines
+        .into_iter()
+        .filter_map(|line| {
+            parse_bsd_line(line).or_else(|| {
+                if is_sfv {
+                    parse_sfv_line(line)
+                } else {
+                    parse_gnu_line(line, default).or_else(|| parse_sfv_line(line))
+                }
+            })
+        })
+        .collect()
+}
+
+/// Hashes every file listed in the manifest at `path` and compares it with the expected
+/// digest. Progress is reported as `hash-progress` events; `cancel_hash` with the job id
+/// from those events stops the run.
+#[tauri::command]
+pub async fn verify_checksum_file(
+    window: tauri::Window,
+    path: String,
+) -> Result<Vec<ChecksumResult>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let manifest = PathBuf::from(&path);
+        let size = std::fs::metadata(&manifest)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?
+            .len();
+        if size > MAX_MANIFEST_SIZE {
+            return Err("File is too large to be a checksum list".to_string());
+        }
+        let bytes =
+            std::fs::read(&manifest).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let text = String::from_utf8_lossy(&bytes);
+        let manifest_name = manifest
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let entries = parse_manifest(&text, &manifest_name);
+        if entries.is_empty() {
+            return Err("No checksums found in this file".to_string());
+        }
+
+        let base = manifest.parent().map(Path::to_path_buf).unwrap_or_default();
+        let targets: Vec<(PathBuf, Option<u64>)> = entries
+            .iter()
+            .map(|e| {
+                let target = base.join(e.file_name.replace('/', "\\"));
+                let size = std::fs::metadata(&target)
+                    .ok()
+                    .filter(|m| m.is_file())
+                    .map(|m| m.len());
+                (target, size)
+            })
+            .collect();
+
+        let (job_id, cancelled) = hashing::register_job();
+        log::info!(
+            "[HASH] {} verifying {} ({} entries)",
+            job_id,
+            path,
+            entries.len()
+        );
+        let total = targets.iter().filter_map(|(_, s)| *s).sum();
+        let mut progress = ProgressReporter::new(window, job_id.clone(), total);
+
+        let mut results = Vec::with_capacity(entries.len());
+        for (entry, (target, size)) in entries.into_iter().zip(targets) {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let mut result = ChecksumResult {
+                path: target.to_string_lossy().to_string(),
+                algorithm: entry.algorithm,
+                expected: entry.expected,
+                actual: None,
+                status: ChecksumStatus::Missing,
+                error: None,
+            };
+            if let Some(size) = size {
+                let hashed =
+                    hashing::hash_file(&target, &[entry.algorithm], None, &cancelled, |done| {
+                        progress.file_progress(&target, done, size)
+                    });
+                progress.file_finished(size);
+                match hashed.map(|mut h| h.remove(&entry.algorithm)) {
+                    Ok(Some(actual)) => {
+                        result.status = if actual == result.expected {
+                            ChecksumStatus::Ok
+                        } else {
+                            ChecksumStatus::Failed
+                        };
+                        result.actual = Some(actual);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        result.status = ChecksumStatus::Failed;
+                        result.error = Some(e);
+                    }
+                }
+            }
+            results.push(result);
+        }
+
+        hashing::finish_job(&job_id);
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Verification cancelled".to_string());
+        }
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Checks `path` against the hex digest `expected`, trying every algorithm that makes
+/// digests of that length in one pass. Progress is reported as `hash-progress` events;
+/// `cancel_hash` with the job id from those events stops the run.
+#[tauri::command]
+pub async fn verify_checksum(
+    window: tauri::Window,
+    path: String,
+    expected: String,
+) -> Result<ChecksumResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let expected = expected.trim().to_lowercase();
+        let candidates = candidates_for_digest(&expected);
+        if !is_hex(&expected) || candidates.is_empty() {
+            return Err("Not an MD5, SHA-1, SHA-256, BLAKE3 or CRC32 checksum".to_string());
+        }
+        let file = PathBuf::from(&path);
+        let size = std::fs::metadata(&file)
+            .ok()
+            .filter(|m| m.is_file())
+            .map(|m| m.len());
+        let mut result = ChecksumResult {
+            path: path.clone(),
+            algorithm: candidates[0],
+            expected,
+            actual: None,
+            status: ChecksumStatus::Missing,
+            error: None,
+        };
+        let Some(size) = size else {
+            return Ok(result);
+        };
+
+        let (job_id, cancelled) = hashing::register_job();
+        log::info!("[HASH] {} verifying {} ({:?})", job_id, path, candidates);
+        let mut progress = ProgressReporter::new(window, job_id.clone(), size);
+        let hashed = hashing::hash_file(&file, &candidates, None, &cancelled, |done| {
+            progress.file_progress(&file, done, size)
+        });
+        hashing::finish_job(&job_id);
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Verification cancelled".to_string());
+        }
+
+        match hashed {
+            Ok(hashes) => {
+                // Report the algorithm that matched, or the most likely one
+                let algorithm = candidates
+                    .iter()
+                    .copied()
+                    .find(|a| hashes.get(a) == Some(&result.expected))
+                    .unwrap_or(candidates[0]);
+                result.algorithm = algorithm;
+                result.actual = hashes.get(&algorithm).cloned();
+                result.status = if result.actual.as_ref() == Some(&result.expected) {
+                    ChecksumStatus::Ok
+                } else {
+                    ChecksumStatus::Failed
+                };
+            }
+            Err(e) => {
+                result.status = ChecksumStatus::Failed;
+                result.error = Some(e);
+            }
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gnu_and_bsd_lines() {
+        let text = "\
+d41d8cd98f00b204e9800998ecf8427e  empty.txt
+900150983CD24FB0D6963F7D28E17F72 *dir/abc file.bin
+SHA256 (tagged.iso) = BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD
+";
+        let entries = parse_manifest(text, "MD5SUMS");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].file_name, "empty.txt");
+        assert_eq!(entries[0].algorithm, HashAlgorithm::Md5);
+        assert_eq!(entries[1].file_name, "dir/abc file.bin");
+        assert_eq!(entries[1].expected, "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(entries[2].file_name, "tagged.iso");
+        assert_eq!(entries[2].algorithm, HashAlgorithm::Sha256);
+    }
+
+    #[test]
+    fn test_parse_sfv() {
+        let text = "; Generated by some tool\r\nTrack 01.flac 352441C2\r\nother.bin deadbeef\r\n";
+        let entries = parse_manifest(text, "album.sfv");
+        assert_eq!(
+            entries,
+            vec![
+                ManifestEntry {
+                    file_name: "Track 01.flac".to_string(),
+                    algorithm: HashAlgorithm::Crc32,
+                    expected: "352441c2".to_string(),
+                },
+                ManifestEntry {
+                    file_name: "other.bin".to_string(),
+                    algorithm: HashAlgorithm::Crc32,
+                    expected: "deadbeef".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lone_digest_names_sibling_file() {
+        let entries = parse_manifest(
+            "a9993e364706816aba3e25717850c26c9cd0d89d\n",
+            "setup.exe.sha1",
+        );
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].file_name, "setup.exe");
+        assert_eq!(entries[0].algorithm, HashAlgorithm::Sha1);
+    }
+
+    #[test]
+    fn test_digest_length_candidates() {
+        assert_eq!(
+            candidates_for_digest("352441c2"),
+            vec![HashAlgorithm::Crc32]
+        );
+        assert_eq!(
+            candidates_for_digest(&"a".repeat(64)),
+            vec![HashAlgorithm::Sha256, HashAlgorithm::Blake3]
+        );
+        assert!(candidates_for_digest("abc").is_empty());
+
+        let entries = parse_manifest(&"b".repeat(64), "setup.exe.b3");
+        assert_eq!(entries[0].algorithm, HashAlgorithm::Blake3);
+    }
+}
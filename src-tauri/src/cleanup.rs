@@ -0,0 +1,326 @@
+//! Cleanup Module
+//!
+//! "Free up space" report for one drive. Each category lists concrete paths
+//! with their sizes so the panel can hand the selection straight to
+//! `delete_items`; the Recycle Bin category has no items and is cleared with
+//! `empty_recycle_bin` instead. Nothing here deletes anything.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+use windows::core::HSTRING;
+use windows::Win32::UI::Shell::{SHQueryRecycleBinW, SHQUERYRBINFO};
+
+/// Downloads untouched for this long count as old
+const OLD_DOWNLOAD_AGE: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+/// Files at least this big are listed as huge
+const HUGE_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+const MAX_HUGE_FILES: usize = 50;
+/// The huge-file scan walks the whole drive; stop it after this long and report what was found
+const HUGE_FILE_SCAN_BUDGET: Duration = Duration::from_secs(30);
+/// Top-level folders the huge-file scan skips: system-owned, nothing to clean by hand
+const SYSTEM_FOLDERS: &[&str] = &[
+    "Windows",
+    "Program Files",
+    "Program Files (x86)",
+    "ProgramData",
+    "$Recycle.Bin",
+    "System Volume Information",
+    "Recovery",
+];
+
+/// Chromium-based browsers' "User Data" folders under %LOCALAPPDATA%...
+const CHROMIUM_BROWSERS: &[&str] = &[
+    "Google\\Chrome\\User Data",
+    "Microsoft\\Edge\\User Data",
+    "BraveSoftware\\Brave-Browser\\User Data",
+    "Vivaldi\\User Data",
+];
+/// ...and the cache folders inside each of their profiles
+const CHROMIUM_CACHES: &[&str] = &["Cache", "Code Cache", "GPUCache"];
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct CleanupItem {
+    pub path: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    /// Unix seconds
+    #[ts(type = "number | null")]
+    pub modified_timestamp: Option<i64>,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct CleanupCategory {
+    /// Stable identifier: "temp", "browser_cache", "old_downloads", "huge_files", "recycle_bin"
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[ts(type = "number")]
+    pub total_size: u64,
+    /// Largest first
+    pub items: Vec<CleanupItem>,
+    /// False when the scan stopped early and the category may be incomplete
+    pub complete: bool,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct CleanupReport {
+    pub drive: String,
+    pub categories: Vec<CleanupCategory>,
+    #[ts(type = "number")]
+    pub total_size: u64,
+}
+
+fn is_on_drive(path: &Path, drive: char) -> bool {
+    path.to_string_lossy()
+        .chars()
+        .next()
+        .is_some_and(|c| c.eq_ignore_ascii_case(&drive))
+}
+
+fn modified_timestamp(metadata: &std::fs::Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
+
+/// Total size of the files beneath `path` (or the file itself)
+pub(crate) fn path_size(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => jwalk::WalkDir::new(path)
+            .skip_hidden(false)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.metadata().ok())
+            .map(|m| m.len())
+            .sum(),
+        Ok(m) => m.len(),
+        Err(_) => 0,
+    }
+}
+
+fn item(path: PathBuf) -> Option<CleanupItem> {
+    let metadata = std::fs::symlink_metadata(&path).ok()?;
+    let size = path_size(&path);
+    (size > 0).then(|| CleanupItem {
+        path: path.to_string_lossy().to_string(),
+        size,
+        modified_timestamp: modified_timestamp(&metadata),
+    })
+}
+
+fn category(
+    id: &str,
+    name: &str,
+    description: &str,
+    mut items: Vec<CleanupItem>,
+) -> CleanupCategory {
+    items.sort_by_key(|i| std::cmp::Reverse(i.size));
+    CleanupCategory {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        total_size: items.iter().map(|i| i.size).sum(),
+        items,
+        complete: true,
+    }
+}
+
+fn children(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(Result::ok).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+fn temp_files(drive: char) -> CleanupCategory {
+    let mut dirs = vec![std::env::temp_dir()];
+    if let Ok(windir) = std::env::var("SystemRoot") {
+        dirs.push(Path::new(&windir).join("Temp"));
+    }
+    let items = dirs
+        .iter()
+        .filter(|d| is_on_drive(d, drive))
+        .flat_map(|d| children(d))
+        .filter_map(item)
+        .collect();
+    category(
+        "temp",
+        "Temporary files",
+        "Files apps left behind in the Temp folders. Items still in use are skipped when deleting.",
+        items,
+    )
+}
+
+fn browser_caches(drive: char) -> CleanupCategory {
+    let local = PathBuf::from(std::env::var("LOCALAPPDATA").unwrap_or_default());
+    let mut caches = Vec::new();
+    if is_on_drive(&local, drive) {
+        for browser in CHROMIUM_BROWSERS {
+            // Profiles are "Default", "Profile 1", ...
+            for profile in children(&local.join(browser)) {
+                caches.extend(
+                    CHROMIUM_CACHES
+                        .iter()
+                        .map(|c| profile.join(c))
+                        .filter(|p| p.is_dir()),
+                );
+            }
+        }
+        let firefox = local.join("Mozilla\\Firefox\\Profiles");
+        caches.extend(
+            children(&firefox)
+                .into_iter()
+                .map(|p| p.join("cache2"))
+                .filter(|p| p.is_dir()),
+        );
+    }
+    category(
+        "browser_cache",
+        "Browser caches",
+        "Cached web content. Close the browser first; it rebuilds the cache as you browse.",
+        caches.into_iter().filter_map(item).collect(),
+    )
+}
+
+fn old_downloads(drive: char) -> CleanupCategory {
+    let downloads = crate::paths::expand_path_str("shell:Downloads")
+        .map(PathBuf::from)
+        .ok()
+        .filter(|d| is_on_drive(d, drive));
+    let cutoff = SystemTime::now() - OLD_DOWNLOAD_AGE;
+    let items = downloads
+        .map(|d| children(&d))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| {
+            std::fs::metadata(p)
+                .and_then(|m| m.modified())
+                .is_ok_and(|t| t < cutoff)
+        })
+        .filter_map(item)
+        .collect();
+    category(
+        "old_downloads",
+        "Old downloads",
+        "Items in Downloads not modified in the last 90 days.",
+        items,
+    )
+}
+
+fn huge_files(drive: char) -> CleanupCategory {
+    let root = PathBuf::from(format!("{}:\\", drive));
+    let start = Instant::now();
+    let walker = jwalk::WalkDir::new(&root)
+        .skip_hidden(false)
+        .follow_links(false)
+        .process_read_dir(move |depth, _, _, children| {
+            if start.elapsed() > HUGE_FILE_SCAN_BUDGET {
+                children.clear();
+                return;
+            }
+            if depth == Some(0) {
+                children.retain(|child| {
+                    child.as_ref().map_or(true, |e| {
+                        let name = e.file_name().to_string_lossy();
+                        !SYSTEM_FOLDERS.iter().any(|s| s.eq_ignore_ascii_case(&name))
+                    })
+                });
+            }
+        });
+
+    let mut items = Vec::new();
+    for entry in walker.into_iter().filter_map(Result::ok) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.len() >= HUGE_FILE_SIZE {
+            items.push(CleanupItem {
+                path: entry.path().to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified_timestamp: modified_timestamp(&metadata),
+            });
+        }
+    }
+    items.sort_by_key(|i| std::cmp::Reverse(i.size));
+    items.truncate(MAX_HUGE_FILES);
+
+    let mut huge = category(
+        "huge_files",
+        "Large files",
+        "Files of 1 GB or more outside system folders. Review before deleting.",
+        items,
+    );
+    huge.complete = start.elapsed() <= HUGE_FILE_SCAN_BUDGET;
+    huge
+}
+
+fn recycle_bin(drive: char) -> CleanupCategory {
+    let mut info = SHQUERYRBINFO {
+        cbSize: std::mem::size_of::<SHQUERYRBINFO>() as u32,
+        ..Default::default()
+    };
+    let root = HSTRING::from(format!("{}:\\", drive));
+    let size = unsafe { SHQueryRecycleBinW(&root, &mut info) }
+        .map(|_| info.i64Size.max(0) as u64)
+        .unwrap_or(0);
+    let mut bin = category(
+        "recycle_bin",
+        "Recycle Bin",
+        "Deleted items still taking up space on this drive.",
+        Vec::new(),
+    );
+    bin.total_size = size;
+    bin
+}
+
+/// Categorized list of what could be deleted to free space on `drive` ("C", "C:" or "C:\")
+#[tauri::command]
+pub async fn get_cleanup_candidates(drive: String) -> Result<CleanupReport, String> {
+    let letter = drive
+        .chars()
+        .next()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .ok_or_else(|| format!("Invalid drive: {}", drive))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let start = Instant::now();
+        let categories = vec![
+            temp_files(letter),
+            browser_caches(letter),
+            old_downloads(letter),
+            huge_files(letter),
+            recycle_bin(letter),
+        ];
+        let total_size = categories
+            .iter()
+            .filter(|c| c.id != "huge_files")
+            .map(|c| c.total_size)
+            .sum();
+        log::info!(
+            "[CLEANUP] Scanned {}: in {}ms, {} bytes reclaimable",
+            letter,
+            start.elapsed().as_millis(),
+            total_size
+        );
+        Ok(CleanupReport {
+            drive: format!("{}:", letter),
+            categories,
+            total_size,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
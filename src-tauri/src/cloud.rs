@@ -0,0 +1,153 @@
+//! Cloud Module
+//!
+//! Files kept by OneDrive and the other sync engines built on the Cloud Files
+//! API. Their placeholders look like ordinary files but download as soon as
+//! anything reads them, so listings tell them apart from their attribute bits
+//! alone and the code paths that read contents (URL targets, ffmpeg thumbnails,
+//! prefetch) skip online-only files. Browsing a OneDrive folder must never
+//! download it.
+//!
+//! `pin_file` and `free_up_space` are Explorer's "Always keep on this device"
+//! and "Free up space": they set the pin state and leave the downloading or
+//! dehydrating to the sync engine.
+
+use serde::Serialize;
+use std::path::Path;
+use ts_rs::TS;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Storage::CloudFilters::{
+    CfSetPinState, CF_PIN_STATE, CF_PIN_STATE_PINNED, CF_PIN_STATE_UNPINNED,
+    CF_SET_PIN_FLAG_RECURSE,
+};
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, GetFileAttributesW, FILE_ATTRIBUTE_PINNED, FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS,
+    FILE_ATTRIBUTE_RECALL_ON_OPEN, FILE_ATTRIBUTE_REPARSE_POINT, FILE_ATTRIBUTE_UNPINNED,
+    FILE_FLAGS_AND_ATTRIBUTES, FILE_FLAG_BACKUP_SEMANTICS, FILE_READ_ATTRIBUTES, FILE_SHARE_DELETE,
+    FILE_SHARE_READ, FILE_SHARE_WRITE, FILE_WRITE_ATTRIBUTES, INVALID_FILE_ATTRIBUTES,
+    OPEN_EXISTING,
+};
+
+/// IO_REPARSE_TAG_CLOUD through IO_REPARSE_TAG_CLOUD_F differ only in bits 12-15
+const IO_REPARSE_TAG_CLOUD: u32 = 0x9000_001A;
+const CLOUD_TAG_MASK: u32 = 0xFFFF_0FFF;
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum CloudStatus {
+    /// Only a placeholder; opening it downloads it
+    OnlineOnly,
+    /// Downloaded, but may be freed up again
+    LocallyAvailable,
+    /// "Always keep on this device"
+    Pinned,
+}
+
+/// Cloud state of an item from its attributes and, when known, its reparse
+/// tag. None for items that aren't managed by a sync engine.
+pub fn cloud_status(attributes: u32, reparse_tag: Option<u32>) -> Option<CloudStatus> {
+    let has = |flag: FILE_FLAGS_AND_ATTRIBUTES| attributes & flag.0 != 0;
+    if has(FILE_ATTRIBUTE_PINNED) {
+        return Some(CloudStatus::Pinned);
+    }
+    if has(FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS) || has(FILE_ATTRIBUTE_RECALL_ON_OPEN) {
+        return Some(CloudStatus::OnlineOnly);
+    }
+    let is_cloud_tag = reparse_tag.is_some_and(|tag| tag & CLOUD_TAG_MASK == IO_REPARSE_TAG_CLOUD);
+    (has(FILE_ATTRIBUTE_UNPINNED) || (has(FILE_ATTRIBUTE_REPARSE_POINT) && is_cloud_tag))
+        .then_some(CloudStatus::LocallyAvailable)
+}
+
+/// Whether reading `path` would download it first
+pub fn is_online_only(path: &Path) -> bool {
+    let attributes =
+        unsafe { GetFileAttributesW(&HSTRING::from(crate::paths::long_path(path).as_path())) };
+    attributes != INVALID_FILE_ATTRIBUTES
+        && cloud_status(attributes, None) == Some(CloudStatus::OnlineOnly)
+}
+
+fn set_pin_state(path: &str, state: CF_PIN_STATE) -> Result<(), String> {
+    unsafe {
+        // An attributes-only handle is enough and doesn't start a download
+        let handle = CreateFileW(
+            &HSTRING::from(crate::paths::long_path(Path::new(path)).as_path()),
+            (FILE_READ_ATTRIBUTES | FILE_WRITE_ATTRIBUTES).0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAG_BACKUP_SEMANTICS,
+            None,
+        )
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        let result = CfSetPinState(handle, state, CF_SET_PIN_FLAG_RECURSE, None);
+        let _ = CloseHandle(handle);
+        result.map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+fn set_pin_states(paths: Vec<String>, state: CF_PIN_STATE) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for path in paths {
+        let path = crate::expand_env_vars(&path);
+        match set_pin_state(&path, state) {
+            Ok(()) => log::info!("[CLOUD] Pin state of {} set to {}", path, state.0),
+            Err(e) => errors.push(e),
+        }
+    }
+    // The attribute changes arrive through the watchers, but not right away
+    crate::listing::invalidate_cache();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Failed to change {}", errors.join(", ")))
+    }
+}
+
+/// Keeps `paths` (folders recursively) on this device; the sync engine downloads them
+#[tauri::command]
+pub async fn pin_file(paths: Vec<String>) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || set_pin_states(paths, CF_PIN_STATE_PINNED))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Lets the sync engine replace the local copies of `paths` with placeholders
+#[tauri::command]
+pub async fn free_up_space(paths: Vec<String>) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || set_pin_states(paths, CF_PIN_STATE_UNPINNED))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_status() {
+        const ARCHIVE: u32 = 0x20;
+        assert_eq!(cloud_status(ARCHIVE, None), None);
+        assert_eq!(
+            cloud_status(ARCHIVE | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS.0, None),
+            Some(CloudStatus::OnlineOnly)
+        );
+        assert_eq!(
+            cloud_status(
+                FILE_ATTRIBUTE_PINNED.0 | FILE_ATTRIBUTE_REPARSE_POINT.0,
+                None
+            ),
+            Some(CloudStatus::Pinned)
+        );
+        // IO_REPARSE_TAG_CLOUD_3
+        assert_eq!(
+            cloud_status(ARCHIVE | FILE_ATTRIBUTE_REPARSE_POINT.0, Some(0x9000_301A)),
+            Some(CloudStatus::LocallyAvailable)
+        );
+        // A symbolic link is a reparse point but not a placeholder
+        assert_eq!(
+            cloud_status(FILE_ATTRIBUTE_REPARSE_POINT.0, Some(0xA000_000C)),
+            None
+        );
+    }
+}
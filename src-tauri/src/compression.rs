@@ -0,0 +1,377 @@
+//! Compression Module
+//!
+//! Builds ZIP, 7Z and TAR.GZ archives from a selection, the counterpart of
+//! `extraction`. Folders are added with everything beneath them, under their
+//! own name. Progress is reported like extraction's, as `compression-progress`
+//! events and on the taskbar button; a failed archive is deleted rather than
+//! left half-written.
+
+use serde::Deserialize;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::extraction::{finish_progress, report_progress};
+
+const PROGRESS_EVENT: &str = "compression-progress";
+/// Used when the caller doesn't pick a level
+const DEFAULT_LEVEL: u32 = 6;
+
+#[derive(Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ArchiveFormat {
+    Zip,
+    #[serde(rename = "7z")]
+    SevenZip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => ".zip",
+            ArchiveFormat::SevenZip => ".7z",
+            ArchiveFormat::TarGz => ".tar.gz",
+        }
+    }
+}
+
+/// A file or folder to add, with its `/`-separated name inside the archive
+struct SourceEntry {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+    size: u64,
+}
+
+fn collect_dir(dir: &Path, name: &str, entries: &mut Vec<SourceEntry>) {
+    entries.push(SourceEntry {
+        path: dir.to_path_buf(),
+        name: name.to_string(),
+        is_dir: true,
+        size: 0,
+    });
+    let children = match fs::read_dir(dir) {
+        Ok(children) => children,
+        Err(e) => {
+            log::warn!("[COMPRESS] Skipping unreadable folder {:?}: {}", dir, e);
+            return;
+        }
+    };
+    for child in children.flatten() {
+        let Ok(metadata) = child.metadata() else {
+            continue;
+        };
+        let child_name = format!("{}/{}", name, child.file_name().to_string_lossy());
+        // Entry metadata doesn't follow links, so symlinks and junctions are left out,
+        // which also keeps a junction loop from recursing forever
+        if metadata.is_dir() {
+            collect_dir(&child.path(), &child_name, entries);
+        } else if metadata.is_file() {
+            entries.push(SourceEntry {
+                path: child.path(),
+                name: child_name,
+                is_dir: false,
+                size: metadata.len(),
+            });
+        }
+    }
+}
+
+/// Everything to add for `paths`, each under its own name at the top of the archive
+fn collect_entries(paths: &[String]) -> Result<Vec<SourceEntry>, String> {
+    let mut entries = Vec::new();
+    for path in paths {
+        let path = Path::new(path);
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Cannot archive {}", path.display()))?;
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if metadata.is_dir() {
+            collect_dir(path, &name, &mut entries);
+        } else {
+            entries.push(SourceEntry {
+                path: path.to_path_buf(),
+                name,
+                is_dir: false,
+                size: metadata.len(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// `target` with the format's extension, numbered when the name is taken
+fn unique_target(target: &str, format: ArchiveFormat) -> PathBuf {
+    let extension = format.extension();
+    let base = target
+        .strip_suffix(extension)
+        .or_else(|| {
+            // Case-insensitive strip, keeping the user's spelling of the stem
+            let split = target.len().checked_sub(extension.len())?;
+            let (stem, ext) = (target.get(..split)?, target.get(split..)?);
+            ext.eq_ignore_ascii_case(extension).then_some(stem)
+        })
+        .unwrap_or(target);
+    let candidate = PathBuf::from(format!("{}{}", base, extension));
+    if !candidate.exists() {
+        return candidate;
+    }
+    let preferences = crate::settings::preferences();
+    (2..)
+        .map(|n| {
+            PathBuf::from(format!(
+                "{}{}",
+                preferences.duplicate_name(base, n),
+                extension
+            ))
+        })
+        .find(|p| !p.exists())
+        .unwrap_or(candidate)
+}
+
+/// Byte counts of the files written so far, reported as they grow
+struct Progress<'a> {
+    window: &'a tauri::Window,
+    total_bytes: u64,
+    bytes_read: u64,
+    last_pct: u32,
+}
+
+impl<'a> Progress<'a> {
+    /// Opens `entry`'s file for reading, counting what is read towards the progress
+    fn open<'p>(&'p mut self, entry: &'p SourceEntry) -> Result<ProgressReader<'p, 'a>, String> {
+        let file = fs::File::open(&entry.path)
+            .map_err(|e| format!("Failed to open {}: {}", entry.path.display(), e))?;
+        Ok(ProgressReader {
+            file,
+            name: &entry.name,
+            progress: self,
+        })
+    }
+}
+
+struct ProgressReader<'p, 'a> {
+    file: fs::File,
+    name: &'p str,
+    progress: &'p mut Progress<'a>,
+}
+
+impl Read for ProgressReader<'_, '_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.file.read(buf)?;
+        let progress = &mut *self.progress;
+        progress.bytes_read += n as u64;
+        report_progress(
+            progress.window,
+            PROGRESS_EVENT,
+            &mut progress.last_pct,
+            progress.bytes_read,
+            progress.total_bytes,
+            self.name,
+        );
+        Ok(n)
+    }
+}
+
+fn write_zip(
+    target: &Path,
+    entries: &[SourceEntry],
+    level: u32,
+    progress: &mut Progress,
+) -> Result<(), String> {
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+
+    let file = fs::File::create(target).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = if level == 0 {
+        SimpleFileOptions::default().compression_method(CompressionMethod::Stored)
+    } else {
+        SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .compression_level(Some(level as i64))
+    };
+
+    for entry in entries {
+        if entry.is_dir {
+            zip.add_directory(entry.name.as_str(), options)
+                .map_err(|e| format!("Failed to add {}: {}", entry.name, e))?;
+            continue;
+        }
+        let options = options.large_file(entry.size >= u32::MAX as u64);
+        zip.start_file(entry.name.as_str(), options)
+            .map_err(|e| format!("Failed to add {}: {}", entry.name, e))?;
+        std::io::copy(&mut progress.open(entry)?, &mut zip)
+            .map_err(|e| format!("Failed to add {}: {}", entry.name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish ZIP archive: {}", e))?;
+    Ok(())
+}
+
+fn write_7z(
+    target: &Path,
+    entries: &[SourceEntry],
+    level: u32,
+    progress: &mut Progress,
+) -> Result<(), String> {
+    use sevenz_rust::{lzma::LZMA2Options, SevenZArchiveEntry, SevenZWriter};
+
+    let mut writer =
+        SevenZWriter::create(target).map_err(|e| format!("Failed to create archive: {}", e))?;
+    writer.set_content_methods(vec![LZMA2Options::with_preset(level).into()]);
+
+    for entry in entries {
+        let archive_entry = SevenZArchiveEntry::from_path(&entry.path, entry.name.clone());
+        if entry.is_dir {
+            writer
+                .push_archive_entry::<fs::File>(archive_entry, None)
+                .map_err(|e| format!("Failed to add {}: {}", entry.name, e))?;
+            continue;
+        }
+        writer
+            .push_archive_entry(archive_entry, Some(progress.open(entry)?))
+            .map_err(|e| format!("Failed to add {}: {}", entry.name, e))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finish 7Z archive: {}", e))?;
+    Ok(())
+}
+
+fn write_tar_gz(
+    target: &Path,
+    entries: &[SourceEntry],
+    level: u32,
+    progress: &mut Progress,
+) -> Result<(), String> {
+    let file = fs::File::create(target).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::new(level));
+    let mut tar = tar::Builder::new(encoder);
+
+    for entry in entries {
+        let metadata = fs::metadata(&entry.path)
+            .map_err(|e| format!("Failed to read {}: {}", entry.path.display(), e))?;
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata(&metadata);
+        if entry.is_dir {
+            header.set_size(0);
+            tar.append_data(&mut header, format!("{}/", entry.name), std::io::empty())
+                .map_err(|e| format!("Failed to add {}: {}", entry.name, e))?;
+            continue;
+        }
+        // A file that changes size while it's read would corrupt the archive; tar reads
+        // exactly `size` bytes and fails on a short file
+        header.set_size(entry.size);
+        let reader = progress.open(entry)?.take(entry.size);
+        tar.append_data(&mut header, &entry.name, reader)
+            .map_err(|e| format!("Failed to add {}: {}", entry.name, e))?;
+    }
+    tar.into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to finish TAR.GZ archive: {}", e))?;
+    Ok(())
+}
+
+/// Creates an archive of `paths` at `target` (the extension is added when missing, and
+/// a number when the name is taken). `level` runs from 0 (store) to 9 (smallest).
+/// Returns the path of the archive.
+#[tauri::command]
+pub async fn create_archive(
+    window: tauri::Window,
+    paths: Vec<String>,
+    target: String,
+    format: ArchiveFormat,
+    level: Option<u32>,
+) -> Result<String, String> {
+    if paths.is_empty() {
+        return Err("Nothing to archive".to_string());
+    }
+    let level = level.unwrap_or(DEFAULT_LEVEL).min(9);
+
+    tokio::task::spawn_blocking(move || {
+        let paths: Vec<String> = paths.iter().map(|p| crate::expand_env_vars(p)).collect();
+        let target = unique_target(&crate::expand_env_vars(&target), format);
+        let entries = collect_entries(&paths)?;
+        let mut progress = Progress {
+            window: &window,
+            total_bytes: entries.iter().map(|e| e.size).sum(),
+            bytes_read: 0,
+            last_pct: 0,
+        };
+        log::info!(
+            "[COMPRESS] Creating {} ({} entries, level {})",
+            target.display(),
+            entries.len(),
+            level
+        );
+
+        let result = match format {
+            ArchiveFormat::Zip => write_zip(&target, &entries, level, &mut progress),
+            ArchiveFormat::SevenZip => write_7z(&target, &entries, level, &mut progress),
+            ArchiveFormat::TarGz => write_tar_gz(&target, &entries, level, &mut progress),
+        };
+        finish_progress(&window);
+
+        if let Err(e) = result {
+            log::error!("[COMPRESS] {} failed: {}", target.display(), e);
+            let _ = fs::remove_file(&target);
+            return Err(e);
+        }
+        Ok(target.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_entries_and_target() {
+        let root = std::env::temp_dir().join("quick-explorer-compress-test");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("photos").join("2024")).unwrap();
+        fs::write(root.join("photos").join("2024").join("a.jpg"), b"abc").unwrap();
+        fs::write(root.join("notes.txt"), b"hello").unwrap();
+
+        let paths = [
+            root.join("photos").to_string_lossy().to_string(),
+            root.join("notes.txt").to_string_lossy().to_string(),
+        ];
+        let entries = collect_entries(&paths).unwrap();
+        let mut names: Vec<(&str, bool)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.is_dir))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                ("notes.txt", false),
+                ("photos", true),
+                ("photos/2024", true),
+                ("photos/2024/a.jpg", false),
+            ]
+        );
+        assert_eq!(entries.iter().map(|e| e.size).sum::<u64>(), 8);
+
+        let target = root.join("notes").to_string_lossy().to_string();
+        assert_eq!(
+            unique_target(&target, ArchiveFormat::TarGz),
+            root.join("notes.tar.gz")
+        );
+        fs::write(root.join("notes.zip"), b"").unwrap();
+        assert_eq!(
+            unique_target(&format!("{}.ZIP", target), ArchiveFormat::Zip),
+            root.join("notes (2).zip")
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,272 @@
+//! Conflicts Module
+//!
+//! Name collisions for paste and drop. `find_conflicts` lists the items whose
+//! name is already taken in the destination; the frontend asks what to do with
+//! each one and passes the answers back with the paste/drop, which then
+//! overwrites, skips or keeps both per item instead of letting the shell rename
+//! every collision.
+
+use crate::FileEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+use windows::Win32::UI::Shell::{
+    FILEOPERATION_FLAGS, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOCONFIRMMKDIR,
+    FOF_RENAMEONCOLLISION,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ConflictResolution {
+    Overwrite,
+    Skip,
+    /// Keep both, numbering the incoming item
+    Rename,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct FileConflict {
+    pub source: FileEntry,
+    /// The item already in the destination
+    pub existing: FileEntry,
+}
+
+/// Answers from the frontend, keyed by source path
+pub type Resolutions = HashMap<String, ConflictResolution>;
+
+/// What happens to one item of a paste/drop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    Transfer,
+    Overwrite,
+    /// Transfer under a numbered name
+    KeepBoth,
+    /// Copy into its own folder, named like Explorer's "- Copy"
+    Duplicate,
+    Skip,
+}
+
+fn decide(
+    resolution: Option<ConflictResolution>,
+    exists: bool,
+    onto_itself: bool,
+    is_move: bool,
+) -> Decision {
+    match (onto_itself, exists, resolution) {
+        // Moving an item into the folder it is in does nothing
+        (true, _, _) if is_move => Decision::Skip,
+        (true, _, _) => Decision::Duplicate,
+        (false, false, _) => Decision::Transfer,
+        (false, true, Some(ConflictResolution::Overwrite)) => Decision::Overwrite,
+        (false, true, Some(ConflictResolution::Skip)) => Decision::Skip,
+        (false, true, Some(ConflictResolution::Rename) | None) => Decision::KeepBoth,
+    }
+}
+
+/// One item to hand to IFileOperation, with the name to give it (None keeps its own)
+pub(crate) struct PlannedItem {
+    pub source: String,
+    pub new_name: Option<String>,
+}
+
+pub(crate) struct Plan {
+    pub items: Vec<PlannedItem>,
+    /// Without answers the shell renames every collision itself
+    rename_on_collision: bool,
+    overwrite: bool,
+}
+
+impl Plan {
+    /// Every item under its own name, leaving collisions to the shell
+    pub(crate) fn shell_renames(sources: &[String]) -> Self {
+        Plan {
+            items: sources
+                .iter()
+                .map(|s| PlannedItem {
+                    source: s.clone(),
+                    new_name: None,
+                })
+                .collect(),
+            rename_on_collision: true,
+            overwrite: false,
+        }
+    }
+
+    pub(crate) fn operation_flags(&self) -> FILEOPERATION_FLAGS {
+        let mut flags = FOF_ALLOWUNDO | FOF_NOCONFIRMMKDIR;
+        if self.rename_on_collision {
+            flags |= FOF_RENAMEONCOLLISION;
+        }
+        // Answers the shell's "Replace the file?" with yes; only asked-for items collide
+        if self.overwrite {
+            flags |= FOF_NOCONFIRMATION;
+        }
+        flags
+    }
+}
+
+/// Where `source` lands in `target`
+fn destination(source: &str, target: &str) -> Option<PathBuf> {
+    Some(Path::new(target).join(Path::new(source).file_name()?))
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    let normalize = |p: &Path| {
+        p.to_string_lossy()
+            .trim_end_matches(['\\', '/'])
+            .to_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Applies `resolutions` to a paste/drop of `sources` into `target`. Sources that
+/// share a name would land on each other; every one after the first is numbered,
+/// whatever its answer, so the batch never overwrites its own items
+pub(crate) fn plan(
+    sources: &[String],
+    target: &str,
+    is_move: bool,
+    resolutions: &Resolutions,
+) -> Plan {
+    let mut plan = Plan {
+        items: Vec::new(),
+        rename_on_collision: false,
+        overwrite: false,
+    };
+    // Lowercased names given out so far in this batch
+    let mut assigned = HashSet::new();
+    for source in sources {
+        let Some(dest) = destination(source, target) else {
+            continue;
+        };
+        let name = dest
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if assigned.contains(&name.to_lowercase()) {
+            let new_name = crate::new_file::unique_name_in(target, &name, &assigned);
+            assigned.insert(new_name.to_lowercase());
+            plan.items.push(PlannedItem {
+                source: source.clone(),
+                new_name: Some(new_name),
+            });
+            continue;
+        }
+        let onto_itself = same_path(Path::new(source), &dest);
+        let new_name = match decide(
+            resolutions.get(source).copied(),
+            dest.exists(),
+            onto_itself,
+            is_move,
+        ) {
+            Decision::Skip => continue,
+            Decision::Transfer => None,
+            Decision::Overwrite => {
+                plan.overwrite = true;
+                None
+            }
+            Decision::KeepBoth => Some(crate::new_file::unique_name_in(target, &name, &assigned)),
+            Decision::Duplicate => crate::get_next_available_path(target, &name)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string()),
+        };
+        assigned.insert(new_name.as_deref().unwrap_or(&name).to_lowercase());
+        plan.items.push(PlannedItem {
+            source: source.clone(),
+            new_name,
+        });
+    }
+    plan
+}
+
+/// Items of `paths` whose name is already taken in `target_path`. Items pasted into
+/// their own folder aren't conflicts: they are duplicated or left alone.
+#[tauri::command]
+pub async fn find_conflicts(
+    paths: Vec<String>,
+    target_path: String,
+) -> Result<Vec<FileConflict>, String> {
+    tokio::task::spawn_blocking(move || {
+        let target = crate::expand_env_vars(&target_path);
+        let conflicts = paths
+            .iter()
+            .filter_map(|source| {
+                let dest = destination(source, &target)?;
+                if same_path(Path::new(source), &dest) || !dest.exists() {
+                    return None;
+                }
+                Some(FileConflict {
+                    source: crate::get_file_entry(Path::new(source)).ok()?,
+                    existing: crate::get_file_entry(&dest).ok()?,
+                })
+            })
+            .collect::<Vec<_>>();
+        log::info!(
+            "[CONFLICTS] {} of {} items collide in {}",
+            conflicts.len(),
+            paths.len(),
+            target
+        );
+        Ok(conflicts)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide() {
+        assert_eq!(decide(None, false, false, false), Decision::Transfer);
+        assert_eq!(decide(None, true, false, true), Decision::KeepBoth);
+        assert_eq!(
+            decide(Some(ConflictResolution::Overwrite), true, false, false),
+            Decision::Overwrite
+        );
+        assert_eq!(
+            decide(Some(ConflictResolution::Skip), true, false, true),
+            Decision::Skip
+        );
+        // An answer for a name that has since been freed still transfers
+        assert_eq!(
+            decide(Some(ConflictResolution::Skip), false, false, false),
+            Decision::Transfer
+        );
+        assert_eq!(decide(None, true, true, false), Decision::Duplicate);
+        assert_eq!(decide(None, true, true, true), Decision::Skip);
+    }
+
+    #[test]
+    fn test_plan_same_names() {
+        let sources = [
+            "C:\\a\\x.txt".to_string(),
+            "C:\\b\\x.txt".to_string(),
+            "C:\\c\\X.TXT".to_string(),
+            "C:\\c\\y.txt".to_string(),
+        ];
+        let resolutions = Resolutions::from([(sources[1].clone(), ConflictResolution::Overwrite)]);
+        let plan = plan(&sources, "Q:\\no such folder", false, &resolutions);
+        let names: Vec<String> = plan
+            .items
+            .iter()
+            .zip(&sources)
+            .map(|(item, source)| {
+                item.new_name
+                    .clone()
+                    .unwrap_or_else(|| source.rsplit('\\').next().unwrap().to_string())
+                    .to_lowercase()
+            })
+            .collect();
+        assert_eq!(names.len(), 4);
+        assert_eq!(names[0], "x.txt");
+        assert_eq!(names[3], "y.txt");
+        assert!(names[1] != "x.txt" && names[2] != "x.txt" && names[1] != names[2]);
+        // Nothing in the batch collides on disk, so nothing may be overwritten
+        assert!(!plan.overwrite);
+    }
+}
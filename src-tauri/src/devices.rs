@@ -0,0 +1,242 @@
+//! Devices Module
+//!
+//! Removable drives coming and going. A hidden top-level window receives the
+//! WM_DEVICECHANGE broadcasts Windows sends when a volume arrives or leaves
+//! (message-only windows don't get broadcasts) and turns them into
+//! `drive-added` / `drive-removed` events.
+//!
+//! `eject_drive` is the tray's "Safely remove": the volume is locked, so no
+//! other handle can write to it, dismounted to flush it, and then ejected.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::Emitter;
+use ts_rs::TS;
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, GENERIC_READ, GENERIC_WRITE, HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, WPARAM,
+};
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+};
+use windows::Win32::System::Ioctl::{
+    FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME, IOCTL_STORAGE_EJECT_MEDIA,
+    IOCTL_STORAGE_MEDIA_REMOVAL, PREVENT_MEDIA_REMOVAL,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::IO::DeviceIoControl;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+    TranslateMessage, DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE, DBT_DEVTYP_VOLUME,
+    DEV_BROADCAST_HDR, DEV_BROADCAST_VOLUME, MSG, WINDOW_EX_STYLE, WM_DEVICECHANGE, WNDCLASSW,
+    WS_POPUP,
+};
+
+/// Another program may hold the volume for a moment (antivirus, indexer)
+const LOCK_ATTEMPTS: u32 = 10;
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct DriveChange {
+    /// Drive root, e.g. "E:\"
+    pub root: String,
+}
+
+/// `\\.\E:`, the volume device of drive "E", "E:" or "E:\"
+fn volume_device(letter: &str) -> Result<String, String> {
+    match letter.trim_end_matches('\\').as_bytes() {
+        [l] | [l, b':'] if l.is_ascii_alphabetic() => {
+            Ok(format!("\\\\.\\{}:", (*l as char).to_ascii_uppercase()))
+        }
+        _ => Err(format!("Invalid drive letter: {}", letter)),
+    }
+}
+
+/// Drive roots of the letters set in a DEV_BROADCAST_VOLUME unit mask
+fn unit_mask_roots(mask: u32) -> Vec<String> {
+    (0..26u8)
+        .filter(|i| mask & (1 << i) != 0)
+        .map(|i| format!("{}:\\", (b'A' + i) as char))
+        .collect()
+}
+
+/// Volume handle, closed when dropped
+struct Volume(HANDLE);
+
+impl Drop for Volume {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+impl Volume {
+    fn control(&self, code: u32, input: Option<&[u8]>) -> windows::core::Result<()> {
+        unsafe {
+            DeviceIoControl(
+                self.0,
+                code,
+                input.map(|i| i.as_ptr() as *const _),
+                input.map_or(0, |i| i.len() as u32),
+                None,
+                0,
+                None,
+                None,
+            )
+        }
+    }
+}
+
+fn eject(device: &str) -> Result<(), String> {
+    let handle = unsafe {
+        CreateFileW(
+            &HSTRING::from(device),
+            (GENERIC_READ | GENERIC_WRITE).0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            None,
+        )
+    }
+    .map_err(|e| format!("Failed to open {}: {}", device, e))?;
+    let volume = Volume(handle);
+
+    let mut locked = volume.control(FSCTL_LOCK_VOLUME, None);
+    for _ in 1..LOCK_ATTEMPTS {
+        if locked.is_ok() {
+            break;
+        }
+        std::thread::sleep(LOCK_RETRY_DELAY);
+        locked = volume.control(FSCTL_LOCK_VOLUME, None);
+    }
+    locked.map_err(|e| format!("The drive is in use by another program: {}", e))?;
+
+    volume
+        .control(FSCTL_DISMOUNT_VOLUME, None)
+        .map_err(|e| format!("Failed to dismount the drive: {}", e))?;
+    let allow_removal = PREVENT_MEDIA_REMOVAL {
+        PreventMediaRemoval: false,
+    };
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            &allow_removal as *const _ as *const u8,
+            std::mem::size_of::<PREVENT_MEDIA_REMOVAL>(),
+        )
+    };
+    volume
+        .control(IOCTL_STORAGE_MEDIA_REMOVAL, Some(bytes))
+        .map_err(|e| format!("The drive can't be removed: {}", e))?;
+    volume
+        .control(IOCTL_STORAGE_EJECT_MEDIA, None)
+        .map_err(|e| format!("Failed to eject the drive: {}", e))
+}
+
+/// Flushes and ejects removable drive `letter` so it can be unplugged safely
+#[tauri::command]
+pub async fn eject_drive(letter: String) -> Result<(), String> {
+    let device = volume_device(&letter)?;
+    // Cached listings keep a watcher, and so a handle, open on the drive
+    crate::listing::invalidate_cache();
+    tauri::async_runtime::spawn_blocking(move || {
+        eject(&device)?;
+        log::info!("[DEVICES] Ejected {}", device);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+unsafe extern "system" fn device_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_DEVICECHANGE {
+        let event = match wparam.0 as u32 {
+            DBT_DEVICEARRIVAL => Some("drive-added"),
+            DBT_DEVICEREMOVECOMPLETE => Some("drive-removed"),
+            _ => None,
+        };
+        let header = lparam.0 as *const DEV_BROADCAST_HDR;
+        if let Some(event) = event {
+            if !header.is_null() && (*header).dbch_devicetype == DBT_DEVTYP_VOLUME {
+                let volume = &*(lparam.0 as *const DEV_BROADCAST_VOLUME);
+                // Free space and labels of the old media no longer apply
+                crate::disk_monitor::clear_disk_info_cache();
+                for root in unit_mask_roots(volume.dbcv_unitmask) {
+                    log::info!("[DEVICES] {}: {}", event, root);
+                    if let Some(app) = crate::APP_HANDLE.get() {
+                        let _ = app.emit(event, DriveChange { root });
+                    }
+                }
+            }
+        }
+        return LRESULT(1);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// Starts the thread owning the device notification window; call once from setup
+pub fn start() {
+    std::thread::spawn(|| unsafe {
+        let instance = match GetModuleHandleW(None) {
+            Ok(instance) => HINSTANCE(instance.0),
+            Err(e) => {
+                log::error!("[DEVICES] GetModuleHandleW failed: {}", e);
+                return;
+            }
+        };
+        let class_name: PCWSTR = w!("QuickExplorerDeviceWatcher");
+        let wnd_class = WNDCLASSW {
+            lpfnWndProc: Some(device_wnd_proc),
+            hInstance: instance,
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        RegisterClassW(&wnd_class);
+        // Top-level but never shown: broadcasts only reach top-level windows
+        if let Err(e) = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            PCWSTR::null(),
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance),
+            None,
+        ) {
+            log::error!("[DEVICES] Failed to create the device window: {}", e);
+            return;
+        }
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drive_letters() {
+        assert_eq!(volume_device("e").as_deref(), Ok("\\\\.\\E:"));
+        assert_eq!(volume_device("E:\\").as_deref(), Ok("\\\\.\\E:"));
+        assert!(volume_device("\\\\NAS").is_err());
+        assert_eq!(
+            unit_mask_roots(0b10010),
+            vec!["B:\\".to_string(), "E:\\".to_string()]
+        );
+    }
+}
@@ -0,0 +1,502 @@
+//! Disk Monitor Module
+//!
+//! Polls free space once a minute and emits `low-disk-space` when a volume
+//! drops below the configured threshold, so the UI can warn before copies
+//! start failing. Watched volumes are every fixed drive plus the drives of
+//! the paths the open tabs report through `set_monitored_paths` (network
+//! shares and removable media included). The event fires once per crossing:
+//! a volume has to recover above the threshold before it can warn again.
+//!
+//! It also keeps the `DiskInfo` shown for drives: `disk_info` answers from a
+//! per-volume cache for a few seconds before asking Windows again, and the
+//! poll pushes `disk-info-changed` when a volume's free space moves enough to
+//! show in the sidebar. Besides space it carries what the drives view labels
+//! a drive with: volume label, file system, kind of device and BitLocker state.
+
+use crate::settings::SettingsStore;
+use crate::DiskInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use ts_rs::TS;
+use windows::core::{w, HSTRING};
+use windows::Win32::Foundation::{MAX_PATH, PROPERTYKEY};
+use windows::Win32::Storage::FileSystem::{
+    GetDiskFreeSpaceExW, GetDriveTypeW, GetLogicalDrives, GetVolumeInformationW, GetVolumePathNameW,
+};
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+use windows::Win32::UI::Shell::PropertiesSystem::PSGetPropertyKeyFromName;
+use windows::Win32::UI::Shell::{IShellItem2, SHCreateItemFromParsingName};
+
+const SETTINGS_KEY: &str = "low_disk_space";
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// GetDriveTypeW results
+const DRIVE_REMOVABLE: u32 = 2;
+const DRIVE_FIXED: u32 = 3;
+const DRIVE_REMOTE: u32 = 4;
+const DRIVE_CDROM: u32 = 5;
+const DRIVE_RAMDISK: u32 = 6;
+/// `System.Volume.BitLockerProtection` values: 2 is off, 6 is locked and any
+/// other non-zero value is on (encrypting, decrypting, suspended, unlocked)
+const BITLOCKER_OFF: i32 = 2;
+const BITLOCKER_LOCKED: i32 = 6;
+/// How long a volume's `DiskInfo` is reused before Windows is asked again
+const DISK_INFO_TTL: Duration = Duration::from_secs(10);
+/// Free space must move by this share of the volume before `disk-info-changed` fires
+const MATERIAL_CHANGE_PERCENT: f64 = 0.5;
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug, PartialEq)]
+#[serde(default)]
+#[ts(export)]
+pub struct LowDiskSettings {
+    pub enabled: bool,
+    /// Warn when free space falls below this many bytes...
+    #[ts(type = "number")]
+    pub min_free_bytes: u64,
+    /// ...or below this percentage of the volume
+    pub min_free_percent: f64,
+}
+
+impl Default for LowDiskSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_free_bytes: 1024 * 1024 * 1024,
+            min_free_percent: 5.0,
+        }
+    }
+}
+
+impl LowDiskSettings {
+    fn is_low(&self, free: u64, total: u64) -> bool {
+        if total == 0 {
+            return false;
+        }
+        free < self.min_free_bytes || (free as f64 / total as f64) * 100.0 < self.min_free_percent
+    }
+}
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum DriveType {
+    Unknown,
+    Removable,
+    Fixed,
+    Network,
+    Cdrom,
+    Ramdisk,
+}
+
+impl DriveType {
+    fn of(root: &str) -> Self {
+        match unsafe { GetDriveTypeW(&HSTRING::from(root)) } {
+            DRIVE_REMOVABLE => Self::Removable,
+            DRIVE_FIXED => Self::Fixed,
+            DRIVE_REMOTE => Self::Network,
+            DRIVE_CDROM => Self::Cdrom,
+            DRIVE_RAMDISK => Self::Ramdisk,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Name Explorer gives a drive of this kind that has no label
+    pub fn default_label(self) -> &'static str {
+        use crate::i18n::{tr, Text};
+        match self {
+            Self::Removable => tr(Text::RemovableDisk),
+            Self::Network => tr(Text::NetworkDrive),
+            Self::Cdrom => tr(Text::CdDrive),
+            _ => tr(Text::LocalDisk),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum BitLockerState {
+    Off,
+    /// Encrypted and readable
+    Unlocked,
+    /// Encrypted and waiting for its password or recovery key
+    Locked,
+}
+
+impl BitLockerState {
+    fn from_protection(value: i32) -> Option<Self> {
+        match value {
+            0 => None,
+            BITLOCKER_OFF => Some(Self::Off),
+            BITLOCKER_LOCKED => Some(Self::Locked),
+            _ => Some(Self::Unlocked),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct LowDiskSpace {
+    /// Volume root, e.g. "C:\" or "\\server\share\"
+    pub volume: String,
+    #[ts(type = "number")]
+    pub free_bytes: u64,
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+    pub free_percent: f64,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct DiskInfoChanged {
+    /// Volume root, e.g. "C:\"
+    pub volume: String,
+    pub disk_info: DiskInfo,
+}
+
+struct CachedDiskInfo {
+    info: DiskInfo,
+    fetched_at: Instant,
+    /// Free space as last shown to the UI
+    reported_free: u64,
+}
+
+static DISK_INFO: OnceLock<Mutex<HashMap<String, CachedDiskInfo>>> = OnceLock::new();
+
+fn disk_info_cache() -> &'static Mutex<HashMap<String, CachedDiskInfo>> {
+    DISK_INFO.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+struct MonitorState {
+    /// Volume roots of the paths open in tabs
+    tab_volumes: HashSet<String>,
+    /// Volumes already reported as low, so each crossing warns once
+    warned: HashSet<String>,
+}
+
+static STATE: OnceLock<Mutex<MonitorState>> = OnceLock::new();
+
+fn state() -> &'static Mutex<MonitorState> {
+    STATE.get_or_init(|| {
+        Mutex::new(MonitorState {
+            tab_volumes: HashSet::new(),
+            warned: HashSet::new(),
+        })
+    })
+}
+
+fn settings() -> LowDiskSettings {
+    SettingsStore::global()
+        .get::<LowDiskSettings>(SETTINGS_KEY)
+        .unwrap_or_default()
+}
+
+fn volume_root(path: &str) -> Option<String> {
+    let mut buffer = [0u16; 1024];
+    unsafe { GetVolumePathNameW(&HSTRING::from(path), &mut buffer).ok()? };
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+fn fixed_drives() -> Vec<String> {
+    let mask = unsafe { GetLogicalDrives() };
+    (0..26u8)
+        .filter(|i| mask & (1 << i) != 0)
+        .map(|i| format!("{}:\\", (b'A' + i) as char))
+        .filter(|root| unsafe { GetDriveTypeW(&HSTRING::from(root.as_str())) } == DRIVE_FIXED)
+        .collect()
+}
+
+/// (free, total) bytes available to the current user
+fn free_space(root: &str) -> Option<(u64, u64)> {
+    let mut free = 0u64;
+    let mut total = 0u64;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            &HSTRING::from(root),
+            Some(&mut free),
+            Some(&mut total),
+            None,
+        )
+        .ok()?;
+    }
+    Some((free, total))
+}
+
+/// (label, file system name) of the volume at `root`
+fn volume_information(root: &str) -> Option<(String, String)> {
+    let mut label = [0u16; MAX_PATH as usize + 1];
+    let mut file_system = [0u16; MAX_PATH as usize + 1];
+    unsafe {
+        GetVolumeInformationW(
+            &HSTRING::from(root),
+            Some(&mut label),
+            None,
+            None,
+            None,
+            Some(&mut file_system),
+        )
+        .ok()?;
+    }
+    let text = |buffer: &[u16]| {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        String::from_utf16_lossy(&buffer[..len])
+    };
+    Some((text(&label), text(&file_system)))
+}
+
+/// BitLocker state of the volume at `root`, read through the shell property
+/// Explorer uses for its padlock icons; it works without administrator rights
+fn bitlocker_state(root: &str) -> Option<BitLockerState> {
+    unsafe {
+        // Callers may be on a thread without COM; only balance what we initialized
+        let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        let protection = (|| {
+            let mut key = PROPERTYKEY::default();
+            PSGetPropertyKeyFromName(w!("System.Volume.BitLockerProtection"), &mut key).ok()?;
+            let item: IShellItem2 = SHCreateItemFromParsingName(&HSTRING::from(root), None).ok()?;
+            item.GetInt32(&key).ok()
+        })();
+        if com_initialized {
+            CoUninitialize();
+        }
+        BitLockerState::from_protection(protection?)
+    }
+}
+
+fn query_disk_info(root: &str, is_ssd: Option<bool>) -> Option<DiskInfo> {
+    let drive_type = DriveType::of(root);
+    // Only local disks can be BitLocker volumes
+    let bitlocker = match drive_type {
+        DriveType::Fixed | DriveType::Removable => bitlocker_state(root),
+        _ => None,
+    };
+    let mut total = 0u64;
+    let mut total_free = 0u64;
+    let readable = unsafe {
+        GetDiskFreeSpaceExW(
+            &HSTRING::from(root),
+            None,
+            Some(&mut total),
+            Some(&mut total_free),
+        )
+        .is_ok()
+    };
+    // A locked volume can't be read, but it is still a drive to show (and unlock)
+    if !readable && bitlocker != Some(BitLockerState::Locked) {
+        return None;
+    }
+    let (label, file_system) = volume_information(root).unwrap_or_default();
+    let system_drive = std::env::var("SystemDrive")
+        .unwrap_or_else(|_| "C:".to_string())
+        .to_uppercase();
+    Some(DiskInfo {
+        total,
+        used: total - total_free,
+        free: total_free,
+        is_system: root.to_uppercase().starts_with(&system_drive),
+        // The drive's media doesn't change, so it is only probed once
+        is_ssd: is_ssd.unwrap_or_else(|| crate::is_ssd(root)),
+        label,
+        file_system,
+        drive_type,
+        bitlocker,
+    })
+}
+
+fn is_material_change(old_free: u64, new_free: u64, total: u64) -> bool {
+    total > 0
+        && old_free.abs_diff(new_free) as f64 / total as f64 * 100.0 >= MATERIAL_CHANGE_PERCENT
+}
+
+/// Size and free space of the volume at `root` (e.g. "C:\"), cached for a few seconds
+pub fn disk_info(root: &str) -> Option<DiskInfo> {
+    let key = root.to_uppercase();
+    let is_ssd = match disk_info_cache().lock() {
+        Ok(cache) => match cache.get(&key) {
+            Some(cached) if cached.fetched_at.elapsed() < DISK_INFO_TTL => {
+                return Some(cached.info.clone())
+            }
+            Some(cached) => Some(cached.info.is_ssd),
+            None => None,
+        },
+        Err(_) => None,
+    };
+
+    let info = query_disk_info(root, is_ssd)?;
+    let mut changed = None;
+    if let Ok(mut cache) = disk_info_cache().lock() {
+        let reported_free = match cache.get(&key) {
+            Some(old) if is_material_change(old.reported_free, info.free, info.total) => {
+                changed = Some(info.clone());
+                info.free
+            }
+            Some(old) => old.reported_free,
+            None => info.free,
+        };
+        cache.insert(
+            key,
+            CachedDiskInfo {
+                info: info.clone(),
+                fetched_at: Instant::now(),
+                reported_free,
+            },
+        );
+    }
+    if let (Some(disk_info), Some(app)) = (changed, crate::APP_HANDLE.get()) {
+        let _ = app.emit(
+            "disk-info-changed",
+            DiskInfoChanged {
+                volume: root.to_string(),
+                disk_info,
+            },
+        );
+    }
+    Some(info)
+}
+
+/// Forgets all cached `DiskInfo`; the next lookups ask Windows again
+pub fn clear_disk_info_cache() {
+    if let Ok(mut cache) = disk_info_cache().lock() {
+        cache.clear();
+    }
+}
+
+fn watched_volumes() -> HashSet<String> {
+    let mut volumes: HashSet<String> = fixed_drives().into_iter().collect();
+    if let Ok(state) = state().lock() {
+        volumes.extend(state.tab_volumes.iter().cloned());
+    }
+    volumes
+}
+
+fn check_volumes() {
+    let volumes = watched_volumes();
+    // Refreshes the cached info and pushes changes the UI should show
+    for volume in &volumes {
+        disk_info(volume);
+    }
+
+    let settings = settings();
+    if !settings.enabled {
+        return;
+    }
+    for volume in volumes {
+        let Some((free, total)) = free_space(&volume) else {
+            continue;
+        };
+        let low = settings.is_low(free, total);
+        let newly_low = match state().lock() {
+            Ok(mut state) if low => state.warned.insert(volume.clone()),
+            Ok(mut state) => {
+                state.warned.remove(&volume);
+                false
+            }
+            Err(_) => false,
+        };
+        if !newly_low {
+            continue;
+        }
+
+        let free_percent = free as f64 / total as f64 * 100.0;
+        log::warn!(
+            "[DISK-MONITOR] {} is low on space: {} bytes free ({:.1}%)",
+            volume,
+            free,
+            free_percent
+        );
+        if let Some(app) = crate::APP_HANDLE.get() {
+            let _ = app.emit(
+                "low-disk-space",
+                LowDiskSpace {
+                    volume,
+                    free_bytes: free,
+                    total_bytes: total,
+                    free_percent,
+                },
+            );
+        }
+    }
+}
+
+/// Starts the polling thread; call once from setup
+pub fn start() {
+    std::thread::spawn(|| loop {
+        check_volumes();
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+/// The folders currently open in tabs; their volumes are watched alongside the fixed drives
+#[tauri::command]
+pub fn set_monitored_paths(paths: Vec<String>) {
+    let volumes: HashSet<String> = paths
+        .iter()
+        .filter(|p| !p.starts_with("shell:"))
+        .filter_map(|p| volume_root(&crate::expand_env_vars(p)))
+        .collect();
+    if let Ok(mut state) = state().lock() {
+        state.tab_volumes = volumes;
+    }
+}
+
+#[tauri::command]
+pub fn get_low_disk_settings() -> LowDiskSettings {
+    settings()
+}
+
+#[tauri::command]
+pub fn set_low_disk_settings(settings: LowDiskSettings) -> Result<(), String> {
+    SettingsStore::global().set(SETTINGS_KEY, &settings)?;
+    // Re-evaluate against the new threshold on the next poll
+    if let Ok(mut state) = state().lock() {
+        state.warned.clear();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_material_change() {
+        let gib = 1024 * 1024 * 1024;
+        // 1 GB of 1 TB is 0.1%: not worth a refresh
+        assert!(!is_material_change(500 * gib, 499 * gib, 1000 * gib));
+        assert!(is_material_change(500 * gib, 494 * gib, 1000 * gib));
+        assert!(!is_material_change(0, 10, 0));
+    }
+
+    #[test]
+    fn test_bitlocker_protection() {
+        assert_eq!(BitLockerState::from_protection(0), None);
+        assert_eq!(
+            BitLockerState::from_protection(1),
+            Some(BitLockerState::Unlocked)
+        );
+        assert_eq!(
+            BitLockerState::from_protection(2),
+            Some(BitLockerState::Off)
+        );
+        assert_eq!(
+            BitLockerState::from_protection(6),
+            Some(BitLockerState::Locked)
+        );
+    }
+
+    #[test]
+    fn test_threshold() {
+        let gib = 1024 * 1024 * 1024;
+        let settings = LowDiskSettings::default();
+        // 500 MB free: below the byte floor
+        assert!(settings.is_low(gib / 2, 100 * gib));
+        // 3 GB of 100 GB: above 1 GB but under 5%
+        assert!(settings.is_low(3 * gib, 100 * gib));
+        // 60 GB of 1 TB: plenty
+        assert!(!settings.is_low(60 * gib, 1024 * gib));
+        assert!(!settings.is_low(0, 0));
+    }
+}
@@ -0,0 +1,430 @@
+//! Disk Usage Module
+//!
+//! Backs the treemap / sunburst view. `analyze_disk_usage` walks a folder or
+//! a whole drive in parallel (jwalk, with file sizes read on the walker
+//! threads) and aggregates sizes per directory as it goes. While the walk
+//! runs, `disk-usage-progress` events carry running totals plus a shallow
+//! snapshot of the tree; `disk-usage-finished` carries the final tree, cut
+//! to a depth and per-folder child limit the frontend can lay out.
+//!
+//! Whole NTFS drives are read from the master file table instead when the app
+//! runs elevated (see `mft`), which takes seconds rather than minutes.
+//!
+//! Symbolic links and junctions are not followed, so nothing is counted twice.
+
+use jwalk::WalkDirGeneric;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tauri::Emitter;
+use ts_rs::TS;
+
+/// Levels below the root included in the final tree
+const MAX_DEPTH: usize = 8;
+/// Most children listed per folder; the rest is summed into `other_size`
+const MAX_CHILDREN: usize = 100;
+/// Minimum time between two `disk-usage-progress` events of one job
+const PROGRESS_INTERVAL_MS: u128 = 250;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct DiskUsageNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    /// Total bytes, including everything beneath a folder
+    #[ts(type = "number")]
+    pub size: u64,
+    /// Files beneath a folder (1 for a file)
+    #[ts(type = "number")]
+    pub file_count: u64,
+    /// Largest first
+    pub children: Vec<DiskUsageNode>,
+    /// Bytes of the children left out of `children`
+    #[ts(type = "number")]
+    pub other_size: u64,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct DiskUsageProgress {
+    pub job_id: String,
+    #[ts(type = "number")]
+    pub files_scanned: u64,
+    #[ts(type = "number")]
+    pub bytes_scanned: u64,
+    pub current_path: String,
+    /// The root and its direct children as counted so far
+    pub tree: DiskUsageNode,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct DiskUsageFinished {
+    pub job_id: String,
+    pub cancelled: bool,
+    /// None when the job was cancelled
+    pub tree: Option<DiskUsageNode>,
+    /// Folders that could not be read (access denied, vanished mid-walk)
+    #[ts(type = "number")]
+    pub errors: u64,
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+}
+
+struct DirNode {
+    name: String,
+    parent: Option<usize>,
+    size: u64,
+    file_count: u64,
+    children: Vec<usize>,
+    /// The folder's own largest files, at most MAX_CHILDREN, largest first
+    largest_files: Vec<(String, u64)>,
+}
+
+/// Folder tree with sizes rolled up into every ancestor as files are added,
+/// so a snapshot can be taken at any point of the walk
+pub(crate) struct UsageTree {
+    root: PathBuf,
+    dirs: Vec<DirNode>,
+}
+
+impl UsageTree {
+    /// Index of the root folder
+    pub(crate) const ROOT: usize = 0;
+
+    pub(crate) fn new(root: &Path) -> Self {
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| root.to_string_lossy().to_string());
+        Self {
+            root: root.to_path_buf(),
+            dirs: vec![DirNode {
+                name,
+                parent: None,
+                size: 0,
+                file_count: 0,
+                children: Vec::new(),
+                largest_files: Vec::new(),
+            }],
+        }
+    }
+
+    pub(crate) fn add_dir(&mut self, parent: usize, name: String) -> usize {
+        let index = self.dirs.len();
+        self.dirs.push(DirNode {
+            name,
+            parent: Some(parent),
+            size: 0,
+            file_count: 0,
+            children: Vec::new(),
+            largest_files: Vec::new(),
+        });
+        self.dirs[parent].children.push(index);
+        index
+    }
+
+    pub(crate) fn add_file(&mut self, parent: usize, name: &str, size: u64) {
+        let files = &mut self.dirs[parent].largest_files;
+        if files.len() < MAX_CHILDREN || files.last().is_some_and(|(_, s)| size > *s) {
+            let at = files.partition_point(|(_, s)| *s >= size);
+            files.insert(at, (name.to_string(), size));
+            files.truncate(MAX_CHILDREN);
+        }
+
+        let mut current = Some(parent);
+        while let Some(index) = current {
+            let dir = &mut self.dirs[index];
+            dir.size += size;
+            dir.file_count += 1;
+            current = dir.parent;
+        }
+    }
+
+    pub(crate) fn total_size(&self) -> u64 {
+        self.dirs[Self::ROOT].size
+    }
+
+    /// The tree down to `depth` levels below the root, listing the `limit`
+    /// largest children of each folder
+    pub(crate) fn snapshot(&self, depth: usize, limit: usize) -> DiskUsageNode {
+        self.node(Self::ROOT, &self.root, depth, limit.min(MAX_CHILDREN))
+    }
+
+    fn node(&self, index: usize, path: &Path, depth: usize, limit: usize) -> DiskUsageNode {
+        let dir = &self.dirs[index];
+        let mut children = Vec::new();
+        if depth > 0 {
+            children.extend(dir.children.iter().map(|&child| {
+                let name = &self.dirs[child].name;
+                self.node(child, &path.join(name), depth - 1, limit)
+            }));
+            children.extend(dir.largest_files.iter().map(|(name, size)| DiskUsageNode {
+                name: name.clone(),
+                path: path.join(name).to_string_lossy().to_string(),
+                is_dir: false,
+                size: *size,
+                file_count: 1,
+                children: Vec::new(),
+                other_size: 0,
+            }));
+            children.sort_by_key(|c| std::cmp::Reverse(c.size));
+            children.truncate(limit);
+        }
+        let listed: u64 = children.iter().map(|c| c.size).sum();
+        DiskUsageNode {
+            name: dir.name.clone(),
+            path: path.to_string_lossy().to_string(),
+            is_dir: true,
+            size: dir.size,
+            file_count: dir.file_count,
+            children,
+            other_size: dir.size.saturating_sub(listed),
+        }
+    }
+}
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+static ACTIVE_JOBS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn active_jobs() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    ACTIVE_JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Fills the tree from a drive's file table; nothing in it is unreadable
+fn scan_volume(
+    root: &Path,
+    volume: &crate::mft::MftVolume,
+    mut on_progress: impl FnMut(&UsageTree, u64, &Path),
+) -> (UsageTree, u64) {
+    let mut tree = UsageTree::new(root);
+    let mut files_scanned = 0u64;
+    volume.walk(UsageTree::ROOT, |&parent, file| {
+        if file.is_dir {
+            return Some(tree.add_dir(parent, file.name.to_string()));
+        }
+        tree.add_file(parent, &file.name, file.size);
+        files_scanned += 1;
+        if files_scanned.is_multiple_of(4096) {
+            on_progress(&tree, files_scanned, root);
+        }
+        None
+    });
+    (tree, 0)
+}
+
+/// Walks `root` and returns the filled tree and the number of unreadable folders,
+/// or None when cancelled
+fn scan(
+    root: &Path,
+    cancelled: &Arc<AtomicBool>,
+    mut on_progress: impl FnMut(&UsageTree, u64, &Path),
+) -> Option<(UsageTree, u64)> {
+    if crate::mft::is_volume_root(root) {
+        match crate::mft::read_volume(root, &|| cancelled.load(Ordering::Relaxed)) {
+            Ok(volume) if !cancelled.load(Ordering::Relaxed) => {
+                return Some(scan_volume(root, &volume, on_progress));
+            }
+            Ok(_) => return None,
+            Err(e) => log::debug!(
+                "[DISK-USAGE] Walking {} (file table unavailable: {})",
+                root.display(),
+                e
+            ),
+        }
+    }
+
+    let read_dir_cancelled = Arc::clone(cancelled);
+    // Each entry carries its file size, read on the walker threads rather than here
+    let walker = WalkDirGeneric::<((), u64)>::new(root)
+        .skip_hidden(false)
+        .follow_links(false)
+        .process_read_dir(move |_, _, _, children| {
+            if read_dir_cancelled.load(Ordering::Relaxed) {
+                children.clear();
+                return;
+            }
+            for child in children.iter_mut().flatten() {
+                if child.file_type().is_file() {
+                    child.client_state = child.metadata().map(|m| m.len()).unwrap_or(0);
+                }
+            }
+        });
+
+    let mut tree = UsageTree::new(root);
+    let mut dirs: HashMap<PathBuf, usize> = HashMap::new();
+    dirs.insert(root.to_path_buf(), UsageTree::ROOT);
+    let mut files_scanned = 0u64;
+    let mut errors = 0u64;
+
+    for entry in walker {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let entry = match entry {
+            Ok(e) => e,
+            Err(_) => {
+                errors += 1;
+                continue;
+            }
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            continue;
+        }
+        let Some(&parent) = dirs.get(entry.parent_path()) else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+        if file_type.is_dir() {
+            if entry.read_children_error.is_some() {
+                errors += 1;
+            }
+            let index = tree.add_dir(parent, name);
+            dirs.insert(entry.path(), index);
+        } else {
+            tree.add_file(parent, &name, entry.client_state);
+            files_scanned += 1;
+            if files_scanned.is_multiple_of(256) {
+                on_progress(&tree, files_scanned, entry.parent_path());
+            }
+        }
+    }
+
+    if cancelled.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some((tree, errors))
+}
+
+/// Starts a background scan of `root` and returns the job id immediately.
+/// Progress arrives as `disk-usage-progress` events and the result as `disk-usage-finished`,
+/// whose tree lists the `top` largest children of each folder (all up to MAX_CHILDREN
+/// when omitted).
+#[tauri::command]
+pub fn analyze_disk_usage(
+    window: tauri::Window,
+    root: String,
+    top: Option<usize>,
+) -> Result<String, String> {
+    let limit = top.unwrap_or(MAX_CHILDREN);
+    let root_path = PathBuf::from(&root);
+    if !root_path.is_dir() {
+        return Err(format!("Not a folder: {}", root));
+    }
+
+    let job_id = format!("usage-{}", NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    if let Ok(mut jobs) = active_jobs().lock() {
+        jobs.insert(job_id.clone(), Arc::clone(&cancelled));
+    }
+    log::info!("[DISK-USAGE] {} started for {}", job_id, root);
+
+    let id = job_id.clone();
+    std::thread::spawn(move || {
+        let start = Instant::now();
+        let mut last_emit = Instant::now();
+        let result = scan(&root_path, &cancelled, |tree, files_scanned, current| {
+            if last_emit.elapsed().as_millis() < PROGRESS_INTERVAL_MS {
+                return;
+            }
+            last_emit = Instant::now();
+            let _ = window.emit(
+                "disk-usage-progress",
+                DiskUsageProgress {
+                    job_id: id.clone(),
+                    files_scanned,
+                    bytes_scanned: tree.total_size(),
+                    current_path: current.to_string_lossy().to_string(),
+                    tree: tree.snapshot(1, limit),
+                },
+            );
+        });
+
+        if let Ok(mut jobs) = active_jobs().lock() {
+            jobs.remove(&id);
+        }
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        let finished = match result {
+            Some((tree, errors)) => {
+                log::info!(
+                    "[DISK-USAGE] {} finished: {} bytes in {}ms ({} unreadable folders)",
+                    id,
+                    tree.total_size(),
+                    elapsed_ms,
+                    errors
+                );
+                DiskUsageFinished {
+                    job_id: id,
+                    cancelled: false,
+                    tree: Some(tree.snapshot(MAX_DEPTH, limit)),
+                    errors,
+                    elapsed_ms,
+                }
+            }
+            None => {
+                log::info!("[DISK-USAGE] {} cancelled", id);
+                DiskUsageFinished {
+                    job_id: id,
+                    cancelled: true,
+                    tree: None,
+                    errors: 0,
+                    elapsed_ms,
+                }
+            }
+        };
+        let _ = window.emit("disk-usage-finished", finished);
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub fn cancel_disk_usage(job_id: String) {
+    if let Ok(jobs) = active_jobs().lock() {
+        if let Some(flag) = jobs.get(&job_id) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_roll_up_and_children_are_ranked() {
+        let mut tree = UsageTree::new(Path::new("C:\\data"));
+        let photos = tree.add_dir(UsageTree::ROOT, "photos".to_string());
+        let raw = tree.add_dir(photos, "raw".to_string());
+        tree.add_file(UsageTree::ROOT, "notes.txt", 10);
+        tree.add_file(photos, "a.jpg", 300);
+        tree.add_file(raw, "b.cr2", 5_000);
+
+        assert_eq!(tree.total_size(), 5_310);
+        let root = tree.snapshot(MAX_DEPTH, MAX_CHILDREN);
+        assert_eq!(root.file_count, 3);
+        assert_eq!(root.children[0].name, "photos");
+        assert_eq!(root.children[0].size, 5_300);
+        assert_eq!(root.children[0].children[0].name, "raw");
+        assert_eq!(root.children[1].name, "notes.txt");
+        assert_eq!(root.other_size, 0);
+
+        // A shallow snapshot reports what it leaves out
+        let shallow = tree.snapshot(0, MAX_CHILDREN);
+        assert!(shallow.children.is_empty());
+        assert_eq!(shallow.other_size, 5_310);
+
+        // So does one cut to the largest child
+        let top = tree.snapshot(MAX_DEPTH, 1);
+        assert_eq!(top.children.len(), 1);
+        assert_eq!(top.other_size, 10);
+    }
+}
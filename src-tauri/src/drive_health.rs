@@ -0,0 +1,446 @@
+//! Drive Health Module
+//!
+//! S.M.A.R.T. summary for the drives view, read straight from the storage
+//! stack with DeviceIoControl (no WMI):
+//! - model and bus from `StorageDeviceProperty`
+//! - temperature from `StorageDeviceTemperatureProperty` (Windows 10+)
+//! - NVMe drives: the SMART / Health Information log page
+//! - ATA drives: `IOCTL_STORAGE_PREDICT_FAILURE`, whose vendor block is the
+//!   raw SMART attribute table
+//!
+//! Every reading is optional; USB bridges and RAID controllers often hide
+//! some or all of them, in which case the status is `Unknown`.
+
+use serde::Serialize;
+use ts_rs::TS;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Storage::FileSystem::{
+    BusTypeNvme, CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_READ, FILE_SHARE_WRITE,
+    OPEN_EXISTING,
+};
+use windows::Win32::System::Ioctl::{
+    NVMeDataTypeLogPage, ProtocolTypeNvme, StorageDeviceProperty,
+    StorageDeviceProtocolSpecificProperty, StorageDeviceTemperatureProperty,
+    IOCTL_STORAGE_GET_DEVICE_NUMBER, IOCTL_STORAGE_PREDICT_FAILURE, IOCTL_STORAGE_QUERY_PROPERTY,
+    STORAGE_DEVICE_DESCRIPTOR, STORAGE_DEVICE_NUMBER, STORAGE_PREDICT_FAILURE, STORAGE_PROPERTY_ID,
+    STORAGE_PROPERTY_QUERY, STORAGE_PROTOCOL_DATA_DESCRIPTOR, STORAGE_PROTOCOL_SPECIFIC_DATA,
+    STORAGE_QUERY_TYPE, STORAGE_TEMPERATURE_DATA_DESCRIPTOR,
+};
+use windows::Win32::System::IO::DeviceIoControl;
+
+/// NVMe "SMART / Health Information" log identifier
+const NVME_LOG_PAGE_HEALTH_INFO: u32 = 2;
+const NVME_HEALTH_LOG_SIZE: usize = 512;
+
+/// ATA attribute ids
+const ATTR_REALLOCATED_SECTORS: u8 = 5;
+const ATTR_POWER_ON_HOURS: u8 = 9;
+const ATTR_AIRFLOW_TEMPERATURE: u8 = 190;
+const ATTR_TEMPERATURE: u8 = 194;
+const ATTR_PENDING_SECTORS: u8 = 197;
+const ATTR_UNCORRECTABLE_SECTORS: u8 = 198;
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+#[ts(export)]
+pub enum HealthStatus {
+    Healthy,
+    Warning,
+    Failing,
+    Unknown,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct DriveHealth {
+    pub letter: String,
+    pub model: Option<String>,
+    pub serial_number: Option<String>,
+    pub is_nvme: bool,
+    pub temperature_celsius: Option<i32>,
+    #[ts(type = "number | null")]
+    pub power_on_hours: Option<u64>,
+    /// NVMe wear estimate; may exceed 100 on drives past their rated endurance
+    pub percentage_used: Option<u8>,
+    pub status: HealthStatus,
+    /// Why the status is not `Healthy`
+    pub warnings: Vec<String>,
+}
+
+/// Readings from the ATA SMART attribute table
+#[derive(Debug, Default, PartialEq, Eq)]
+struct AtaSmart {
+    power_on_hours: Option<u64>,
+    temperature: Option<i32>,
+    reallocated_sectors: u64,
+    pending_sectors: u64,
+    uncorrectable_sectors: u64,
+}
+
+/// Readings from the NVMe health log page
+#[derive(Debug, Default, PartialEq, Eq)]
+struct NvmeHealth {
+    critical_warning: u8,
+    temperature: Option<i32>,
+    available_spare: u8,
+    spare_threshold: u8,
+    percentage_used: u8,
+    power_on_hours: u64,
+    media_errors: u64,
+}
+
+/// Parses the 512-byte SMART data block: a 2-byte revision followed by 30
+/// 12-byte attribute entries (id, flags, value, worst, 6-byte raw, reserved)
+fn parse_ata_smart(data: &[u8]) -> AtaSmart {
+    let mut smart = AtaSmart::default();
+    for entry in data.get(2..2 + 30 * 12).unwrap_or(&[]).chunks_exact(12) {
+        let raw = &entry[5..11];
+        let raw_u64 = raw.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        match entry[0] {
+            ATTR_REALLOCATED_SECTORS => smart.reallocated_sectors = raw_u64,
+            // Some vendors pack extra counters into the upper raw bytes
+            ATTR_POWER_ON_HOURS => {
+                smart.power_on_hours =
+                    Some(u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as u64)
+            }
+            ATTR_TEMPERATURE => smart.temperature = Some(raw[0] as i32),
+            ATTR_AIRFLOW_TEMPERATURE if smart.temperature.is_none() => {
+                smart.temperature = Some(raw[0] as i32)
+            }
+            ATTR_PENDING_SECTORS => smart.pending_sectors = raw_u64,
+            ATTR_UNCORRECTABLE_SECTORS => smart.uncorrectable_sectors = raw_u64,
+            _ => {}
+        }
+    }
+    smart
+}
+
+fn le_u64(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .take(8)
+        .rev()
+        .fold(0u64, |acc, &b| (acc << 8) | b as u64)
+}
+
+/// Parses the NVMe SMART / Health Information log page (NVMe spec, figure "Get Log Page – SMART")
+fn parse_nvme_health(log: &[u8]) -> Option<NvmeHealth> {
+    if log.len() < NVME_HEALTH_LOG_SIZE {
+        return None;
+    }
+    let kelvin = u16::from_le_bytes([log[1], log[2]]) as i32;
+    Some(NvmeHealth {
+        critical_warning: log[0],
+        temperature: (kelvin > 0).then_some(kelvin - 273),
+        available_spare: log[3],
+        spare_threshold: log[4],
+        percentage_used: log[5],
+        // 128-bit counters; the low 64 bits are plenty
+        power_on_hours: le_u64(&log[128..144]),
+        media_errors: le_u64(&log[160..176]),
+    })
+}
+
+/// Closes the device handle when dropped
+struct DeviceHandle(HANDLE);
+
+impl Drop for DeviceHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+fn open_device(path: &str) -> Option<DeviceHandle> {
+    let wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        CreateFileW(
+            PCWSTR(wide.as_ptr()),
+            0, // No access needed for queries
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            FILE_ATTRIBUTE_NORMAL,
+            None,
+        )
+        .ok()
+        .filter(|h| !h.is_invalid())
+        .map(DeviceHandle)
+    }
+}
+
+/// Sends `ioctl` with `input` and returns up to `output_len` bytes of output
+fn device_io(
+    device: &DeviceHandle,
+    ioctl: u32,
+    input: &[u8],
+    output_len: usize,
+) -> Option<Vec<u8>> {
+    let mut output = vec![0u8; output_len];
+    let mut returned = 0u32;
+    unsafe {
+        DeviceIoControl(
+            device.0,
+            ioctl,
+            (!input.is_empty()).then_some(input.as_ptr() as *const _),
+            input.len() as u32,
+            Some(output.as_mut_ptr() as *mut _),
+            output_len as u32,
+            Some(&mut returned),
+            None,
+        )
+        .ok()?;
+    }
+    output.truncate(returned as usize);
+    Some(output)
+}
+
+fn as_bytes<T>(value: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>()) }
+}
+
+fn read_struct<T: Copy>(bytes: &[u8]) -> Option<T> {
+    (bytes.len() >= std::mem::size_of::<T>())
+        .then(|| unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) })
+}
+
+fn query_property(
+    device: &DeviceHandle,
+    property: STORAGE_PROPERTY_ID,
+    output_len: usize,
+) -> Option<Vec<u8>> {
+    let query = STORAGE_PROPERTY_QUERY {
+        PropertyId: property,
+        QueryType: STORAGE_QUERY_TYPE(0), // PropertyStandardQuery
+        AdditionalParameters: [0; 1],
+    };
+    device_io(
+        device,
+        IOCTL_STORAGE_QUERY_PROPERTY,
+        as_bytes(&query),
+        output_len,
+    )
+}
+
+/// Nul-terminated ASCII string at `offset` of a descriptor buffer (0 = absent)
+fn descriptor_string(buffer: &[u8], offset: u32) -> Option<String> {
+    let bytes = buffer.get(offset as usize..).filter(|_| offset != 0)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let text = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn read_nvme_health(device: &DeviceHandle) -> Option<NvmeHealth> {
+    let header_len = std::mem::offset_of!(STORAGE_PROPERTY_QUERY, AdditionalParameters);
+    let protocol_len = std::mem::size_of::<STORAGE_PROTOCOL_SPECIFIC_DATA>();
+    let request = STORAGE_PROTOCOL_SPECIFIC_DATA {
+        ProtocolType: ProtocolTypeNvme,
+        DataType: NVMeDataTypeLogPage.0 as u32,
+        ProtocolDataRequestValue: NVME_LOG_PAGE_HEALTH_INFO,
+        ProtocolDataOffset: protocol_len as u32,
+        ProtocolDataLength: NVME_HEALTH_LOG_SIZE as u32,
+        ..Default::default()
+    };
+    let query = STORAGE_PROPERTY_QUERY {
+        PropertyId: StorageDeviceProtocolSpecificProperty,
+        QueryType: STORAGE_QUERY_TYPE(0),
+        AdditionalParameters: [0; 1],
+    };
+    let mut input = vec![0u8; header_len + protocol_len + NVME_HEALTH_LOG_SIZE];
+    input[..header_len].copy_from_slice(&as_bytes(&query)[..header_len]);
+    input[header_len..header_len + protocol_len].copy_from_slice(as_bytes(&request));
+
+    let output = device_io(device, IOCTL_STORAGE_QUERY_PROPERTY, &input, input.len())?;
+    let descriptor: STORAGE_PROTOCOL_DATA_DESCRIPTOR = read_struct(&output)?;
+    let data = descriptor.ProtocolSpecificData;
+    // The offset counts from the start of ProtocolSpecificData
+    let start = std::mem::offset_of!(STORAGE_PROTOCOL_DATA_DESCRIPTOR, ProtocolSpecificData)
+        + data.ProtocolDataOffset as usize;
+    parse_nvme_health(output.get(start..start + data.ProtocolDataLength as usize)?)
+}
+
+fn read_temperature(device: &DeviceHandle) -> Option<i32> {
+    let output = query_property(device, StorageDeviceTemperatureProperty, 1024)?;
+    let descriptor: STORAGE_TEMPERATURE_DATA_DESCRIPTOR = read_struct(&output)?;
+    (descriptor.InfoCount > 0).then_some(descriptor.TemperatureInfo[0].Temperature as i32)
+}
+
+fn drive_letter(letter: &str) -> Result<char, String> {
+    letter
+        .chars()
+        .next()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .ok_or_else(|| format!("Invalid drive letter: {}", letter))
+}
+
+fn drive_health(letter: char) -> Result<DriveHealth, String> {
+    let volume = open_device(&format!("\\\\.\\{}:", letter))
+        .ok_or_else(|| format!("Drive {}: is not available", letter))?;
+    // Queries are most reliable on the physical disk behind the volume
+    let disk = device_io(&volume, IOCTL_STORAGE_GET_DEVICE_NUMBER, &[], 64)
+        .and_then(|out| read_struct::<STORAGE_DEVICE_NUMBER>(&out))
+        .and_then(|n| open_device(&format!("\\\\.\\PhysicalDrive{}", n.DeviceNumber)));
+    let device = disk.as_ref().unwrap_or(&volume);
+
+    let mut health = DriveHealth {
+        letter: format!("{}:", letter),
+        model: None,
+        serial_number: None,
+        is_nvme: false,
+        temperature_celsius: None,
+        power_on_hours: None,
+        percentage_used: None,
+        status: HealthStatus::Unknown,
+        warnings: Vec::new(),
+    };
+
+    if let Some(buffer) = query_property(device, StorageDeviceProperty, 1024) {
+        if let Some(descriptor) = read_struct::<STORAGE_DEVICE_DESCRIPTOR>(&buffer) {
+            let vendor = descriptor_string(&buffer, descriptor.VendorIdOffset);
+            let product = descriptor_string(&buffer, descriptor.ProductIdOffset);
+            health.model = match (vendor, product) {
+                (Some(v), Some(p)) if !p.starts_with(&v) => Some(format!("{} {}", v, p)),
+                (v, p) => p.or(v),
+            };
+            health.serial_number = descriptor_string(&buffer, descriptor.SerialNumberOffset);
+            health.is_nvme = descriptor.BusType == BusTypeNvme;
+        }
+    }
+    health.temperature_celsius = read_temperature(device);
+
+    let mut has_readings = false;
+    if health.is_nvme {
+        if let Some(nvme) = read_nvme_health(device) {
+            has_readings = true;
+            health.power_on_hours = Some(nvme.power_on_hours);
+            health.percentage_used = Some(nvme.percentage_used);
+            health.temperature_celsius = health.temperature_celsius.or(nvme.temperature);
+            if nvme.critical_warning != 0 {
+                health.status = HealthStatus::Failing;
+                health.warnings.push(format!(
+                    "The drive reports a critical warning (0x{:02x})",
+                    nvme.critical_warning
+                ));
+            }
+            if nvme.available_spare < nvme.spare_threshold {
+                health.warnings.push(format!(
+                    "Spare capacity is down to {}%",
+                    nvme.available_spare
+                ));
+            }
+            if nvme.percentage_used >= 90 {
+                health.warnings.push(format!(
+                    "{}% of the rated endurance is used up",
+                    nvme.percentage_used
+                ));
+            }
+            if nvme.media_errors > 0 {
+                health
+                    .warnings
+                    .push(format!("{} media errors logged", nvme.media_errors));
+            }
+        }
+    } else if let Some(output) = device_io(
+        device,
+        IOCTL_STORAGE_PREDICT_FAILURE,
+        &[],
+        std::mem::size_of::<STORAGE_PREDICT_FAILURE>(),
+    ) {
+        if let Some(prediction) = read_struct::<STORAGE_PREDICT_FAILURE>(&output) {
+            has_readings = true;
+            let smart = parse_ata_smart(&prediction.VendorSpecific);
+            health.power_on_hours = smart.power_on_hours;
+            health.temperature_celsius = health.temperature_celsius.or(smart.temperature);
+            if prediction.PredictFailure != 0 {
+                health.status = HealthStatus::Failing;
+                health
+                    .warnings
+                    .push("The drive predicts it is about to fail".to_string());
+            }
+            for (count, what) in [
+                (smart.reallocated_sectors, "reallocated sectors"),
+                (smart.pending_sectors, "sectors pending reallocation"),
+                (smart.uncorrectable_sectors, "uncorrectable sectors"),
+            ] {
+                if count > 0 {
+                    health.warnings.push(format!("{} {}", count, what));
+                }
+            }
+        }
+    }
+
+    if has_readings && health.status == HealthStatus::Unknown {
+        health.status = if health.warnings.is_empty() {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Warning
+        };
+    }
+    Ok(health)
+}
+
+/// S.M.A.R.T. summary of the physical disk holding drive `letter` ("C", "C:" or "C:\")
+#[tauri::command]
+pub async fn get_drive_health(letter: String) -> Result<DriveHealth, String> {
+    let letter = drive_letter(&letter)?;
+    tauri::async_runtime::spawn_blocking(move || drive_health(letter))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(id: u8, raw: [u8; 6]) -> [u8; 12] {
+        let mut entry = [0u8; 12];
+        entry[0] = id;
+        entry[3] = 100;
+        entry[4] = 100;
+        entry[5..11].copy_from_slice(&raw);
+        entry
+    }
+
+    #[test]
+    fn test_parse_ata_smart() {
+        let mut data = vec![0u8; 512];
+        let attrs = [
+            attribute(ATTR_REALLOCATED_SECTORS, [3, 0, 0, 0, 0, 0]),
+            // 12345 hours; upper raw bytes hold an unrelated counter
+            attribute(ATTR_POWER_ON_HOURS, [0x39, 0x30, 0, 0, 7, 0]),
+            attribute(ATTR_TEMPERATURE, [38, 0, 20, 0, 45, 0]),
+        ];
+        for (i, attr) in attrs.iter().enumerate() {
+            data[2 + i * 12..2 + (i + 1) * 12].copy_from_slice(attr);
+        }
+        assert_eq!(
+            parse_ata_smart(&data),
+            AtaSmart {
+                power_on_hours: Some(12345),
+                temperature: Some(38),
+                reallocated_sectors: 3,
+                pending_sectors: 0,
+                uncorrectable_sectors: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_nvme_health() {
+        let mut log = vec![0u8; NVME_HEALTH_LOG_SIZE];
+        log[1..3].copy_from_slice(&313u16.to_le_bytes()); // 40 °C
+        log[3] = 100;
+        log[4] = 10;
+        log[5] = 7;
+        log[128..136].copy_from_slice(&4321u64.to_le_bytes());
+        let health = parse_nvme_health(&log).unwrap();
+        assert_eq!(health.temperature, Some(40));
+        assert_eq!(health.percentage_used, 7);
+        assert_eq!(health.power_on_hours, 4321);
+        assert_eq!(health.critical_warning, 0);
+        assert!(parse_nvme_health(&log[..100]).is_none());
+    }
+}
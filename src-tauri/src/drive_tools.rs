@@ -0,0 +1,125 @@
+//! Drive Tools Module
+//!
+//! The two buttons of Explorer's drive Properties > Tools tab:
+//! - "Check": `chkdsk <drive> /scan`, an online scan that leaves the volume mounted
+//! - "Optimize": `defrag <drive> /O`, which lets Windows pick defragmentation
+//!   for hard disks or retrim for SSDs
+//!
+//! Both need administrator rights, so they are started through the `runas`
+//! verb (one UAC prompt) with a hidden console. The command returns a job id
+//! right away; a `drive-tool-finished` event reports the exit status once the
+//! tool is done, or that it never started (e.g. the prompt was declined).
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::Emitter;
+use ts_rs::TS;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+#[derive(Serialize, Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum DriveTool {
+    Check,
+    Optimize,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct DriveToolFinished {
+    pub job_id: String,
+    pub drive: String,
+    pub tool: DriveTool,
+    pub exit_code: Option<u32>,
+    pub success: bool,
+    pub message: String,
+}
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Maps a tool's exit code to a summary for the UI
+fn describe_exit(tool: DriveTool, exit_code: u32) -> (bool, String) {
+    match (tool, exit_code) {
+        (DriveTool::Check, 0) => (true, "Windows found no errors on this drive".to_string()),
+        (DriveTool::Check, 1) => (true, "Errors were found and fixed".to_string()),
+        (DriveTool::Check, 2) => (true, "The drive was checked and cleaned up".to_string()),
+        (DriveTool::Check, _) => (
+            false,
+            "Errors were found. Repair the drive to fix them".to_string(),
+        ),
+        (DriveTool::Optimize, 0) => (true, "The drive was optimized".to_string()),
+        (DriveTool::Optimize, code) => (false, format!("Optimization failed (code {})", code)),
+    }
+}
+
+fn launch(window: tauri::Window, drive: char, tool: DriveTool) -> Result<String, String> {
+    let (program, params) = match tool {
+        DriveTool::Check => ("chkdsk.exe", format!("{}: /scan", drive)),
+        DriveTool::Optimize => ("defrag.exe", format!("{}: /O", drive)),
+    };
+    let job_id = format!("drive-tool-{}", NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed));
+    log::info!("[DRIVE-TOOLS] {} starting: {} {}", job_id, program, params);
+
+    let id = job_id.clone();
+    // `HWND` wraps a raw pointer; pass it to the thread as a number
+    let hwnd = crate::get_root_hwnd(&window).0 as isize;
+    std::thread::spawn(move || {
+        let result = crate::elevation::shell_execute_runas(
+            HWND(hwnd as *mut _),
+            program,
+            &params,
+            None,
+            SW_HIDE,
+            true,
+        );
+        let (exit_code, success, message) = match result {
+            Ok(Some(code)) => {
+                let (success, message) = describe_exit(tool, code);
+                (Some(code), success, message)
+            }
+            Ok(None) => (None, false, "Could not read the tool's result".to_string()),
+            Err(e) => (None, false, e),
+        };
+        log::info!(
+            "[DRIVE-TOOLS] {} finished with {:?}: {}",
+            id,
+            exit_code,
+            message
+        );
+        let _ = window.emit(
+            "drive-tool-finished",
+            DriveToolFinished {
+                job_id: id,
+                drive: format!("{}:", drive),
+                tool,
+                exit_code,
+                success,
+                message,
+            },
+        );
+    });
+
+    Ok(job_id)
+}
+
+fn drive_letter(drive: &str) -> Result<char, String> {
+    drive
+        .chars()
+        .next()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .ok_or_else(|| format!("Invalid drive: {}", drive))
+}
+
+/// Starts an online error check of `drive`; see `drive-tool-finished` for the outcome
+#[tauri::command]
+pub fn check_drive(window: tauri::Window, drive: String) -> Result<String, String> {
+    launch(window, drive_letter(&drive)?, DriveTool::Check)
+}
+
+/// Starts defragmentation or retrim of `drive`, whichever suits the media
+#[tauri::command]
+pub fn optimize_drive(window: tauri::Window, drive: String) -> Result<String, String> {
+    launch(window, drive_letter(&drive)?, DriveTool::Optimize)
+}
@@ -0,0 +1,427 @@
+//! Elevation Module
+//!
+//! Runs file operations that were refused access again with administrator
+//! rights. The app starts a second copy of itself through the `runas` verb with
+//! `--elevated-operation <pipe>`; that copy skips the window and the
+//! single-instance check, reads one `HelperTask` from the pipe, performs it and
+//! writes the result back before exiting. File operations go through
+//! IFileOperation; `permissions::take_ownership` sends its takeown/icacls work
+//! the same way, so paths never pass through a `cmd` command line.
+//!
+//! The operation queue keeps the sources the shell reported as access denied
+//! (see `operations`), and `retry_elevated` sends just those, so items that
+//! already went through are not copied twice.
+
+use crate::operations::{self, OperationKind};
+use crate::paths::to_wide;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::windows::io::FromRawHandle;
+use std::sync::atomic::{AtomicU64, Ordering};
+use windows::core::{HRESULT, PCWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_CANCELLED, ERROR_PIPE_CONNECTED, E_ACCESSDENIED, HWND,
+};
+use windows::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_APARTMENTTHREADED,
+    COINIT_DISABLE_OLE1DDE,
+};
+use windows::Win32::System::Ole::{OleInitialize, OleUninitialize};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+    PIPE_TYPE_BYTE, PIPE_WAIT,
+};
+use windows::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+use windows::Win32::UI::Shell::{
+    FileOperation, IFileOperation, IShellItem, SHCreateItemFromParsingName, ShellExecuteExW,
+    COPYENGINE_E_ACCESS_DENIED_DEST, COPYENGINE_E_ACCESS_DENIED_SRC, FOF_ALLOWUNDO,
+    FOF_NOCONFIRMATION, FOF_NOCONFIRMMKDIR, FOF_NOERRORUI, FOF_RENAMEONCOLLISION, FOF_SILENT,
+    SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{SHOW_WINDOW_CMD, SW_HIDE};
+
+const HELPER_FLAG: &str = "--elevated-operation";
+/// The helper only talks to pipes the app itself created
+const PIPE_PREFIX: &str = "\\\\.\\pipe\\QuickExplorer-Elevated-";
+
+static NEXT_PIPE: AtomicU64 = AtomicU64::new(1);
+
+/// A copy, move or delete for the elevated helper
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ElevatedRequest {
+    pub kind: OperationKind,
+    pub sources: Vec<String>,
+    /// Destination folder; None for deletes
+    pub target: Option<String>,
+}
+
+/// One job for the elevated helper, sent as a JSON line over its pipe
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum HelperTask {
+    FileOperation(ElevatedRequest),
+    /// Make the user `sid` the owner of `paths` with full control
+    TakeOwnership {
+        paths: Vec<String>,
+        sid: String,
+    },
+}
+
+/// Whether the shell failed an item because the user lacks the rights for it
+pub fn is_access_denied(result: HRESULT) -> bool {
+    [
+        E_ACCESSDENIED,
+        COPYENGINE_E_ACCESS_DENIED_SRC,
+        COPYENGINE_E_ACCESS_DENIED_DEST,
+    ]
+    .contains(&result)
+}
+
+/// Same check on an error message, for operations that failed as a whole
+pub fn is_access_denied_error(error: &str) -> bool {
+    [
+        E_ACCESSDENIED,
+        COPYENGINE_E_ACCESS_DENIED_SRC,
+        COPYENGINE_E_ACCESS_DENIED_DEST,
+    ]
+    .iter()
+    .any(|code| error.contains(&code.to_string()))
+}
+
+/// The pipe named after `--elevated-operation`, if this process is a helper
+fn helper_pipe(args: &[String]) -> Option<&str> {
+    let index = args.iter().position(|a| a == HELPER_FLAG)?;
+    args.get(index + 1)
+        .map(String::as_str)
+        .filter(|pipe| pipe.starts_with(PIPE_PREFIX))
+}
+
+fn shell_item(path: &str) -> Result<IShellItem, String> {
+    let wide = to_wide(path);
+    unsafe { SHCreateItemFromParsingName(PCWSTR(wide.as_ptr()), None) }
+        .map_err(|e| format!("Failed to create item for {}: {}", path, e))
+}
+
+unsafe fn perform_file_operation(request: &ElevatedRequest) -> Result<(), String> {
+    let file_op: IFileOperation = CoCreateInstance(&FileOperation, None, CLSCTX_ALL)
+        .map_err(|e| format!("Failed to create IFileOperation: {}", e))?;
+    // Nobody is there to answer the shell's dialogs
+    let _ = file_op.SetOperationFlags(
+        FOF_ALLOWUNDO
+            | FOF_RENAMEONCOLLISION
+            | FOF_NOCONFIRMMKDIR
+            | FOF_NOCONFIRMATION
+            | FOF_NOERRORUI
+            | FOF_SILENT,
+    );
+
+    let target = request.target.as_deref().map(shell_item).transpose()?;
+    for source in &request.sources {
+        let item = shell_item(source)?;
+        match (request.kind, &target) {
+            (OperationKind::Copy, Some(target)) => {
+                file_op.CopyItem(&item, target, PCWSTR::null(), None)
+            }
+            (OperationKind::Move, Some(target)) => {
+                file_op.MoveItem(&item, target, PCWSTR::null(), None)
+            }
+            (OperationKind::Delete, _) => file_op.DeleteItem(&item, None),
+            (kind, _) => return Err(format!("{:?} can't be run elevated", kind)),
+        }
+        .map_err(|e| format!("Failed to queue {}: {}", source, e))?;
+    }
+
+    file_op
+        .PerformOperations()
+        .map_err(|e| format!("PerformOperations failed: {}", e))?;
+    if file_op
+        .GetAnyOperationsAborted()
+        .map(|b| b.as_bool())
+        .unwrap_or(false)
+    {
+        return Err("Some items could not be processed".to_string());
+    }
+    Ok(())
+}
+
+fn perform(request: &ElevatedRequest) -> Result<(), String> {
+    unsafe {
+        OleInitialize(None).map_err(|e| format!("OleInitialize failed: {}", e))?;
+        let result = perform_file_operation(request);
+        OleUninitialize();
+        result
+    }
+}
+
+/// The helper's side of the pipe: one request in, one result out
+fn serve(pipe: &str) -> std::io::Result<()> {
+    let mut pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(pipe)?;
+    let mut line = String::new();
+    BufReader::new(&pipe).read_line(&mut line)?;
+    let result = serde_json::from_str::<HelperTask>(&line)
+        .map_err(|e| format!("Malformed request: {}", e))
+        .and_then(|task| match task {
+            HelperTask::FileOperation(request) => perform(&request),
+            HelperTask::TakeOwnership { paths, sid } => {
+                crate::permissions::apply_ownership(&paths, &sid)
+            }
+        });
+    pipe.write_all(&serde_json::to_vec(&result)?)
+}
+
+/// Does the work of an elevated helper if this process is one. Returns true when it
+/// was, in which case the process should exit; call first thing in `main`, before
+/// the single-instance check hands the launch to the running app.
+pub fn run_elevated_helper() -> bool {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(pipe) = helper_pipe(&args) else {
+        return false;
+    };
+    // Nothing is logged here: the helper never sets up the logger
+    let _ = serve(pipe);
+    true
+}
+
+/// Runs `file` with `params` through the `runas` verb, so Windows shows the UAC
+/// prompt (owned by `hwnd`) first. With `wait`, blocks until the program exits and
+/// returns its exit code; None when not waiting or when the code can't be read.
+pub fn shell_execute_runas(
+    hwnd: HWND,
+    file: &str,
+    params: &str,
+    dir: Option<&str>,
+    show: SHOW_WINDOW_CMD,
+    wait: bool,
+) -> Result<Option<u32>, String> {
+    let verb_wide = to_wide("runas");
+    let file_wide = to_wide(file);
+    let params_wide = to_wide(params);
+    let dir_wide = dir.map(to_wide);
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: if wait {
+            SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC
+        } else {
+            SEE_MASK_NOASYNC
+        },
+        hwnd,
+        lpVerb: PCWSTR(verb_wide.as_ptr()),
+        lpFile: PCWSTR(file_wide.as_ptr()),
+        lpParameters: PCWSTR(params_wide.as_ptr()),
+        lpDirectory: dir_wide
+            .as_ref()
+            .map_or(PCWSTR::null(), |dir| PCWSTR(dir.as_ptr())),
+        nShow: show.0,
+        ..Default::default()
+    };
+    unsafe {
+        // ShellExecuteEx may hand the launch to shell extensions, which need COM.
+        // Callers may be on a tokio worker without COM; only balance what we initialized
+        let com_initialized =
+            CoInitializeEx(None, COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE).is_ok();
+        let result = ShellExecuteExW(&mut info);
+        if com_initialized {
+            CoUninitialize();
+        }
+        result.map_err(|e| {
+            // ERROR_CANCELLED: the user declined the UAC prompt
+            if e.code() == ERROR_CANCELLED.to_hresult() {
+                "Elevation was cancelled by the user".to_string()
+            } else {
+                format!("Failed to start {} as administrator: {}", file, e)
+            }
+        })?;
+        if !wait {
+            return Ok(None);
+        }
+        if info.hProcess.is_invalid() {
+            return Err(format!("Failed to start {} as administrator", file));
+        }
+        WaitForSingleObject(info.hProcess, INFINITE);
+        let mut code = 0u32;
+        let exit_code = GetExitCodeProcess(info.hProcess, &mut code)
+            .ok()
+            .map(|_| code);
+        let _ = CloseHandle(info.hProcess);
+        Ok(exit_code)
+    }
+}
+
+/// Runs `request` in an elevated helper process and waits for its result
+pub fn run_elevated(hwnd: HWND, request: &ElevatedRequest) -> Result<(), String> {
+    run_helper(hwnd, &HelperTask::FileOperation(request.clone()))
+}
+
+/// Runs `task` in an elevated helper process and waits for its result
+pub fn run_helper(hwnd: HWND, task: &HelperTask) -> Result<(), String> {
+    let name = format!(
+        "{}{}-{}",
+        PIPE_PREFIX,
+        std::process::id(),
+        NEXT_PIPE.fetch_add(1, Ordering::Relaxed)
+    );
+    let name_wide = to_wide(&name);
+    // First instance only, so nothing else can be listening under this name
+    let pipe = unsafe {
+        CreateNamedPipeW(
+            PCWSTR(name_wide.as_ptr()),
+            PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            1,
+            64 * 1024,
+            64 * 1024,
+            0,
+            None,
+        )
+    };
+    if pipe.is_invalid() {
+        return Err(format!(
+            "Failed to create pipe: {}",
+            windows::core::Error::from_win32()
+        ));
+    }
+    // Owns the handle from here on and closes it when dropped
+    let mut file = unsafe { std::fs::File::from_raw_handle(pipe.0) };
+
+    match task {
+        HelperTask::FileOperation(request) => log::info!(
+            "[ELEVATION] {:?} of {} item(s) through {}",
+            request.kind,
+            request.sources.len(),
+            name
+        ),
+        HelperTask::TakeOwnership { paths, .. } => log::info!(
+            "[ELEVATION] Ownership of {} item(s) through {}",
+            paths.len(),
+            name
+        ),
+    }
+    // `HWND` wraps a raw pointer; pass it to the launching thread as a number
+    let hwnd = hwnd.0 as isize;
+    let params = format!("{} \"{}\"", HELPER_FLAG, name);
+    let client_name = name.clone();
+    let helper = std::thread::spawn(move || {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Failed to locate the executable: {}", e))?;
+        let result = shell_execute_runas(
+            HWND(hwnd as *mut _),
+            &exe.to_string_lossy(),
+            &params,
+            None,
+            SW_HIDE,
+            true,
+        );
+        // Releases ConnectNamedPipe if the helper never connected (declined prompt, crash)
+        let _ = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&client_name);
+        result
+    });
+
+    let connected = match unsafe { ConnectNamedPipe(pipe, None) } {
+        Ok(()) => true,
+        Err(e) => e.code() == ERROR_PIPE_CONNECTED.to_hresult(),
+    };
+    let mut data = Vec::new();
+    if connected {
+        if let Ok(mut request_line) = serde_json::to_vec(task) {
+            request_line.push(b'\n');
+            if file.write_all(&request_line).is_ok() {
+                let _ = file.read_to_end(&mut data);
+            }
+        }
+    }
+    drop(file);
+    let exit_code = helper
+        .join()
+        .map_err(|_| "The elevated helper's launcher panicked".to_string())??;
+
+    let result = serde_json::from_slice::<Result<(), String>>(&data).unwrap_or_else(|_| {
+        Err(format!(
+            "The elevated helper exited without a result (exit code {:?})",
+            exit_code
+        ))
+    });
+    log::info!("[ELEVATION] {} finished: {:?}", name, result);
+    result
+}
+
+/// Runs the sources of an operation that were refused access again as
+/// administrator, as a new operation in the queue
+#[tauri::command]
+pub async fn retry_elevated(window: tauri::Window, operation_id: String) -> Result<(), String> {
+    let info =
+        operations::info(&operation_id).ok_or_else(|| format!("No operation {}", operation_id))?;
+    if !matches!(
+        info.kind,
+        OperationKind::Copy | OperationKind::Move | OperationKind::Delete
+    ) {
+        return Err(format!(
+            "{:?} operations can't be retried as administrator",
+            info.kind
+        ));
+    }
+    if info.access_denied.is_empty() {
+        return Err(format!("Operation {} was not denied access", operation_id));
+    }
+
+    let request = ElevatedRequest {
+        kind: info.kind,
+        sources: info.access_denied,
+        target: info.target,
+    };
+    let hwnd = crate::get_root_hwnd(&window).0 as isize;
+    operations::run(
+        request.kind,
+        request.sources.clone(),
+        request.target.clone(),
+        move |control| {
+            if control.is_cancelled() {
+                return Err("Operation cancelled".to_string());
+            }
+            run_elevated(HWND(hwnd as *mut _), &request).map(|_| Vec::new())
+        },
+    )
+    .await
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_helper_pipe() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            helper_pipe(&args(&[
+                "--elevated-operation",
+                "\\\\.\\pipe\\QuickExplorer-Elevated-4-1"
+            ])),
+            Some("\\\\.\\pipe\\QuickExplorer-Elevated-4-1")
+        );
+        assert_eq!(
+            helper_pipe(&args(&["--elevated-operation", "\\\\.\\pipe\\other"])),
+            None
+        );
+        assert_eq!(helper_pipe(&args(&["--elevated-operation"])), None);
+        assert_eq!(helper_pipe(&args(&["C:\\Users"])), None);
+    }
+
+    #[test]
+    fn test_is_access_denied() {
+        assert!(is_access_denied(E_ACCESSDENIED));
+        assert!(is_access_denied(COPYENGINE_E_ACCESS_DENIED_DEST));
+        assert!(!is_access_denied(HRESULT(0)));
+        assert!(is_access_denied_error(
+            "PerformOperations failed: Access is denied. (0x80070005)"
+        ));
+        assert!(!is_access_denied_error(
+            "PerformOperations failed: The operation was canceled by the user. (0x800704C7)"
+        ));
+    }
+}
@@ -1,21 +1,181 @@
 use serde::Serialize;
 use std::fs;
+use std::io::BufReader;
 use std::io::Read;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use tauri::window::{ProgressBarState, ProgressBarStatus};
 use tauri::Emitter;
+use ts_rs::TS;
+
+use crate::operations::{OperationControl, OperationKind};
+use crate::settings::SettingsStore;
+
+/// Settings key: copy the archive's Mark-of-the-Web onto extracted executables/scripts
+const PROPAGATE_MOTW_KEY: &str = "extraction.propagate_motw";
+
+/// Extensions that SmartScreen and Office treat differently when they carry a MOTW,
+/// matching what Explorer's zip handler tags
+const MOTW_EXTENSIONS: &[&str] = &[
+    "exe",
+    "com",
+    "scr",
+    "pif",
+    "msi",
+    "msix",
+    "msixbundle",
+    "appx",
+    "appxbundle",
+    "cpl",
+    "dll",
+    "ocx",
+    "sys",
+    "bat",
+    "cmd",
+    "ps1",
+    "psm1",
+    "vbs",
+    "vbe",
+    "js",
+    "jse",
+    "wsf",
+    "wsh",
+    "hta",
+    "lnk",
+    "url",
+    "reg",
+    "jar",
+    "application",
+    "docm",
+    "dotm",
+    "xlsm",
+    "xltm",
+    "xlam",
+    "pptm",
+    "potm",
+    "ppam",
+];
 
 #[derive(Clone, Serialize)]
-struct ProgressPayload {
+pub(crate) struct ProgressPayload {
     percentage: f32,
     current_file: String,
 }
 
-/// Helper: update taskbar + emit event, but only if percentage changed by ≥1%
-fn report_progress(
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct ExtractionFinished {
+    pub job_id: String,
+    pub cancelled: bool,
+    /// The extracted folder or file; None when the job failed or was cancelled
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Error returned by the extractors once the job's cancel flag is set
+const CANCELLED: &str = "Extraction cancelled";
+
+/// Removes what a cancelled extraction wrote, unless `keep` is called first
+struct PartialOutput<'a> {
+    path: PathBuf,
+    control: &'a OperationControl,
+    kept: bool,
+}
+
+impl<'a> PartialOutput<'a> {
+    fn new(path: impl Into<PathBuf>, control: &'a OperationControl) -> Self {
+        Self {
+            path: path.into(),
+            control,
+            kept: false,
+        }
+    }
+
+    fn keep(mut self) {
+        self.kept = true;
+    }
+}
+
+impl Drop for PartialOutput<'_> {
+    fn drop(&mut self) {
+        if self.kept || !self.control.is_cancelled() {
+            return;
+        }
+        let removed = if self.path.is_dir() {
+            fs::remove_dir_all(&self.path)
+        } else {
+            fs::remove_file(&self.path)
+        };
+        match removed {
+            Ok(()) => log::info!("[EXTRACT] Removed partial output {:?}", self.path),
+            Err(e) => log::warn!(
+                "[EXTRACT] Failed to remove partial output {:?}: {}",
+                self.path,
+                e
+            ),
+        }
+    }
+}
+
+/// The archive's `Zone.Identifier` stream, copied onto risky files as they are extracted
+struct MarkOfTheWeb {
+    zone_identifier: String,
+}
+
+impl MarkOfTheWeb {
+    /// None when the archive carries no MOTW or propagation is turned off in settings
+    fn from_archive(archive_path: &str) -> Option<Self> {
+        let enabled = SettingsStore::global()
+            .get::<bool>(PROPAGATE_MOTW_KEY)
+            .unwrap_or(true);
+        if !enabled {
+            return None;
+        }
+        let zone_identifier =
+            fs::read_to_string(format!("{}:Zone.Identifier", archive_path)).ok()?;
+        if zone_identifier.trim().is_empty() {
+            return None;
+        }
+        Some(Self { zone_identifier })
+    }
+
+    fn apply(&self, path: &Path) {
+        let is_risky = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| MOTW_EXTENSIONS.iter().any(|m| m.eq_ignore_ascii_case(e)));
+        if !is_risky {
+            return;
+        }
+        let mut stream = path.as_os_str().to_owned();
+        stream.push(":Zone.Identifier");
+        if let Err(e) = fs::write(&stream, &self.zone_identifier) {
+            log::warn!("[EXTRACT] Failed to mark {:?} as downloaded: {}", path, e);
+        }
+    }
+
+    /// `apply` for every file under `dir`, for output written by an external program
+    fn apply_tree(&self, dir: &Path) {
+        let Ok(children) = fs::read_dir(dir) else {
+            return;
+        };
+        for child in children.flatten() {
+            match child.file_type() {
+                Ok(t) if t.is_dir() => self.apply_tree(&child.path()),
+                Ok(t) if t.is_file() => self.apply(&child.path()),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Helper: update taskbar + emit `event`, but only if percentage changed by ≥1%
+pub(crate) fn report_progress(
     window: &tauri::Window,
+    event: &str,
     last_pct: &mut u32,
     bytes_written: u64,
     total_bytes: u64,
@@ -31,7 +191,7 @@ fn report_progress(
     *last_pct = pct;
 
     let _ = window.emit(
-        "extraction-progress",
+        event,
         ProgressPayload {
             percentage: pct as f32,
             current_file: current_file.to_string(),
@@ -43,54 +203,144 @@ fn report_progress(
     });
 }
 
-/// Extract a ZIP or 7Z archive to the target directory.
-/// Returns the path to the extracted folder/files on success.
+/// Fills the taskbar progress bar, then clears it
+pub(crate) fn finish_progress(window: &tauri::Window) {
+    // Send 100% and wait briefly so Windows can animate the full bar
+    let _ = window.set_progress_bar(ProgressBarState {
+        progress: Some(100),
+        status: Some(ProgressBarStatus::Normal),
+    });
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    // Reset progress bar
+    let _ = window.set_progress_bar(ProgressBarState {
+        progress: None,
+        status: Some(ProgressBarStatus::None),
+    });
+}
+
+/// Formats `extract_archive` understands, recognized by file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    SevenZ,
+    Tar,
+    TarGz,
+    TarXz,
+    /// A single gzip-compressed file
+    Gz,
+    /// A single xz-compressed file
+    Xz,
+    Rar,
+}
+
+/// Longest suffixes first, so ".tar.gz" wins over ".gz"
+const ARCHIVE_SUFFIXES: &[(&str, ArchiveKind)] = &[
+    (".tar.gz", ArchiveKind::TarGz),
+    (".tar.xz", ArchiveKind::TarXz),
+    (".tgz", ArchiveKind::TarGz),
+    (".txz", ArchiveKind::TarXz),
+    (".tar", ArchiveKind::Tar),
+    (".zip", ArchiveKind::Zip),
+    (".rar", ArchiveKind::Rar),
+    (".gz", ArchiveKind::Gz),
+    (".xz", ArchiveKind::Xz),
+    (".7z", ArchiveKind::SevenZ),
+];
+
+/// The kind of archive `name` is, and the name without its archive extension
+fn archive_kind(name: &str) -> Option<(ArchiveKind, &str)> {
+    ARCHIVE_SUFFIXES.iter().find_map(|&(suffix, kind)| {
+        let split = name.len().checked_sub(suffix.len())?;
+        let tail = name.get(split..)?;
+        tail.eq_ignore_ascii_case(suffix)
+            .then(|| (kind, &name[..split]))
+    })
+}
+
+/// Extract an archive (ZIP, 7Z, RAR, TAR, TAR.GZ, TAR.XZ) to the target directory, or
+/// decompress a single GZ/XZ file into it, as a job in the operation queue.
+/// Returns the job id immediately; progress arrives as `extraction-progress` events and
+/// the extracted folder/file path as `extraction-finished`.
 #[tauri::command]
-pub async fn extract_archive(
+pub fn extract_archive(
     window: tauri::Window,
     archive_path: String,
     target_dir: String,
 ) -> Result<String, String> {
-    let archive = archive_path.clone();
-    let target = target_dir.clone();
+    let file_name = Path::new(&archive_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let Some((kind, stem)) = archive_kind(&file_name) else {
+        return Err(format!("Unsupported archive format: {}", file_name));
+    };
+    let stem = if stem.is_empty() { "extracted" } else { stem }.to_string();
 
-    tokio::task::spawn_blocking(move || {
-        let path = Path::new(&archive);
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-
-        let stem = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("extracted")
-            .to_string();
-
-        let result = match ext.as_str() {
-            "zip" => extract_zip(&window, &archive, &target, &stem),
-            "7z" => extract_7z(&window, &archive, &target, &stem),
-            _ => Err(format!("Unsupported archive format: .{}", ext)),
-        };
+    let sources = vec![archive_path.clone()];
+    let (job_id, _) = crate::operations::enqueue(
+        OperationKind::Extract,
+        sources,
+        Some(target_dir.clone()),
+        move |control| {
+            let id = control.id().to_string();
+            log::info!("[EXTRACT] {} started for {}", id, archive_path);
+            let (archive, target) = (archive_path.as_str(), target_dir.as_str());
+            let result = if control.is_cancelled() {
+                Err(CANCELLED.to_string())
+            } else {
+                match kind {
+                    ArchiveKind::Zip => extract_zip(&window, archive, target, &stem, control),
+                    ArchiveKind::SevenZ => extract_7z(&window, archive, target, &stem, control),
+                    ArchiveKind::Tar | ArchiveKind::TarGz | ArchiveKind::TarXz => {
+                        extract_tar(&window, archive, target, &stem, kind, control)
+                    }
+                    ArchiveKind::Gz | ArchiveKind::Xz => {
+                        decompress_file(&window, archive, target, &stem, kind, control)
+                    }
+                    ArchiveKind::Rar => extract_rar(&window, archive, target, &stem, control),
+                }
+            };
+            finish_progress(&window);
 
-        // Send 100% and wait briefly so Windows can animate the full bar
-        let _ = window.set_progress_bar(ProgressBarState {
-            progress: Some(100),
-            status: Some(ProgressBarStatus::Normal),
-        });
-        std::thread::sleep(std::time::Duration::from_millis(200));
+            let finished = match &result {
+                Ok(output) => ExtractionFinished {
+                    job_id: id,
+                    cancelled: false,
+                    output: Some(output.clone()),
+                    error: None,
+                },
+                Err(_) if control.is_cancelled() => {
+                    log::info!("[EXTRACT] {} cancelled", id);
+                    ExtractionFinished {
+                        job_id: id,
+                        cancelled: true,
+                        output: None,
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    log::warn!("[EXTRACT] {} failed: {}", id, e);
+                    ExtractionFinished {
+                        job_id: id,
+                        cancelled: false,
+                        output: None,
+                        error: Some(e.clone()),
+                    }
+                }
+            };
+            let _ = window.emit("extraction-finished", finished);
+            result.map(|output| vec![output])
+        },
+    );
 
-        // Reset progress bar
-        let _ = window.set_progress_bar(ProgressBarState {
-            progress: None,
-            status: Some(ProgressBarStatus::None),
-        });
+    Ok(job_id)
+}
 
-        result
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+/// Stops a running extraction; what it wrote so far is removed
+#[tauri::command]
+pub fn cancel_extraction(job_id: String) -> Result<(), String> {
+    crate::operations::cancel_operation(job_id)
 }
 
 /// Extract a ZIP archive using the `zip` crate with byte-level progress.
@@ -99,6 +349,7 @@ fn extract_zip(
     archive_path: &str,
     target_dir: &str,
     stem: &str,
+    control: &Arc<OperationControl>,
 ) -> Result<String, String> {
     let file =
         fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
@@ -120,8 +371,10 @@ fn extract_zip(
     let output_dir = determine_output_dir(&mut archive, target_dir, stem)?;
     fs::create_dir_all(&output_dir)
         .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    let partial = PartialOutput::new(&output_dir, control);
 
     let single_root = get_zip_single_root(&mut archive);
+    let motw = MarkOfTheWeb::from_archive(archive_path);
 
     let mut bytes_written: u64 = 0;
     let mut last_pct: u32 = 0;
@@ -163,6 +416,9 @@ fn extract_zip(
             // Buffered copy with byte-level progress
             let mut buf = [0u8; 65536]; // 64KB buffer
             loop {
+                if !control.wait_while_paused() {
+                    return Err(CANCELLED.to_string());
+                }
                 let n = entry
                     .read(&mut buf)
                     .map_err(|e| format!("Failed to read from archive: {}", e))?;
@@ -175,15 +431,21 @@ fn extract_zip(
                 bytes_written += n as u64;
                 report_progress(
                     window,
+                    "extraction-progress",
                     &mut last_pct,
                     bytes_written,
                     total_bytes,
                     &entry_name,
                 );
             }
+            drop(out_file);
+            if let Some(motw) = &motw {
+                motw.apply(&out_path);
+            }
         }
     }
 
+    partial.keep();
     Ok(output_dir)
 }
 
@@ -193,11 +455,13 @@ fn extract_7z(
     archive_path: &str,
     target_dir: &str,
     stem: &str,
+    control: &Arc<OperationControl>,
 ) -> Result<String, String> {
     let output_dir = get_unique_dir(target_dir, stem);
 
     fs::create_dir_all(&output_dir)
         .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    let partial = PartialOutput::new(&output_dir, control);
 
     // Pre-scan: count total uncompressed bytes from archive metadata
     let file = fs::File::open(archive_path).map_err(|e| format!("Failed to open 7z: {}", e))?;
@@ -215,6 +479,7 @@ fn extract_7z(
     }
 
     let win_clone = window.clone();
+    let motw = MarkOfTheWeb::from_archive(archive_path);
     let mut bytes_written: u64 = 0;
     let mut last_pct: u32 = 0;
 
@@ -246,6 +511,9 @@ fn extract_7z(
             // Manual buffered copy with byte-level progress (same as ZIP)
             let mut buf = [0u8; 65536]; // 64KB buffer
             loop {
+                if !control.wait_while_paused() {
+                    return Err(sevenz_rust::Error::other(CANCELLED));
+                }
                 let n = reader
                     .read(&mut buf)
                     .map_err(|e| sevenz_rust::Error::io(e))?;
@@ -274,6 +542,10 @@ fn extract_7z(
                     });
                 }
             }
+            drop(out_file);
+            if let Some(motw) = &motw {
+                motw.apply(&out_path);
+            }
 
             Ok(true)
         },
@@ -282,19 +554,444 @@ fn extract_7z(
 
     flatten_single_child_dir(&output_dir)?;
 
+    partial.keep();
     Ok(output_dir)
 }
 
-/// Get a unique directory path, appending " (2)", " (3)", etc. if it already exists.
+/// Counts the bytes read from the archive file, for progress through a decompressor,
+/// and holds the read while the job is paused or fails it once cancelled
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: Arc<AtomicU64>,
+    control: Arc<OperationControl>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if !self.control.wait_while_paused() {
+            return Err(std::io::Error::other(CANCELLED));
+        }
+        let n = self.inner.read(buf)?;
+        self.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+/// Decoded xz data. lzma-rs only decodes into a writer, so a thread decodes into a pipe;
+/// its error, if any, is returned at the end of the stream instead of a clean EOF.
+struct XzReader {
+    pipe: std::io::PipeReader,
+    decoder: Option<std::thread::JoinHandle<Result<(), String>>>,
+}
+
+impl XzReader {
+    fn new<R: Read + Send + 'static>(input: R) -> Result<Self, String> {
+        let (pipe, mut writer) =
+            std::io::pipe().map_err(|e| format!("Failed to create pipe: {}", e))?;
+        let decoder = std::thread::spawn(move || {
+            lzma_rs::xz_decompress(&mut BufReader::new(input), &mut writer)
+                .map_err(|e| format!("Failed to decompress XZ data: {}", e))
+        });
+        Ok(Self {
+            pipe,
+            decoder: Some(decoder),
+        })
+    }
+}
+
+impl Read for XzReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.pipe.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            if let Some(decoder) = self.decoder.take() {
+                decoder
+                    .join()
+                    .unwrap_or_else(|_| Err("XZ decoder panicked".to_string()))
+                    .map_err(std::io::Error::other)?;
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// An archive's decoded contents, with the progress through the compressed file
+struct DecodedArchive {
+    reader: Box<dyn Read>,
+    /// Size of the archive file
+    total_bytes: u64,
+    /// Bytes of the archive file read so far
+    bytes_read: Arc<AtomicU64>,
+}
+
+/// Opens `archive_path` with the decompressor `kind` calls for
+fn open_decoded(
+    archive_path: &str,
+    kind: ArchiveKind,
+    control: &Arc<OperationControl>,
+) -> Result<DecodedArchive, String> {
+    let file =
+        fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let bytes_read = Arc::new(AtomicU64::new(0));
+    let counted = CountingReader {
+        inner: file,
+        bytes_read: Arc::clone(&bytes_read),
+        control: Arc::clone(control),
+    };
+    let decoded: Box<dyn Read> = match kind {
+        ArchiveKind::TarGz | ArchiveKind::Gz => {
+            Box::new(flate2::read::MultiGzDecoder::new(BufReader::new(counted)))
+        }
+        ArchiveKind::TarXz | ArchiveKind::Xz => Box::new(XzReader::new(counted)?),
+        _ => Box::new(BufReader::new(counted)),
+    };
+    Ok(DecodedArchive {
+        reader: decoded,
+        total_bytes,
+        bytes_read,
+    })
+}
+
+/// Extract a TAR archive, optionally gzip or xz compressed. Progress follows the
+/// compressed bytes read, since the unpacked size is only known at the end.
+fn extract_tar(
+    window: &tauri::Window,
+    archive_path: &str,
+    target_dir: &str,
+    stem: &str,
+    kind: ArchiveKind,
+    control: &Arc<OperationControl>,
+) -> Result<String, String> {
+    let decoded = open_decoded(archive_path, kind, control)?;
+    let output_dir = get_unique_dir(target_dir, stem);
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    let partial = PartialOutput::new(&output_dir, control);
+
+    let motw = MarkOfTheWeb::from_archive(archive_path);
+    let mut last_pct: u32 = 0;
+    let mut archive = tar::Archive::new(decoded.reader);
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read TAR archive: {}", e))?;
+
+    for entry in entries {
+        if !control.wait_while_paused() {
+            return Err(CANCELLED.to_string());
+        }
+        let mut entry = entry.map_err(|e| format!("Failed to read TAR archive: {}", e))?;
+        let entry_name = entry
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // unpack_in refuses paths that would land outside the output folder
+        let unpacked = entry
+            .unpack_in(&output_dir)
+            .map_err(|e| format!("Failed to extract {}: {}", entry_name, e))?;
+        if unpacked && entry.header().entry_type().is_file() {
+            if let Some(motw) = &motw {
+                motw.apply(&Path::new(&output_dir).join(&entry_name));
+            }
+        }
+        report_progress(
+            window,
+            "extraction-progress",
+            &mut last_pct,
+            decoded.bytes_read.load(Ordering::Relaxed),
+            decoded.total_bytes,
+            &entry_name,
+        );
+    }
+
+    flatten_single_child_dir(&output_dir)?;
+    partial.keep();
+    Ok(output_dir)
+}
+
+/// Decompress a single GZ or XZ file into the target directory, named after the
+/// archive without its extension.
+fn decompress_file(
+    window: &tauri::Window,
+    archive_path: &str,
+    target_dir: &str,
+    stem: &str,
+    kind: ArchiveKind,
+    control: &Arc<OperationControl>,
+) -> Result<String, String> {
+    let mut decoded = open_decoded(archive_path, kind, control)?;
+    let (out_path, mut out_file) = crate::new_file::open_unique(target_dir, stem)?;
+
+    let mut last_pct: u32 = 0;
+    let mut buf = [0u8; 65536]; // 64KB buffer
+    let result = loop {
+        let n = match decoded.reader.read(&mut buf) {
+            Ok(0) => break Ok(()),
+            Ok(n) => n,
+            Err(e) => break Err(format!("Failed to decompress {}: {}", stem, e)),
+        };
+        if let Err(e) = out_file.write_all(&buf[..n]) {
+            break Err(format!("Failed to write file {:?}: {}", out_path, e));
+        }
+        report_progress(
+            window,
+            "extraction-progress",
+            &mut last_pct,
+            decoded.bytes_read.load(Ordering::Relaxed),
+            decoded.total_bytes,
+            stem,
+        );
+    };
+    drop(out_file);
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&out_path);
+        return Err(e);
+    }
+    if let Some(motw) = MarkOfTheWeb::from_archive(archive_path) {
+        motw.apply(&out_path);
+    }
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RarTool {
+    SevenZip,
+    UnRar,
+    /// The bsdtar shipped with Windows; its libarchive reads RAR since Windows 11 23H2
+    Tar,
+}
+
+/// Installed programs that may extract RAR archives, in order of preference
+fn rar_tools() -> Vec<(RarTool, PathBuf)> {
+    let mut candidates = Vec::new();
+    for var in ["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)"] {
+        if let Ok(dir) = std::env::var(var) {
+            candidates.push((
+                RarTool::SevenZip,
+                Path::new(&dir).join("7-Zip").join("7z.exe"),
+            ));
+            candidates.push((
+                RarTool::UnRar,
+                Path::new(&dir).join("WinRAR").join("UnRAR.exe"),
+            ));
+        }
+    }
+    let windows = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    candidates.push((
+        RarTool::Tar,
+        Path::new(&windows).join("System32").join("tar.exe"),
+    ));
+
+    let mut seen = std::collections::HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|(_, path)| path.is_file() && seen.insert(path.clone()))
+        .collect()
+}
+
+/// Runs `cmd` to completion with its output captured, killing it if the job is cancelled.
+/// The program can't be paused, so it keeps running while the job is.
+fn run_cancellable(
+    cmd: &mut std::process::Command,
+    control: &OperationControl,
+) -> Result<std::process::Output, String> {
+    use std::process::Stdio;
+
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    // Drain the pipes on their own threads so a chatty tool can't block on a full pipe
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut out = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut out);
+            }
+            out
+        })
+    };
+    let stdout = drain(
+        child
+            .stdout
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+    let stderr = drain(
+        child
+            .stderr
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+
+    let status = loop {
+        if control.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CANCELLED.to_string());
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(100)),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+    Ok(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Extract a RAR archive with an external program, since there is no Rust decoder.
+/// The taskbar shows indeterminate progress meanwhile.
+fn extract_rar(
+    window: &tauri::Window,
+    archive_path: &str,
+    target_dir: &str,
+    stem: &str,
+    control: &Arc<OperationControl>,
+) -> Result<String, String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let tools = rar_tools();
+    if tools.is_empty() {
+        return Err("Extracting RAR archives needs 7-Zip or WinRAR to be installed".to_string());
+    }
+    let output_dir = get_unique_dir(target_dir, stem);
+    let _ = window.set_progress_bar(ProgressBarState {
+        progress: None,
+        status: Some(ProgressBarStatus::Indeterminate),
+    });
+
+    let mut last_error = String::new();
+    for (tool, program) in tools {
+        fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        let mut cmd = std::process::Command::new(&program);
+        match tool {
+            RarTool::SevenZip => cmd
+                .args(["x", "-y", "-bd"])
+                .arg(format!("-o{}", output_dir))
+                .arg(archive_path),
+            RarTool::UnRar => cmd
+                .args(["x", "-y", "-idq"])
+                .arg(archive_path)
+                .arg(format!("{}\\", output_dir)),
+            RarTool::Tar => cmd.arg("-xf").arg(archive_path).arg("-C").arg(&output_dir),
+        };
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        match run_cancellable(&mut cmd, control) {
+            Ok(output) if output.status.success() => {
+                log::info!("[EXTRACT] Extracted {} with {:?}", archive_path, tool);
+                if let Some(motw) = MarkOfTheWeb::from_archive(archive_path) {
+                    motw.apply_tree(Path::new(&output_dir));
+                }
+                flatten_single_child_dir(&output_dir)?;
+                return Ok(output_dir);
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let message = if stderr.trim().is_empty() {
+                    stdout
+                } else {
+                    stderr
+                };
+                last_error = format!("{:?} failed: {}", tool, message.trim());
+            }
+            Err(_) if control.is_cancelled() => {
+                let _ = fs::remove_dir_all(&output_dir);
+                return Err(CANCELLED.to_string());
+            }
+            Err(e) => last_error = format!("Failed to run {}: {}", program.display(), e),
+        }
+        log::warn!("[EXTRACT] {}", last_error);
+        // Start the next program from an empty folder
+        let _ = fs::remove_dir_all(&output_dir);
+    }
+    Err(format!("Failed to extract RAR archive: {}", last_error))
+}
+
+/// One entry of an archive's table of contents
+pub struct ArchiveEntry {
+    /// Path inside the archive, `/`-separated as stored
+    pub name: String,
+    pub is_dir: bool,
+    /// Uncompressed size in bytes
+    pub size: u64,
+}
+
+/// Archive formats `list_archive_entries` can read
+pub fn is_listable_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("zip") || e.eq_ignore_ascii_case("7z"))
+}
+
+/// Reads the table of contents of a ZIP or 7Z archive without extracting anything.
+pub fn list_archive_entries(archive_path: &Path) -> Result<Vec<ArchiveEntry>, String> {
+    let ext = archive_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let file =
+        fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+
+    match ext.as_str() {
+        "zip" => {
+            let mut archive = zip::ZipArchive::new(file)
+                .map_err(|e| format!("Failed to read ZIP archive: {}", e))?;
+            let mut entries = Vec::with_capacity(archive.len());
+            for i in 0..archive.len() {
+                // Raw access reads only the central directory, so encrypted entries list fine
+                if let Ok(entry) = archive.by_index_raw(i) {
+                    entries.push(ArchiveEntry {
+                        name: entry.name().to_string(),
+                        is_dir: entry.is_dir(),
+                        size: entry.size(),
+                    });
+                }
+            }
+            Ok(entries)
+        }
+        "7z" => {
+            let len = file
+                .metadata()
+                .map_err(|e| format!("Failed to get 7z metadata: {}", e))?
+                .len();
+            let reader = sevenz_rust::SevenZReader::new(file, len, sevenz_rust::Password::empty())
+                .map_err(|e| format!("Failed to read 7z: {}", e))?;
+            Ok(reader
+                .archive()
+                .files
+                .iter()
+                .map(|f| ArchiveEntry {
+                    name: f.name().to_string(),
+                    is_dir: f.is_directory(),
+                    size: f.size(),
+                })
+                .collect())
+        }
+        _ => Err(format!("Unsupported archive format: .{}", ext)),
+    }
+}
+
+/// Get a unique directory path, appending the duplicate suffix (" (2)", " (3)", etc. by default) if it already exists.
 fn get_unique_dir(parent: &str, name: &str) -> String {
     let base = Path::new(parent).join(name);
     if !base.exists() {
         return base.to_string_lossy().to_string();
     }
 
+    let preferences = crate::settings::preferences();
     let mut counter = 2;
     loop {
-        let candidate = Path::new(parent).join(format!("{} ({})", name, counter));
+        let candidate = Path::new(parent).join(preferences.duplicate_name(name, counter));
         if !candidate.exists() {
             return candidate.to_string_lossy().to_string();
         }
@@ -359,3 +1056,32 @@ fn flatten_single_child_dir(dir: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_kind() {
+        assert_eq!(
+            archive_kind("backup.TAR.GZ"),
+            Some((ArchiveKind::TarGz, "backup"))
+        );
+        assert_eq!(archive_kind("src.tgz"), Some((ArchiveKind::TarGz, "src")));
+        assert_eq!(
+            archive_kind("linux-6.9.tar.xz"),
+            Some((ArchiveKind::TarXz, "linux-6.9"))
+        );
+        assert_eq!(
+            archive_kind("dump.sql.gz"),
+            Some((ArchiveKind::Gz, "dump.sql"))
+        );
+        assert_eq!(archive_kind("Fotos.rar"), Some((ArchiveKind::Rar, "Fotos")));
+        assert_eq!(
+            archive_kind("ñandú.7z"),
+            Some((ArchiveKind::SevenZ, "ñandú"))
+        );
+        assert_eq!(archive_kind("notes.txt"), None);
+        assert_eq!(archive_kind("gz"), None);
+    }
+}
@@ -0,0 +1,304 @@
+//! FFmpeg Module
+//!
+//! Locates the ffmpeg binary used for video thumbnails and dimension probes.
+//! In order of preference: the path set in the settings, a copy bundled with
+//! the app (next to the executable, as Tauri installs sidecars), then
+//! `ffmpeg` on PATH. `check_ffmpeg` tells the UI which one is in use and
+//! whether it actually runs, so a missing ffmpeg can be pointed out instead
+//! of video thumbnails just not appearing.
+//!
+//! Video thumbnails are grabbed at a configurable position, either a time or
+//! a share of the clip's length, kept inside the clip for short videos.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use ts_rs::TS;
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+const BINARY_NAME: &str = "ffmpeg.exe";
+/// Where video thumbnails are grabbed unless asked otherwise
+pub const DEFAULT_SEEK: VideoSeek = VideoSeek::Seconds(2.0);
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum FfmpegSource {
+    /// The path set in the settings
+    Custom,
+    /// Shipped next to the app's executable
+    Bundled,
+    /// Whatever `ffmpeg` resolves to on PATH
+    Path,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct FfmpegStatus {
+    pub available: bool,
+    /// e.g. "6.1.1-full_build-www.gyan.dev"
+    pub version: Option<String>,
+    pub program: String,
+    pub source: FfmpegSource,
+    /// Why it couldn't be run
+    pub error: Option<String>,
+}
+
+/// Position of the frame used as a video's thumbnail
+#[derive(Serialize, Deserialize, Clone, Copy, TS, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum VideoSeek {
+    Seconds(f64),
+    /// 0-100 of the duration
+    Percent(f64),
+}
+
+impl VideoSeek {
+    /// Parses the `seek` query value of thumbnail URLs: "3.5" (seconds) or "10%"
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let seek = match value.strip_suffix('%') {
+            Some(percent) => VideoSeek::Percent(percent.trim().parse().ok()?),
+            None => VideoSeek::Seconds(value.trim_end_matches('s').parse().ok()?),
+        };
+        match seek {
+            VideoSeek::Seconds(s) | VideoSeek::Percent(s) if s.is_finite() && s >= 0.0 => {
+                Some(seek)
+            }
+            _ => None,
+        }
+    }
+
+    /// Seconds into a clip of `duration` seconds (None when unknown). Positions at or
+    /// past the end fall back to the middle of the clip, and percentages of an unknown
+    /// duration to the default position.
+    fn resolve(self, duration: Option<f64>) -> f64 {
+        let target = match (self, duration) {
+            (VideoSeek::Seconds(s), _) => s,
+            (VideoSeek::Percent(p), Some(d)) => d * p.min(100.0) / 100.0,
+            (VideoSeek::Percent(_), None) => return DEFAULT_SEEK.resolve(None),
+        };
+        match duration {
+            Some(d) if d > 0.0 && target >= d => d / 2.0,
+            _ => target,
+        }
+    }
+}
+
+impl std::fmt::Display for VideoSeek {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoSeek::Seconds(s) => write!(f, "{}s", s),
+            VideoSeek::Percent(p) => write!(f, "{}%", p),
+        }
+    }
+}
+
+/// Set once the missing-ffmpeg warning has been logged
+static REPORTED_MISSING: AtomicBool = AtomicBool::new(false);
+
+fn bundled() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    [
+        dir.join(BINARY_NAME),
+        dir.join("binaries").join(BINARY_NAME),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
+fn resolve(custom: Option<&str>) -> (PathBuf, FfmpegSource) {
+    if let Some(custom) = custom {
+        return (PathBuf::from(custom), FfmpegSource::Custom);
+    }
+    match bundled() {
+        Some(path) => (path, FfmpegSource::Bundled),
+        None => (PathBuf::from("ffmpeg"), FfmpegSource::Path),
+    }
+}
+
+/// The ffmpeg binary to run
+pub fn program() -> PathBuf {
+    resolve(crate::settings::preferences().custom_ffmpeg()).0
+}
+
+/// Logs, once per run, that ffmpeg could not be started; callers fall back silently otherwise
+pub fn report_spawn_error(program: &Path, e: &std::io::Error) {
+    if !REPORTED_MISSING.swap(true, Ordering::SeqCst) {
+        log::warn!(
+            "[FFMPEG] Could not run {}: {}. Video thumbnails and sizes need ffmpeg",
+            program.display(),
+            e
+        );
+    }
+}
+
+/// Version from the first line of `ffmpeg -version`
+fn parse_version(output: &str) -> Option<String> {
+    let rest = output
+        .lines()
+        .next()?
+        .trim()
+        .strip_prefix("ffmpeg version ")?;
+    rest.split_whitespace().next().map(str::to_string)
+}
+
+/// Seconds from the "Duration: HH:MM:SS.ss" line ffmpeg prints for an input
+fn parse_duration(stderr: &str) -> Option<f64> {
+    let rest = stderr.split("Duration: ").nth(1)?;
+    let time = rest.split(',').next()?.trim();
+    let mut seconds = 0.0;
+    for part in time.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+fn tokio_command(program: &Path) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new(program);
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+async fn probe_duration(program: &Path, path: &str) -> Option<f64> {
+    // Without an output ffmpeg exits with an error after describing the input
+    let output = tokio_command(program)
+        .arg("-hide_banner")
+        .arg("-i")
+        .arg(path)
+        .output()
+        .await
+        .ok()?;
+    parse_duration(&String::from_utf8_lossy(&output.stderr))
+}
+
+async fn grab_frame_at(
+    program: &Path,
+    path: &str,
+    seconds: f64,
+) -> std::io::Result<Option<Vec<u8>>> {
+    let output = tokio_command(program)
+        .arg("-ss")
+        .arg(format!("{:.3}", seconds))
+        .arg("-i")
+        .arg(path)
+        .arg("-vf")
+        .arg("scale=480:-1:flags=lanczos")
+        .arg("-vframes")
+        .arg("1")
+        .arg("-f")
+        .arg("image2")
+        .arg("-c:v")
+        .arg("mjpeg")
+        .arg("pipe:1")
+        .output()
+        .await?;
+    // Seeking past the end succeeds without writing a frame
+    Ok((output.status.success() && !output.stdout.is_empty()).then_some(output.stdout))
+}
+
+/// JPEG of the frame at `seek`. Percentages probe the duration first; a time past the
+/// end of a short clip is retried at its middle.
+pub async fn grab_frame(path: &str, seek: VideoSeek) -> Option<Vec<u8>> {
+    let program = program();
+    let duration = match seek {
+        VideoSeek::Percent(_) => probe_duration(&program, path).await,
+        VideoSeek::Seconds(_) => None,
+    };
+    let seconds = seek.resolve(duration);
+    match grab_frame_at(&program, path, seconds).await {
+        Ok(Some(frame)) => return Some(frame),
+        Ok(None) => {}
+        Err(e) => {
+            report_spawn_error(&program, &e);
+            return None;
+        }
+    }
+    if duration.is_some() || seconds == 0.0 {
+        return None;
+    }
+    let retry = seek.resolve(Some(probe_duration(&program, path).await?));
+    if retry >= seconds {
+        return None;
+    }
+    grab_frame_at(&program, path, retry).await.ok().flatten()
+}
+
+fn check(program: &Path) -> Result<String, String> {
+    let mut cmd = std::process::Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = cmd
+        .arg("-hide_banner")
+        .arg("-version")
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}",
+            program.display(),
+            output.status
+        ));
+    }
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("{} is not ffmpeg", program.display()))
+}
+
+/// Which ffmpeg would be used and whether it runs
+#[tauri::command]
+pub async fn check_ffmpeg() -> Result<FfmpegStatus, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let (program, source) = resolve(crate::settings::preferences().custom_ffmpeg());
+        let result = check(&program);
+        if result.is_ok() {
+            // A later failure is worth reporting again
+            REPORTED_MISSING.store(false, Ordering::SeqCst);
+        }
+        FfmpegStatus {
+            available: result.is_ok(),
+            program: program.to_string_lossy().into_owned(),
+            source,
+            version: result.as_ref().ok().cloned(),
+            error: result.err(),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        let output =
+            "ffmpeg version 6.1.1-full_build-www.gyan.dev Copyright (c) 2000-2023\nbuilt with gcc";
+        assert_eq!(
+            parse_version(output).as_deref(),
+            Some("6.1.1-full_build-www.gyan.dev")
+        );
+        assert_eq!(parse_version("ffprobe version 6.1"), None);
+        assert_eq!(
+            parse_duration("  Duration: 00:01:23.50, start: 0.000000, bitrate: 1205 kb/s"),
+            Some(83.5)
+        );
+        assert_eq!(parse_duration("  Duration: N/A, bitrate: N/A"), None);
+        assert_eq!(VideoSeek::parse("10%"), Some(VideoSeek::Percent(10.0)));
+        assert_eq!(VideoSeek::parse("3.5"), Some(VideoSeek::Seconds(3.5)));
+        assert_eq!(VideoSeek::parse("-1"), None);
+        assert_eq!(VideoSeek::Percent(25.0).resolve(Some(40.0)), 10.0);
+        assert_eq!(VideoSeek::Seconds(2.0).resolve(Some(1.0)), 0.5);
+        assert_eq!(VideoSeek::Seconds(2.0).resolve(None), 2.0);
+        assert_eq!(
+            resolve(Some("D:\\tools\\ffmpeg.exe")),
+            (PathBuf::from("D:\\tools\\ffmpeg.exe"), FfmpegSource::Custom)
+        );
+    }
+}
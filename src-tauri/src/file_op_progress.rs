@@ -0,0 +1,469 @@
+//! File Operation Progress Module
+//!
+//! IFileOperationProgressSink that mirrors copy/move/delete progress onto the
+//! taskbar button (same ProgressBarState plumbing extraction uses) and flashes
+//! the taskbar icon when a job finishes while the window is in the background.
+//! The same progress goes to the frontend as `file-op-progress` events, so it
+//! can draw its own bar instead of relying on the shell's dialog. The sink is
+//! also how the operation queue pauses and cancels a running shell operation.
+//! Each item the shell reports as done is also recorded in the operation
+//! history (see `history`), and each one refused for lack of rights or held
+//! open by another process is noted on the operation for a retry.
+
+use crate::history::{self, HistoryOperation};
+use crate::operations::OperationControl;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{Emitter, Manager, UserAttentionType};
+use ts_rs::TS;
+use windows::core::{implement, Ref, HRESULT, PCWSTR};
+use windows::Win32::Foundation::ERROR_CANCELLED;
+use windows::Win32::System::Com::CoTaskMemFree;
+use windows::Win32::UI::Shell::{
+    IFileOperation, IFileOperationProgressSink, IFileOperationProgressSink_Impl, IShellItem,
+    SIGDN_FILESYSPATH,
+};
+
+/// Minimum time between two `file-op-progress` events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a finished operation's full bar stays up, so Windows can animate it
+const FULL_BAR_DELAY: Duration = Duration::from_millis(200);
+
+/// Bumped on every taskbar change, so a delayed clear can tell a newer operation took over
+static TASKBAR_UPDATES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct FileOpProgress {
+    /// Id in the operation queue
+    pub operation_id: String,
+    pub operation: HistoryOperation,
+    /// Estimated from the shell's progress and the size of the sources
+    #[ts(type = "number")]
+    pub bytes_done: u64,
+    /// 0 until the sources have been measured
+    #[ts(type = "number")]
+    pub bytes_total: u64,
+    pub percentage: f32,
+    /// The item the shell is working on
+    pub current_item: Option<String>,
+    /// Average since the operation started, not counting time spent in shell dialogs
+    #[ts(type = "number")]
+    pub bytes_per_second: u64,
+    pub finished: bool,
+}
+
+fn main_window() -> Option<tauri::WebviewWindow> {
+    crate::APP_HANDLE.get()?.get_webview_window("main")
+}
+
+fn set_taskbar_state(progress: Option<u64>, status: ProgressBarStatus) {
+    TASKBAR_UPDATES.fetch_add(1, Ordering::SeqCst);
+    if let Some(window) = main_window() {
+        let _ = window.set_progress_bar(ProgressBarState {
+            progress,
+            status: Some(status),
+        });
+    }
+}
+
+/// File system path of a shell item, if it has one
+fn item_path(item: &Ref<'_, IShellItem>) -> Option<String> {
+    unsafe {
+        let path = item.as_ref()?.GetDisplayName(SIGDN_FILESYSPATH).ok()?;
+        let result = path.to_string().ok();
+        CoTaskMemFree(Some(path.as_ptr() as *const _));
+        result
+    }
+}
+
+/// What the sink has seen of the running operation
+#[derive(Default)]
+struct ProgressState {
+    work_done: u32,
+    work_total: u32,
+    current_item: Option<String>,
+    started: Option<Instant>,
+    /// Set while a conflict/confirmation dialog is up
+    paused_at: Option<Instant>,
+    paused: Duration,
+    last_emit: Option<Instant>,
+}
+
+#[implement(IFileOperationProgressSink)]
+struct FileOpProgressSink {
+    operation: HistoryOperation,
+    control: Arc<OperationControl>,
+    /// Size of the sources, filled in by a background walk (0 = not known yet)
+    bytes_total: Arc<AtomicU64>,
+    /// Last percentage pushed to the taskbar (u32::MAX = nothing reported yet)
+    last_pct: AtomicU32,
+    state: Mutex<ProgressState>,
+}
+
+impl FileOpProgressSink {
+    fn new(
+        operation: HistoryOperation,
+        sources: &[String],
+        control: &Arc<OperationControl>,
+    ) -> Self {
+        let bytes_total = Arc::new(AtomicU64::new(0));
+        let measured = Arc::clone(&bytes_total);
+        let sources: Vec<String> = sources.to_vec();
+        // The shell pre-scans too but doesn't share byte counts; measure alongside it
+        std::thread::spawn(move || {
+            let total = sources
+                .iter()
+                .map(|s| crate::cleanup::path_size(std::path::Path::new(s)))
+                .sum();
+            measured.store(total, Ordering::Relaxed);
+        });
+        Self {
+            operation,
+            control: Arc::clone(control),
+            bytes_total,
+            last_pct: AtomicU32::new(u32::MAX),
+            state: Mutex::new(ProgressState::default()),
+        }
+    }
+
+    /// Sends a `file-op-progress` event, at most every `PROGRESS_INTERVAL` unless `finished`
+    fn emit_progress(&self, finished: bool) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        if !finished
+            && state
+                .last_emit
+                .is_some_and(|last| now.duration_since(last) < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        state.last_emit = Some(now);
+
+        let fraction = if finished {
+            1.0
+        } else if state.work_total > 0 {
+            (state.work_done as f64 / state.work_total as f64).min(1.0)
+        } else {
+            0.0
+        };
+        let bytes_total = self.bytes_total.load(Ordering::Relaxed);
+        let bytes_done = (bytes_total as f64 * fraction) as u64;
+        let active = state
+            .started
+            .map(|start| now.duration_since(start).saturating_sub(state.paused))
+            .unwrap_or_default();
+        let bytes_per_second = if active.as_secs_f64() > 0.0 {
+            (bytes_done as f64 / active.as_secs_f64()) as u64
+        } else {
+            0
+        };
+
+        if let Some(window) = main_window() {
+            let _ = window.emit(
+                "file-op-progress",
+                FileOpProgress {
+                    operation_id: self.control.id().to_string(),
+                    operation: self.operation,
+                    bytes_done,
+                    bytes_total,
+                    percentage: (fraction * 100.0) as f32,
+                    current_item: state.current_item.clone(),
+                    bytes_per_second,
+                    finished,
+                },
+            );
+        }
+    }
+
+    fn set_current_item(&self, item: &Ref<'_, IShellItem>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.current_item = item_path(item);
+        }
+    }
+
+    fn pause_timer(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.paused_at.get_or_insert_with(Instant::now);
+        }
+        set_taskbar_state(None, ProgressBarStatus::Paused);
+    }
+
+    fn resume_timer(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(paused_at) = state.paused_at.take() {
+                state.paused += paused_at.elapsed();
+            }
+        }
+        let last = self.last_pct.load(Ordering::Relaxed);
+        if last == u32::MAX {
+            set_taskbar_state(None, ProgressBarStatus::Indeterminate);
+        } else {
+            set_taskbar_state(Some(last as u64), ProgressBarStatus::Normal);
+        }
+    }
+
+    /// Logs an item the shell finished with; skipped and failed items are not logged
+    fn record(
+        &self,
+        result: HRESULT,
+        operation: HistoryOperation,
+        source: Option<String>,
+        destination: Option<String>,
+    ) {
+        if result.is_ok() {
+            history::record(operation, source.as_deref(), destination.as_deref());
+        } else if let Some(source) = source {
+            if crate::elevation::is_access_denied(result) {
+                self.control.note_access_denied(source);
+            } else if crate::locks::is_sharing_violation(result) {
+                self.control.note_locked(source);
+            }
+        }
+    }
+
+    /// Blocks while the queue has the operation paused. The error returned once it is
+    /// cancelled makes the shell abort the operation.
+    fn check_control(&self) -> windows_core::Result<()> {
+        if self.control.is_paused() {
+            self.pause_timer();
+            let resumed = self.control.wait_while_paused();
+            self.resume_timer();
+            if resumed {
+                return Ok(());
+            }
+        }
+        if self.control.is_cancelled() {
+            return Err(ERROR_CANCELLED.to_hresult().into());
+        }
+        Ok(())
+    }
+}
+
+/// Registers a progress sink for an `operation` on `sources` on `file_op`, run under
+/// `control`. The operation object is single-use, so the sink simply lives until it
+/// is released.
+pub unsafe fn attach(
+    file_op: &IFileOperation,
+    operation: HistoryOperation,
+    sources: &[String],
+    control: &Arc<OperationControl>,
+) {
+    let sink: IFileOperationProgressSink =
+        FileOpProgressSink::new(operation, sources, control).into();
+    if let Err(e) = file_op.Advise(&sink) {
+        log::warn!("[FILE-OP] Failed to register progress sink: {}", e);
+    }
+}
+
+impl IFileOperationProgressSink_Impl for FileOpProgressSink_Impl {
+    fn StartOperations(&self) -> windows_core::Result<()> {
+        if let Ok(mut state) = self.state.lock() {
+            state.started = Some(Instant::now());
+        }
+        // Total work is unknown until the shell finishes its pre-scan
+        set_taskbar_state(None, ProgressBarStatus::Indeterminate);
+        Ok(())
+    }
+
+    fn FinishOperations(&self, hrresult: HRESULT) -> windows_core::Result<()> {
+        if hrresult.is_ok() {
+            // Cleared off the operation thread, unless another operation has shown progress since
+            set_taskbar_state(Some(100), ProgressBarStatus::Normal);
+            let shown = TASKBAR_UPDATES.load(Ordering::SeqCst);
+            std::thread::spawn(move || {
+                std::thread::sleep(FULL_BAR_DELAY);
+                if TASKBAR_UPDATES.load(Ordering::SeqCst) == shown {
+                    set_taskbar_state(None, ProgressBarStatus::None);
+                }
+            });
+        } else {
+            set_taskbar_state(None, ProgressBarStatus::None);
+        }
+        self.emit_progress(true);
+
+        if let Some(window) = main_window() {
+            if !window.is_focused().unwrap_or(true) {
+                let _ = window.request_user_attention(Some(UserAttentionType::Informational));
+            }
+        }
+        Ok(())
+    }
+
+    fn UpdateProgress(&self, iworktotal: u32, iworksofar: u32) -> windows_core::Result<()> {
+        if iworktotal == 0 {
+            return Ok(());
+        }
+        if let Ok(mut state) = self.state.lock() {
+            state.work_done = iworksofar;
+            state.work_total = iworktotal;
+        }
+        self.emit_progress(false);
+        self.check_control()?;
+        let pct = ((iworksofar as f64 / iworktotal as f64) * 100.0).min(100.0) as u32;
+        // Only touch the taskbar when the percentage actually changes
+        if self.last_pct.swap(pct, Ordering::Relaxed) != pct {
+            set_taskbar_state(Some(pct as u64), ProgressBarStatus::Normal);
+        }
+        Ok(())
+    }
+
+    fn PauseTimer(&self) -> windows_core::Result<()> {
+        // The shell pauses the timer while a conflict/confirmation dialog is up
+        self.pause_timer();
+        Ok(())
+    }
+
+    fn ResumeTimer(&self) -> windows_core::Result<()> {
+        self.resume_timer();
+        Ok(())
+    }
+
+    fn ResetTimer(&self) -> windows_core::Result<()> {
+        Ok(())
+    }
+
+    fn PreRenameItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+    ) -> windows_core::Result<()> {
+        self.set_current_item(&psiitem);
+        self.check_control()
+    }
+
+    fn PostRenameItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+        hrrename: HRESULT,
+        psinewlycreated: Ref<'_, IShellItem>,
+    ) -> windows_core::Result<()> {
+        self.record(
+            hrrename,
+            HistoryOperation::Rename,
+            item_path(&psiitem),
+            item_path(&psinewlycreated),
+        );
+        Ok(())
+    }
+
+    fn PreMoveItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+        _psidestinationfolder: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+    ) -> windows_core::Result<()> {
+        self.set_current_item(&psiitem);
+        self.check_control()
+    }
+
+    fn PostMoveItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+        _psidestinationfolder: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+        hrmove: HRESULT,
+        psinewlycreated: Ref<'_, IShellItem>,
+    ) -> windows_core::Result<()> {
+        self.record(
+            hrmove,
+            HistoryOperation::Move,
+            item_path(&psiitem),
+            item_path(&psinewlycreated),
+        );
+        Ok(())
+    }
+
+    fn PreCopyItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+        _psidestinationfolder: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+    ) -> windows_core::Result<()> {
+        self.set_current_item(&psiitem);
+        self.check_control()
+    }
+
+    fn PostCopyItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+        _psidestinationfolder: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+        hrcopy: HRESULT,
+        psinewlycreated: Ref<'_, IShellItem>,
+    ) -> windows_core::Result<()> {
+        self.record(
+            hrcopy,
+            HistoryOperation::Copy,
+            item_path(&psiitem),
+            item_path(&psinewlycreated),
+        );
+        Ok(())
+    }
+
+    fn PreDeleteItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+    ) -> windows_core::Result<()> {
+        self.set_current_item(&psiitem);
+        self.check_control()
+    }
+
+    fn PostDeleteItem(
+        &self,
+        _dwflags: u32,
+        psiitem: Ref<'_, IShellItem>,
+        hrdelete: HRESULT,
+        _psinewlycreated: Ref<'_, IShellItem>,
+    ) -> windows_core::Result<()> {
+        // psinewlycreated is the item's copy in the Recycle Bin, not a place to jump to
+        self.record(
+            hrdelete,
+            HistoryOperation::Delete,
+            item_path(&psiitem),
+            None,
+        );
+        Ok(())
+    }
+
+    fn PreNewItem(
+        &self,
+        _dwflags: u32,
+        _psidestinationfolder: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+    ) -> windows_core::Result<()> {
+        Ok(())
+    }
+
+    fn PostNewItem(
+        &self,
+        _dwflags: u32,
+        _psidestinationfolder: Ref<'_, IShellItem>,
+        _psznewname: &PCWSTR,
+        _psztemplatename: &PCWSTR,
+        _dwfileattributes: u32,
+        hrnew: HRESULT,
+        psinewitem: Ref<'_, IShellItem>,
+    ) -> windows_core::Result<()> {
+        self.record(
+            hrnew,
+            HistoryOperation::Create,
+            None,
+            item_path(&psinewitem),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+//! File Stream Module
+//!
+//! Serves file contents to the webview over the `stream:` protocol with HTTP
+//! range support, so the preview pane can play and seek multi-GB videos
+//! without the file ever being held in memory. The UI builds the URLs with
+//! `convertFileSrc(path, "stream")`, which puts the percent-encoded path in
+//! the URL path. Each response carries at most `MAX_CHUNK` bytes; media
+//! elements request the rest as they need it. Paths outside
+//! `assetProtocol.scope` in tauri.conf.json are refused, the same as for the
+//! `asset:` protocol this replaces.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::scope::fs::Scope;
+
+/// Largest body sent in one response
+const MAX_CHUNK: u64 = 4 * 1024 * 1024;
+
+/// Decodes `%XX` escapes; None when the result isn't valid UTF-8
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Inclusive byte range requested by a `Range` header for a file of `len` bytes.
+/// Only the first range of a multi-range request is served. None means unsatisfiable.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    let first = spec.split(',').next()?.trim();
+    let (start, end) = first.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        // "bytes=-500": the last 500 bytes
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 {
+                return None;
+            }
+            (len.saturating_sub(suffix), len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => (
+            start.parse().ok()?,
+            end.parse::<u64>().ok()?.min(len.checked_sub(1)?),
+        ),
+    };
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn mime_type(path: &str) -> &'static str {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match ext.as_str() {
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        "mov" => "video/quicktime",
+        "avi" => "video/x-msvideo",
+        "wmv" => "video/x-ms-wmv",
+        "mpg" | "mpeg" => "video/mpeg",
+        "ogv" => "video/ogg",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "aac" => "audio/aac",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "ogg" | "oga" => "audio/ogg",
+        "opus" => "audio/opus",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "pdf" => "application/pdf",
+        "txt" | "log" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+fn empty(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder().status(status).body(Vec::new()).unwrap()
+}
+
+fn read_range(path: &str, start: u64, end: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut data = Vec::with_capacity((end - start + 1) as usize);
+    file.take(end - start + 1).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Answers one `stream:` request for a path inside `scope`; blocking, so run it
+/// off the async runtime
+pub fn handle(request: &Request<Vec<u8>>, scope: &Scope) -> Response<Vec<u8>> {
+    let Some(path) = percent_decode(request.uri().path().trim_start_matches('/')) else {
+        return empty(StatusCode::BAD_REQUEST);
+    };
+    if !scope.is_allowed(&path) {
+        log::warn!(
+            "[STREAM] Refused {}: outside the asset protocol scope",
+            path
+        );
+        return empty(StatusCode::FORBIDDEN);
+    }
+    let len = match std::fs::metadata(&path) {
+        Ok(meta) if meta.is_file() => meta.len(),
+        _ => return empty(StatusCode::NOT_FOUND),
+    };
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok());
+    let (start, end) = match range {
+        Some(value) => match parse_range(value, len) {
+            Some(range) => range,
+            None => {
+                let mut response = empty(StatusCode::RANGE_NOT_SATISFIABLE);
+                if let Ok(value) = format!("bytes */{}", len).parse() {
+                    response.headers_mut().insert(header::CONTENT_RANGE, value);
+                }
+                return response;
+            }
+        },
+        None if len == 0 => (0, 0),
+        None => (0, len - 1),
+    };
+    // Too-large requests are cut short; a 206 tells the client where to continue
+    let end = end.min(start + MAX_CHUNK - 1);
+    let partial = range.is_some() || end + 1 < len;
+
+    let data = if len == 0 {
+        Vec::new()
+    } else {
+        match read_range(&path, start, end) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("[STREAM] Failed to read {}: {}", path, e);
+                return empty(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    };
+
+    let mut builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type(&path))
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CONTENT_LENGTH, data.len());
+    builder = if partial {
+        builder.status(StatusCode::PARTIAL_CONTENT).header(
+            header::CONTENT_RANGE,
+            format!("bytes {}-{}/{}", start, end, len),
+        )
+    } else {
+        builder.status(StatusCode::OK)
+    };
+    builder
+        .body(data)
+        .unwrap_or_else(|_| empty(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-", 1000), Some((0, 999)));
+        assert_eq!(parse_range("bytes=100-199", 1000), Some((100, 199)));
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=0-9, 20-29", 1000), Some((0, 9)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=0-", 0), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+        assert_eq!(
+            percent_decode("C%3A%5CVideos%5Cmy%20clip.mp4").as_deref(),
+            Some("C:\\Videos\\my clip.mp4")
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Folder Size Module
+//!
+//! Real sizes for folders, whose `FileEntry.size` is always 0. `get_folder_size`
+//! walks the tree in parallel on the rayon pool, sending running totals as
+//! `folder-size-progress` events, and resolves with the final size. Results are
+//! cached by path and last-modified time, so reselecting an unchanged folder
+//! answers at once. A folder's time only changes when its direct children do,
+//! so a cached size can miss edits made deeper down.
+//!
+//! Symbolic links and junctions are not followed, so nothing is counted twice.
+
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime};
+use tauri::Emitter;
+use ts_rs::TS;
+
+/// Minimum time between two `folder-size-progress` events of one walk
+const PROGRESS_INTERVAL_MS: u128 = 200;
+/// Cached folders before the cache starts over
+const MAX_CACHED: usize = 1000;
+
+#[derive(Serialize, Clone, TS, Debug, PartialEq, Eq)]
+#[ts(export)]
+pub struct FolderSize {
+    pub path: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    #[ts(type = "number")]
+    pub file_count: u64,
+    #[ts(type = "number")]
+    pub folder_count: u64,
+    /// False while the walk is still running
+    pub finished: bool,
+}
+
+fn cache() -> &'static Mutex<HashMap<String, (SystemTime, FolderSize)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (SystemTime, FolderSize)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Running totals shared by the walker threads
+struct Totals {
+    path: String,
+    size: AtomicU64,
+    files: AtomicU64,
+    folders: AtomicU64,
+    last_emit: Mutex<Instant>,
+}
+
+impl Totals {
+    fn snapshot(&self, finished: bool) -> FolderSize {
+        FolderSize {
+            path: self.path.clone(),
+            size: self.size.load(Ordering::Relaxed),
+            file_count: self.files.load(Ordering::Relaxed),
+            folder_count: self.folders.load(Ordering::Relaxed),
+            finished,
+        }
+    }
+
+    /// Sends the totals so far unless another thread just did
+    fn maybe_emit(&self) {
+        let Ok(mut last) = self.last_emit.try_lock() else {
+            return;
+        };
+        if last.elapsed().as_millis() < PROGRESS_INTERVAL_MS {
+            return;
+        }
+        *last = Instant::now();
+        drop(last);
+        if let Some(app) = crate::APP_HANDLE.get() {
+            let _ = app.emit("folder-size-progress", self.snapshot(false));
+        }
+    }
+}
+
+/// Adds up the files directly in `dir`, then walks its subfolders in parallel
+fn walk(dir: &Path, totals: &Totals) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut subdirs: Vec<PathBuf> = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        // DirEntry::file_type doesn't follow links
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            subdirs.push(entry.path());
+        } else if file_type.is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                totals.size.fetch_add(metadata.len(), Ordering::Relaxed);
+                totals.files.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+    totals
+        .folders
+        .fetch_add(subdirs.len() as u64, Ordering::Relaxed);
+    totals.maybe_emit();
+    subdirs.par_iter().for_each(|subdir| walk(subdir, totals));
+}
+
+fn folder_size(path: &str) -> Result<FolderSize, String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a folder", path));
+    }
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    if let Ok(cache) = cache().lock() {
+        if let Some((cached_modified, size)) = cache.get(path) {
+            if *cached_modified == modified {
+                return Ok(size.clone());
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let totals = Totals {
+        path: path.to_string(),
+        size: AtomicU64::new(0),
+        files: AtomicU64::new(0),
+        folders: AtomicU64::new(0),
+        last_emit: Mutex::new(Instant::now()),
+    };
+    walk(Path::new(path), &totals);
+    let size = totals.snapshot(true);
+    log::info!(
+        "[FOLDER_SIZE] {}: {} bytes in {} files, {} folders ({:?})",
+        path,
+        size.size,
+        size.file_count,
+        size.folder_count,
+        start.elapsed()
+    );
+
+    if let Ok(mut cache) = cache().lock() {
+        if cache.len() >= MAX_CACHED {
+            cache.clear();
+        }
+        cache.insert(path.to_string(), (modified, size.clone()));
+    }
+    Ok(size)
+}
+
+/// Total size of everything beneath `path`, with `folder-size-progress` events
+/// while the walk runs
+#[tauri::command]
+pub async fn get_folder_size(path: String) -> Result<FolderSize, String> {
+    let path = crate::expand_env_vars(&path);
+    tokio::task::spawn_blocking(move || folder_size(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folder_size() {
+        let root = std::env::temp_dir().join(format!("qe-folder-size-{}", std::process::id()));
+        let nested = root.join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join("one.txt"), vec![0u8; 10]).unwrap();
+        std::fs::write(nested.join("two.txt"), vec![0u8; 32]).unwrap();
+
+        let size = folder_size(&root.to_string_lossy()).unwrap();
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(size.size, 42);
+        assert_eq!(size.file_count, 2);
+        assert_eq!(size.folder_count, 2);
+        assert!(size.finished);
+    }
+}
@@ -0,0 +1,278 @@
+//! Frecency Module
+//!
+//! Remembers which folders the user opens and how recently, persisted to
+//! `frecency.json` in the app data folder. Search ranking uses the resulting
+//! score to lift results that live in folders the user actually works in;
+//! `get_frequent_folders` lists the top folders for the Home view and the
+//! path bar suggestions. Visits are saved by a background thread once the
+//! user settles, and `flush` writes what is left when the window closes.
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use ts_rs::TS;
+
+/// Keep the file small: least valuable entries are dropped past this size
+const MAX_ENTRIES: usize = 1000;
+/// Listings of the same folder within this window count as one visit
+const REVISIT_WINDOW_SECS: i64 = 60;
+/// Quiet period after the last recorded visit before the store is written
+const SAVE_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VisitStats {
+    pub visit_count: u32,
+    /// Unix seconds of the most recent visit
+    pub last_visit: i64,
+    /// Path as last opened, with its original casing (keys are lowercased)
+    #[serde(default)]
+    pub path: String,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct FrequentFolder {
+    pub path: String,
+    pub visit_count: u32,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub last_visit: i64,
+    pub score: f64,
+}
+
+impl VisitStats {
+    /// Visit count weighted by how recently the folder was opened
+    pub fn score(&self, now: i64) -> f64 {
+        let age_days = (now - self.last_visit).max(0) as f64 / 86_400.0;
+        let recency_weight = if age_days < 1.0 {
+            100.0
+        } else if age_days < 4.0 {
+            80.0
+        } else if age_days < 14.0 {
+            60.0
+        } else if age_days < 31.0 {
+            40.0
+        } else if age_days < 90.0 {
+            20.0
+        } else {
+            10.0
+        };
+        (1.0 + self.visit_count as f64).ln() * recency_weight
+    }
+}
+
+pub struct FrecencyStore {
+    /// Keyed by lowercased path without trailing separator
+    folders: RwLock<HashMap<String, VisitStats>>,
+    storage_file: PathBuf,
+    /// Set by `record_visit` until the change is on disk
+    dirty: AtomicBool,
+    /// Wakes the writer thread on every change
+    changed: Mutex<Sender<()>>,
+}
+
+static INSTANCE: OnceLock<Arc<FrecencyStore>> = OnceLock::new();
+
+/// Normalizes a path into the store's key format
+pub fn folder_key(path: &str) -> String {
+    let trimmed = path.trim_end_matches(['\\', '/']);
+    // Keep "C:\" rather than the drive-relative "C:"
+    let trimmed = if trimmed.ends_with(':') {
+        path
+    } else {
+        trimmed
+    };
+    trimmed.replace('/', "\\").to_lowercase()
+}
+
+impl FrecencyStore {
+    pub fn global() -> Arc<Self> {
+        INSTANCE
+            .get_or_init(|| {
+                let storage_file = crate::paths::app_data_dir().join("frecency.json");
+                let folders = std::fs::read(&storage_file)
+                    .ok()
+                    .and_then(|data| serde_json::from_slice(&data).ok())
+                    .unwrap_or_default();
+                let (tx, rx) = channel::<()>();
+                std::thread::spawn(move || {
+                    while rx.recv().is_ok() {
+                        // Keep waiting while visits keep coming
+                        loop {
+                            match rx.recv_timeout(SAVE_DELAY) {
+                                Ok(()) => continue,
+                                Err(RecvTimeoutError::Timeout) => break,
+                                Err(RecvTimeoutError::Disconnected) => return,
+                            }
+                        }
+                        flush();
+                    }
+                });
+                Arc::new(FrecencyStore {
+                    folders: RwLock::new(folders),
+                    storage_file,
+                    dirty: AtomicBool::new(false),
+                    changed: Mutex::new(tx),
+                })
+            })
+            .clone()
+    }
+
+    /// Records a visit to `path`; the store is saved in the background
+    pub fn record_visit(&self, path: &str) {
+        // Virtual locations (drive list, Recycle Bin) are not worth ranking
+        if path.is_empty() || path.starts_with("shell:") {
+            return;
+        }
+        let now = chrono::Utc::now().timestamp();
+        {
+            let mut folders = self.folders.write();
+            let stats = folders.entry(folder_key(path)).or_insert(VisitStats {
+                visit_count: 0,
+                last_visit: now,
+                path: String::new(),
+            });
+            // Refreshes and quick back/forward hops re-list the same folder; count them once
+            let is_new_visit =
+                stats.visit_count == 0 || now - stats.last_visit >= REVISIT_WINDOW_SECS;
+            if !is_new_visit && stats.path == path {
+                return;
+            }
+            if is_new_visit {
+                stats.visit_count = stats.visit_count.saturating_add(1);
+                stats.last_visit = now;
+            }
+            stats.path = path.to_string();
+
+            if folders.len() > MAX_ENTRIES {
+                let mut ranked: Vec<(String, f64)> = folders
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.score(now)))
+                    .collect();
+                ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+                for (key, _) in ranked.into_iter().take(folders.len() - MAX_ENTRIES) {
+                    folders.remove(&key);
+                }
+            }
+        }
+        self.dirty.store(true, Ordering::SeqCst);
+        let _ = self.changed.lock().send(());
+    }
+
+    fn save(&self) {
+        let data = match serde_json::to_vec(&*self.folders.read()) {
+            Ok(d) => d,
+            Err(e) => {
+                log::error!("[FRECENCY] Failed to serialize store: {}", e);
+                return;
+            }
+        };
+        // Write then rename so a crash never leaves a truncated file behind
+        let tmp_file = self.storage_file.with_extension("json.tmp");
+        let result = std::fs::write(&tmp_file, data)
+            .and_then(|_| std::fs::rename(&tmp_file, &self.storage_file));
+        if let Err(e) = result {
+            log::error!(
+                "[FRECENCY] Failed to write {}: {}",
+                self.storage_file.display(),
+                e
+            );
+        }
+    }
+
+    /// The whole store as JSON, for backups
+    pub fn to_json(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(&*self.folders.read()).map_err(|e| e.to_string())
+    }
+
+    /// Replaces the visit history with a backup made by `to_json`
+    pub fn replace_from_json(&self, data: &[u8]) -> Result<(), String> {
+        let folders: HashMap<String, VisitStats> = serde_json::from_slice(data)
+            .map_err(|e| format!("Invalid folder history backup: {}", e))?;
+        *self.folders.write() = folders;
+        self.dirty.store(false, Ordering::SeqCst);
+        self.save();
+        Ok(())
+    }
+
+    /// Scores of every known folder, for ranking many candidates without re-locking
+    pub fn snapshot(&self) -> HashMap<String, f64> {
+        let now = chrono::Utc::now().timestamp();
+        self.folders
+            .read()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.score(now)))
+            .collect()
+    }
+
+    /// Highest-scoring folders, best first, optionally only those under `prefix`
+    pub fn top(&self, limit: usize, prefix: Option<&str>) -> Vec<FrequentFolder> {
+        let now = chrono::Utc::now().timestamp();
+        let prefix = prefix.map(folder_key);
+        let mut ranked: Vec<FrequentFolder> = self
+            .folders
+            .read()
+            .iter()
+            .filter(|(key, _)| prefix.as_ref().is_none_or(|p| key.starts_with(p.as_str())))
+            .map(|(key, stats)| FrequentFolder {
+                // Entries saved before paths were kept only have the lowercased key
+                path: if stats.path.is_empty() {
+                    key.clone()
+                } else {
+                    stats.path.clone()
+                },
+                visit_count: stats.visit_count,
+                last_visit: stats.last_visit,
+                score: stats.score(now),
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+/// Writes visits not yet on disk right away; called when the window closes
+pub fn flush() {
+    if let Some(store) = INSTANCE.get() {
+        if store.dirty.swap(false, Ordering::SeqCst) {
+            store.save();
+        }
+    }
+}
+
+/// Most frecent folders that still exist; `prefix` narrows them for path bar suggestions
+#[tauri::command]
+pub fn get_frequent_folders(limit: usize, prefix: Option<String>) -> Vec<FrequentFolder> {
+    // Ask for a few extra so deleted folders don't leave the list short
+    FrecencyStore::global()
+        .top(limit.saturating_mul(2), prefix.as_deref())
+        .into_iter()
+        .filter(|f| Path::new(&f.path).is_dir())
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_prefers_recent_and_frequent() {
+        let now = 1_700_000_000;
+        let stats = |visit_count, age_days: i64| VisitStats {
+            visit_count,
+            last_visit: now - age_days * 86_400,
+            path: String::new(),
+        };
+        assert!(stats(10, 0).score(now) > stats(2, 0).score(now));
+        assert!(stats(5, 0).score(now) > stats(5, 30).score(now));
+        // A daily haunt from months ago loses to this week's folder
+        assert!(stats(3, 2).score(now) > stats(20, 200).score(now));
+    }
+}
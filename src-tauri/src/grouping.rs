@@ -0,0 +1,180 @@
+//! Grouping Module
+//!
+//! Group-by for list views: by type, by date bucket ("Today", "Last week"),
+//! by Explorer's size ranges or by first letter. `list_files` orders a sorted
+//! listing by group, keeping the sort order inside each group, and returns
+//! the groups as ranges of that listing so the UI only draws the headers.
+
+use crate::i18n::{tr, Text};
+use crate::FileEntry;
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+const KB: u64 = 1024;
+const MB: u64 = 1024 * KB;
+const GB: u64 = 1024 * MB;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    Type,
+    /// Modification time
+    Date,
+    Size,
+    /// First letter of the name
+    Letter,
+}
+
+#[derive(Serialize, Clone, TS, Debug, PartialEq)]
+#[ts(export)]
+pub struct ListingGroup {
+    /// Stable id of the group, e.g. "today" or "text document"
+    pub key: String,
+    /// Header in the app's language
+    pub label: String,
+    /// Index of the group's first entry in the whole (unpaged) listing
+    pub start: usize,
+    pub count: usize,
+}
+
+/// Where an entry goes; groups are ordered by rank, then by key
+struct Bucket {
+    rank: u32,
+    key: String,
+    label: String,
+}
+
+impl Bucket {
+    fn new(rank: u32, key: &str, label: &str) -> Self {
+        Bucket {
+            rank,
+            key: key.to_string(),
+            label: label.to_string(),
+        }
+    }
+}
+
+/// Rank, key and header of the date bucket `date` falls in, newest first
+fn date_bucket(date: NaiveDate, today: NaiveDate) -> (u32, &'static str, Text) {
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = today.with_day(1).unwrap_or(today);
+    let last_month_start = (month_start - Duration::days(1))
+        .with_day(1)
+        .unwrap_or(month_start);
+    let year_start = today.with_ordinal(1).unwrap_or(today);
+    if date >= today {
+        (0, "today", Text::Today)
+    } else if date == today - Duration::days(1) {
+        (1, "yesterday", Text::Yesterday)
+    } else if date >= week_start {
+        (2, "earlier_this_week", Text::EarlierThisWeek)
+    } else if date >= week_start - Duration::days(7) {
+        (3, "last_week", Text::LastWeek)
+    } else if date >= month_start {
+        (4, "earlier_this_month", Text::EarlierThisMonth)
+    } else if date >= last_month_start {
+        (5, "last_month", Text::LastMonth)
+    } else if date >= year_start {
+        (6, "earlier_this_year", Text::EarlierThisYear)
+    } else {
+        (7, "long_ago", Text::LongAgo)
+    }
+}
+
+/// Rank, key and header of Explorer's size range for `size` bytes
+fn size_bucket(size: u64) -> (u32, &'static str, Text) {
+    match size {
+        0 => (1, "empty", Text::SizeEmpty),
+        s if s < 16 * KB => (2, "tiny", Text::SizeTiny),
+        s if s < MB => (3, "small", Text::SizeSmall),
+        s if s < 128 * MB => (4, "medium", Text::SizeMedium),
+        s if s < GB => (5, "large", Text::SizeLarge),
+        s if s < 4 * GB => (6, "huge", Text::SizeHuge),
+        _ => (7, "gigantic", Text::SizeGigantic),
+    }
+}
+
+fn bucket(entry: &FileEntry, group_by: GroupBy, today: NaiveDate) -> Bucket {
+    match group_by {
+        GroupBy::Type => Bucket {
+            // Folders before files, then alphabetically
+            rank: u32::from(!entry.is_dir),
+            key: entry.file_type.to_lowercase(),
+            label: entry.file_type.clone(),
+        },
+        GroupBy::Date => {
+            let date = Local
+                .timestamp_opt(entry.modified_timestamp, 0)
+                .single()
+                .map(|d| d.date_naive())
+                .unwrap_or(NaiveDate::MIN);
+            let (rank, key, text) = date_bucket(date, today);
+            Bucket::new(rank, key, tr(text))
+        }
+        GroupBy::Size if entry.is_dir => Bucket::new(0, "folders", tr(Text::Folders)),
+        GroupBy::Size => {
+            let (rank, key, text) = size_bucket(entry.size);
+            Bucket::new(rank, key, tr(text))
+        }
+        GroupBy::Letter => match entry.name.chars().next() {
+            Some(c) if c.is_alphabetic() => {
+                let letter: String = c.to_uppercase().collect();
+                Bucket::new(1, &letter, &letter)
+            }
+            Some(c) if c.is_numeric() => Bucket::new(0, "0-9", "0-9"),
+            _ => Bucket::new(0, "#", "#"),
+        },
+    }
+}
+
+/// Orders `entries` by group, keeping their order within each group, and returns the groups
+pub fn group_entries(entries: &mut Vec<FileEntry>, group_by: GroupBy) -> Vec<ListingGroup> {
+    let today = Local::now().date_naive();
+    let mut keyed: Vec<(Bucket, FileEntry)> = std::mem::take(entries)
+        .into_iter()
+        .map(|entry| (bucket(&entry, group_by, today), entry))
+        .collect();
+    // Stable, so the listing's sort order holds inside each group
+    keyed.sort_by(|(a, _), (b, _)| (a.rank, &a.key).cmp(&(b.rank, &b.key)));
+
+    let mut groups: Vec<ListingGroup> = Vec::new();
+    for (index, (bucket, _)) in keyed.iter().enumerate() {
+        match groups.last_mut() {
+            Some(group) if group.key == bucket.key => group.count += 1,
+            _ => groups.push(ListingGroup {
+                key: bucket.key.clone(),
+                label: bucket.label.clone(),
+                start: index,
+                count: 1,
+            }),
+        }
+    }
+    *entries = keyed.into_iter().map(|(_, entry)| entry).collect();
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let key = |y, m, d| date_bucket(NaiveDate::from_ymd_opt(y, m, d).unwrap(), today).1;
+        assert_eq!(key(2024, 5, 15), "today");
+        assert_eq!(key(2024, 5, 14), "yesterday");
+        assert_eq!(key(2024, 5, 13), "earlier_this_week");
+        assert_eq!(key(2024, 5, 6), "last_week");
+        assert_eq!(key(2024, 5, 2), "earlier_this_month");
+        assert_eq!(key(2024, 4, 1), "last_month");
+        assert_eq!(key(2024, 1, 1), "earlier_this_year");
+        assert_eq!(key(2023, 12, 31), "long_ago");
+
+        assert_eq!(size_bucket(0).1, "empty");
+        assert_eq!(size_bucket(16 * KB - 1).1, "tiny");
+        assert_eq!(size_bucket(16 * KB).1, "small");
+        assert_eq!(size_bucket(5 * GB).1, "gigantic");
+    }
+}
@@ -0,0 +1,370 @@
+//! Hashing Module
+//!
+//! Streaming file hashes shared by the duplicate finder and the checksum
+//! commands. Files are read in fixed-size chunks so memory stays flat no
+//! matter how large the file is, and every chunk checks the cancel flag.
+//! All requested algorithms are fed from the same read, so asking for
+//! MD5 + SHA-256 costs one pass over the file, not two.
+//!
+//! `compute_hash` runs as a background job: it returns a job id at once and
+//! reports through `hash-progress`, `hash-result` and `hash-finished` events.
+
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tauri::Emitter;
+use ts_rs::TS;
+
+const CHUNK_SIZE: usize = 256 * 1024;
+/// Minimum time between two `hash-progress` events of one job
+const PROGRESS_INTERVAL_MS: u128 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Crc32,
+    Blake3,
+}
+
+enum Hasher {
+    Md5(md5::Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Crc32(crc32fast::Hasher),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => Hasher::Md5(md5::Md5::new()),
+            HashAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Crc32 => Hasher::Crc32(crc32fast::Hasher::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Md5(h) => h.update(data),
+            Hasher::Sha1(h) => h.update(data),
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Crc32(h) => h.update(data),
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Md5(h) => to_hex(&h.finalize()),
+            Hasher::Sha1(h) => to_hex(&h.finalize()),
+            Hasher::Sha256(h) => to_hex(&h.finalize()),
+            Hasher::Crc32(h) => format!("{:08x}", h.finalize()),
+            Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hashes `path` with every algorithm in one pass, reading at most `limit` bytes when given.
+/// `on_progress` receives the number of bytes read so far after each chunk.
+pub fn hash_file(
+    path: &Path,
+    algorithms: &[HashAlgorithm],
+    limit: Option<u64>,
+    cancelled: &AtomicBool,
+    mut on_progress: impl FnMut(u64),
+) -> Result<HashMap<HashAlgorithm, String>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut reader: Box<dyn Read> = match limit {
+        Some(limit) => Box::new(file.take(limit)),
+        None => Box::new(file),
+    };
+
+    let mut hashers: Vec<(HashAlgorithm, Hasher)> =
+        algorithms.iter().map(|&a| (a, Hasher::new(a))).collect();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut done = 0u64;
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("Hashing cancelled".to_string());
+        }
+        let n = reader
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        for (_, hasher) in hashers.iter_mut() {
+            hasher.update(&buf[..n]);
+        }
+        done += n as u64;
+        on_progress(done);
+    }
+    Ok(hashers
+        .into_iter()
+        .map(|(algorithm, hasher)| (algorithm, hasher.finalize()))
+        .collect())
+}
+
+/// SHA-256 of the whole file, or of its first `limit` bytes when given
+pub fn sha256_file(
+    path: &Path,
+    limit: Option<u64>,
+    cancelled: &AtomicBool,
+) -> Result<String, String> {
+    hash_file(path, &[HashAlgorithm::Sha256], limit, cancelled, |_| {})?
+        .remove(&HashAlgorithm::Sha256)
+        .ok_or_else(|| "SHA-256 missing".to_string())
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct HashProgress {
+    pub job_id: String,
+    pub path: String,
+    #[ts(type = "number")]
+    pub file_bytes_done: u64,
+    #[ts(type = "number")]
+    pub file_bytes_total: u64,
+    /// Across every file of the job
+    #[ts(type = "number")]
+    pub bytes_done: u64,
+    #[ts(type = "number")]
+    pub bytes_total: u64,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct FileHashResult {
+    pub job_id: String,
+    pub path: String,
+    /// Lowercase hex digests keyed by algorithm; empty when `error` is set
+    pub hashes: HashMap<HashAlgorithm, String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct HashFinished {
+    pub job_id: String,
+    pub cancelled: bool,
+}
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+static ACTIVE_JOBS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn active_jobs() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    ACTIVE_JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Registers a new cancellable hashing job and returns its id and cancel flag
+pub(crate) fn register_job() -> (String, Arc<AtomicBool>) {
+    let job_id = format!("hash-{}", NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    if let Ok(mut jobs) = active_jobs().lock() {
+        jobs.insert(job_id.clone(), Arc::clone(&cancelled));
+    }
+    (job_id, cancelled)
+}
+
+pub(crate) fn finish_job(job_id: &str) {
+    if let Ok(mut jobs) = active_jobs().lock() {
+        jobs.remove(job_id);
+    }
+}
+
+/// Emits throttled `hash-progress` events for one job
+pub(crate) struct ProgressReporter {
+    window: tauri::Window,
+    job_id: String,
+    bytes_total: u64,
+    bytes_before: u64,
+    last_emit: Instant,
+}
+
+impl ProgressReporter {
+    pub(crate) fn new(window: tauri::Window, job_id: String, bytes_total: u64) -> Self {
+        Self {
+            window,
+            job_id,
+            bytes_total,
+            bytes_before: 0,
+            last_emit: Instant::now(),
+        }
+    }
+
+    pub(crate) fn file_progress(&mut self, path: &Path, file_done: u64, file_total: u64) {
+        if file_done < file_total && self.last_emit.elapsed().as_millis() < PROGRESS_INTERVAL_MS {
+            return;
+        }
+        self.last_emit = Instant::now();
+        let _ = self.window.emit(
+            "hash-progress",
+            HashProgress {
+                job_id: self.job_id.clone(),
+                path: path.to_string_lossy().to_string(),
+                file_bytes_done: file_done,
+                file_bytes_total: file_total,
+                bytes_done: self.bytes_before + file_done,
+                bytes_total: self.bytes_total,
+            },
+        );
+    }
+
+    /// Call once a file is done (or skipped) so overall progress moves past it
+    pub(crate) fn file_finished(&mut self, file_total: u64) {
+        self.bytes_before += file_total;
+    }
+}
+
+/// Hashes every file in `paths` with each of `algorithms` on a background thread and
+/// returns the job id immediately. Each file produces one `hash-result` event; the job
+/// ends with `hash-finished`.
+#[tauri::command]
+pub fn compute_hash(
+    window: tauri::Window,
+    paths: Vec<String>,
+    algorithms: Vec<HashAlgorithm>,
+) -> Result<String, String> {
+    if paths.is_empty() {
+        return Err("No files to hash".to_string());
+    }
+    let mut unique: Vec<HashAlgorithm> = Vec::new();
+    for algorithm in algorithms {
+        if !unique.contains(&algorithm) {
+            unique.push(algorithm);
+        }
+    }
+    let mut algorithms = unique;
+    if algorithms.is_empty() {
+        algorithms.push(HashAlgorithm::Sha256);
+    }
+
+    let (job_id, cancelled) = register_job();
+    log::info!(
+        "[HASH] {} started for {} file(s) ({:?})",
+        job_id,
+        paths.len(),
+        algorithms
+    );
+
+    let id = job_id.clone();
+    std::thread::spawn(move || {
+        let sizes: Vec<u64> = paths
+            .iter()
+            .map(|p| std::fs::metadata(p).map(|m| m.len()).unwrap_or(0))
+            .collect();
+        let mut progress = ProgressReporter::new(window.clone(), id.clone(), sizes.iter().sum());
+
+        for (path, size) in paths.iter().zip(sizes) {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let file = Path::new(path);
+            let result = if file.is_dir() {
+                Err("Folders cannot be hashed".to_string())
+            } else {
+                hash_file(file, &algorithms, None, &cancelled, |done| {
+                    progress.file_progress(file, done, size)
+                })
+            };
+            progress.file_finished(size);
+
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let (hashes, error) = match result {
+                Ok(hashes) => (hashes, None),
+                Err(e) => (HashMap::new(), Some(e)),
+            };
+            let _ = window.emit(
+                "hash-result",
+                FileHashResult {
+                    job_id: id.clone(),
+                    path: path.clone(),
+                    hashes,
+                    error,
+                },
+            );
+        }
+
+        let was_cancelled = cancelled.load(Ordering::Relaxed);
+        finish_job(&id);
+        log::info!("[HASH] {} finished (cancelled: {})", id, was_cancelled);
+        let _ = window.emit(
+            "hash-finished",
+            HashFinished {
+                job_id: id,
+                cancelled: was_cancelled,
+            },
+        );
+    });
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub fn cancel_hash(job_id: String) {
+    if let Ok(jobs) = active_jobs().lock() {
+        if let Some(flag) = jobs.get(&job_id) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_digests() {
+        let path = std::env::temp_dir().join("qe_hash_test.txt");
+        std::fs::write(&path, b"abc").unwrap();
+        let cancelled = AtomicBool::new(false);
+        let all = [
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha1,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Crc32,
+            HashAlgorithm::Blake3,
+        ];
+        let hashes = hash_file(&path, &all, None, &cancelled, |_| {}).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            hashes[&HashAlgorithm::Md5],
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            hashes[&HashAlgorithm::Sha1],
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hashes[&HashAlgorithm::Sha256],
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(hashes[&HashAlgorithm::Crc32], "352441c2");
+        assert_eq!(
+            hashes[&HashAlgorithm::Blake3],
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+}
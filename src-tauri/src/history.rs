@@ -0,0 +1,280 @@
+//! History Module
+//!
+//! Audit log of the file operations the app performs, kept in `history.db`
+//! (SQLite) in the app data folder. Entries are recorded by the file
+//! operation progress sink as the shell reports each top-level item done, so
+//! only operations that actually happened are logged. `query_history` pages
+//! through them for the History panel.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use ts_rs::TS;
+
+/// Entries older than this are dropped when the app starts logging
+const RETENTION_DAYS: i64 = 365;
+const MAX_PAGE_SIZE: u32 = 500;
+
+#[derive(Serialize, Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum HistoryOperation {
+    Copy,
+    Move,
+    Rename,
+    Delete,
+    Create,
+}
+
+impl HistoryOperation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Copy => "copy",
+            Self::Move => "move",
+            Self::Rename => "rename",
+            Self::Delete => "delete",
+            Self::Create => "create",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "copy" => Some(Self::Copy),
+            "move" => Some(Self::Move),
+            "rename" => Some(Self::Rename),
+            "delete" => Some(Self::Delete),
+            "create" => Some(Self::Create),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct HistoryEntry {
+    #[ts(type = "number")]
+    pub id: i64,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub timestamp: i64,
+    pub operation: HistoryOperation,
+    /// The item operated on; None for created items
+    pub source: Option<String>,
+    /// Where the item ended up; None for deletions
+    pub destination: Option<String>,
+}
+
+#[derive(Deserialize, Clone, TS, Debug, Default)]
+#[serde(default)]
+#[ts(export)]
+pub struct HistoryFilter {
+    /// Unix seconds, inclusive
+    #[ts(type = "number | null")]
+    pub from: Option<i64>,
+    /// Unix seconds, exclusive
+    #[ts(type = "number | null")]
+    pub to: Option<i64>,
+    /// Case-insensitive substring of the source or destination path
+    pub path_contains: Option<String>,
+    /// Only these operations; empty means all
+    pub operations: Vec<HistoryOperation>,
+    pub offset: u32,
+    /// Page size; 0 uses the default of 100
+    pub limit: u32,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct HistoryPage {
+    /// Newest first
+    pub entries: Vec<HistoryEntry>,
+    /// Number of entries matching the filter across all pages
+    #[ts(type = "number")]
+    pub total: u64,
+}
+
+static DB: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+fn open_db() -> Result<Connection, String> {
+    let path = crate::paths::app_data_dir().join("history.db");
+    let conn =
+        Connection::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS operations (
+             id INTEGER PRIMARY KEY,
+             timestamp INTEGER NOT NULL,
+             operation TEXT NOT NULL,
+             source TEXT,
+             destination TEXT
+         );
+         CREATE INDEX IF NOT EXISTS operations_by_time ON operations(timestamp);",
+    )
+    .map_err(|e| format!("Failed to initialize history database: {}", e))?;
+    let cutoff = chrono::Utc::now().timestamp() - RETENTION_DAYS * 86_400;
+    conn.execute(
+        "DELETE FROM operations WHERE timestamp < ?1",
+        params![cutoff],
+    )
+    .map_err(|e| format!("Failed to prune history: {}", e))?;
+    Ok(conn)
+}
+
+fn db() -> Result<MutexGuard<'static, Connection>, String> {
+    if DB.get().is_none() {
+        let conn = open_db()?;
+        let _ = DB.set(Mutex::new(conn));
+    }
+    DB.get()
+        .ok_or("History database unavailable")?
+        .lock()
+        .map_err(|e| format!("History database lock poisoned: {}", e))
+}
+
+fn sql_err(e: rusqlite::Error) -> String {
+    format!("History database error: {}", e)
+}
+
+/// Logs one completed operation; failures are only logged, never surfaced to the operation
+pub fn record(operation: HistoryOperation, source: Option<&str>, destination: Option<&str>) {
+    let result = db().and_then(|conn| {
+        conn.execute(
+            "INSERT INTO operations (timestamp, operation, source, destination)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                chrono::Utc::now().timestamp(),
+                operation.as_str(),
+                source,
+                destination
+            ],
+        )
+        .map_err(sql_err)
+    });
+    if let Err(e) = result {
+        log::warn!("[HISTORY] Failed to record {:?}: {}", operation, e);
+    }
+}
+
+/// WHERE clause and its parameters for `filter`
+fn where_clause(filter: &HistoryFilter) -> (String, Vec<rusqlite::types::Value>) {
+    use rusqlite::types::Value;
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+    if let Some(from) = filter.from {
+        values.push(Value::Integer(from));
+        conditions.push(format!("timestamp >= ?{}", values.len()));
+    }
+    if let Some(to) = filter.to {
+        values.push(Value::Integer(to));
+        conditions.push(format!("timestamp < ?{}", values.len()));
+    }
+    if let Some(text) = filter.path_contains.as_ref().filter(|t| !t.is_empty()) {
+        // instr() rather than LIKE so '%' and '_' in paths match literally
+        values.push(Value::Text(text.to_lowercase()));
+        let n = values.len();
+        conditions.push(format!(
+            "(instr(lower(source), ?{n}) > 0 OR instr(lower(destination), ?{n}) > 0)"
+        ));
+    }
+    if !filter.operations.is_empty() {
+        let mut placeholders = Vec::new();
+        for op in &filter.operations {
+            values.push(Value::Text(op.as_str().to_string()));
+            placeholders.push(format!("?{}", values.len()));
+        }
+        conditions.push(format!("operation IN ({})", placeholders.join(", ")));
+    }
+    let clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+    (clause, values)
+}
+
+/// One page of logged operations matching `filter`, newest first
+#[tauri::command]
+pub fn query_history(filter: HistoryFilter) -> Result<HistoryPage, String> {
+    let conn = db()?;
+    let (clause, values) = where_clause(&filter);
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM operations {}", clause),
+            rusqlite::params_from_iter(values.iter()),
+            |row| row.get(0),
+        )
+        .map_err(sql_err)?;
+
+    let limit = match filter.limit {
+        0 => 100,
+        n => n.min(MAX_PAGE_SIZE),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, timestamp, operation, source, destination FROM operations {}
+             ORDER BY timestamp DESC, id DESC LIMIT {} OFFSET {}",
+            clause, limit, filter.offset
+        ))
+        .map_err(sql_err)?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })
+        .map_err(sql_err)?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let (id, timestamp, operation, source, destination) = row.map_err(sql_err)?;
+        let Some(operation) = HistoryOperation::parse(&operation) else {
+            continue;
+        };
+        entries.push(HistoryEntry {
+            id,
+            timestamp,
+            operation,
+            source,
+            destination,
+        });
+    }
+    Ok(HistoryPage {
+        entries,
+        total: total as u64,
+    })
+}
+
+#[tauri::command]
+pub fn clear_history() -> Result<(), String> {
+    db()?
+        .execute("DELETE FROM operations", [])
+        .map_err(sql_err)?;
+    log::info!("[HISTORY] Cleared");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_where_clause_numbers_parameters_in_order() {
+        let filter = HistoryFilter {
+            from: Some(100),
+            path_contains: Some("Downloads".to_string()),
+            operations: vec![HistoryOperation::Move, HistoryOperation::Delete],
+            ..Default::default()
+        };
+        let (clause, values) = where_clause(&filter);
+        assert_eq!(
+            clause,
+            "WHERE timestamp >= ?1 AND (instr(lower(source), ?2) > 0 OR instr(lower(destination), ?2) > 0) AND operation IN (?3, ?4)"
+        );
+        assert_eq!(values.len(), 4);
+        assert_eq!(where_clause(&HistoryFilter::default()).0, "");
+    }
+}
@@ -0,0 +1,218 @@
+//! I18n Module
+//!
+//! Strings the backend writes into serialized data — file type labels and
+//! the names it generates for new folders, copies and screenshots — in the
+//! app's display language. The `language` preference mirrors the UI's
+//! choice; "auto" follows the Windows display language, as the UI follows
+//! the browser's.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    En,
+    Es,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Text {
+    Folder,
+    File,
+    /// "{0}" is the uppercase extension
+    FileOfType,
+    Drive,
+    LocalDisk,
+    RemovableDisk,
+    NetworkDrive,
+    CdDrive,
+    NetworkShare,
+    Shortcut,
+    InternetShortcut,
+    DeletedItem,
+    DeletedFolder,
+    NewFolder,
+    NewTextDocument,
+    /// "{0}" is the type name from the registry
+    NewItem,
+    /// Appended to the stem of a pasted copy
+    CopySuffix,
+    /// Appended to the target's name for a new shortcut
+    ShortcutSuffix,
+    Screenshot,
+    /// Group headers of grouped listings
+    Folders,
+    Today,
+    Yesterday,
+    EarlierThisWeek,
+    LastWeek,
+    EarlierThisMonth,
+    LastMonth,
+    EarlierThisYear,
+    LongAgo,
+    SizeEmpty,
+    SizeTiny,
+    SizeSmall,
+    SizeMedium,
+    SizeLarge,
+    SizeHuge,
+    SizeGigantic,
+}
+
+/// 0 until resolved from the preferences, then 1 + the `Language` index
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+const LANGUAGES: [Language; 2] = [Language::En, Language::Es];
+
+impl Language {
+    fn from_code(code: &str) -> Option<Self> {
+        match code.get(..2)?.to_ascii_lowercase().as_str() {
+            "en" => Some(Self::En),
+            "es" => Some(Self::Es),
+            _ => None,
+        }
+    }
+
+    /// The Windows display language, or English when it isn't one the app has
+    fn system() -> Self {
+        use windows::Win32::Globalization::GetUserDefaultUILanguage;
+        const LANG_SPANISH: u16 = 0x0a;
+        // The low 10 bits of a LANGID are the primary language
+        match unsafe { GetUserDefaultUILanguage() } & 0x3ff {
+            LANG_SPANISH => Self::Es,
+            _ => Self::En,
+        }
+    }
+}
+
+/// The language backend strings are written in
+pub fn language() -> Language {
+    match CURRENT.load(Ordering::Relaxed) {
+        0 => {
+            let language = Language::from_code(&crate::settings::preferences().language)
+                .unwrap_or_else(Language::system);
+            let index = LANGUAGES.iter().position(|l| *l == language).unwrap_or(0);
+            CURRENT.store(index as u8 + 1, Ordering::Relaxed);
+            language
+        }
+        n => LANGUAGES[n as usize - 1],
+    }
+}
+
+/// Drops the resolved language so the next string follows a changed preference
+pub fn reset() {
+    CURRENT.store(0, Ordering::Relaxed);
+}
+
+fn lookup(language: Language, text: Text) -> &'static str {
+    match language {
+        Language::En => match text {
+            Text::Folder => "Folder",
+            Text::File => "File",
+            Text::FileOfType => "{0} File",
+            Text::Drive => "Drive",
+            Text::LocalDisk => "Local Disk",
+            Text::RemovableDisk => "USB Drive",
+            Text::NetworkDrive => "Network Drive",
+            Text::CdDrive => "CD Drive",
+            Text::NetworkShare => "Network Share",
+            Text::Shortcut => "Shortcut",
+            Text::InternetShortcut => "Internet Shortcut",
+            Text::DeletedItem => "Deleted Item",
+            Text::DeletedFolder => "Deleted Folder",
+            Text::NewFolder => "New Folder",
+            Text::NewTextDocument => "New Text Document",
+            Text::NewItem => "New {0}",
+            Text::CopySuffix => " - Copy",
+            Text::ShortcutSuffix => " - Shortcut",
+            Text::Screenshot => "Screenshot",
+            Text::Folders => "Folders",
+            Text::Today => "Today",
+            Text::Yesterday => "Yesterday",
+            Text::EarlierThisWeek => "Earlier this week",
+            Text::LastWeek => "Last week",
+            Text::EarlierThisMonth => "Earlier this month",
+            Text::LastMonth => "Last month",
+            Text::EarlierThisYear => "Earlier this year",
+            Text::LongAgo => "A long time ago",
+            Text::SizeEmpty => "Empty (0 KB)",
+            Text::SizeTiny => "Tiny (0 - 16 KB)",
+            Text::SizeSmall => "Small (16 KB - 1 MB)",
+            Text::SizeMedium => "Medium (1 - 128 MB)",
+            Text::SizeLarge => "Large (128 MB - 1 GB)",
+            Text::SizeHuge => "Huge (1 - 4 GB)",
+            Text::SizeGigantic => "Gigantic (> 4 GB)",
+        },
+        Language::Es => match text {
+            Text::Folder => "Carpeta",
+            Text::File => "Archivo",
+            Text::FileOfType => "Archivo {0}",
+            Text::Drive => "Unidad",
+            Text::LocalDisk => "Disco local",
+            Text::RemovableDisk => "Unidad USB",
+            Text::NetworkDrive => "Unidad de red",
+            Text::CdDrive => "Unidad de CD",
+            Text::NetworkShare => "Recurso compartido de red",
+            Text::Shortcut => "Acceso directo",
+            Text::InternetShortcut => "Acceso directo a Internet",
+            Text::DeletedItem => "Elemento eliminado",
+            Text::DeletedFolder => "Carpeta eliminada",
+            Text::NewFolder => "Nueva carpeta",
+            Text::NewTextDocument => "Nuevo documento de texto",
+            Text::NewItem => "Nuevo {0}",
+            Text::CopySuffix => " - copia",
+            Text::ShortcutSuffix => " - Acceso directo",
+            Text::Screenshot => "Captura de pantalla",
+            Text::Folders => "Carpetas",
+            Text::Today => "Hoy",
+            Text::Yesterday => "Ayer",
+            Text::EarlierThisWeek => "A principios de esta semana",
+            Text::LastWeek => "La semana pasada",
+            Text::EarlierThisMonth => "A principios de este mes",
+            Text::LastMonth => "El mes pasado",
+            Text::EarlierThisYear => "A principios de este año",
+            Text::LongAgo => "Hace mucho tiempo",
+            Text::SizeEmpty => "Vacío (0 KB)",
+            Text::SizeTiny => "Diminuto (0 - 16 KB)",
+            Text::SizeSmall => "Pequeño (16 KB - 1 MB)",
+            Text::SizeMedium => "Mediano (1 - 128 MB)",
+            Text::SizeLarge => "Grande (128 MB - 1 GB)",
+            Text::SizeHuge => "Enorme (1 - 4 GB)",
+            Text::SizeGigantic => "Gigante (> 4 GB)",
+        },
+    }
+}
+
+/// `text` in the current language
+pub fn tr(text: Text) -> &'static str {
+    lookup(language(), text)
+}
+
+/// `text` in the current language with "{0}" replaced by `value`
+pub fn tr_with(text: Text, value: &str) -> String {
+    tr(text).replace("{0}", value)
+}
+
+/// The type label for a file with extension `ext`, or for one without any
+pub fn file_type_label(ext: Option<&str>) -> String {
+    match ext {
+        Some(ext) => tr_with(Text::FileOfType, &ext.to_uppercase()),
+        None => tr(Text::File).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Language::from_code("es-MX"), Some(Language::Es));
+        assert_eq!(Language::from_code("EN"), Some(Language::En));
+        assert_eq!(Language::from_code("auto"), None);
+        assert_eq!(Language::from_code(""), None);
+        assert_eq!(
+            lookup(Language::Es, Text::FileOfType).replace("{0}", "PDF"),
+            "Archivo PDF"
+        );
+    }
+}
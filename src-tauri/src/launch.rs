@@ -0,0 +1,115 @@
+//! Launch Module
+//!
+//! Command-line arguments, so shell verbs and scripts can run
+//! `quick-explorer.exe <path>`. A folder argument opens that folder; a file
+//! opens its folder with the file selected. The target of the process's own
+//! arguments is kept until the UI asks for it with `take_launch_target`, since
+//! the window isn't listening yet when setup runs. Launches forwarded by a
+//! second instance arrive while the UI runs and are sent as "open-path" events.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Emitter;
+use ts_rs::TS;
+
+#[derive(Serialize, Clone, TS, Debug, PartialEq)]
+#[ts(export)]
+pub struct LaunchTarget {
+    /// Folder to open
+    pub path: String,
+    /// File to select in it
+    pub select: Option<String>,
+}
+
+static PENDING: Mutex<Option<LaunchTarget>> = Mutex::new(None);
+
+/// The target named by `args` (without the program name), resolved against `cwd`.
+/// Flags are skipped; the first other argument wins.
+fn parse_args(args: &[String], cwd: &Path) -> Option<LaunchTarget> {
+    let arg = args
+        .iter()
+        .map(|a| a.trim().trim_matches('"'))
+        .find(|a| !a.is_empty() && !a.starts_with('-'))?;
+
+    if arg.to_lowercase().starts_with("shell:") {
+        return Some(LaunchTarget {
+            path: arg.to_string(),
+            select: None,
+        });
+    }
+    let expanded = PathBuf::from(crate::expand_env_vars(arg));
+    let path = if expanded.is_absolute() {
+        expanded
+    } else {
+        cwd.join(expanded)
+    };
+
+    if path.is_dir() {
+        Some(LaunchTarget {
+            path: path.to_string_lossy().to_string(),
+            select: None,
+        })
+    } else if path.is_file() {
+        Some(LaunchTarget {
+            path: path.parent()?.to_string_lossy().to_string(),
+            select: Some(path.to_string_lossy().to_string()),
+        })
+    } else {
+        log::warn!("[LAUNCH] Ignoring argument, not found: {}", arg);
+        None
+    }
+}
+
+/// Remembers the target of this process's own arguments; call once from setup
+pub fn init() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let target = parse_args(&args, &cwd);
+    if let Some(target) = &target {
+        log::info!("[LAUNCH] Opening {:?} from the command line", target);
+    }
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = target;
+    }
+}
+
+/// Sends the target of another launch's arguments to the UI as an "open-path" event
+pub fn open(app: &tauri::AppHandle, args: &[String], cwd: &str) {
+    if let Some(target) = parse_args(args, Path::new(cwd)) {
+        log::info!("[LAUNCH] Opening {:?}", target);
+        let _ = app.emit("open-path", target);
+    }
+}
+
+/// The folder to open from the command line, once; None afterwards or without arguments
+#[tauri::command]
+pub fn take_launch_target() -> Option<LaunchTarget> {
+    PENDING.lock().ok().and_then(|mut pending| pending.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        let dir = std::env::temp_dir();
+        let file = dir.join("quick-explorer-launch-test.txt");
+        std::fs::write(&file, b"").unwrap();
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let target = parse_args(&args(&["--flag", "quick-explorer-launch-test.txt"]), &dir);
+        assert_eq!(
+            target,
+            Some(LaunchTarget {
+                path: file.parent().unwrap().to_string_lossy().to_string(),
+                select: Some(file.to_string_lossy().to_string()),
+            })
+        );
+        let target = parse_args(&args(&[&format!("\"{}\"", dir.display())]), Path::new("."));
+        assert_eq!(target.map(|t| t.select), Some(None));
+        assert_eq!(parse_args(&args(&["-v"]), &dir), None);
+        let _ = std::fs::remove_file(file);
+    }
+}
@@ -11,12 +11,11 @@ use std::time::SystemTime;
 use tauri::Emitter;
 use tauri::Manager;
 use ts_rs::TS;
-// use window_vibrancy::apply_mica;
 use windows::core::{Interface, PCWSTR};
 
 use windows::Win32::Foundation::PROPERTYKEY;
 use windows::Win32::Security::TOKEN_QUERY;
-use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED, STGM};
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
 use windows::Win32::System::DataExchange::{
     EmptyClipboard, GetClipboardSequenceNumber, SetClipboardData,
 };
@@ -34,11 +33,69 @@ use windows::Win32::UI::WindowsAndMessaging::{
     SetForegroundWindow, SetWindowPos, GA_ROOT, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
 };
 
+mod app_data;
+mod appearance;
+mod associations;
+mod attributes;
+mod checksums;
+mod cleanup;
+mod cloud;
 mod commands;
+mod compression;
+mod batch_rename;
+mod conflicts;
+mod devices;
+mod folder_size;
+mod grouping;
+mod disk_monitor;
+mod disk_usage;
+mod drive_health;
+mod drive_tools;
 mod drop_overlay;
+mod elevation;
 mod extraction;
+mod ffmpeg;
+mod file_op_progress;
+mod file_stream;
+mod frecency;
+mod hashing;
+mod history;
+mod i18n;
+mod launch;
+mod links;
+mod listing;
+mod locks;
+mod memory;
+mod mft;
+mod navigation;
+mod network;
+mod new_file;
+mod operations;
+mod paths;
+mod permissions;
+mod prefetch;
+mod quick_access;
+mod recent;
+mod reputation;
+mod rotation;
 mod search_engine;
+mod session;
+mod settings;
+mod shell_actions;
+mod shortcuts;
+mod single_instance;
+mod storage_stats;
+mod streams;
+mod tags;
 mod sta_worker;
+mod terminal;
+mod tray;
+mod watcher;
+
+pub use elevation::run_elevated_helper;
+pub use single_instance::forward_to_running_instance;
+
+use operations::OperationKind;
 
 pub static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
 
@@ -70,6 +127,13 @@ pub struct DiskInfo {
     pub free: u64,
     pub is_system: bool,
     pub is_ssd: bool,
+    /// Volume label; empty when the volume has none
+    pub label: String,
+    /// "NTFS", "FAT32", "exFAT"...; empty when the volume can't be read
+    pub file_system: String,
+    pub drive_type: disk_monitor::DriveType,
+    /// None for drives BitLocker can't protect
+    pub bitlocker: Option<disk_monitor::BitLockerState>,
 }
 
 #[derive(Serialize, Default, Clone, TS)]
@@ -101,6 +165,29 @@ pub struct FileEntry {
     #[ts(type = "number")]
     pub created_timestamp: i64,
     pub dimensions: Option<String>,
+    /// Target URL for .url internet shortcuts
+    pub url_target: Option<String>,
+    /// Where and when a Recycle Bin item was deleted
+    pub recycle_info: Option<RecycleInfo>,
+    /// Symbolic link, to a file or a folder
+    pub is_symlink: bool,
+    /// NTFS junction to a folder
+    pub is_junction: bool,
+    /// Where a symbolic link or junction points
+    pub link_target: Option<String>,
+    pub attributes: attributes::FileAttributes,
+    /// OneDrive and other sync engines' placeholders; None for ordinary files
+    pub cloud_status: Option<cloud::CloudStatus>,
+}
+
+#[derive(Serialize, TS, Clone, Debug)]
+#[ts(export)]
+pub struct RecycleInfo {
+    /// Folder the item was deleted from
+    pub original_location: String,
+    pub deleted_at: String,
+    #[ts(type = "number")]
+    pub deleted_timestamp: i64,
 }
 
 #[derive(Serialize, Clone, TS)]
@@ -131,6 +218,7 @@ fn get_nav_id_mutex() -> &'static std::sync::Mutex<String> {
 }
 
 pub fn get_file_entry(path: &std::path::Path) -> Result<FileEntry, String> {
+    use std::os::windows::fs::MetadataExt;
     let metadata = path.metadata().map_err(|e| e.to_string())?;
     let name = path
         .file_name()
@@ -143,20 +231,14 @@ pub fn get_file_entry(path: &std::path::Path) -> Result<FileEntry, String> {
 
     let formatted_size = if is_dir {
         String::new()
-    } else if size < 1024 {
-        format!("{} B", size)
-    } else if size < 1024 * 1024 {
-        format!("{:.1} KB", size as f64 / 1024.0)
     } else {
-        format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
+        crate::settings::format_size(size)
     };
 
     let file_type = if is_dir {
-        "Folder".to_string()
+        i18n::tr(i18n::Text::Folder).to_string()
     } else {
-        path.extension()
-            .map(|ext| ext.to_string_lossy().to_uppercase() + " File")
-            .unwrap_or_else(|| "File".to_string())
+        i18n::file_type_label(path.extension().map(|ext| ext.to_string_lossy()).as_deref())
     };
 
     let extension = path
@@ -165,9 +247,16 @@ pub fn get_file_entry(path: &std::path::Path) -> Result<FileEntry, String> {
         .unwrap_or("")
         .to_lowercase();
     let is_shortcut = extension == "lnk";
+    let url_target = if extension == "url" {
+        crate::shortcuts::read_url_target(path)
+    } else {
+        None
+    };
 
     let file_type = if is_shortcut {
         "Shortcut".to_string()
+    } else if url_target.is_some() {
+        "Internet Shortcut".to_string()
     } else {
         file_type
     };
@@ -200,7 +289,15 @@ pub fn get_file_entry(path: &std::path::Path) -> Result<FileEntry, String> {
             .unwrap_or_default()
             .as_secs() as i64,
         dimensions: None,
-    })
+        url_target,
+        recycle_info: None,
+        is_symlink: false,
+        is_junction: false,
+        link_target: None,
+        attributes: attributes::FileAttributes::from_raw(metadata.file_attributes()),
+        cloud_status: cloud::cloud_status(metadata.file_attributes(), None),
+    }
+    .with_link(crate::links::read_link(path)))
 }
 
 #[derive(Serialize, TS)]
@@ -208,6 +305,12 @@ pub fn get_file_entry(path: &std::path::Path) -> Result<FileEntry, String> {
 pub struct ListFilesResult {
     pub entries: Vec<FileEntry>,
     pub expanded_path: String,
+    /// Identifies this listing for `diff_list` on the next refresh
+    pub fingerprint: String,
+    /// Entries in the folder; more than `entries` when a page was asked for
+    pub total: usize,
+    /// Ranges of the whole listing when grouping was asked for
+    pub groups: Option<Vec<crate::grouping::ListingGroup>>,
 }
 
 pub fn expand_env_vars(path: &str) -> String {
@@ -254,27 +357,79 @@ pub fn expand_env_vars(path: &str) -> String {
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn list_files(
     path: String,
     show_hidden: bool,
     nav_id: Option<String>,
+    sort_by: Option<crate::listing::SortBy>,
+    sort_dir: Option<crate::listing::SortDir>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    group_by: Option<crate::grouping::GroupBy>,
 ) -> Result<ListFilesResult, String> {
     if let Some(id) = &nav_id {
         if let Ok(mut current_id) = get_nav_id_mutex().lock() {
             *current_id = id.clone();
         }
     }
-    let expanded_path = expand_env_vars(&path);
-    let entries = crate::sta_worker::StaWorker::global().list_files(expanded_path.clone(), show_hidden, nav_id)?;
+    let expanded_path = crate::paths::expand_path_str(&path)?;
+    let mut entries = crate::listing::list_cached(&expanded_path, show_hidden, || {
+        let entries = crate::sta_worker::StaWorker::global().list_files(expanded_path.clone(), show_hidden, nav_id.clone())?;
+        // A listing cut short by a newer navigation must not be cached
+        let complete = nav_id.as_ref().is_none_or(|id| {
+            get_nav_id_mutex().lock().map(|current| *current == *id).unwrap_or(false)
+        });
+        Ok((entries, complete))
+    })?;
+    // Later pages of the same listing are not new visits
+    if offset.unwrap_or(0) == 0 {
+        crate::frecency::FrecencyStore::global().record_visit(&expanded_path);
+    }
+    let fingerprint = crate::listing::remember(&entries);
+    let total = entries.len();
+    if let Some(sort_by) = sort_by {
+        crate::listing::sort_entries(&mut entries, sort_by, sort_dir.unwrap_or_default());
+    }
+    let groups = group_by.map(|group_by| crate::grouping::group_entries(&mut entries, group_by));
+    if offset.is_some() || limit.is_some() {
+        entries = crate::listing::page(entries, offset.unwrap_or(0), limit);
+    }
     
     Ok(ListFilesResult {
         entries,
         expanded_path,
+        fingerprint,
+        total,
+        groups,
     })
 }
 
+/// Number of entries in `path`, to size a virtualized view before asking for pages
+#[tauri::command]
+async fn list_files_count(path: String, show_hidden: bool) -> Result<usize, String> {
+    let expanded_path = crate::paths::expand_path_str(&path)?;
+    let entries = crate::listing::list_cached(&expanded_path, show_hidden, || {
+        let entries = crate::sta_worker::StaWorker::global().list_files(expanded_path.clone(), show_hidden, None)?;
+        Ok((entries, true))
+    })?;
+    Ok(entries.len())
+}
+
+/// Largest file `read_file_base64` will load
+const MAX_BASE64_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Deprecated: loads the whole file into memory. Use the `stream:` protocol
+/// (`convertFileSrc(path, "stream")`) instead, which serves byte ranges.
 #[tauri::command]
 fn read_file_base64(path: &str) -> Result<String, String> {
+    let size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_BASE64_FILE_SIZE {
+        return Err(format!(
+            "File is too large to load at once ({:.1} MB); use the stream protocol",
+            size as f64 / (1024.0 * 1024.0)
+        ));
+    }
     Ok(BASE64_STANDARD.encode(fs::read(path).map_err(|e| e.to_string())?))
 }
 
@@ -366,7 +521,7 @@ fn open_file(
 async fn create_folder(window: tauri::Window, parent_path: String) -> Result<String, String> {
     use windows::Win32::UI::Shell::SHCreateDirectoryExW;
 
-    let folder_name = "New Folder".to_string();
+    let folder_name = i18n::tr(i18n::Text::NewFolder).to_string();
     let mut count = 1;
 
     let root_hwnd = get_root_hwnd(&window);
@@ -447,7 +602,7 @@ async fn open_with(window: tauri::Window, path: String) {
     }
 }
 
-fn get_root_hwnd(window: &tauri::Window) -> windows::Win32::Foundation::HWND {
+pub(crate) fn get_root_hwnd(window: &tauri::Window) -> windows::Win32::Foundation::HWND {
     let hwnd = window
         .hwnd()
         .ok()
@@ -532,11 +687,18 @@ fn harden_focus(hwnd: windows::Win32::Foundation::HWND) {
 
 #[tauri::command]
 async fn delete_item(window: tauri::Window, path: String) -> Result<(), String> {
-    let root_hwnd = get_root_hwnd(&window);
-    harden_focus(root_hwnd);
+    let hwnd = {
+        let root_hwnd = get_root_hwnd(&window);
+        harden_focus(root_hwnd);
+        Some(root_hwnd.0 as isize)
+    };
 
-    let _ =
-        crate::sta_worker::StaWorker::global().delete_items(vec![expand_env_vars(&path)], Some(root_hwnd.0 as isize));
+    let paths = vec![expand_env_vars(&path)];
+    let _ = operations::run(OperationKind::Delete, paths.clone(), None, move |control| {
+        sta_worker::run_file_operation(control, || sta_worker::delete_items_impl(paths, hwnd, control))
+            .map(|_| Vec::new())
+    })
+    .await;
 
     Ok(())
 }
@@ -559,7 +721,11 @@ async fn rename_item(
 }
 
 #[tauri::command]
-async fn paste_items(window: tauri::Window, target_path: String) -> Result<(), String> {
+async fn paste_items(
+    window: tauri::Window,
+    target_path: String,
+    resolutions: Option<conflicts::Resolutions>,
+) -> Result<(), String> {
     let paths: Vec<String> = clipboard_win::get_clipboard(formats::FileList).unwrap_or_default();
     if paths.is_empty() {
         return Err("Clipboard is empty".into());
@@ -580,19 +746,23 @@ async fn paste_items(window: tauri::Window, target_path: String) -> Result<(), S
         }
     }
 
-    let root_hwnd = get_root_hwnd(&window);
-    harden_focus(root_hwnd);
+    let hwnd = {
+        let root_hwnd = get_root_hwnd(&window);
+        harden_focus(root_hwnd);
+        Some(root_hwnd.0 as isize)
+    };
 
-    let _ = crate::sta_worker::StaWorker::global().paste_items(
-        paths,
-        expand_env_vars(&target_path),
-        is_move,
-        Some(root_hwnd.0 as isize),
-    );
+    let target_path = expand_env_vars(&target_path);
+    let kind = if is_move { OperationKind::Move } else { OperationKind::Copy };
+    let _ = operations::run(kind, paths.clone(), Some(target_path.clone()), move |control| {
+        sta_worker::run_file_operation(control, || {
+            sta_worker::paste_items_impl(paths, target_path, is_move, resolutions, hwnd, control)
+        })
+    })
+    .await;
 
     // Always empty the clipboard after success.
     // Retry a few times in case check_clipboard holds the lock.
-    let _root_hwnd_clone = root_hwnd;
     std::thread::spawn(move || {
         let mut cleared = false;
         for _ in 0..10 {
@@ -621,15 +791,20 @@ async fn drop_items(
     window: tauri::Window,
     files: Vec<String>,
     target_path: String,
+    resolutions: Option<conflicts::Resolutions>,
 ) -> Result<(), String> {
-    let root_hwnd = get_root_hwnd(&window);
-    harden_focus(root_hwnd);
+    let hwnd = {
+        let root_hwnd = get_root_hwnd(&window);
+        harden_focus(root_hwnd);
+        Some(root_hwnd.0 as isize)
+    };
 
-    let _ = crate::sta_worker::StaWorker::global().drop_items(
-        files,
-        target_path,
-        Some(root_hwnd.0 as isize),
-    );
+    let _ = operations::run(OperationKind::Copy, files.clone(), Some(target_path.clone()), move |control| {
+        sta_worker::run_file_operation(control, || {
+            sta_worker::drop_items_impl(files, target_path, resolutions, hwnd, control)
+        })
+    })
+    .await;
     Ok(())
 }
 
@@ -639,14 +814,19 @@ async fn move_items(
     paths: Vec<String>,
     target_path: String,
 ) -> Result<(), String> {
-    let root_hwnd = get_root_hwnd(&window);
-    harden_focus(root_hwnd);
+    let hwnd = {
+        let root_hwnd = get_root_hwnd(&window);
+        harden_focus(root_hwnd);
+        Some(root_hwnd.0 as isize)
+    };
 
-    let _ = crate::sta_worker::StaWorker::global().move_items(
-        paths,
-        target_path,
-        Some(root_hwnd.0 as isize),
-    );
+    let _ = operations::run(OperationKind::Move, paths.clone(), Some(target_path.clone()), move |control| {
+        sta_worker::run_file_operation(control, || {
+            sta_worker::move_items_impl(paths, target_path, hwnd, control)
+        })
+        .map(|_| Vec::new())
+    })
+    .await;
     Ok(())
 }
 
@@ -656,10 +836,17 @@ async fn delete_items(
     paths: Vec<String>,
     _silent: bool,
 ) -> Result<(), String> {
-    let root_hwnd = get_root_hwnd(&window);
-    harden_focus(root_hwnd);
+    let hwnd = {
+        let root_hwnd = get_root_hwnd(&window);
+        harden_focus(root_hwnd);
+        Some(root_hwnd.0 as isize)
+    };
 
-    let _ = crate::sta_worker::StaWorker::global().delete_items(paths, Some(root_hwnd.0 as isize));
+    let _ = operations::run(OperationKind::Delete, paths.clone(), None, move |control| {
+        sta_worker::run_file_operation(control, || sta_worker::delete_items_impl(paths, hwnd, control))
+            .map(|_| Vec::new())
+    })
+    .await;
     Ok(())
 }
 
@@ -735,68 +922,6 @@ fn cut_items(paths: Vec<String>) -> Result<(), String> {
     set_file_drop(paths, 2)
 }
 
-#[tauri::command]
-fn open_terminal(path: String) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        use std::process::Command;
-
-        Command::new("cmd")
-            .args(["/c", "start", "wt.exe", "-d", "."])
-            .current_dir(&path)
-            .creation_flags(0x08000000)
-            .spawn()
-            .map_err(|e| e.to_string())?;
-        Ok(())
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("Only supported on Windows".to_string())
-    }
-}
-
-#[tauri::command]
-fn resolve_shortcut(path: String) -> Result<String, String> {
-    #[cfg(target_os = "windows")]
-    {
-        use windows::Win32::System::Com::{CoCreateInstance, IPersistFile, CLSCTX_INPROC_SERVER};
-        use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
-
-        unsafe {
-            let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
-                .map_err(|e| format!("CoCreateInstance failed: {}", e))?;
-
-            let persist_file: IPersistFile = shell_link
-                .cast()
-                .map_err(|e| format!("QueryInterface(IPersistFile) failed: {}", e))?;
-
-            let path_wide: Vec<u16> = OsStr::new(&path)
-                .encode_wide()
-                .chain(std::iter::once(0))
-                .collect();
-            persist_file
-                .Load(windows::core::PCWSTR(path_wide.as_ptr()), STGM(0))
-                .map_err(|e| format!("IPersistFile::Load failed: {}", e))?;
-
-            let mut target_path = [0u16; 260];
-            shell_link
-                .GetPath(&mut target_path, std::ptr::null_mut(), 0)
-                .map_err(|e| format!("IShellLink::GetPath failed: {}", e))?;
-
-            let end = target_path
-                .iter()
-                .position(|&c| c == 0)
-                .unwrap_or(target_path.len());
-            Ok(String::from_utf16_lossy(&target_path[..end]))
-        }
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("Unsupported operating system".into())
-    }
-}
-
 pub fn get_next_available_path(target_dir: &str, original_name: &str) -> std::path::PathBuf {
     let base_path = std::path::Path::new(target_dir).join(original_name);
     if !base_path.exists() {
@@ -809,7 +934,8 @@ pub fn get_next_available_path(target_dir: &str, original_name: &str) -> std::pa
         .map(|e| format!(".{}", e.to_string_lossy()))
         .unwrap_or_default();
 
-    let copy_name = format!("{} - Copia{}", stem, extension);
+    let suffix = i18n::tr(i18n::Text::CopySuffix);
+    let copy_name = format!("{}{}{}", stem, suffix, extension);
     let mut check_path = std::path::Path::new(target_dir).join(&copy_name);
     if !check_path.exists() {
         return check_path;
@@ -817,7 +943,7 @@ pub fn get_next_available_path(target_dir: &str, original_name: &str) -> std::pa
 
     let mut count = 2;
     loop {
-        let name = format!("{} - Copia ({}){}", stem, count, extension);
+        let name = format!("{}{} ({}){}", stem, suffix, count, extension);
         check_path = std::path::Path::new(target_dir).join(&name);
         if !check_path.exists() {
             return check_path;
@@ -861,7 +987,10 @@ fn save_clipboard_image(window: tauri::Window, target_path: String) -> Result<Fi
                     image::load_from_memory_with_format(&bmp_data, image::ImageFormat::Bmp)
                 {
                     let now = chrono::Local::now();
-                    let filename = format!("Screenshot_{}.jpg", now.format("%d_%m_%Y_%H_%M_%S"));
+                    let filename = format!(
+                        "{}_{}.jpg",
+                        i18n::tr(i18n::Text::Screenshot),
+                        now.format("%d_%m_%Y_%H_%M_%S"));
                     let target_file_path = get_next_available_path(&target_path, &filename);
 
                     if let Err(e) = img.save(&target_file_path) {
@@ -869,56 +998,26 @@ fn save_clipboard_image(window: tauri::Window, target_path: String) -> Result<Fi
                         if err_str.contains("os error 5")
                             || err_str.to_lowercase().contains("access is denied")
                         {
-                            let temp_dir = std::env::temp_dir();
-                            let temp_path = temp_dir.join(&filename);
+                            // Saved under its final name, so moving it into place keeps that name
+                            let temp_path = std::env::temp_dir().join(
+                                target_file_path
+                                    .file_name()
+                                    .unwrap_or(OsStr::new(&filename)),
+                            );
 
                             img.save(&temp_path)
                                 .map_err(|e| format!("Failed to save temp image: {}", e))?;
 
-                            let cmd = "cmd.exe";
-                            let params = format!(
-                                "/c move /Y \"{}\" \"{}\"",
-                                temp_path.display(),
-                                target_file_path.display()
-                            );
-
-                            use windows::Win32::UI::Shell::ShellExecuteW;
-                            use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
-
-                            let file_wide: Vec<u16> = OsStr::new(cmd)
-                                .encode_wide()
-                                .chain(std::iter::once(0))
-                                .collect();
-                            let params_wide: Vec<u16> = OsStr::new(&params)
-                                .encode_wide()
-                                .chain(std::iter::once(0))
-                                .collect();
-                            let verb_wide: Vec<u16> = OsStr::new("runas")
-                                .encode_wide()
-                                .chain(std::iter::once(0))
-                                .collect();
-
                             let root_hwnd = get_root_hwnd(&window);
                             harden_focus(root_hwnd);
                             std::thread::sleep(std::time::Duration::from_millis(50));
 
-                            unsafe {
-                                let result = ShellExecuteW(
-                                    Some(root_hwnd),
-                                    PCWSTR(verb_wide.as_ptr()),
-                                    PCWSTR(file_wide.as_ptr()),
-                                    PCWSTR(params_wide.as_ptr()),
-                                    PCWSTR(std::ptr::null()),
-                                    SW_HIDE,
-                                );
-
-                                if (result.0 as isize) <= 32 {
-                                    return Err(
-                                        "Failed to request admin permissions or user cancelled"
-                                            .to_string(),
-                                    );
-                                }
-                            }
+                            let request = elevation::ElevatedRequest {
+                                kind: OperationKind::Move,
+                                sources: vec![temp_path.to_string_lossy().to_string()],
+                                target: Some(target_path.clone()),
+                            };
+                            elevation::run_elevated(root_hwnd, &request)?;
                             return get_file_entry(&target_file_path);
                         } else {
                             return Err(format!("Failed to save image: {}", e));
@@ -937,15 +1036,16 @@ async fn get_thumbnail_bytes(
     size: u32,
     modified: i64,
     state: tauri::State<'_, ThumbnailCache>,
+    limit: tauri::State<'_, ThumbnailConcurrencyLimit>,
     is_video: bool,
+    seek: Option<crate::ffmpeg::VideoSeek>,
 ) -> Result<Vec<u8>, String> {
-    let cache_key = format!(
-        "{}:{}:{}:{}",
-        if is_video { "video" } else { "image" },
-        path,
-        size,
-        modified
-    );
+    let kind = match (is_video, seek) {
+        (true, Some(seek)) => format!("video@{}", seek),
+        (true, None) => "video".to_string(),
+        (false, _) => "image".to_string(),
+    };
+    let cache_key = format!("{}:{}:{}:{}", kind, path, size, modified);
 
     {
         let mut cache = state.0.lock().unwrap();
@@ -954,40 +1054,25 @@ async fn get_thumbnail_bytes(
         }
     }
 
+    // Only cache misses take a worker slot
+    let _permit = limit
+        .0
+        .acquire()
+        .await
+        .map_err(|e| format!("Thumbnail pool closed: {}", e))?;
+
     let mut result_bytes: Option<Vec<u8>> = None;
 
-    if let Ok(bytes) = generate_shell_thumbnail(&path, size) {
-        result_bytes = Some(bytes);
+    // The shell picks its own frame, so an explicit position goes straight to ffmpeg
+    if seek.is_none() || !is_video {
+        if let Ok(bytes) = generate_shell_thumbnail(&path, size) {
+            result_bytes = Some(bytes);
+        }
     }
 
-    if result_bytes.is_none() && is_video {
-        let mut cmd = tokio::process::Command::new("ffmpeg");
-        #[cfg(windows)]
-        cmd.creation_flags(0x08000000);
-
-        let output_res = cmd
-            .arg("-ss")
-            .arg("2.0")
-            .arg("-i")
-            .arg(&path)
-            .arg("-vf")
-            .arg("scale=480:-1:flags=lanczos")
-            .arg("-vframes")
-            .arg("1")
-            .arg("-f")
-            .arg("image2")
-            .arg("-c:v")
-            .arg("mjpeg")
-            .arg("-pipe:1")
-            .arg("pipe:1")
-            .output()
-            .await;
-
-        if let Ok(output) = output_res {
-            if output.status.success() {
-                result_bytes = Some(output.stdout);
-            }
-        }
+    // ffmpeg reads the file, which would download an online-only video
+    if result_bytes.is_none() && is_video && !crate::cloud::is_online_only(std::path::Path::new(&path)) {
+        result_bytes = crate::ffmpeg::grab_frame(&path, seek.unwrap_or(crate::ffmpeg::DEFAULT_SEEK)).await;
     }
 
     if let Some(bytes) = result_bytes {
@@ -1007,8 +1092,10 @@ async fn get_video_thumbnail(
     size: u32,
     modified: i64,
     state: tauri::State<'_, ThumbnailCache>,
+    limit: tauri::State<'_, ThumbnailConcurrencyLimit>,
+    seek: Option<crate::ffmpeg::VideoSeek>,
 ) -> Result<String, String> {
-    let bytes = get_thumbnail_bytes(path, size, modified, state, true).await?;
+    let bytes = get_thumbnail_bytes(path, size, modified, state, limit, true, seek).await?;
     let base64_img = base64::engine::general_purpose::STANDARD.encode(&bytes);
     Ok(format!("data:image/jpeg;base64,{}", base64_img))
 }
@@ -1019,13 +1106,14 @@ async fn get_thumbnail(
     size: u32,
     modified: i64,
     state: tauri::State<'_, ThumbnailCache>,
+    limit: tauri::State<'_, ThumbnailConcurrencyLimit>,
 ) -> Result<String, String> {
-    let bytes = get_thumbnail_bytes(path, size, modified, state, false).await?;
+    let bytes = get_thumbnail_bytes(path, size, modified, state, limit, false, None).await?;
     let base64_img = base64::engine::general_purpose::STANDARD.encode(&bytes);
     Ok(format!("data:image/jpeg;base64,{}", base64_img))
 }
 
-fn generate_shell_thumbnail(path: &str, size: u32) -> Result<Vec<u8>, String> {
+pub(crate) fn generate_shell_thumbnail(path: &str, size: u32) -> Result<Vec<u8>, String> {
     use windows::Win32::Foundation::SIZE;
     use windows::Win32::Graphics::Gdi::{
         CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW, SelectObject, BITMAP,
@@ -1159,115 +1247,227 @@ fn generate_shell_thumbnail(path: &str, size: u32) -> Result<Vec<u8>, String> {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 struct FileDimensionsResult {
     dimensions: String,
     source: String,
 }
 
-#[tauri::command]
-async fn get_file_dimensions(path: String) -> Result<Option<FileDimensionsResult>, String> {
-    tokio::task::spawn_blocking(move || {
-        unsafe {
-            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+/// "WxH" from the first video stream in ffmpeg's description of one input
+fn parse_ffmpeg_video_size(info: &str) -> Option<String> {
+    let v_idx = info.find("Video:")?;
+    for part in info[v_idx..].split(',') {
+        let part = part.trim();
+        if let Some(x_idx) = part.find('x') {
+            if x_idx > 0 && x_idx < part.len() - 1 {
+                let w_str = &part[0..x_idx];
+                let h_str = &part[x_idx + 1..];
+                let h_str_clean = h_str.split_whitespace().next().unwrap_or(h_str);
+                if w_str.chars().all(char::is_numeric) && h_str_clean.chars().all(char::is_numeric)
+                {
+                    return Some(format!("{}x{}", w_str, h_str_clean));
+                }
+            }
+        }
+    }
+    None
+}
 
-            let path_wide: Vec<u16> = std::ffi::OsStr::new(&path)
-                .encode_wide()
-                .chain(std::iter::once(0))
-                .collect();
+/// Dimensions from the shell property store or the image header; COM must be initialized
+fn probe_native_dimensions(path: &str) -> Option<FileDimensionsResult> {
+    unsafe {
+        let path_wide: Vec<u16> = std::ffi::OsStr::new(path)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
 
-            let shell_item_result: Result<IShellItem2, _> =
-                SHCreateItemFromParsingName(PCWSTR(path_wide.as_ptr()), None);
-
-            if let Ok(shell_item) = shell_item_result {
-                // PKEY_Video_FrameWidth: {64440489-4C8E-11D1-8C70-00C04FC2B64F}, 3
-                let k_width = PROPERTYKEY {
-                    fmtid: windows::core::GUID::from_values(
-                        0x64440489,
-                        0x4C8E,
-                        0x11D1,
-                        [0x8C, 0x70, 0x00, 0xC0, 0x4F, 0xC2, 0xB6, 0x4F],
-                    ),
-                    pid: 3,
-                };
-                // PKEY_Video_FrameHeight: {64440489-4C8E-11D1-8C70-00C04FC2B64F}, 4
-                let k_height = PROPERTYKEY {
-                    fmtid: windows::core::GUID::from_values(
-                        0x64440489,
-                        0x4C8E,
-                        0x11D1,
-                        [0x8C, 0x70, 0x00, 0xC0, 0x4F, 0xC2, 0xB6, 0x4F],
-                    ),
-                    pid: 4,
-                };
+        let shell_item_result: Result<IShellItem2, _> =
+            SHCreateItemFromParsingName(PCWSTR(path_wide.as_ptr()), None);
+
+        if let Ok(shell_item) = shell_item_result {
+            // PKEY_Video_FrameWidth: {64440489-4C8E-11D1-8C70-00C04FC2B64F}, 3
+            let k_width = PROPERTYKEY {
+                fmtid: windows::core::GUID::from_values(
+                    0x64440489,
+                    0x4C8E,
+                    0x11D1,
+                    [0x8C, 0x70, 0x00, 0xC0, 0x4F, 0xC2, 0xB6, 0x4F],
+                ),
+                pid: 3,
+            };
+            // PKEY_Video_FrameHeight: {64440489-4C8E-11D1-8C70-00C04FC2B64F}, 4
+            let k_height = PROPERTYKEY {
+                fmtid: windows::core::GUID::from_values(
+                    0x64440489,
+                    0x4C8E,
+                    0x11D1,
+                    [0x8C, 0x70, 0x00, 0xC0, 0x4F, 0xC2, 0xB6, 0x4F],
+                ),
+                pid: 4,
+            };
 
-                if let (Ok(w), Ok(h)) = (
-                    shell_item.GetUInt32(&k_width),
-                    shell_item.GetUInt32(&k_height),
-                ) {
-                    if w > 0 && h > 0 {
-                        CoUninitialize();
-                        return Ok(Some(FileDimensionsResult {
-                            dimensions: format!("{}x{}", w, h),
-                            source: "native".to_string(),
-                        }));
-                    }
+            if let (Ok(w), Ok(h)) = (
+                shell_item.GetUInt32(&k_width),
+                shell_item.GetUInt32(&k_height),
+            ) {
+                if w > 0 && h > 0 {
+                    return Some(FileDimensionsResult {
+                        dimensions: format!("{}x{}", w, h),
+                        source: "native".to_string(),
+                    });
                 }
             }
+        }
+    }
 
-            // Fallback for images via the `image` crate
-            if let Ok((w, h)) = image::image_dimensions(&path) {
-                CoUninitialize();
-                return Ok(Some(FileDimensionsResult {
-                    dimensions: format!("{}x{}", w, h),
-                    source: "native".to_string(),
-                }));
-            }
+    // Fallback for images via the `image` crate
+    if let Ok((w, h)) = image::image_dimensions(path) {
+        return Some(FileDimensionsResult {
+            dimensions: format!("{}x{}", w, h),
+            source: "native".to_string(),
+        });
+    }
+    None
+}
 
-            // Fallback for videos (FFmpeg probe)
-            let mut cmd = std::process::Command::new("ffmpeg");
-            #[cfg(windows)]
-            {
-                use std::os::windows::process::CommandExt;
-                cmd.creation_flags(0x08000000);
+/// Video sizes for `paths` from as few ffmpeg runs as possible: every file is passed as
+/// an input of one process, which describes each in turn. ffmpeg stops at the first file
+/// it can't open, so the run is repeated for the files after it.
+fn probe_ffmpeg_dimensions(paths: &[String]) -> Vec<Option<String>> {
+    let mut results = vec![None; paths.len()];
+    let mut next = 0;
+    while next < paths.len() {
+        let program = crate::ffmpeg::program();
+        let mut cmd = std::process::Command::new(&program);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000);
+        }
+        cmd.arg("-hide_banner");
+        for path in &paths[next..] {
+            cmd.arg("-i").arg(path);
+        }
+        let output = match cmd.output() {
+            Ok(output) => output,
+            Err(e) => {
+                crate::ffmpeg::report_spawn_error(&program, &e);
+                break;
             }
-            let output = cmd.args(&["-i", &path]).output();
-
-            if let Ok(output) = output {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                if let Some(v_idx) = stderr.find("Video:") {
-                    let sub = &stderr[v_idx..];
-                    let parts: Vec<&str> = sub.split(',').collect();
-                    for part in parts {
-                        let part = part.trim();
-                        if let Some(x_idx) = part.find('x') {
-                            if x_idx > 0 && x_idx < part.len() - 1 {
-                                let w_str = &part[0..x_idx];
-                                let h_str = &part[x_idx + 1..];
-                                let h_str_clean = h_str.split_whitespace().next().unwrap_or(h_str);
-                                if w_str.chars().all(char::is_numeric)
-                                    && h_str_clean.chars().all(char::is_numeric)
-                                {
-                                    CoUninitialize();
-                                    return Ok(Some(FileDimensionsResult {
-                                        dimensions: format!("{}x{}", w_str, h_str_clean),
-                                        source: "ffmpeg".to_string(),
-                                    }));
-                                }
-                            }
-                        }
-                    }
-                }
+        };
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        // One "Input #N" block per file that could be opened, in order
+        let mut described = 0;
+        for block in stderr.split("Input #").skip(1) {
+            results[next + described] = parse_ffmpeg_video_size(block);
+            described += 1;
+            if next + described == paths.len() {
+                break;
             }
+        }
+        // Skip the file ffmpeg gave up on
+        next += described + 1;
+    }
+    results
+}
 
+#[tauri::command]
+async fn get_file_dimensions(
+    path: String,
+    limit: tauri::State<'_, ThumbnailConcurrencyLimit>,
+) -> Result<Option<FileDimensionsResult>, String> {
+    let _permit = limit
+        .0
+        .acquire()
+        .await
+        .map_err(|e| format!("Thumbnail pool closed: {}", e))?;
+    tokio::task::spawn_blocking(move || {
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        }
+        let native = probe_native_dimensions(&path);
+        unsafe {
             CoUninitialize();
-            Ok(None)
         }
+        if native.is_some() {
+            return Ok(native);
+        }
+
+        // Fallback for videos (FFmpeg probe)
+        Ok(probe_ffmpeg_dimensions(std::slice::from_ref(&path))
+            .pop()
+            .flatten()
+            .map(|dimensions| FileDimensionsResult {
+                dimensions,
+                source: "ffmpeg".to_string(),
+            }))
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
+/// Dimensions of many files (e.g. the visible rows) in one worker pass: COM is set up
+/// once and the files the shell can't measure share a single ffmpeg run. Files without
+/// dimensions are left out of the result.
+#[tauri::command]
+async fn get_dimensions_batch(
+    paths: Vec<String>,
+    limit: tauri::State<'_, ThumbnailConcurrencyLimit>,
+) -> Result<std::collections::HashMap<String, FileDimensionsResult>, String> {
+    let _permit = limit
+        .0
+        .acquire()
+        .await
+        .map_err(|e| format!("Thumbnail pool closed: {}", e))?;
+    tokio::task::spawn_blocking(move || {
+        let mut results = std::collections::HashMap::new();
+        let mut unresolved = Vec::new();
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        }
+        for path in paths {
+            match probe_native_dimensions(&path) {
+                Some(dims) => {
+                    results.insert(path, dims);
+                }
+                None => unresolved.push(path),
+            }
+        }
+        unsafe {
+            CoUninitialize();
+        }
+
+        // Only videos are worth handing to ffmpeg
+        unresolved.retain(|p| {
+            let ext = std::path::Path::new(p)
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            [
+                "mp4", "mkv", "avi", "mov", "wmv", "webm", "flv", "mpg", "mpeg", "m4v", "ts",
+            ]
+            .contains(&ext.as_str())
+        });
+        let probed = probe_ffmpeg_dimensions(&unresolved);
+        for (path, dimensions) in unresolved.into_iter().zip(probed) {
+            if let Some(dimensions) = dimensions {
+                results.insert(
+                    path,
+                    FileDimensionsResult {
+                        dimensions,
+                        source: "ffmpeg".to_string(),
+                    },
+                );
+            }
+        }
+        results
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn get_system_default_paths() -> Result<std::collections::HashMap<String, String>, String> {
     #[cfg(target_os = "windows")]
@@ -1676,16 +1876,32 @@ async fn debug_window_hierarchy(window: tauri::Window) -> Vec<serde_json::Value>
     }
     results
 }
+/// Normalizes "C", "C:" or "C:\" to the drive root the Recycle Bin APIs expect
+/// Normalizes "C", "C:" or "C:\\" to the drive root the Recycle Bin APIs expect
+fn recycle_bin_root(drive: Option<String>) -> Result<Option<String>, String> {
+    match drive {
+        None => Ok(None),
+        Some(drive) => drive
+            .chars()
+            .next()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| Some(format!("{}:\\", c.to_ascii_uppercase())))
+            .ok_or_else(|| format!("Invalid drive: {}", drive)),
+    }
+}
 
+/// Recycle Bin usage across all drives, or only `drive`'s bin when given
 #[tauri::command]
-fn get_recycle_bin_status() -> Result<RecycleBinStatus, String> {
+fn get_recycle_bin_status(drive: Option<String>) -> Result<RecycleBinStatus, String> {
+    let root = recycle_bin_root(drive)?.map(|r| windows::core::HSTRING::from(r.as_str()));
     unsafe {
         let mut info = SHQUERYRBINFO {
             cbSize: std::mem::size_of::<SHQUERYRBINFO>() as u32,
             ..Default::default()
         };
+        let root_ptr = root.as_ref().map(|r| PCWSTR(r.as_ptr())).unwrap_or(PCWSTR::null());
 
-        match SHQueryRecycleBinW(None, &mut info) {
+        match SHQueryRecycleBinW(root_ptr, &mut info) {
             Ok(_) => Ok(RecycleBinStatus {
                 is_empty: info.i64NumItems == 0,
                 item_count: info.i64NumItems,
@@ -1696,9 +1912,10 @@ fn get_recycle_bin_status() -> Result<RecycleBinStatus, String> {
     }
 }
 
+/// Empties every drive's Recycle Bin, or only `drive`'s bin when given
 #[tauri::command]
-fn empty_recycle_bin() -> Result<(), String> {
-    crate::sta_worker::StaWorker::global().empty_recycle_bin()
+fn empty_recycle_bin(drive: Option<String>) -> Result<(), String> {
+    crate::sta_worker::StaWorker::global().empty_recycle_bin(recycle_bin_root(drive)?)
 }
 
 #[tauri::command]
@@ -1817,15 +2034,7 @@ async fn calculate_folder_size(app_handle: tauri::AppHandle, path: String, nav_i
                 // If timed out, the size is partial so we prefix with ">"
                 let timed_out = is_timed_out();
                 let formatted_size = {
-                    let raw = if size < 1024 {
-                        format!("{} B", size)
-                    } else if size < 1024 * 1024 {
-                        format!("{:.1} KB", size as f64 / 1024.0)
-                    } else if size < 1024 * 1024 * 1024 {
-                        format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
-                    } else {
-                        format!("{:.2} GB", size as f64 / (1024.0 * 1024.0 * 1024.0))
-                    };
+                    let raw = crate::settings::format_size(size);
                     if timed_out {
                         format!("> {}", raw)
                     } else {
@@ -1900,6 +2109,7 @@ fn calculate_dir_size_recursive(
     total_size
 }
 
+/// Shared by thumbnail generation and dimension probes, which may each spawn ffmpeg
 struct ThumbnailConcurrencyLimit(tokio::sync::Semaphore);
 struct FolderSizeHDDLimit(std::sync::Arc<tokio::sync::Semaphore>);
 struct FolderSizeSSDLimit(std::sync::Arc<tokio::sync::Semaphore>);
@@ -1913,16 +2123,34 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_drag::init())
         .manage(ThumbnailCache(std::sync::Mutex::new(lru::LruCache::new(
-            std::num::NonZeroUsize::new(500).unwrap(),
+            std::num::NonZeroUsize::new(crate::settings::preferences().thumbnail_cache_entries)
+                .unwrap_or(std::num::NonZeroUsize::new(500).unwrap()),
         ))))
         .manage(ClipboardCache(std::sync::Mutex::new(None)))
-        .manage(ThumbnailConcurrencyLimit(tokio::sync::Semaphore::new(4)))
+        .manage(ThumbnailConcurrencyLimit(tokio::sync::Semaphore::new(
+            crate::settings::preferences().thumbnail_worker_count(),
+        )))
         .manage(FolderSizeHDDLimit(std::sync::Arc::new(tokio::sync::Semaphore::new(1))))
         .manage(FolderSizeSSDLimit(std::sync::Arc::new(tokio::sync::Semaphore::new(8))))
-        .on_window_event(|_window, event| {
+        .on_window_event(|window, event| {
             if let tauri::WindowEvent::Focused(focused) = event {
                 log::info!("!!! [RUST] Window focused: {}", focused);
             }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                session::flush();
+                frecency::flush();
+                if crate::settings::preferences().close_to_tray {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
+        .register_asynchronous_uri_scheme_protocol("stream", |app, request, responder| {
+            use tauri::Manager;
+            let scope = app.app_handle().asset_protocol_scope();
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(file_stream::handle(&request, &scope));
+            });
         })
         .register_asynchronous_uri_scheme_protocol("thumbnail", |app, request, responder| {
             use tauri::Manager;
@@ -1944,9 +2172,11 @@ pub fn run() {
             let mut path = String::new();
             let mut size = 256;
             let mut modified = 0;
+            let mut seek = None;
 
             for (k, v) in url.query_pairs() {
                 match k.as_ref() {
+                    "seek" => seek = crate::ffmpeg::VideoSeek::parse(&v),
                     "path" => path = v.into_owned(),
                     "s" => size = v.parse().unwrap_or(256),
                     "m" => modified = v.parse().unwrap_or(0),
@@ -1982,23 +2212,9 @@ pub fn run() {
             let app_handle = app.app_handle().clone();
 
             tauri::async_runtime::spawn(async move {
-                let limit_state = app_handle.state::<ThumbnailConcurrencyLimit>();
-                let default_permit_result = limit_state.0.acquire().await;
-                let _permit = match default_permit_result {
-                    Ok(p) => p,
-                    Err(_) => {
-                        responder.respond(
-                            tauri::http::Response::builder()
-                                .status(500)
-                                .body(Vec::new())
-                                .unwrap(),
-                        );
-                        return;
-                    }
-                };
-
                 let state = app_handle.state::<ThumbnailCache>();
-                match get_thumbnail_bytes(path.clone(), size, modified, state, is_video).await {
+                let limit = app_handle.state::<ThumbnailConcurrencyLimit>();
+                match get_thumbnail_bytes(path.clone(), size, modified, state, limit, is_video, seek).await {
                     Ok(bytes) => {
                         let response = tauri::http::Response::builder()
                             .header("Access-Control-Allow-Origin", "*")
@@ -2023,6 +2239,12 @@ pub fn run() {
         .setup(|app| {
             let _ = APP_HANDLE.set(app.handle().clone());
             let _ = GLOBAL_NAV_ID.set(std::sync::Mutex::new(String::new()));
+            launch::init();
+            single_instance::start_server(app.handle().clone());
+            appearance::start(app.handle());
+            if let Err(e) = tray::start(app.handle()) {
+                log::error!("[TRAY] Failed to create tray icon: {}", e);
+            }
             let window = app.get_webview_window("main").unwrap();
 
             #[cfg(target_os = "windows")]
@@ -2035,16 +2257,27 @@ pub fn run() {
                 drop_overlay::create_drop_overlay(whwnd);
                 log::debug!("[SETUP] Drop overlay creation triggered");
             }
+
+            search_engine::folder_index::start();
+            search_engine::volume_index::start();
+            prefetch::start(app.handle().clone());
+            disk_monitor::start();
+            devices::start();
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             list_files,
+            list_files_count,
             read_file_base64,
             commands::run_recursive_search,
             show_item_properties,
             open_file,
             open_with,
             create_folder,
+            launch::take_launch_target,
+            new_file::create_text_file,
+            new_file::get_new_menu_items,
+            new_file::create_from_shell_new,
             delete_item,
             rename_item,
             copy_items,
@@ -2054,6 +2287,8 @@ pub fn run() {
             move_items,
             delete_items,
             get_file_dimensions,
+            get_dimensions_batch,
+            ffmpeg::check_ffmpeg,
             get_system_default_paths,
             get_clipboard_info,
             get_dropped_file_paths,
@@ -2064,11 +2299,117 @@ pub fn run() {
             restore_items,
             save_clipboard_image,
             get_clipboard_text,
-            open_terminal,
-            resolve_shortcut,
+            terminal::open_terminal,
+            terminal::list_terminal_profiles,
+            shortcuts::resolve_shortcut,
+            shortcuts::update_shortcut,
+            shortcuts::create_url_file,
+            shortcuts::create_shortcut,
+            associations::get_association,
+            shell_actions::show_in_explorer,
+            shell_actions::run_elevated,
+            shell_actions::open_file_with,
+            shell_actions::print_files,
+            tray::set_tray_folders,
+            appearance::set_window_backdrop,
+            appearance::get_system_theme,
+            watcher::watch_directory,
+            watcher::unwatch_directory,
+            rotation::rotate_image,
+            shell_actions::list_special_folders,
+            shell_actions::open_special_folder,
+            shell_actions::show_shell_context_menu,
+            paths::expand_path,
+            search_engine::live_search::start_search,
+            search_engine::live_search::cancel_search,
+            search_engine::folder_index::quick_search,
+            search_engine::volume_index::instant_search,
+            search_engine::windows_search::windows_search,
+            hashing::compute_hash,
+            hashing::cancel_hash,
+            checksums::verify_checksum_file,
+            checksums::verify_checksum,
+            settings::get_settings,
+            settings::set_settings,
+            settings::get_setting,
+            settings::set_setting,
+            session::save_session,
+            session::get_last_session,
+            tags::list_tags,
+            tags::set_tag,
+            tags::rename_tag,
+            tags::delete_tag,
+            tags::tag_files,
+            tags::untag_files,
+            tags::get_file_tags,
+            tags::get_tags_for_paths,
+            tags::get_files_by_tag,
+            frecency::get_frequent_folders,
+            app_data::export_app_data,
+            app_data::import_app_data,
+            history::query_history,
+            history::clear_history,
+            listing::diff_list,
+            memory::get_memory_stats,
+            memory::purge_caches,
+            permissions::get_effective_access,
+            permissions::get_security_info,
+            permissions::take_ownership,
+            elevation::retry_elevated,
+            locks::find_locking_processes,
+            locks::close_handles_and_retry,
+            links::get_link_chain,
+            links::create_symlink,
+            links::create_hardlink,
+            links::create_junction,
+            attributes::set_file_attributes,
+            streams::list_alternate_streams,
+            streams::remove_zone_identifier,
+            network::list_network_computers,
+            network::list_shares,
+            network::map_network_drive,
+            network::unmap_network_drive,
+            devices::eject_drive,
+            cloud::pin_file,
+            cloud::free_up_space,
+            quick_access::get_pinned_folders,
+            quick_access::set_pinned_folders,
+            quick_access::pin_folder,
+            quick_access::unpin_folder,
+            recent::get_recent_files,
+            navigation::push_history,
+            navigation::navigate_back,
+            navigation::navigate_forward,
+            navigation::get_history,
+            reputation::check_file_reputation,
+            reputation::set_virustotal_api_key,
+            reputation::has_virustotal_api_key,
+            disk_usage::analyze_disk_usage,
+            disk_usage::cancel_disk_usage,
+            drive_health::get_drive_health,
+            cleanup::get_cleanup_candidates,
+            drive_tools::check_drive,
+            drive_tools::optimize_drive,
+            storage_stats::get_folder_storage_stats,
+            disk_monitor::set_monitored_paths,
+            disk_monitor::get_low_disk_settings,
+            disk_monitor::set_low_disk_settings,
+            search_engine::saved_searches::list_saved_searches,
+            search_engine::saved_searches::save_search,
+            search_engine::saved_searches::delete_saved_search,
+            search_engine::saved_searches::run_saved_search,
             drop_overlay::show_overlay,
             drop_overlay::hide_overlay,
             extraction::extract_archive,
+            extraction::cancel_extraction,
+            conflicts::find_conflicts,
+            folder_size::get_folder_size,
+            batch_rename::batch_rename,
+            operations::list_operations,
+            operations::pause_operation,
+            operations::resume_operation,
+            operations::cancel_operation,
+            compression::create_archive,
             read_preview_text,
             calculate_folder_size,
             cancel_folder_size_calculations,
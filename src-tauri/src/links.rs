@@ -0,0 +1,424 @@
+//! Links Module
+//!
+//! Symbolic links, junctions and the loops they can form. Two NTFS paths
+//! name the same folder exactly when their volume serial and file index
+//! match, so recursive walks that follow links keep a `VisitedDirs` set of
+//! those ids and skip any folder they have already entered; this is what
+//! stops endless descents through junction cycles. `get_link_chain` resolves
+//! a link hop by hop for the details pane.
+//!
+//! `create_symlink`, `create_hardlink` and `create_junction` make new links
+//! next to a folder's other items (shortcuts are in `shortcuts`). Symbolic
+//! links need administrator rights unless Developer Mode is on; junctions and
+//! hard links never do, but only reach local folders and same-volume files.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_INVALID_FUNCTION, ERROR_INVALID_PARAMETER, ERROR_NOT_SAME_DEVICE,
+    ERROR_PRIVILEGE_NOT_HELD, ERROR_TOO_MANY_LINKS,
+};
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, CreateHardLinkW, CreateSymbolicLinkW, FindClose, FindFirstFileW,
+    GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION, FILE_ATTRIBUTE_REPARSE_POINT,
+    FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT, FILE_GENERIC_WRITE,
+    FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    SYMBOLIC_LINK_FLAG_ALLOW_UNPRIVILEGED_CREATE, SYMBOLIC_LINK_FLAG_DIRECTORY, WIN32_FIND_DATAW,
+};
+use windows::Win32::System::Ioctl::FSCTL_SET_REPARSE_POINT;
+use windows::Win32::System::IO::DeviceIoControl;
+
+const IO_REPARSE_TAG_MOUNT_POINT: u32 = 0xA000_0003;
+const IO_REPARSE_TAG_SYMLINK: u32 = 0xA000_000C;
+/// Windows refuses to resolve more than 63 reparse points in one path
+const MAX_LINK_HOPS: usize = 63;
+
+/// Identity of a file or folder on its volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileId {
+    volume_serial: u32,
+    file_index: u64,
+}
+
+/// Identity of whatever `path` finally resolves to (links are followed)
+pub fn file_id(path: &Path) -> Option<FileId> {
+    unsafe {
+        let handle = CreateFileW(
+            &HSTRING::from(crate::paths::long_path(path).as_path()),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            None,
+            OPEN_EXISTING,
+            // Required to open folders
+            FILE_FLAG_BACKUP_SEMANTICS,
+            None,
+        )
+        .ok()?;
+        let mut info = BY_HANDLE_FILE_INFORMATION::default();
+        let result = GetFileInformationByHandle(handle, &mut info);
+        let _ = CloseHandle(handle);
+        result.ok()?;
+        Some(FileId {
+            volume_serial: info.dwVolumeSerialNumber,
+            file_index: ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64,
+        })
+    }
+}
+
+/// Folders a recursive walk has already entered, keyed by identity rather than path
+#[derive(Default)]
+pub struct VisitedDirs(HashSet<FileId>);
+
+impl VisitedDirs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True the first time a folder is seen. Folders whose id cannot be read are
+    /// always entered; they can't be told apart, but they can't loop back either.
+    pub fn first_visit(&mut self, dir: &Path) -> bool {
+        match file_id(dir) {
+            Some(id) => self.0.insert(id),
+            None => true,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum LinkKind {
+    Symlink,
+    Junction,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct LinkHop {
+    pub path: String,
+    pub kind: LinkKind,
+    /// Target as stored in the link (may be relative for symlinks)
+    pub target: String,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct LinkChain {
+    /// One entry per link followed, starting with `path` itself
+    pub hops: Vec<LinkHop>,
+    /// Where the chain ends
+    pub final_path: String,
+    /// The final path does not exist
+    pub is_broken: bool,
+    /// The chain leads back to a link it already passed through
+    pub is_cycle: bool,
+}
+
+/// Reparse tag of `path` itself (the link, not its target)
+fn reparse_tag(path: &Path) -> Option<u32> {
+    let mut data = WIN32_FIND_DATAW::default();
+    unsafe {
+        let handle = FindFirstFileW(
+            &HSTRING::from(crate::paths::long_path(path).as_path()),
+            &mut data,
+        )
+        .ok()?;
+        let _ = FindClose(handle);
+    }
+    // For reparse points, FindFirstFile reports the tag in dwReserved0
+    (data.dwFileAttributes & FILE_ATTRIBUTE_REPARSE_POINT.0 != 0).then_some(data.dwReserved0)
+}
+
+/// Removes the `\\?\` prefix from drive-letter paths so they display normally
+fn strip_verbatim(path: &Path) -> PathBuf {
+    let s = path.to_string_lossy();
+    match s.strip_prefix(r"\\?\") {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Kind and stored target of `path` when it is a symbolic link or junction
+pub fn read_link(path: &Path) -> Option<(LinkKind, PathBuf)> {
+    read_link_tagged(path, reparse_tag(path)?)
+}
+
+/// `read_link` for a path whose reparse tag is already known, e.g. from a listing.
+/// Other reparse points (cloud placeholders, dedup) aren't links and return None.
+pub fn read_link_tagged(path: &Path, tag: u32) -> Option<(LinkKind, PathBuf)> {
+    let kind = match tag {
+        IO_REPARSE_TAG_SYMLINK => LinkKind::Symlink,
+        IO_REPARSE_TAG_MOUNT_POINT => LinkKind::Junction,
+        _ => return None,
+    };
+    let target = std::fs::read_link(path).ok()?;
+    Some((kind, strip_verbatim(&target)))
+}
+
+impl crate::FileEntry {
+    /// The entry with its link fields set from `read_link`'s answer
+    pub(crate) fn with_link(mut self, link: Option<(LinkKind, PathBuf)>) -> Self {
+        if let Some((kind, target)) = link {
+            self.is_symlink = kind == LinkKind::Symlink;
+            self.is_junction = kind == LinkKind::Junction;
+            // Relative symlink targets are relative to the folder holding the link
+            let target = match Path::new(&self.path).parent() {
+                Some(parent) if target.is_relative() => parent.join(&target),
+                _ => target,
+            };
+            self.link_target = Some(target.to_string_lossy().to_string());
+        }
+        self
+    }
+}
+
+fn link_chain(path: &Path) -> LinkChain {
+    let mut hops = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = path.to_path_buf();
+    let mut is_cycle = false;
+
+    while let Some((kind, target)) = read_link(&current) {
+        if !seen.insert(current.to_string_lossy().to_lowercase()) || hops.len() >= MAX_LINK_HOPS {
+            is_cycle = true;
+            break;
+        }
+        hops.push(LinkHop {
+            path: current.to_string_lossy().to_string(),
+            kind,
+            target: target.to_string_lossy().to_string(),
+        });
+        // Relative symlink targets are relative to the folder holding the link
+        current = match current.parent() {
+            Some(parent) if target.is_relative() => parent.join(&target),
+            _ => target,
+        };
+    }
+
+    LinkChain {
+        hops,
+        final_path: current.to_string_lossy().to_string(),
+        is_broken: !is_cycle && std::fs::metadata(&current).is_err(),
+        is_cycle,
+    }
+}
+
+/// Follows `path` through every symbolic link / junction it passes, for the details pane
+#[tauri::command]
+pub fn get_link_chain(path: String) -> LinkChain {
+    link_chain(Path::new(&crate::expand_env_vars(&path)))
+}
+
+/// Name for a new link to `target`: its own name, or the drive letter / share name
+/// for a root
+pub(crate) fn link_name(target: &Path) -> String {
+    if let Some(name) = target.file_name() {
+        return name.to_string_lossy().to_string();
+    }
+    target
+        .to_string_lossy()
+        .rsplit(['\\', '/'])
+        .find(|part| !part.is_empty())
+        .unwrap_or_default()
+        .replace(':', "")
+}
+
+/// Expanded `target` and a free path for a link to it in the folder `location`
+fn new_link_path(target: &str, location: &str) -> Result<(PathBuf, PathBuf), String> {
+    let target = PathBuf::from(crate::expand_env_vars(target));
+    let location = crate::expand_env_vars(location);
+    if std::fs::symlink_metadata(&target).is_err() {
+        return Err(format!("Target not found: {}", target.display()));
+    }
+    if !Path::new(&location).is_dir() {
+        return Err(format!("Not a folder: {}", location));
+    }
+    let name = crate::new_file::unique_name(&location, &link_name(&target));
+    let link = Path::new(&location).join(name);
+    Ok((target, link))
+}
+
+/// Message for a link that couldn't be created, naming the usual causes
+fn link_error(kind: &str, error: windows::core::Error) -> String {
+    let code = error.code();
+    let reason = if code == ERROR_PRIVILEGE_NOT_HELD.to_hresult() {
+        "this needs administrator rights, or Developer Mode turned on in Settings > System > For developers".to_string()
+    } else if code == ERROR_NOT_SAME_DEVICE.to_hresult() {
+        "hard links must be on the same volume as their target".to_string()
+    } else if code == ERROR_TOO_MANY_LINKS.to_hresult() {
+        "the file already has as many hard links as it can".to_string()
+    } else if code == ERROR_INVALID_FUNCTION.to_hresult() {
+        "the volume doesn't support it".to_string()
+    } else {
+        error.message()
+    };
+    format!("Failed to create {}: {}", kind, reason)
+}
+
+/// Creates a symbolic link to `target` in the folder `location`
+#[tauri::command]
+pub fn create_symlink(target: String, location: String) -> Result<crate::FileEntry, String> {
+    let (target, link) = new_link_path(&target, &location)?;
+    let mut flags = SYMBOLIC_LINK_FLAG_ALLOW_UNPRIVILEGED_CREATE;
+    if target.is_dir() {
+        flags |= SYMBOLIC_LINK_FLAG_DIRECTORY;
+    }
+    let link_h = HSTRING::from(crate::paths::long_path(&link).as_path());
+    let target_h = HSTRING::from(target.as_path());
+    let create = |flags| {
+        unsafe { CreateSymbolicLinkW(&link_h, &target_h, flags) }
+            .then_some(())
+            .ok_or_else(windows::core::Error::from_win32)
+    };
+    create(flags)
+        .or_else(|e| {
+            // Windows before 10 1703 rejects the unprivileged flag itself
+            if e.code() == ERROR_INVALID_PARAMETER.to_hresult() {
+                create(flags & !SYMBOLIC_LINK_FLAG_ALLOW_UNPRIVILEGED_CREATE)
+            } else {
+                Err(e)
+            }
+        })
+        .map_err(|e| link_error("symbolic link", e))?;
+    log::info!("[LINKS] Symlink {} -> {}", link.display(), target.display());
+    crate::get_file_entry(&link)
+}
+
+/// Creates a hard link to the file `target` in the folder `location`
+#[tauri::command]
+pub fn create_hardlink(target: String, location: String) -> Result<crate::FileEntry, String> {
+    let (target, link) = new_link_path(&target, &location)?;
+    if !target.is_file() {
+        return Err("Hard links can only point to files".to_string());
+    }
+    unsafe {
+        CreateHardLinkW(
+            &HSTRING::from(crate::paths::long_path(&link).as_path()),
+            &HSTRING::from(crate::paths::long_path(&target).as_path()),
+            None,
+        )
+        .map_err(|e| link_error("hard link", e))?;
+    }
+    log::info!(
+        "[LINKS] Hard link {} -> {}",
+        link.display(),
+        target.display()
+    );
+    crate::get_file_entry(&link)
+}
+
+/// REPARSE_DATA_BUFFER for a junction to the absolute path `target`
+fn mount_point_buffer(target: &str) -> Vec<u8> {
+    // The substitute name is an NT path; the print name is what tools display
+    let substitute: Vec<u16> = format!("\\??\\{}", target).encode_utf16().collect();
+    let print: Vec<u16> = target.encode_utf16().collect();
+    let substitute_len = (substitute.len() * 2) as u16;
+    let print_len = (print.len() * 2) as u16;
+    // Four offset/length fields, then both names with their terminating nulls
+    let data_len = 8 + substitute_len + 2 + print_len + 2;
+
+    let mut buffer = Vec::with_capacity(8 + data_len as usize);
+    buffer.extend(IO_REPARSE_TAG_MOUNT_POINT.to_le_bytes());
+    buffer.extend(data_len.to_le_bytes());
+    buffer.extend(0u16.to_le_bytes()); // Reserved
+    buffer.extend(0u16.to_le_bytes()); // SubstituteNameOffset
+    buffer.extend(substitute_len.to_le_bytes());
+    buffer.extend((substitute_len + 2).to_le_bytes()); // PrintNameOffset
+    buffer.extend(print_len.to_le_bytes());
+    for c in substitute.iter().chain(&[0]).chain(&print).chain(&[0]) {
+        buffer.extend(c.to_le_bytes());
+    }
+    buffer
+}
+
+/// Creates a junction to the local folder `target` in the folder `location`
+#[tauri::command]
+pub fn create_junction(target: String, location: String) -> Result<crate::FileEntry, String> {
+    let (target, link) = new_link_path(&target, &location)?;
+    if !target.is_dir() {
+        return Err("Junctions can only point to folders".to_string());
+    }
+    let target_str = strip_verbatim(&target).to_string_lossy().to_string();
+    if target_str.as_bytes().get(1) != Some(&b':') {
+        return Err("Junctions can only point to folders on a local drive".to_string());
+    }
+
+    std::fs::create_dir(&link)
+        .map_err(|e| format!("Failed to create {}: {}", link.display(), e))?;
+    let buffer = mount_point_buffer(&target_str);
+    let result = unsafe {
+        CreateFileW(
+            &HSTRING::from(crate::paths::long_path(&link).as_path()),
+            FILE_GENERIC_WRITE.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS,
+            None,
+        )
+        .and_then(|handle| {
+            let result = DeviceIoControl(
+                handle,
+                FSCTL_SET_REPARSE_POINT,
+                Some(buffer.as_ptr() as *const _),
+                buffer.len() as u32,
+                None,
+                0,
+                None,
+                None,
+            );
+            let _ = CloseHandle(handle);
+            result
+        })
+    };
+    if let Err(e) = result {
+        let _ = std::fs::remove_dir(&link);
+        return Err(link_error("junction", e));
+    }
+    log::info!("[LINKS] Junction {} -> {}", link.display(), target_str);
+    crate::get_file_entry(&link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_name() {
+        assert_eq!(
+            link_name(Path::new("C:\\Users\\me\\notes.txt")),
+            "notes.txt"
+        );
+        assert_eq!(link_name(Path::new("C:\\")), "C");
+        assert_eq!(link_name(Path::new("\\\\server\\share\\")), "share");
+    }
+
+    #[test]
+    fn test_mount_point_buffer() {
+        let buffer = mount_point_buffer("C:\\t");
+        // \??\C:\t is 8 characters and C:\t 4, each followed by a null
+        assert_eq!(buffer.len(), 8 + 8 + 18 + 10);
+        assert_eq!(buffer[..4], IO_REPARSE_TAG_MOUNT_POINT.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([buffer[4], buffer[5]]), 36);
+        assert_eq!(u16::from_le_bytes([buffer[12], buffer[13]]), 18);
+        assert_eq!(u16::from_le_bytes([buffer[14], buffer[15]]), 8);
+    }
+
+    #[test]
+    fn test_strip_verbatim() {
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\?\C:\Users")),
+            PathBuf::from(r"C:\Users")
+        );
+        // Volume GUID targets of mounted folders have no drive letter to fall back to
+        let guid = r"\\?\Volume{01234567-89ab-cdef-0123-456789abcdef}\";
+        assert_eq!(strip_verbatim(Path::new(guid)), PathBuf::from(guid));
+        assert_eq!(
+            strip_verbatim(Path::new(r"..\shared")),
+            PathBuf::from(r"..\shared")
+        );
+    }
+}
@@ -0,0 +1,471 @@
+//! Listing Module
+//!
+//! Incremental folder refreshes. Every listing sent to the UI is tagged with a
+//! fingerprint of its contents and kept for a while; on refresh the UI passes
+//! back the fingerprint of what it shows and `diff_list` answers with only the
+//! entries that were added, removed or changed since. When the old listing is
+//! no longer known the full listing is returned instead.
+//!
+//! Recently opened folders are also cached by path, so going back and forth
+//! between two large folders doesn't re-enumerate them. The cache holds no
+//! handles of its own: while a tab shows a folder, that tab's watcher (see
+//! `watcher`) drops the folder's listing on any change. Folders no tab shows
+//! fall back to their last-write time, which only changes when an entry is
+//! added, removed or renamed, and are trusted for `UNWATCHED_CACHE_AGE` at
+//! most. File operations run by the app drop the whole cache.
+//!
+//! `list_files` can also sort and page a listing here, so a folder with 100k
+//! entries can be shown in a virtualized view without sending all of it over IPC.
+
+use crate::paths::to_wide;
+use crate::FileEntry;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+use ts_rs::TS;
+use windows::core::PCWSTR;
+use windows::Win32::UI::Shell::StrCmpLogicalW;
+
+/// Listings kept to diff against; one per recently refreshed tab is enough
+const REMEMBERED_LISTINGS: usize = 16;
+/// Folders kept in the listing cache
+const CACHED_FOLDERS: usize = 8;
+/// Upper bound on trusting a cached listing, in case a change slipped past the watcher
+const MAX_CACHE_AGE: Duration = Duration::from_secs(300);
+/// How long a listing stays trusted once no tab watches its folder; the folder's
+/// timestamp misses edits inside files (and is coarse on FAT and many shares)
+const UNWATCHED_CACHE_AGE: Duration = Duration::from_secs(30);
+/// Longest gap between caching a listing and its tab starting to watch the folder
+/// for the listing to be trusted as watched
+const WATCH_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Clone, TS, Debug, Default)]
+#[ts(export)]
+pub struct ListingDiff {
+    /// Fingerprint of the new listing, to pass on the next refresh
+    pub fingerprint: String,
+    /// Set instead of the other fields when the known listing could not be diffed against
+    pub full: Option<Vec<FileEntry>>,
+    pub added: Vec<FileEntry>,
+    /// Paths of entries that are gone
+    pub removed: Vec<String>,
+    /// New versions of entries whose size, type or times changed
+    pub changed: Vec<FileEntry>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    Name,
+    Size,
+    /// The type description ("Text Document"), as in Explorer's Type column
+    #[serde(alias = "file_type")]
+    Type,
+    /// Modification time
+    #[serde(alias = "modified_at")]
+    Date,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDir {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Explorer's name order: case-insensitive, digits compared as numbers ("file2"
+/// before "file10"); both strings null-terminated UTF-16
+fn compare_wide(a: &[u16], b: &[u16]) -> CmpOrdering {
+    unsafe { StrCmpLogicalW(PCWSTR(a.as_ptr()), PCWSTR(b.as_ptr())) }.cmp(&0)
+}
+
+/// The strings an entry is compared by, converted once per entry rather than per comparison
+struct SortKey {
+    name: Vec<u16>,
+    /// Empty unless sorting by type
+    file_type: Vec<u16>,
+}
+
+impl SortKey {
+    fn new(entry: &FileEntry, sort_by: SortBy) -> Self {
+        SortKey {
+            name: to_wide(&entry.name),
+            file_type: if sort_by == SortBy::Type {
+                to_wide(&entry.file_type)
+            } else {
+                Vec::new()
+            },
+        }
+    }
+}
+
+/// Folders first, then by `sort_by`; ties fall back to the name. The direction
+/// only applies within the folders and within the files, as in Explorer.
+fn compare_entries(
+    (a_key, a): &(SortKey, FileEntry),
+    (b_key, b): &(SortKey, FileEntry),
+    sort_by: SortBy,
+    dir: SortDir,
+) -> CmpOrdering {
+    b.is_dir.cmp(&a.is_dir).then_with(|| {
+        let by_key = match sort_by {
+            SortBy::Name => CmpOrdering::Equal,
+            SortBy::Size => a.size.cmp(&b.size),
+            SortBy::Type => compare_wide(&a_key.file_type, &b_key.file_type),
+            SortBy::Date => a.modified_timestamp.cmp(&b.modified_timestamp),
+        };
+        let ordering = by_key.then_with(|| compare_wide(&a_key.name, &b_key.name));
+        match dir {
+            SortDir::Asc => ordering,
+            SortDir::Desc => ordering.reverse(),
+        }
+    })
+}
+
+pub fn sort_entries(entries: &mut Vec<FileEntry>, sort_by: SortBy, dir: SortDir) {
+    let mut keyed: Vec<(SortKey, FileEntry)> = std::mem::take(entries)
+        .into_iter()
+        .map(|entry| (SortKey::new(&entry, sort_by), entry))
+        .collect();
+    keyed.par_sort_unstable_by(|a, b| compare_entries(a, b, sort_by, dir));
+    *entries = keyed.into_iter().map(|(_, entry)| entry).collect();
+}
+
+/// The `limit` entries starting at `offset`; all of them from `offset` on without a limit
+pub fn page(mut entries: Vec<FileEntry>, offset: usize, limit: Option<usize>) -> Vec<FileEntry> {
+    let end = limit.map_or(entries.len(), |limit| {
+        offset.saturating_add(limit).min(entries.len())
+    });
+    if offset >= end {
+        return Vec::new();
+    }
+    entries.truncate(end);
+    entries.drain(..offset);
+    entries
+}
+
+static LISTINGS: OnceLock<Mutex<lru::LruCache<String, Arc<Vec<FileEntry>>>>> = OnceLock::new();
+
+fn listings() -> &'static Mutex<lru::LruCache<String, Arc<Vec<FileEntry>>>> {
+    LISTINGS.get_or_init(|| {
+        Mutex::new(lru::LruCache::new(
+            NonZeroUsize::new(REMEMBERED_LISTINGS).unwrap(),
+        ))
+    })
+}
+
+/// The parts of an entry the UI displays; a change in any of them makes it "changed"
+fn entry_key(entry: &FileEntry) -> (&str, bool, u64, i64, i64, &Option<String>) {
+    (
+        &entry.name,
+        entry.is_dir,
+        entry.size,
+        entry.modified_timestamp,
+        entry.created_timestamp,
+        &entry.url_target,
+    )
+}
+
+pub fn fingerprint(entries: &[FileEntry]) -> String {
+    let mut hasher = DefaultHasher::new();
+    entries.len().hash(&mut hasher);
+    for entry in entries {
+        entry.path.hash(&mut hasher);
+        entry_key(entry).hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Keeps `entries` for later diffs and returns their fingerprint
+pub fn remember(entries: &[FileEntry]) -> String {
+    let fingerprint = fingerprint(entries);
+    if let Ok(mut listings) = listings().lock() {
+        listings.put(fingerprint.clone(), Arc::new(entries.to_vec()));
+    }
+    fingerprint
+}
+
+struct CachedListing {
+    show_hidden: bool,
+    entries: Arc<Vec<FileEntry>>,
+    cached_at: Instant,
+    /// Last-write time of the folder, read before it was listed
+    modified: SystemTime,
+    /// None while a tab watcher reports the folder's changes
+    unwatched_since: Option<Instant>,
+}
+
+impl CachedListing {
+    fn is_usable(&self, show_hidden: bool, modified: Option<SystemTime>) -> bool {
+        self.show_hidden == show_hidden
+            && modified == Some(self.modified)
+            && self.cached_at.elapsed() < MAX_CACHE_AGE
+            && self
+                .unwatched_since
+                .is_none_or(|since| since.elapsed() < UNWATCHED_CACHE_AGE)
+    }
+}
+
+/// Bumped on every change a tab watcher reports, so a listing taken while one
+/// arrived isn't trusted as watched
+static CHANGES: AtomicU64 = AtomicU64::new(0);
+
+/// Last-write time of the folder at `path`; None for virtual folders and the drive list
+fn folder_modified(path: &str) -> Option<SystemTime> {
+    if path.starts_with("shell:") || !Path::new(path).is_absolute() {
+        return None;
+    }
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+static CACHE: OnceLock<Mutex<lru::LruCache<String, CachedListing>>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<lru::LruCache<String, CachedListing>> {
+    CACHE.get_or_init(|| {
+        Mutex::new(lru::LruCache::new(
+            NonZeroUsize::new(CACHED_FOLDERS).unwrap(),
+        ))
+    })
+}
+
+/// Lists `path` through the cache. `list` returns the entries and whether the listing is
+/// complete; listings cut short (e.g. by a newer navigation) are returned but not cached.
+pub fn list_cached<F>(path: &str, show_hidden: bool, list: F) -> Result<Vec<FileEntry>, String>
+where
+    F: FnOnce() -> Result<(Vec<FileEntry>, bool), String>,
+{
+    let key = crate::frecency::folder_key(path);
+    // Read before listing, so a change made while listing shows up as a newer time
+    let modified = folder_modified(path);
+    if let Ok(mut cache) = cache().lock() {
+        match cache.get(&key) {
+            Some(cached) if cached.is_usable(show_hidden, modified) => {
+                log::debug!("[LISTING] Cache hit for {}", path);
+                return Ok(cached.entries.to_vec());
+            }
+            Some(_) => {
+                cache.pop(&key);
+            }
+            None => {}
+        }
+    }
+
+    let changes = CHANGES.load(Ordering::SeqCst);
+    let (entries, complete) = list()?;
+    if let (Some(modified), true) = (modified, complete) {
+        let watched =
+            crate::watcher::is_folder_watched(path) && CHANGES.load(Ordering::SeqCst) == changes;
+        let cached = CachedListing {
+            show_hidden,
+            entries: Arc::new(entries.clone()),
+            cached_at: Instant::now(),
+            modified,
+            unwatched_since: (!watched).then(Instant::now),
+        };
+        if let Ok(mut cache) = cache().lock() {
+            cache.put(key, cached);
+        }
+    }
+    Ok(entries)
+}
+
+/// Drops the cached listing of `folder`; called by the tab watchers on any change in it
+pub fn forget_folder(folder: &str) {
+    CHANGES.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut cache) = cache().lock() {
+        cache.pop(&crate::frecency::folder_key(folder));
+    }
+}
+
+/// Records whether a tab watcher now reports the changes of `folder`
+pub fn set_watched(folder: &str, watched: bool) {
+    if let Ok(mut cache) = cache().lock() {
+        if let Some(cached) = cache.peek_mut(&crate::frecency::folder_key(folder)) {
+            if !watched {
+                cached.unwatched_since.get_or_insert_with(Instant::now);
+            } else if cached
+                .unwatched_since
+                .is_some_and(|since| since.elapsed() < WATCH_GRACE)
+                && folder_modified(folder) == Some(cached.modified)
+            {
+                // A tab lists a folder first and watches it right after. Listings that
+                // went unwatched for longer may have missed edits, so they keep aging
+                cached.unwatched_since = None;
+            }
+        }
+    }
+}
+
+/// Drops every cached listing; called after the app changes files itself
+pub fn invalidate_cache() {
+    if let Ok(mut cache) = cache().lock() {
+        cache.clear();
+    }
+}
+
+/// Number of cached folders and of listings kept for diffing
+pub fn cache_counts() -> (usize, usize) {
+    let cached = cache().lock().map(|c| c.len()).unwrap_or(0);
+    let remembered = listings().lock().map(|l| l.len()).unwrap_or(0);
+    (cached, remembered)
+}
+
+/// Drops the cached folders and the listings kept for diffing; the next refresh of
+/// every tab then returns its full listing
+pub fn purge() {
+    invalidate_cache();
+    if let Ok(mut listings) = listings().lock() {
+        listings.clear();
+    }
+}
+
+fn diff(old: &[FileEntry], new: &[FileEntry]) -> ListingDiff {
+    let old_by_path: HashMap<&str, &FileEntry> = old.iter().map(|e| (e.path.as_str(), e)).collect();
+    let new_by_path: HashMap<&str, &FileEntry> = new.iter().map(|e| (e.path.as_str(), e)).collect();
+
+    let mut result = ListingDiff::default();
+    for entry in new {
+        match old_by_path.get(entry.path.as_str()) {
+            None => result.added.push(entry.clone()),
+            Some(old) if entry_key(old) != entry_key(entry) => result.changed.push(entry.clone()),
+            Some(_) => {}
+        }
+    }
+    result.removed = old
+        .iter()
+        .filter(|e| !new_by_path.contains_key(e.path.as_str()))
+        .map(|e| e.path.clone())
+        .collect();
+    result
+}
+
+/// Re-lists `path` and returns what changed relative to the listing identified by `fingerprint`
+#[tauri::command]
+pub async fn diff_list(
+    path: String,
+    show_hidden: bool,
+    fingerprint: String,
+) -> Result<ListingDiff, String> {
+    let expanded_path = crate::paths::expand_path_str(&path)?;
+    let entries =
+        crate::sta_worker::StaWorker::global().list_files(expanded_path, show_hidden, None)?;
+    let new_fingerprint = remember(&entries);
+
+    let known = listings()
+        .lock()
+        .ok()
+        .and_then(|mut l| l.get(&fingerprint).cloned());
+    let mut result = match known {
+        Some(_) if new_fingerprint == fingerprint => ListingDiff::default(),
+        Some(old) => diff(&old, &entries),
+        None => ListingDiff {
+            full: Some(entries),
+            ..Default::default()
+        },
+    };
+    result.fingerprint = new_fingerprint;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, size: u64) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            path: format!("C:\\data\\{}", name),
+            is_dir: false,
+            size,
+            formatted_size: String::new(),
+            file_type: "File".to_string(),
+            created_at: String::new(),
+            modified_at: String::new(),
+            is_shortcut: false,
+            disk_info: None,
+            modified_timestamp: 0,
+            created_timestamp: 0,
+            dimensions: None,
+            url_target: None,
+            recycle_info: None,
+            is_symlink: false,
+            is_junction: false,
+            link_target: None,
+            attributes: Default::default(),
+            cloud_status: None,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = vec![entry("a", 1), entry("b", 2), entry("c", 3)];
+        let new = vec![entry("a", 1), entry("b", 20), entry("d", 4)];
+        let result = diff(&old, &new);
+        assert_eq!(result.added.len(), 1);
+        assert_eq!(result.added[0].name, "d");
+        assert_eq!(result.changed.len(), 1);
+        assert_eq!(result.changed[0].size, 20);
+        assert_eq!(result.removed, vec!["C:\\data\\c".to_string()]);
+        assert_ne!(fingerprint(&old), fingerprint(&new));
+        assert_eq!(fingerprint(&old), fingerprint(&old.clone()));
+    }
+
+    #[test]
+    fn test_sort_and_page() {
+        let mut folder = entry("Zeta", 0);
+        folder.is_dir = true;
+        let mut entries = vec![entry("b", 5), entry("A", 10), folder, entry("c", 5)];
+        sort_entries(&mut entries, SortBy::Name, SortDir::Asc);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Zeta", "A", "b", "c"]);
+
+        // Folders stay on top when descending; equal sizes fall back to the name
+        sort_entries(&mut entries, SortBy::Size, SortDir::Desc);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Zeta", "A", "c", "b"]);
+
+        let names = |page: Vec<FileEntry>| page.into_iter().map(|e| e.name).collect::<Vec<_>>();
+        assert_eq!(names(page(entries.clone(), 1, Some(2))), vec!["A", "c"]);
+        assert_eq!(names(page(entries.clone(), 3, None)), vec!["b"]);
+        assert!(page(entries, 10, Some(5)).is_empty());
+
+        // Numbers compare by value, as in Explorer
+        let compare_names = |a: &str, b: &str| compare_wide(&to_wide(a), &to_wide(b));
+        assert_eq!(compare_names("file2", "file10"), CmpOrdering::Less);
+        assert_eq!(compare_names("File10", "file9"), CmpOrdering::Greater);
+        assert_eq!(compare_names("Report", "report"), CmpOrdering::Equal);
+    }
+
+    #[test]
+    fn test_cached_listing_usable() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let mut cached = CachedListing {
+            show_hidden: false,
+            entries: Arc::new(Vec::new()),
+            cached_at: Instant::now(),
+            modified,
+            unwatched_since: None,
+        };
+        assert!(cached.is_usable(false, Some(modified)));
+        assert!(!cached.is_usable(true, Some(modified)));
+        assert!(!cached.is_usable(false, Some(modified + Duration::from_secs(2))));
+        assert!(!cached.is_usable(false, None));
+
+        let now = Instant::now();
+        cached.unwatched_since = Some(now);
+        assert!(cached.is_usable(false, Some(modified)));
+        if let Some(long_ago) = now.checked_sub(UNWATCHED_CACHE_AGE) {
+            cached.unwatched_since = Some(long_ago);
+            assert!(!cached.is_usable(false, Some(modified)));
+        }
+    }
+}
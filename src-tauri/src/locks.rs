@@ -0,0 +1,303 @@
+//! Locks Module
+//!
+//! "What is locking this file?" through the Restart Manager, the API installers
+//! use to find the applications holding files they need to replace. It lists
+//! the processes with a file open and can ask them to close, which is the only
+//! safe way to release a handle owned by another process.
+//!
+//! The Restart Manager only tracks files, so a folder is checked through the
+//! files inside it, up to `MAX_FOLDER_FILES`.
+
+use crate::operations::{self, OperationKind};
+use crate::paths::to_wide;
+use serde::Serialize;
+use std::path::Path;
+use ts_rs::TS;
+use windows::core::{HRESULT, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_LOCK_VIOLATION, ERROR_MORE_DATA, ERROR_SHARING_VIOLATION, ERROR_SUCCESS,
+    WIN32_ERROR,
+};
+use windows::Win32::System::RestartManager::{
+    RmCritical, RmEndSession, RmForceShutdown, RmGetList, RmRegisterResources, RmService,
+    RmShutdown, RmStartSession, CCH_RM_SESSION_KEY, RM_PROCESS_INFO,
+};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Shell::{
+    COPYENGINE_E_SHARING_VIOLATION_DEST, COPYENGINE_E_SHARING_VIOLATION_SRC,
+};
+
+/// Files registered for a folder; enough to find the usual culprits quickly
+const MAX_FOLDER_FILES: usize = 1000;
+/// RmGetList can report more processes between the sizing call and the real one
+const GET_LIST_ATTEMPTS: usize = 3;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct LockingProcess {
+    pub pid: u32,
+    /// Window title or service display name, as the Restart Manager reports it
+    pub name: String,
+    /// Executable path, when the process can be queried
+    pub executable: Option<String>,
+    /// Short name of the service holding the file, for service hosts
+    pub service: Option<String>,
+    /// Part of Windows; it can't be closed without a restart
+    pub critical: bool,
+}
+
+/// Whether the shell failed an item because another process had it open
+pub fn is_sharing_violation(result: HRESULT) -> bool {
+    [
+        ERROR_SHARING_VIOLATION.to_hresult(),
+        ERROR_LOCK_VIOLATION.to_hresult(),
+        COPYENGINE_E_SHARING_VIOLATION_SRC,
+        COPYENGINE_E_SHARING_VIOLATION_DEST,
+    ]
+    .contains(&result)
+}
+
+/// Same check on an error message, for operations that failed as a whole
+pub fn is_sharing_violation_error(error: &str) -> bool {
+    [
+        ERROR_SHARING_VIOLATION.to_hresult(),
+        ERROR_LOCK_VIOLATION.to_hresult(),
+        COPYENGINE_E_SHARING_VIOLATION_SRC,
+        COPYENGINE_E_SHARING_VIOLATION_DEST,
+    ]
+    .iter()
+    .any(|code| error.contains(&code.to_string()))
+}
+
+fn from_wide(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+fn check(status: WIN32_ERROR, what: &str) -> Result<(), String> {
+    if status == ERROR_SUCCESS {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            what,
+            windows::core::Error::from(status.to_hresult())
+        ))
+    }
+}
+
+/// Restart Manager session, ended when dropped
+struct Session(u32);
+
+impl Session {
+    /// Starts a session watching `files`
+    fn new(files: &[String]) -> Result<Self, String> {
+        let mut handle = 0u32;
+        let mut key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+        check(
+            unsafe { RmStartSession(&mut handle, None, PWSTR(key.as_mut_ptr())) },
+            "RmStartSession",
+        )?;
+        let session = Session(handle);
+
+        let wide: Vec<Vec<u16>> = files.iter().map(to_wide).collect();
+        let names: Vec<PCWSTR> = wide.iter().map(|w| PCWSTR(w.as_ptr())).collect();
+        check(
+            unsafe { RmRegisterResources(session.0, Some(&names), None, None) },
+            "RmRegisterResources",
+        )?;
+        Ok(session)
+    }
+
+    fn processes(&self) -> Result<Vec<RM_PROCESS_INFO>, String> {
+        let mut infos: Vec<RM_PROCESS_INFO> = Vec::new();
+        for _ in 0..GET_LIST_ATTEMPTS {
+            let mut needed = 0u32;
+            let mut count = infos.len() as u32;
+            let mut reasons = 0u32;
+            let status = unsafe {
+                RmGetList(
+                    self.0,
+                    &mut needed,
+                    &mut count,
+                    (!infos.is_empty()).then_some(infos.as_mut_ptr()),
+                    &mut reasons,
+                )
+            };
+            if status == ERROR_MORE_DATA {
+                infos.resize(needed as usize, RM_PROCESS_INFO::default());
+                continue;
+            }
+            check(status, "RmGetList")?;
+            infos.truncate(count as usize);
+            return Ok(infos);
+        }
+        Err("The list of processes kept changing".to_string())
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = RmEndSession(self.0);
+        }
+    }
+}
+
+fn executable_path(pid: u32) -> Option<String> {
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+        Some(String::from_utf16_lossy(&buffer[..len as usize]))
+    }
+}
+
+/// The files to register for `paths`: files as they are, folders by their contents
+fn files_to_check(paths: &[String]) -> Vec<String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !Path::new(path).is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+        files.extend(
+            jwalk::WalkDir::new(path)
+                .skip_hidden(false)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .take(MAX_FOLDER_FILES)
+                .map(|entry| entry.path().to_string_lossy().to_string()),
+        );
+    }
+    files
+}
+
+fn locking_processes(paths: &[String]) -> Result<Vec<LockingProcess>, String> {
+    let files = files_to_check(paths);
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let session = Session::new(&files)?;
+    Ok(session
+        .processes()?
+        .iter()
+        .map(|info| {
+            let pid = info.Process.dwProcessId;
+            let service = from_wide(&info.strServiceShortName);
+            LockingProcess {
+                pid,
+                name: from_wide(&info.strAppName),
+                executable: executable_path(pid),
+                service: (info.ApplicationType == RmService && !service.is_empty())
+                    .then_some(service),
+                critical: info.ApplicationType == RmCritical,
+            }
+        })
+        .collect())
+}
+
+/// Asks the applications holding `paths` to close; `force` ends the ones that don't
+fn close_locking(paths: &[String], force: bool) -> Result<(), String> {
+    let files = files_to_check(paths);
+    if files.is_empty() {
+        return Ok(());
+    }
+    let session = Session::new(&files)?;
+    let flags = if force { RmForceShutdown.0 as u32 } else { 0 };
+    log::info!(
+        "[LOCKS] Closing applications holding {} file(s) (force: {})",
+        files.len(),
+        force
+    );
+    check(
+        unsafe { RmShutdown(session.0, flags, None) },
+        "Closing the applications",
+    )
+}
+
+/// Processes that have `path` open (for a folder, any file inside it)
+#[tauri::command]
+pub async fn find_locking_processes(path: String) -> Result<Vec<LockingProcess>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        locking_processes(&[crate::expand_env_vars(&path)])
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Closes the applications holding the items an operation found in use and runs
+/// the operation again for just those items, as a new operation in the queue
+#[tauri::command]
+pub async fn close_handles_and_retry(
+    window: tauri::Window,
+    operation_id: String,
+    force: bool,
+) -> Result<(), String> {
+    let info =
+        operations::info(&operation_id).ok_or_else(|| format!("No operation {}", operation_id))?;
+    if info.locked.is_empty() {
+        return Err(format!(
+            "Operation {} did not find any item in use",
+            operation_id
+        ));
+    }
+    let target = match (info.kind, info.target) {
+        (OperationKind::Delete, _) => String::new(),
+        (OperationKind::Copy | OperationKind::Move, Some(target)) => target,
+        (kind, _) => return Err(format!("{:?} operations can't be retried", kind)),
+    };
+
+    let locked = info.locked;
+    let to_close = locked.clone();
+    tauri::async_runtime::spawn_blocking(move || close_locking(&to_close, force))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+
+    let hwnd = Some(crate::get_root_hwnd(&window).0 as isize);
+    let kind = info.kind;
+    let queued_target = (kind != OperationKind::Delete).then(|| target.clone());
+    operations::run(kind, locked.clone(), queued_target, move |control| {
+        crate::sta_worker::run_file_operation(control, || match kind {
+            OperationKind::Delete => {
+                crate::sta_worker::delete_items_impl(locked, hwnd, control).map(|_| Vec::new())
+            }
+            OperationKind::Move => {
+                crate::sta_worker::move_items_impl(locked, target, hwnd, control)
+                    .map(|_| Vec::new())
+            }
+            _ => crate::sta_worker::paste_items_impl(locked, target, false, None, hwnd, control),
+        })
+    })
+    .await
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_sharing_violation() {
+        assert!(is_sharing_violation(ERROR_SHARING_VIOLATION.to_hresult()));
+        assert!(is_sharing_violation(COPYENGINE_E_SHARING_VIOLATION_SRC));
+        assert!(!is_sharing_violation(HRESULT(0)));
+        assert!(is_sharing_violation_error(
+            "PerformOperations failed: The process cannot access the file because it is being used by another process. (0x80070020)"
+        ));
+        assert!(!is_sharing_violation_error(
+            "PerformOperations failed: Access is denied. (0x80070005)"
+        ));
+    }
+}
@@ -9,6 +9,14 @@ use std::panic;
 use windows::Win32::System::Ole::OleInitialize;
 
 fn main() {
+    // An elevated helper started by the app does its one job and exits
+    if d_speedexplorer_lib::run_elevated_helper() {
+        return;
+    }
+    // Before the logger, which would truncate the running instance's log
+    if d_speedexplorer_lib::forward_to_running_instance() {
+        return;
+    }
     // 0. Initialize OLE for the main thread (required for Native Drag & Drop)
     unsafe {
         let _ = OleInitialize(None);
@@ -0,0 +1,119 @@
+//! Memory Module
+//!
+//! What the app holds in memory, for diagnosing leak reports, and a way for
+//! users on low-RAM machines to trim it. `get_memory_stats` reports the sizes
+//! of the in-memory caches alongside the process working set;
+//! `purge_caches` empties the chosen caches, which all refill on demand.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use windows::Win32::System::ProcessStatus::{
+    EmptyWorkingSet, GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+};
+use windows::Win32::System::Threading::GetCurrentProcess;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct MemoryStats {
+    #[ts(type = "number")]
+    pub thumbnail_cache_entries: u64,
+    /// Encoded thumbnail bytes held by the cache
+    #[ts(type = "number")]
+    pub thumbnail_cache_bytes: u64,
+    /// Folders whose listing is cached for instant navigation
+    #[ts(type = "number")]
+    pub listing_cache_folders: u64,
+    /// Listings kept to answer incremental refreshes
+    #[ts(type = "number")]
+    pub remembered_listings: u64,
+    /// Shell commands (listings, file operations) waiting for the worker thread
+    #[ts(type = "number")]
+    pub worker_queue_depth: u64,
+    #[ts(type = "number")]
+    pub working_set_bytes: u64,
+    #[ts(type = "number")]
+    pub peak_working_set_bytes: u64,
+}
+
+#[derive(Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum CacheKind {
+    Thumbnails,
+    Listings,
+    DiskInfo,
+    /// Asks Windows to page out whatever the process isn't actively using
+    WorkingSet,
+}
+
+/// (working set, peak working set) of this process in bytes
+fn working_set() -> (u64, u64) {
+    let mut counters = PROCESS_MEMORY_COUNTERS {
+        cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        ..Default::default()
+    };
+    let ok =
+        unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) }.is_ok();
+    if !ok {
+        return (0, 0);
+    }
+    (
+        counters.WorkingSetSize as u64,
+        counters.PeakWorkingSetSize as u64,
+    )
+}
+
+fn stats(thumbnails: &crate::ThumbnailCache) -> MemoryStats {
+    let (thumbnail_cache_entries, thumbnail_cache_bytes) = thumbnails
+        .0
+        .lock()
+        .map(|cache| {
+            let bytes: usize = cache.iter().map(|(k, v)| k.len() + v.len()).sum();
+            (cache.len() as u64, bytes as u64)
+        })
+        .unwrap_or_default();
+    let (cached, remembered) = crate::listing::cache_counts();
+    let (working_set_bytes, peak_working_set_bytes) = working_set();
+    MemoryStats {
+        thumbnail_cache_entries,
+        thumbnail_cache_bytes,
+        listing_cache_folders: cached as u64,
+        remembered_listings: remembered as u64,
+        worker_queue_depth: crate::sta_worker::queue_depth() as u64,
+        working_set_bytes,
+        peak_working_set_bytes,
+    }
+}
+
+#[tauri::command]
+pub fn get_memory_stats(thumbnails: tauri::State<'_, crate::ThumbnailCache>) -> MemoryStats {
+    stats(&thumbnails)
+}
+
+/// Empties the given caches (all of them when `kinds` is empty) and returns the stats after
+#[tauri::command]
+pub fn purge_caches(
+    kinds: Vec<CacheKind>,
+    thumbnails: tauri::State<'_, crate::ThumbnailCache>,
+) -> MemoryStats {
+    let purges = |kind| kinds.is_empty() || kinds.contains(&kind);
+    if purges(CacheKind::Thumbnails) {
+        if let Ok(mut cache) = thumbnails.0.lock() {
+            cache.clear();
+        }
+    }
+    if purges(CacheKind::Listings) {
+        crate::listing::purge();
+    }
+    if purges(CacheKind::DiskInfo) {
+        crate::disk_monitor::clear_disk_info_cache();
+    }
+    // Last, so the memory the other caches just released is trimmed too
+    if purges(CacheKind::WorkingSet) {
+        unsafe {
+            let _ = EmptyWorkingSet(GetCurrentProcess());
+        }
+    }
+    log::info!("[MEMORY] Purged caches: {:?}", kinds);
+    stats(&thumbnails)
+}
@@ -0,0 +1,186 @@
+//! Navigation Module
+//!
+//! Back/forward history of every tab, kept in the backend so it survives a
+//! reload of the UI and every window sees the same stacks. Persisted to
+//! `navigation.json` in the app data folder after each change; tabs are keyed
+//! by the ids the session uses, so a restored tab gets its history back.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use ts_rs::TS;
+
+/// Oldest entries of a tab are dropped past this length
+const MAX_ENTRIES: usize = 100;
+/// Histories of tabs closed long ago are dropped past this count
+const MAX_TABS: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug, Default, PartialEq)]
+#[ts(export)]
+pub struct TabHistory {
+    /// Visited folders, oldest first; "" is This PC
+    pub entries: Vec<String>,
+    /// Position of the current folder in `entries`
+    pub index: usize,
+    /// Unix seconds of the last change, for dropping stale tabs
+    #[serde(default)]
+    #[ts(skip)]
+    last_used: i64,
+}
+
+impl TabHistory {
+    pub fn current(&self) -> Option<&str> {
+        self.entries.get(self.index).map(String::as_str)
+    }
+
+    /// Makes `path` the current entry, discarding the forward stack
+    pub fn push(&mut self, path: &str) {
+        let same = self
+            .current()
+            .is_some_and(|c| crate::frecency::folder_key(c) == crate::frecency::folder_key(path));
+        if same {
+            return;
+        }
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.index + 1);
+        }
+        self.entries.push(path.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+        self.index = self.entries.len() - 1;
+    }
+
+    pub fn back(&mut self) -> Option<String> {
+        self.index = self.index.checked_sub(1)?;
+        self.current().map(str::to_string)
+    }
+
+    pub fn forward(&mut self) -> Option<String> {
+        if self.index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.index += 1;
+        self.current().map(str::to_string)
+    }
+}
+
+static HISTORIES: OnceLock<Mutex<HashMap<String, TabHistory>>> = OnceLock::new();
+
+fn history_file() -> PathBuf {
+    crate::paths::app_data_dir().join("navigation.json")
+}
+
+fn histories() -> &'static Mutex<HashMap<String, TabHistory>> {
+    HISTORIES.get_or_init(|| {
+        let histories = std::fs::read(history_file())
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        Mutex::new(histories)
+    })
+}
+
+fn save(histories: &HashMap<String, TabHistory>) {
+    let file = history_file();
+    let result = serde_json::to_vec(histories)
+        .map_err(|e| e.to_string())
+        .and_then(|data| {
+            // Write then rename so a crash never leaves a truncated file behind
+            let tmp_file = file.with_extension("json.tmp");
+            std::fs::write(&tmp_file, data)
+                .and_then(|_| std::fs::rename(&tmp_file, &file))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        log::error!("[NAVIGATION] Failed to save history: {}", e);
+    }
+}
+
+/// Runs `f` on the history of `tab_id` and saves the result when `f` changed it
+fn update<T>(tab_id: &str, f: impl FnOnce(&mut TabHistory) -> T) -> (T, TabHistory) {
+    let mut histories = histories().lock();
+    let history = histories.entry(tab_id.to_string()).or_default();
+    let before = history.clone();
+    let result = f(history);
+    let changed = *history != before;
+    let history = history.clone();
+    if changed {
+        if let Some(entry) = histories.get_mut(tab_id) {
+            entry.last_used = chrono::Utc::now().timestamp();
+        }
+        if histories.len() > MAX_TABS {
+            let mut stale: Vec<(String, i64)> = histories
+                .iter()
+                .map(|(id, h)| (id.clone(), h.last_used))
+                .collect();
+            stale.sort_by_key(|(_, last_used)| *last_used);
+            for (id, _) in stale.into_iter().take(histories.len() - MAX_TABS) {
+                histories.remove(&id);
+            }
+        }
+        save(&histories);
+    }
+    (result, history)
+}
+
+/// Records a navigation of `tab_id` to `path`; returns the tab's history
+#[tauri::command]
+pub fn push_history(tab_id: String, path: String) -> TabHistory {
+    update(&tab_id, |history| history.push(&path)).1
+}
+
+/// Steps `tab_id` back; the folder to open, or None at the start of its history
+#[tauri::command]
+pub fn navigate_back(tab_id: String) -> Option<String> {
+    update(&tab_id, TabHistory::back).0
+}
+
+/// Steps `tab_id` forward; the folder to open, or None at the end of its history
+#[tauri::command]
+pub fn navigate_forward(tab_id: String) -> Option<String> {
+    update(&tab_id, TabHistory::forward).0
+}
+
+/// The history of `tab_id`, or None for a tab that never navigated
+#[tauri::command]
+pub fn get_history(tab_id: String) -> Option<TabHistory> {
+    histories().lock().get(&tab_id).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut history = TabHistory::default();
+        history.push("");
+        history.push("C:\\");
+        history.push("C:\\Users");
+        history.push("c:\\users\\");
+        assert_eq!(history.entries.len(), 3);
+
+        assert_eq!(history.back().as_deref(), Some("C:\\"));
+        assert_eq!(history.back().as_deref(), Some(""));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.index, 0);
+        assert_eq!(history.forward().as_deref(), Some("C:\\"));
+
+        // A new folder drops the forward stack
+        history.push("D:\\");
+        assert_eq!(history.entries, vec!["", "C:\\", "D:\\"]);
+        assert_eq!(history.forward(), None);
+
+        for i in 0..MAX_ENTRIES {
+            history.push(&format!("D:\\{}", i));
+        }
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert_eq!(
+            history.current(),
+            Some(format!("D:\\{}", MAX_ENTRIES - 1).as_str())
+        );
+    }
+}
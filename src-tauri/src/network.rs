@@ -0,0 +1,365 @@
+//! Network Module
+//!
+//! Browsing the network neighborhood: the computers that advertise themselves
+//! on the local network, the shares each one offers, and mapping shares to
+//! drive letters. Everything goes through the WNet API, the same provider
+//! Explorer's Network folder uses.
+//!
+//! `list_files` understands `\\server` (its shares) and `\\server\share` roots.
+//! A share that can't be reached yet is connected with the user's own
+//! credentials first, as Explorer does on first access.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+use windows::core::{HSTRING, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{
+    ERROR_MORE_DATA, ERROR_NO_MORE_ITEMS, ERROR_SESSION_CREDENTIAL_CONFLICT, ERROR_SUCCESS, HANDLE,
+    WIN32_ERROR,
+};
+use windows::Win32::NetworkManagement::WNet::{
+    WNetAddConnection2W, WNetCancelConnection2W, WNetCloseEnum, WNetEnumResourceW, WNetOpenEnumW,
+    CONNECT_TEMPORARY, CONNECT_UPDATE_PROFILE, NETRESOURCEW, NET_RESOURCE_SCOPE, RESOURCETYPE_DISK,
+    RESOURCEUSAGE_CONTAINER, RESOURCEUSAGE_NONE, RESOURCE_CONTEXT, RESOURCE_GLOBALNET,
+};
+
+/// `dwDisplayType` of computers and of their shares
+const RESOURCEDISPLAYTYPE_SERVER: u32 = 2;
+const RESOURCEDISPLAYTYPE_SHARE: u32 = 3;
+/// Size MSDN recommends for a WNetEnumResource buffer
+const ENUM_BUFFER_SIZE: usize = 16 * 1024;
+
+/// A computer or a share on the network
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct NetworkResource {
+    /// Computer or share name, e.g. "NAS" or "Media"
+    pub name: String,
+    /// UNC path, e.g. `\\NAS` or `\\NAS\Media`
+    pub path: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct NetworkCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+fn check(status: WIN32_ERROR, what: &str) -> Result<(), String> {
+    if status == ERROR_SUCCESS {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            what,
+            windows::core::Error::from(status.to_hresult())
+        ))
+    }
+}
+
+/// The host of a bare `\\server` path, which has shares rather than files
+pub fn server_root(path: &str) -> Option<&str> {
+    let host = path.strip_prefix("\\\\")?.trim_end_matches('\\');
+    // `\\?\` and `\\.\` are device namespaces, not hosts
+    (!host.is_empty() && !host.contains('\\') && host != "?" && host != ".").then_some(host)
+}
+
+/// The `\\server\share` a UNC path lives on
+fn share_root(path: &str) -> Option<String> {
+    let mut parts = path.strip_prefix("\\\\")?.splitn(3, '\\');
+    let server = parts
+        .next()
+        .filter(|s| !s.is_empty() && *s != "?" && *s != ".")?;
+    let share = parts.next().filter(|s| !s.is_empty())?;
+    Some(format!("\\\\{}\\{}", server, share))
+}
+
+/// "Z", "Z:" or "Z:\" as the "Z:" the WNet API expects
+fn local_name(letter: &str) -> Result<String, String> {
+    match letter.trim_end_matches('\\').as_bytes() {
+        [l] | [l, b':'] if l.is_ascii_alphabetic() => {
+            Ok(format!("{}:", (*l as char).to_ascii_uppercase()))
+        }
+        _ => Err(format!("Invalid drive letter: {}", letter)),
+    }
+}
+
+/// Enumeration handle, closed when dropped
+struct Enumeration(HANDLE);
+
+impl Drop for Enumeration {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WNetCloseEnum(self.0);
+        }
+    }
+}
+
+fn pwstr_string(s: PWSTR) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    unsafe { s.to_string() }.ok().filter(|s| !s.is_empty())
+}
+
+/// Disk resources of `display_type` in `scope`, inside `container` when given
+fn enumerate(
+    scope: NET_RESOURCE_SCOPE,
+    container: Option<&str>,
+    display_type: u32,
+) -> Result<Vec<NetworkResource>, String> {
+    let mut remote: Option<Vec<u16>> =
+        container.map(|c| c.encode_utf16().chain(std::iter::once(0)).collect());
+    let resource = remote.as_mut().map(|remote| NETRESOURCEW {
+        dwScope: RESOURCE_GLOBALNET,
+        dwType: RESOURCETYPE_DISK,
+        dwUsage: RESOURCEUSAGE_CONTAINER.0,
+        lpRemoteName: PWSTR(remote.as_mut_ptr()),
+        ..Default::default()
+    });
+    let mut handle = HANDLE::default();
+    check(
+        unsafe {
+            WNetOpenEnumW(
+                scope,
+                RESOURCETYPE_DISK,
+                RESOURCEUSAGE_NONE,
+                resource.as_ref().map(|r| r as *const _),
+                &mut handle,
+            )
+        },
+        "WNetOpenEnum",
+    )?;
+    let handle = Enumeration(handle);
+
+    // u64s keep the NETRESOURCEW records aligned
+    let mut buffer = vec![0u64; ENUM_BUFFER_SIZE / 8];
+    let mut found = Vec::new();
+    loop {
+        let mut count = u32::MAX;
+        let mut size = (buffer.len() * 8) as u32;
+        let status = unsafe {
+            WNetEnumResourceW(
+                handle.0,
+                &mut count,
+                buffer.as_mut_ptr() as *mut _,
+                &mut size,
+            )
+        };
+        if status == ERROR_NO_MORE_ITEMS {
+            break;
+        }
+        if status == ERROR_MORE_DATA {
+            buffer.resize(size as usize / 8 + 1, 0);
+            continue;
+        }
+        check(status, "WNetEnumResource")?;
+
+        let resources = unsafe {
+            std::slice::from_raw_parts(buffer.as_ptr() as *const NETRESOURCEW, count as usize)
+        };
+        for resource in resources {
+            if resource.dwDisplayType != display_type {
+                continue;
+            }
+            let Some(path) = pwstr_string(resource.lpRemoteName) else {
+                continue;
+            };
+            found.push(NetworkResource {
+                name: path.rsplit('\\').next().unwrap_or_default().to_string(),
+                comment: pwstr_string(resource.lpComment),
+                path,
+            });
+        }
+    }
+    found.sort_by_key(|r| r.name.to_lowercase());
+    Ok(found)
+}
+
+/// Connects `path`'s share with the user's own credentials when it isn't
+/// reachable yet, so the first listing of a share works without mapping it
+pub fn ensure_connected(path: &str) {
+    let Some(share) = share_root(path) else {
+        return;
+    };
+    if Path::new(&share).exists() {
+        return;
+    }
+    let mut remote: Vec<u16> = share.encode_utf16().chain(std::iter::once(0)).collect();
+    let resource = NETRESOURCEW {
+        dwType: RESOURCETYPE_DISK,
+        lpRemoteName: PWSTR(remote.as_mut_ptr()),
+        ..Default::default()
+    };
+    let status = unsafe {
+        WNetAddConnection2W(&resource, PCWSTR::null(), PCWSTR::null(), CONNECT_TEMPORARY)
+    };
+    match check(status, "WNetAddConnection2") {
+        Ok(()) => log::info!("[NETWORK] Connected to {}", share),
+        Err(e) => log::debug!("[NETWORK] Could not connect to {}: {}", share, e),
+    }
+}
+
+/// The shares of `host` as folder entries, for listing a `\\server` root
+pub fn share_entries(host: &str) -> Result<Vec<crate::FileEntry>, String> {
+    Ok(shares(host)?
+        .into_iter()
+        .map(|share| crate::FileEntry {
+            name: share.name,
+            path: share.path,
+            is_dir: true,
+            size: 0,
+            formatted_size: String::new(),
+            file_type: crate::i18n::tr(crate::i18n::Text::NetworkShare).to_string(),
+            created_at: String::new(),
+            modified_at: String::new(),
+            is_shortcut: false,
+            disk_info: None,
+            modified_timestamp: 0,
+            created_timestamp: 0,
+            dimensions: None,
+            url_target: None,
+            recycle_info: None,
+            is_symlink: false,
+            is_junction: false,
+            link_target: None,
+            attributes: Default::default(),
+            cloud_status: None,
+        })
+        .collect())
+}
+
+fn shares(host: &str) -> Result<Vec<NetworkResource>, String> {
+    let host = host.trim_start_matches('\\').trim_end_matches('\\');
+    enumerate(
+        RESOURCE_GLOBALNET,
+        Some(&format!("\\\\{}", host)),
+        RESOURCEDISPLAYTYPE_SHARE,
+    )
+    .map_err(|e| format!("Failed to list the shares of {}: {}", host, e))
+}
+
+/// Computers visible in the network neighborhood (network discovery must be on)
+#[tauri::command]
+pub async fn list_network_computers() -> Result<Vec<NetworkResource>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        enumerate(RESOURCE_CONTEXT, None, RESOURCEDISPLAYTYPE_SERVER)
+            .map_err(|e| format!("Failed to list network computers: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Disk shares offered by `host` ("NAS" or "\\NAS"); hidden `$` shares are not listed
+#[tauri::command]
+pub async fn list_shares(host: String) -> Result<Vec<NetworkResource>, String> {
+    tauri::async_runtime::spawn_blocking(move || shares(&host))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Maps the share `path` to drive `letter`, reconnected at every sign-in.
+/// Without `credentials` the user's own account is used. Returns the drive root.
+#[tauri::command]
+pub async fn map_network_drive(
+    path: String,
+    letter: String,
+    credentials: Option<NetworkCredentials>,
+) -> Result<String, String> {
+    let local = local_name(&letter)?;
+    let share = share_root(&path).ok_or_else(|| format!("{} is not a network share", path))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut local_w: Vec<u16> = local.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut remote_w: Vec<u16> = share.encode_utf16().chain(std::iter::once(0)).collect();
+        let resource = NETRESOURCEW {
+            dwType: RESOURCETYPE_DISK,
+            lpLocalName: PWSTR(local_w.as_mut_ptr()),
+            lpRemoteName: PWSTR(remote_w.as_mut_ptr()),
+            ..Default::default()
+        };
+        let (username, password) = match &credentials {
+            Some(c) => (
+                HSTRING::from(c.username.as_str()),
+                HSTRING::from(c.password.as_str()),
+            ),
+            None => (HSTRING::new(), HSTRING::new()),
+        };
+        let pcwstr = |s: &HSTRING| {
+            if s.is_empty() {
+                PCWSTR::null()
+            } else {
+                PCWSTR(s.as_ptr())
+            }
+        };
+        let status = unsafe {
+            WNetAddConnection2W(
+                &resource,
+                pcwstr(&password),
+                pcwstr(&username),
+                CONNECT_UPDATE_PROFILE,
+            )
+        };
+        if status == ERROR_SESSION_CREDENTIAL_CONFLICT {
+            return Err(format!(
+                "Already connected to {} with other credentials; disconnect first",
+                share
+            ));
+        }
+        check(status, "WNetAddConnection2")
+            .map_err(|e| format!("Failed to map {} to {}: {}", share, local, e))?;
+        log::info!("[NETWORK] Mapped {} to {}", share, local);
+        Ok(format!("{}\\", local))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Disconnects mapped drive `letter` and forgets it; `force` closes open files on it
+#[tauri::command]
+pub async fn unmap_network_drive(letter: String, force: bool) -> Result<(), String> {
+    let local = local_name(&letter)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        check(
+            unsafe {
+                WNetCancelConnection2W(
+                    &HSTRING::from(local.as_str()),
+                    CONNECT_UPDATE_PROFILE,
+                    force,
+                )
+            },
+            "WNetCancelConnection2",
+        )
+        .map_err(|e| format!("Failed to disconnect {}: {}", local, e))?;
+        log::info!("[NETWORK] Disconnected {}", local);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unc_roots() {
+        assert_eq!(server_root("\\\\NAS"), Some("NAS"));
+        assert_eq!(server_root("\\\\NAS\\"), Some("NAS"));
+        assert_eq!(server_root("\\\\NAS\\Media"), None);
+        assert_eq!(server_root("\\\\?\\"), None);
+        assert_eq!(server_root("C:\\"), None);
+
+        assert_eq!(
+            share_root("\\\\NAS\\Media\\Movies").as_deref(),
+            Some("\\\\NAS\\Media")
+        );
+        assert_eq!(share_root("\\\\NAS"), None);
+        assert_eq!(share_root("\\\\?\\C:\\Users"), None);
+
+        assert_eq!(local_name("z").as_deref(), Ok("Z:"));
+        assert_eq!(local_name("Z:\\").as_deref(), Ok("Z:"));
+        assert!(local_name("ZZ").is_err());
+    }
+}
@@ -0,0 +1,555 @@
+//! New File Module
+//!
+//! Creates new documents from the "New" menu. Text files are written with an
+//! explicit encoding and line ending, since tools differ in what they accept
+//! (regedit wants UTF-16, shell scripts break on CRLF, some Windows tools
+//! need a BOM to detect UTF-8), and common types start from a small template
+//! instead of being empty.
+//!
+//! The rest of the menu mirrors Explorer's: every file type with a `ShellNew`
+//! registry key (Word document, Bitmap image, ...) plus the documents in the
+//! user's and the shared Templates folders.
+
+use crate::i18n;
+use crate::paths::to_wide;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Foundation::ERROR_SUCCESS;
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegEnumKeyExW, RegOpenKeyExW, RegQueryValueExW, HKEY, HKEY_CLASSES_ROOT, KEY_READ,
+    REG_EXPAND_SZ, REG_SZ, REG_VALUE_TYPE,
+};
+
+#[derive(Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+}
+
+#[derive(Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum NewlineStyle {
+    Crlf,
+    Lf,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct NewMenuItem {
+    /// With the dot, e.g. ".docx"
+    pub extension: String,
+    /// Type name shown in the menu, e.g. "Microsoft Word Document"
+    pub name: String,
+    /// Set for documents from a Templates folder; pass it back to `create_from_shell_new`
+    pub template: Option<String>,
+}
+
+/// How a `ShellNew` key says to make a new file
+#[derive(Debug, PartialEq)]
+enum ShellNewSource {
+    Empty,
+    Data(Vec<u8>),
+    /// Template file, absolute or relative to the template folders
+    Template(String),
+}
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Starting content for a new file of type `ext`, with `\n` line endings
+fn template(ext: &str, encoding: TextEncoding) -> String {
+    match ext {
+        "html" | "htm" => "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n    <meta charset=\"utf-8\">\n    <title></title>\n</head>\n<body>\n\n</body>\n</html>\n".to_string(),
+        "xml" => format!(
+            "<?xml version=\"1.0\" encoding=\"{}\"?>\n",
+            if encoding == TextEncoding::Utf16Le { "UTF-16" } else { "UTF-8" }
+        ),
+        "json" => "{}\n".to_string(),
+        "bat" | "cmd" => "@echo off\n".to_string(),
+        "sh" => "#!/bin/sh\n".to_string(),
+        "reg" => "Windows Registry Editor Version 5.00\n\n".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// What the tools that read files of type `ext` expect when the caller doesn't say
+fn default_encoding(ext: &str) -> TextEncoding {
+    match ext {
+        "reg" => TextEncoding::Utf16Le,
+        _ => TextEncoding::Utf8,
+    }
+}
+
+fn default_newline(ext: &str) -> NewlineStyle {
+    match ext {
+        "sh" | "bash" | "zsh" | "py" => NewlineStyle::Lf,
+        _ => NewlineStyle::Crlf,
+    }
+}
+
+/// `text` with every line ending in `newline`, encoded (BOM included) as `encoding`
+fn encode(text: &str, encoding: TextEncoding, newline: NewlineStyle) -> Vec<u8> {
+    let normalized = text.replace("\r\n", "\n");
+    let text = match newline {
+        NewlineStyle::Lf => normalized,
+        NewlineStyle::Crlf => normalized.replace('\n', "\r\n"),
+    };
+    match encoding {
+        TextEncoding::Utf8 => text.into_bytes(),
+        TextEncoding::Utf8Bom => [&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat(),
+        TextEncoding::Utf16Le => [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+    }
+}
+
+/// `name` numbered with the duplicate suffix before its extension: "Notes (2).txt"
+fn numbered_name(name: &str, n: u32) -> String {
+    let preferences = crate::settings::preferences();
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => {
+            format!("{}.{}", preferences.duplicate_name(stem, n), ext)
+        }
+        _ => preferences.duplicate_name(name, n),
+    }
+}
+
+/// `name`, numbered when it is already taken in `parent`
+pub(crate) fn unique_name(parent: &str, name: &str) -> String {
+    unique_name_in(parent, name, &HashSet::new())
+}
+
+/// Like `unique_name`, also passing over the lowercased names in `reserved`, e.g.
+/// ones already given to other items of the same paste
+pub(crate) fn unique_name_in(parent: &str, name: &str, reserved: &HashSet<String>) -> String {
+    let taken = |candidate: &str| {
+        reserved.contains(&candidate.to_lowercase()) || Path::new(parent).join(candidate).exists()
+    };
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| numbered_name(name, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Creates `name` in `parent`, numbering it when the name is taken, and opens it for writing
+pub(crate) fn open_unique(parent: &str, name: &str) -> Result<(PathBuf, std::fs::File), String> {
+    let mut n = 1;
+    loop {
+        let candidate = if n == 1 {
+            name.to_string()
+        } else {
+            numbered_name(name, n)
+        };
+        let path = Path::new(parent).join(&candidate);
+        // create_new so a file that appears meanwhile is never overwritten
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(format!("Failed to create {}: {}", candidate, e)),
+        }
+    }
+}
+
+/// Creates `name` in `parent`, numbering it when the name is taken, and writes `contents`
+pub(crate) fn create_unique(parent: &str, name: &str, contents: &[u8]) -> Result<PathBuf, String> {
+    let (path, mut file) = open_unique(parent, name)?;
+    file.write_all(contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Creates a text file in `parent_path`. `content` replaces the type's template;
+/// encoding and newline style default to what the file type usually needs.
+#[tauri::command]
+pub async fn create_text_file(
+    parent_path: String,
+    name: Option<String>,
+    content: Option<String>,
+    encoding: Option<TextEncoding>,
+    newline: Option<NewlineStyle>,
+) -> Result<crate::FileEntry, String> {
+    let parent = crate::expand_env_vars(&parent_path);
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("{}.txt", i18n::tr(i18n::Text::NewTextDocument)));
+    if name.contains(['\\', '/']) {
+        return Err(format!("Invalid file name: {}", name));
+    }
+
+    let ext = extension(&name);
+    let encoding = encoding.unwrap_or_else(|| default_encoding(&ext));
+    let newline = newline.unwrap_or_else(|| default_newline(&ext));
+    let text = content.unwrap_or_else(|| template(&ext, encoding));
+    let bytes = encode(&text, encoding, newline);
+
+    let path = create_unique(&parent, &name, &bytes)?;
+    log::info!(
+        "[NEW] Created {} ({:?}, {:?})",
+        path.display(),
+        encoding,
+        newline
+    );
+    crate::get_file_entry(&path)
+}
+
+/// Open registry key, closed on drop
+struct RegKey(HKEY);
+
+impl RegKey {
+    fn open(parent: HKEY, path: &str) -> Option<RegKey> {
+        let path_wide = to_wide(path);
+        let mut key = HKEY::default();
+        let status =
+            unsafe { RegOpenKeyExW(parent, PCWSTR(path_wide.as_ptr()), None, KEY_READ, &mut key) };
+        (status == ERROR_SUCCESS).then_some(RegKey(key))
+    }
+
+    fn subkey(&self, path: &str) -> Option<RegKey> {
+        RegKey::open(self.0, path)
+    }
+
+    fn subkey_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut buffer = [0u16; 256];
+        for index in 0.. {
+            let mut len = buffer.len() as u32;
+            let status = unsafe {
+                RegEnumKeyExW(
+                    self.0,
+                    index,
+                    Some(PWSTR(buffer.as_mut_ptr())),
+                    &mut len,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+            };
+            if status != ERROR_SUCCESS {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&buffer[..len as usize]));
+        }
+        names
+    }
+
+    /// Type and raw data of a value; `""` is the key's default value
+    fn value(&self, name: &str) -> Option<(REG_VALUE_TYPE, Vec<u8>)> {
+        let name_wide = to_wide(name);
+        let mut kind = REG_VALUE_TYPE::default();
+        let mut len = 0u32;
+        unsafe {
+            let status = RegQueryValueExW(
+                self.0,
+                PCWSTR(name_wide.as_ptr()),
+                None,
+                Some(&mut kind),
+                None,
+                Some(&mut len),
+            );
+            if status != ERROR_SUCCESS {
+                return None;
+            }
+            let mut data = vec![0u8; len as usize];
+            let status = RegQueryValueExW(
+                self.0,
+                PCWSTR(name_wide.as_ptr()),
+                None,
+                Some(&mut kind),
+                Some(data.as_mut_ptr()),
+                Some(&mut len),
+            );
+            data.truncate(len as usize);
+            (status == ERROR_SUCCESS).then_some((kind, data))
+        }
+    }
+
+    /// A string value, with environment variables expanded
+    fn string(&self, name: &str) -> Option<String> {
+        let (kind, data) = self.value(name)?;
+        if kind != REG_SZ && kind != REG_EXPAND_SZ {
+            return None;
+        }
+        let wide: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect();
+        let value = String::from_utf16_lossy(&wide);
+        Some(crate::expand_env_vars(&value))
+    }
+}
+
+impl Drop for RegKey {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = RegCloseKey(self.0);
+        }
+    }
+}
+
+/// Resolves "@dll,-id" resource references; other strings are returned as they are
+fn load_indirect(value: String) -> String {
+    if !value.starts_with('@') {
+        return value;
+    }
+    use windows::Win32::UI::Shell::SHLoadIndirectString;
+    let value_wide = to_wide(&value);
+    let mut buffer = [0u16; 512];
+    match unsafe { SHLoadIndirectString(PCWSTR(value_wide.as_ptr()), &mut buffer, None) } {
+        Ok(()) => {
+            let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+            String::from_utf16_lossy(&buffer[..len])
+        }
+        Err(_) => value,
+    }
+}
+
+/// The `ShellNew` key of `ext` and the type's ProgID key, if the type has one
+fn shell_new_key(ext_key: &RegKey) -> Option<(RegKey, Option<RegKey>)> {
+    let prog_id = ext_key.string("").filter(|p| !p.is_empty());
+    let prog_key = prog_id
+        .as_deref()
+        .and_then(|p| RegKey::open(HKEY_CLASSES_ROOT, p));
+    // Explorer looks under the ProgID subkey of the extension first
+    let shell_new = prog_id
+        .as_deref()
+        .and_then(|p| ext_key.subkey(&format!("{}\\ShellNew", p)))
+        .or_else(|| ext_key.subkey("ShellNew"))?;
+    Some((shell_new, prog_key))
+}
+
+/// What the key asks for; None for commands and folders, which aren't files we can write
+fn shell_new_source(key: &RegKey) -> Option<ShellNewSource> {
+    if let Some(file) = key.string("FileName") {
+        return Some(ShellNewSource::Template(file));
+    }
+    if let Some((kind, data)) = key.value("Data") {
+        let data = if kind == REG_SZ || kind == REG_EXPAND_SZ {
+            key.string("Data").unwrap_or_default().into_bytes()
+        } else {
+            data
+        };
+        return Some(ShellNewSource::Data(data));
+    }
+    key.value("NullFile").map(|_| ShellNewSource::Empty)
+}
+
+fn type_name(ext: &str, shell_new: &RegKey, prog_key: Option<&RegKey>) -> String {
+    shell_new
+        .string("ItemName")
+        .or_else(|| prog_key.and_then(|k| k.string("FriendlyTypeName")))
+        .or_else(|| prog_key.and_then(|k| k.string("")))
+        .map(load_indirect)
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("{} File", ext.trim_start_matches('.').to_uppercase()))
+}
+
+/// The user's Templates folder, the shared one and the legacy %WINDIR%\ShellNew
+fn template_folders() -> Vec<PathBuf> {
+    use windows::Win32::UI::Shell::{
+        FOLDERID_CommonTemplates, FOLDERID_Templates, SHGetKnownFolderPath, KF_FLAG_DEFAULT,
+    };
+
+    let mut folders = Vec::new();
+    for id in [FOLDERID_Templates, FOLDERID_CommonTemplates] {
+        unsafe {
+            if let Ok(path_ptr) = SHGetKnownFolderPath(&id, KF_FLAG_DEFAULT, None) {
+                if let Ok(path) = path_ptr.to_string() {
+                    folders.push(PathBuf::from(path));
+                }
+                windows::Win32::System::Com::CoTaskMemFree(Some(path_ptr.as_ptr() as *const _));
+            }
+        }
+    }
+    folders.push(PathBuf::from(crate::expand_env_vars("%WINDIR%\\ShellNew")));
+    folders
+}
+
+/// Documents placed in the Templates folders by the user or by installers
+fn template_files(folders: &[PathBuf]) -> Vec<PathBuf> {
+    use std::os::windows::fs::MetadataExt;
+    const HIDDEN_OR_SYSTEM: u32 = 0x2 | 0x4;
+
+    // %WINDIR%\ShellNew only holds the files registry entries point at
+    let mut files: Vec<PathBuf> = folders
+        .iter()
+        .take(2)
+        .filter_map(|folder| std::fs::read_dir(folder).ok())
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .map(|m| m.is_file() && m.file_attributes() & HIDDEN_OR_SYSTEM == 0)
+                .unwrap_or(false)
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort_by_key(|p| p.file_name().map(|n| n.to_string_lossy().to_lowercase()));
+    files
+}
+
+fn list_new_menu_items() -> Vec<NewMenuItem> {
+    // A predefined key, never closed
+    let root = std::mem::ManuallyDrop::new(RegKey(HKEY_CLASSES_ROOT));
+    let mut items: Vec<NewMenuItem> = root
+        .subkey_names()
+        .into_iter()
+        .filter(|name| name.starts_with('.') && name.len() > 1)
+        .filter_map(|ext| {
+            let ext_key = root.subkey(&ext)?;
+            let (shell_new, prog_key) = shell_new_key(&ext_key)?;
+            shell_new_source(&shell_new)?;
+            Some(NewMenuItem {
+                name: type_name(&ext, &shell_new, prog_key.as_ref()),
+                extension: ext.to_lowercase(),
+                template: None,
+            })
+        })
+        .collect();
+    items.sort_by_key(|item| item.name.to_lowercase());
+    items.dedup_by(|a, b| a.extension == b.extension);
+
+    items.extend(template_files(&template_folders()).into_iter().map(|path| {
+        NewMenuItem {
+            extension: path
+                .extension()
+                .map(|e| format!(".{}", e.to_string_lossy().to_lowercase()))
+                .unwrap_or_default(),
+            name: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            template: Some(path.to_string_lossy().to_string()),
+        }
+    }));
+    items
+}
+
+/// The document types of Explorer's "New" menu
+#[tauri::command]
+pub async fn get_new_menu_items() -> Result<Vec<NewMenuItem>, String> {
+    tauri::async_runtime::spawn_blocking(list_new_menu_items)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// File name and contents for a new `extension` document, as its `ShellNew` key describes
+fn shell_new_document(extension: &str, folders: &[PathBuf]) -> Result<(String, Vec<u8>), String> {
+    let ext = format!(".{}", extension.trim_start_matches('.'));
+    let (shell_new, prog_key) = RegKey::open(HKEY_CLASSES_ROOT, &ext)
+        .as_ref()
+        .and_then(shell_new_key)
+        .ok_or_else(|| format!("No \"New\" entry is registered for {} files", ext))?;
+    let source = shell_new_source(&shell_new)
+        .ok_or_else(|| format!("The \"New\" entry for {} files can't be created here", ext))?;
+
+    let contents = match source {
+        ShellNewSource::Empty => Vec::new(),
+        ShellNewSource::Data(data) => data,
+        ShellNewSource::Template(file) => {
+            let file = Path::new(&file);
+            let template = if file.is_absolute() {
+                Some(file.to_path_buf())
+            } else {
+                folders.iter().map(|f| f.join(file)).find(|p| p.is_file())
+            }
+            .ok_or_else(|| format!("Template not found: {}", file.display()))?;
+            std::fs::read(&template)
+                .map_err(|e| format!("Failed to read template {}: {}", template.display(), e))?
+        }
+    };
+    let name = format!(
+        "{}{}",
+        i18n::tr_with(
+            i18n::Text::NewItem,
+            &type_name(&ext, &shell_new, prog_key.as_ref())
+        ),
+        ext
+    );
+    Ok((name, contents))
+}
+
+/// Creates a new document in `parent_path` from the "New" menu item for `extension`,
+/// or from `template` when the item came from a Templates folder
+#[tauri::command]
+pub async fn create_from_shell_new(
+    parent_path: String,
+    extension: String,
+    template: Option<String>,
+) -> Result<crate::FileEntry, String> {
+    let parent = crate::expand_env_vars(&parent_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        let folders = template_folders();
+        let (name, contents) = match template {
+            Some(template) => {
+                let template = PathBuf::from(template);
+                // Only documents listed by get_new_menu_items, not arbitrary files
+                if !folders
+                    .iter()
+                    .any(|f| template.parent() == Some(f.as_path()))
+                {
+                    return Err(format!("Not a template: {}", template.display()));
+                }
+                let name = template
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .ok_or("Invalid template path")?;
+                let contents = std::fs::read(&template)
+                    .map_err(|e| format!("Failed to read template {}: {}", name, e))?;
+                (name, contents)
+            }
+            None => shell_new_document(&extension, &folders)?,
+        };
+        let path = create_unique(&parent, &name, &contents)?;
+        log::info!("[NEW] Created {} from ShellNew", path.display());
+        crate::get_file_entry(&path)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode("a\nb\r\n", TextEncoding::Utf8, NewlineStyle::Crlf),
+            b"a\r\nb\r\n"
+        );
+        assert_eq!(
+            encode("a\r\nb", TextEncoding::Utf8Bom, NewlineStyle::Lf),
+            b"\xEF\xBB\xBFa\nb"
+        );
+        assert_eq!(
+            encode("a\n", TextEncoding::Utf16Le, NewlineStyle::Lf),
+            vec![0xFF, 0xFE, b'a', 0, b'\n', 0]
+        );
+        assert_eq!(default_encoding("reg"), TextEncoding::Utf16Le);
+        assert!(template("xml", TextEncoding::Utf16Le).contains("UTF-16"));
+    }
+}
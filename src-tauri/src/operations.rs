@@ -0,0 +1,435 @@
+//! Operations Module
+//!
+//! Queue for copy/move/delete/rename/extract jobs. Jobs on the same volume run one
+//! after another, since parallel copies on one disk only make it seek; jobs on
+//! different volumes run side by side. Every job can be listed, paused and
+//! cancelled: copy/move/delete/rename through their IFileOperation progress sink (see
+//! `file_op_progress`), which blocks while paused and aborts the shell
+//! operation once cancelled, extraction through its copy loops. Sources the shell
+//! was refused access to are kept on the operation for `retry_elevated` (see
+//! `elevation`), and sources another process had open for
+//! `close_handles_and_retry` (see `locks`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::Emitter;
+use ts_rs::TS;
+
+/// Finished operations kept for `list_operations`
+const MAX_FINISHED: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum OperationKind {
+    Copy,
+    Move,
+    Delete,
+    Rename,
+    Extract,
+}
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum OperationState {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl OperationState {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct OperationInfo {
+    pub id: String,
+    pub kind: OperationKind,
+    pub sources: Vec<String>,
+    /// Destination folder; None for deletes
+    pub target: Option<String>,
+    pub state: OperationState,
+    pub error: Option<String>,
+    /// Sources that failed for lack of rights and can be retried as administrator
+    pub access_denied: Vec<String>,
+    /// Sources that failed because another process had them open
+    pub locked: Vec<String>,
+}
+
+/// Pause and cancel flags a running job polls
+pub struct OperationControl {
+    id: String,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    access_denied: Mutex<Vec<String>>,
+    locked: Mutex<Vec<String>>,
+}
+
+impl OperationControl {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Records a source the shell was refused access to
+    pub fn note_access_denied(&self, source: String) {
+        if let Ok(mut denied) = self.access_denied.lock() {
+            denied.push(source);
+        }
+    }
+
+    /// Records a source another process had open
+    pub fn note_locked(&self, source: String) {
+        if let Ok(mut locked) = self.locked.lock() {
+            locked.push(source);
+        }
+    }
+
+    /// Blocks while the operation is paused. Returns false once it is cancelled.
+    pub fn wait_while_paused(&self) -> bool {
+        while self.paused.load(Ordering::Relaxed) && !self.is_cancelled() {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        !self.is_cancelled()
+    }
+}
+
+type JobResult = Result<Vec<String>, String>;
+type Job = Box<dyn FnOnce(&Arc<OperationControl>) -> JobResult + Send>;
+
+struct Operation {
+    info: OperationInfo,
+    control: Arc<OperationControl>,
+    /// Jobs sharing a volume run one at a time
+    volume: String,
+    /// Whether the running job keeps other jobs off its volume
+    holds_volume: bool,
+    /// Taken when the job starts
+    job: Option<Job>,
+    done: Option<Sender<JobResult>>,
+}
+
+#[derive(Default)]
+struct Queue {
+    operations: Vec<Operation>,
+    busy_volumes: HashSet<String>,
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static QUEUE: OnceLock<Mutex<Queue>> = OnceLock::new();
+
+fn queue() -> &'static Mutex<Queue> {
+    QUEUE.get_or_init(|| Mutex::new(Queue::default()))
+}
+
+/// The volume `path` lives on: its drive letter or UNC share, lowercased
+fn volume_key(path: &str) -> String {
+    let path = path.replace('/', "\\");
+    if let Some(unc) = path.strip_prefix("\\\\") {
+        let share: Vec<&str> = unc.splitn(3, '\\').take(2).collect();
+        return format!("\\\\{}", share.join("\\")).to_lowercase();
+    }
+    match path.as_bytes() {
+        [letter, b':', ..] if letter.is_ascii_alphabetic() => path[..2].to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+fn emit_changed(operations: Vec<OperationInfo>) {
+    if let Some(app) = crate::APP_HANDLE.get() {
+        let _ = app.emit("operations-changed", operations);
+    }
+}
+
+fn snapshot(queue: &Queue) -> Vec<OperationInfo> {
+    queue.operations.iter().map(|op| op.info.clone()).collect()
+}
+
+/// Starts every queued job whose volume is free. Cancelled jobs start right away
+/// so they can report back; they stop before doing any work.
+fn schedule() {
+    let started = {
+        let Ok(mut queue) = queue().lock() else {
+            return;
+        };
+        let mut started = Vec::new();
+        let Queue {
+            operations,
+            busy_volumes,
+        } = &mut *queue;
+        for op in operations.iter_mut() {
+            if op.job.is_none() {
+                continue;
+            }
+            let cancelled = op.control.is_cancelled();
+            let runnable =
+                op.info.state == OperationState::Queued && !busy_volumes.contains(&op.volume);
+            if !cancelled && !runnable {
+                continue;
+            }
+            let Some(job) = op.job.take() else {
+                continue;
+            };
+            if !cancelled {
+                busy_volumes.insert(op.volume.clone());
+                op.holds_volume = true;
+            }
+            op.info.state = OperationState::Running;
+            started.push((job, Arc::clone(&op.control)));
+        }
+        if started.is_empty() {
+            return;
+        }
+        let operations = snapshot(&queue);
+        emit_changed(operations);
+        started
+    };
+
+    for (job, control) in started {
+        std::thread::spawn(move || {
+            log::info!("[OPERATIONS] {} started", control.id);
+            let result = job(&control);
+            finish(&control, result);
+            schedule();
+        });
+    }
+}
+
+fn finish(control: &OperationControl, result: JobResult) {
+    let Ok(mut queue) = queue().lock() else {
+        return;
+    };
+    let Some(index) = queue
+        .operations
+        .iter()
+        .position(|op| op.info.id == control.id)
+    else {
+        return;
+    };
+    let op = &mut queue.operations[index];
+    let noted = |items: &Mutex<Vec<String>>| items.lock().map(|i| i.clone()).unwrap_or_default();
+    op.info.access_denied = noted(&control.access_denied);
+    op.info.locked = noted(&control.locked);
+    // Failed before the shell got to any one item: the reason applies to all of them
+    if let Err(e) = &result {
+        if op.info.access_denied.is_empty() && crate::elevation::is_access_denied_error(e) {
+            op.info.access_denied = op.info.sources.clone();
+        }
+        if op.info.locked.is_empty() && crate::locks::is_sharing_violation_error(e) {
+            op.info.locked = op.info.sources.clone();
+        }
+    }
+    op.info.state = match &result {
+        Ok(_) => OperationState::Completed,
+        Err(_) if control.is_cancelled() => OperationState::Cancelled,
+        Err(e) => {
+            op.info.error = Some(e.clone());
+            OperationState::Failed
+        }
+    };
+    log::info!("[OPERATIONS] {} {:?}", control.id, op.info.state);
+    if let Some(done) = op.done.take() {
+        let _ = done.send(result);
+    }
+    if op.holds_volume {
+        let volume = op.volume.clone();
+        queue.busy_volumes.remove(&volume);
+    }
+
+    // Forget the oldest finished operations
+    let finished = queue
+        .operations
+        .iter()
+        .filter(|op| op.info.state.is_finished())
+        .count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED);
+    queue.operations.retain(|op| {
+        if excess > 0 && op.info.state.is_finished() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+    emit_changed(snapshot(&queue));
+}
+
+/// Queues `job` and returns the operation id and a receiver for its result.
+/// The job gets its own thread once no other job is running on its volume.
+pub fn enqueue<F>(
+    kind: OperationKind,
+    sources: Vec<String>,
+    target: Option<String>,
+    job: F,
+) -> (String, Receiver<JobResult>)
+where
+    F: FnOnce(&Arc<OperationControl>) -> JobResult + Send + 'static,
+{
+    let id = format!("op-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    let volume = volume_key(
+        target
+            .as_deref()
+            .or(sources.first().map(String::as_str))
+            .unwrap_or_default(),
+    );
+    let (tx, rx) = channel();
+    let op = Operation {
+        info: OperationInfo {
+            id: id.clone(),
+            kind,
+            sources,
+            target,
+            state: OperationState::Queued,
+            error: None,
+            access_denied: Vec::new(),
+            locked: Vec::new(),
+        },
+        control: Arc::new(OperationControl {
+            id: id.clone(),
+            paused: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            access_denied: Mutex::new(Vec::new()),
+            locked: Mutex::new(Vec::new()),
+        }),
+        volume,
+        holds_volume: false,
+        job: Some(Box::new(job)),
+        done: Some(tx),
+    };
+    if let Ok(mut queue) = queue().lock() {
+        log::info!("[OPERATIONS] {} queued: {:?} on '{}'", id, kind, op.volume);
+        queue.operations.push(op);
+    }
+    schedule();
+    (id, rx)
+}
+
+/// Queues `job` and waits for it to finish
+pub async fn run<F>(
+    kind: OperationKind,
+    sources: Vec<String>,
+    target: Option<String>,
+    job: F,
+) -> JobResult
+where
+    F: FnOnce(&Arc<OperationControl>) -> JobResult + Send + 'static,
+{
+    let (_, done) = enqueue(kind, sources, target, job);
+    tokio::task::spawn_blocking(move || {
+        done.recv()
+            .map_err(|e| format!("Operation ended without a result: {}", e))?
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Applies `change` to the operation `id` and announces the new list
+fn update(
+    id: &str,
+    change: impl FnOnce(&mut Operation) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut queue = queue()
+        .lock()
+        .map_err(|e| format!("Operation queue poisoned: {}", e))?;
+    let op = queue
+        .operations
+        .iter_mut()
+        .find(|op| op.info.id == id)
+        .ok_or_else(|| format!("No operation {}", id))?;
+    change(op)?;
+    emit_changed(snapshot(&queue));
+    Ok(())
+}
+
+/// The operation `id`, while it is queued, running or among the recently finished
+pub fn info(id: &str) -> Option<OperationInfo> {
+    let queue = queue().lock().ok()?;
+    queue
+        .operations
+        .iter()
+        .find(|op| op.info.id == id)
+        .map(|op| op.info.clone())
+}
+
+/// Queued, running and recently finished operations, oldest first
+#[tauri::command]
+pub fn list_operations() -> Vec<OperationInfo> {
+    queue()
+        .lock()
+        .map(|queue| snapshot(&queue))
+        .unwrap_or_default()
+}
+
+/// Holds a queued operation back, or halts a running one at its next chunk
+#[tauri::command]
+pub fn pause_operation(id: String) -> Result<(), String> {
+    update(&id, |op| match op.info.state {
+        OperationState::Queued | OperationState::Running => {
+            op.control.paused.store(true, Ordering::Relaxed);
+            op.info.state = OperationState::Paused;
+            Ok(())
+        }
+        OperationState::Paused => Ok(()),
+        state => Err(format!("Operation {} is already {:?}", id, state)),
+    })
+}
+
+#[tauri::command]
+pub fn resume_operation(id: String) -> Result<(), String> {
+    update(&id, |op| {
+        if op.info.state == OperationState::Paused {
+            op.control.paused.store(false, Ordering::Relaxed);
+            // A job that never started goes back in line
+            op.info.state = if op.job.is_some() {
+                OperationState::Queued
+            } else {
+                OperationState::Running
+            };
+        }
+        Ok(())
+    })?;
+    schedule();
+    Ok(())
+}
+
+/// Cancels an operation. A running one stops at its next chunk and removes what it
+/// wrote where it can.
+#[tauri::command]
+pub fn cancel_operation(id: String) -> Result<(), String> {
+    update(&id, |op| {
+        op.control.cancelled.store(true, Ordering::Relaxed);
+        Ok(())
+    })?;
+    schedule();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_key() {
+        assert_eq!(volume_key("C:\\Users\\me"), "c:");
+        assert_eq!(volume_key("d:\\"), "d:");
+        assert_eq!(volume_key("\\\\Server\\Share\\dir"), "\\\\server\\share");
+        assert_eq!(volume_key("relative\\path"), "");
+    }
+}
@@ -0,0 +1,249 @@
+//! Path Expansion Module
+//!
+//! Turns what the user types in the path bar into a real file system path:
+//! `%VAR%` environment variables, `~` for the profile folder and `shell:`
+//! known-folder names (`shell:Downloads`, `shell:Startup`, ...).
+//!
+//! Also home to `long_path`, which every direct Win32 file call goes through so
+//! folders deeper than MAX_PATH work. `std::fs` adds the prefix on its own.
+
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use windows::core::PCWSTR;
+use windows::Win32::Storage::FileSystem::GetShortPathNameW;
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+use windows::Win32::UI::Shell::{IShellItem, SHCreateItemFromParsingName, SIGDN_FILESYSPATH};
+
+/// Virtual location understood directly by `list_files`; it has no file system path
+const RECYCLE_BIN: &str = "shell:RecycleBin";
+/// Lifts the MAX_PATH limit off the Win32 file APIs
+const EXTENDED_PREFIX: &str = "\\\\?\\";
+const EXTENDED_UNC_PREFIX: &str = "\\\\?\\UNC\\";
+/// Longest path every plain Win32 call accepts: CreateDirectoryW wants room left
+/// for an 8.3 name (MAX_PATH - 12)
+const PLAIN_PATH_LIMIT: usize = 248;
+
+/// `%LOCALAPPDATA%\Quick Explorer`, where the app keeps its indices and state files
+pub fn app_data_dir() -> std::path::PathBuf {
+    let dir = std::path::PathBuf::from(std::env::var("LOCALAPPDATA").unwrap_or_default())
+        .join("Quick Explorer");
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+/// `s` as a null-terminated UTF-16 string, for passing to Win32 calls
+pub(crate) fn to_wide(s: &(impl AsRef<OsStr> + ?Sized)) -> Vec<u16> {
+    s.as_ref().encode_wide().chain(std::iter::once(0)).collect()
+}
+
+/// Resolves a `shell:` moniker through the shell namespace, exactly like Explorer's address bar
+fn resolve_shell_moniker(moniker: &str) -> Result<String, String> {
+    let wide = to_wide(moniker);
+    unsafe {
+        // Callers may be on a tokio worker without COM; only balance what we initialized
+        let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+
+        let result = SHCreateItemFromParsingName::<_, _, IShellItem>(PCWSTR(wide.as_ptr()), None)
+            .map_err(|e| format!("Unknown shell location {}: {}", moniker, e))
+            .and_then(|item| {
+                item.GetDisplayName(SIGDN_FILESYSPATH)
+                    .map_err(|_| format!("{} has no file system path", moniker))
+            })
+            .and_then(|pwstr| {
+                let path = pwstr.to_string().map_err(|e| e.to_string());
+                windows::Win32::System::Com::CoTaskMemFree(Some(pwstr.as_ptr() as *const _));
+                path
+            });
+
+        if com_initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+/// Expands `input` into an absolute path. Plain paths are returned unchanged
+/// (apart from surrounding whitespace/quotes), and so are `shell:` locations
+/// without a file system path.
+pub fn expand_path_str(input: &str) -> Result<String, String> {
+    let trimmed = input.trim().trim_matches('"').trim();
+
+    if trimmed.eq_ignore_ascii_case(RECYCLE_BIN)
+        || trimmed.eq_ignore_ascii_case("shell:RecycleBinFolder")
+    {
+        return Ok(RECYCLE_BIN.to_string());
+    }
+
+    // `shell:Name\sub\dir` -> known folder + remainder
+    if trimmed.len() > 6
+        && trimmed
+            .get(..6)
+            .is_some_and(|p| p.eq_ignore_ascii_case("shell:"))
+    {
+        let (moniker, rest) = match trimmed.find(['\\', '/']) {
+            Some(idx) => (&trimmed[..idx], &trimmed[idx + 1..]),
+            None => (trimmed, ""),
+        };
+        // Virtual folders (Control Panel, This PC, `shell:::{CLSID}`) have no file
+        // system path; the shell enumerator lists them by their `shell:` name
+        let Ok(base) = resolve_shell_moniker(moniker) else {
+            return Ok(trimmed.to_string());
+        };
+        return Ok(if rest.is_empty() {
+            base
+        } else {
+            std::path::Path::new(&base)
+                .join(crate::expand_env_vars(rest))
+                .to_string_lossy()
+                .to_string()
+        });
+    }
+
+    // `~`, `~\Downloads`, `~/Downloads`
+    if trimmed == "~" || trimmed.starts_with("~\\") || trimmed.starts_with("~/") {
+        let profile =
+            std::env::var("USERPROFILE").map_err(|_| "USERPROFILE is not set".to_string())?;
+        let rest = trimmed[1..].trim_start_matches(['\\', '/']);
+        return Ok(if rest.is_empty() {
+            profile
+        } else {
+            std::path::Path::new(&profile)
+                .join(rest.replace('/', "\\"))
+                .to_string_lossy()
+                .to_string()
+        });
+    }
+
+    Ok(crate::expand_env_vars(trimmed))
+}
+
+/// Resolves `.` and `..` and turns `/` into `\\`, the normalization Windows skips
+/// for `\\?\` paths. None for relative paths.
+fn normalize(path: &str) -> Option<String> {
+    let path = path.replace('/', "\\");
+    let (root, rest) = if let Some(unc) = path.strip_prefix("\\\\") {
+        let mut parts = unc.splitn(3, '\\');
+        let server = parts.next().unwrap_or_default();
+        let share = parts.next().unwrap_or_default();
+        (
+            format!("\\\\{}\\{}", server, share),
+            parts.next().unwrap_or_default(),
+        )
+    } else {
+        match path.as_bytes() {
+            [letter, b':', b'\\', ..] if letter.is_ascii_alphabetic() => {
+                (path[..2].to_string(), &path[3..])
+            }
+            _ => return None,
+        }
+    };
+    let mut components: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            _ => components.push(part),
+        }
+    }
+    Some(format!("{}\\{}", root, components.join("\\")))
+}
+
+/// `path` in a form the Win32 file APIs accept at any length: absolute paths
+/// past the plain limit get the `\\?\` prefix (`\\?\UNC\` for shares).
+/// Short, relative and already prefixed paths come back unchanged.
+pub fn long_path(path: &Path) -> PathBuf {
+    let Some(text) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if text.encode_utf16().count() < PLAIN_PATH_LIMIT || text.starts_with(EXTENDED_PREFIX) {
+        return path.to_path_buf();
+    }
+    match normalize(text) {
+        Some(normalized) => PathBuf::from(match normalized.strip_prefix("\\\\") {
+            Some(unc) => format!("{}{}", EXTENDED_UNC_PREFIX, unc),
+            None => format!("{}{}", EXTENDED_PREFIX, normalized),
+        }),
+        None => path.to_path_buf(),
+    }
+}
+
+/// The usual form of a path `long_path` prefixed, for display and for the shell
+/// namespace, which doesn't parse the prefix. Other `\\?\` paths (volume GUIDs)
+/// are left alone.
+pub fn display_path(path: &str) -> String {
+    if let Some(unc) = path.strip_prefix(EXTENDED_UNC_PREFIX) {
+        return format!("\\\\{}", unc);
+    }
+    match path.strip_prefix(EXTENDED_PREFIX) {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// The 8.3 form of an existing `path`, which often fits within MAX_PATH where
+/// the full one doesn't. None when the volume keeps no short names.
+pub fn short_path(path: &str) -> Option<String> {
+    let long = to_wide(&long_path(Path::new(path)));
+    unsafe {
+        let needed = GetShortPathNameW(PCWSTR(long.as_ptr()), None);
+        if needed == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; needed as usize];
+        let len = GetShortPathNameW(PCWSTR(long.as_ptr()), Some(&mut buffer));
+        if len == 0 || len as usize >= buffer.len() {
+            return None;
+        }
+        Some(display_path(&String::from_utf16_lossy(
+            &buffer[..len as usize],
+        )))
+    }
+}
+
+#[tauri::command]
+pub fn expand_path(input: String) -> Result<String, String> {
+    let expanded = expand_path_str(&input)?;
+    if expanded != input {
+        log::info!("[PATH] Expanded '{}' -> '{}'", input, expanded);
+    }
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_path() {
+        let short = Path::new("C:\\Users\\me");
+        assert_eq!(long_path(short), short);
+
+        let deep = format!(
+            "C:\\src/{}\\.\\x\\..\\file.txt",
+            "node_modules\\".repeat(20)
+        );
+        let long = long_path(Path::new(&deep));
+        let expected = format!("\\\\?\\C:\\src\\{}file.txt", "node_modules\\".repeat(20));
+        assert_eq!(long.to_str(), Some(expected.as_str()));
+        assert_eq!(long_path(&long), long);
+        assert_eq!(display_path(&expected), &expected[4..]);
+
+        let share = format!("\\\\server\\share\\{}", "a".repeat(300));
+        let long = long_path(Path::new(&share));
+        assert!(long
+            .to_str()
+            .unwrap()
+            .starts_with("\\\\?\\UNC\\server\\share\\a"));
+        assert_eq!(display_path(long.to_str().unwrap()), share);
+
+        let relative = "a\\".repeat(200);
+        assert_eq!(long_path(Path::new(&relative)), Path::new(&relative));
+        assert_eq!(
+            display_path("\\\\?\\Volume{1234}\\"),
+            "\\\\?\\Volume{1234}\\"
+        );
+    }
+}
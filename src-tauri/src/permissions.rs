@@ -0,0 +1,637 @@
+//! Permissions Module
+//!
+//! Answers "what can the current user do with this item" from the item's ACL,
+//! so the UI can explain up front why a rename or delete will fail instead of
+//! surfacing an HRESULT afterwards. The check is the kernel's own
+//! `AccessCheck` run against an impersonation copy of the process token, so
+//! group membership, deny ACEs and inheritance are all accounted for.
+//!
+//! `get_security_info` returns the descriptor itself (owner, group and the
+//! DACL's entries decoded into Explorer's permission levels) for the in-app
+//! Security tab. `take_ownership` is the fix for "You need permission to
+//! access this folder": `takeown` + `icacls`, run by the elevated helper (see
+//! `elevation`), make the current user the owner and grant them full control.
+
+use crate::paths::to_wide;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, LocalFree, ERROR_SUCCESS, GENERIC_ALL, GENERIC_EXECUTE, GENERIC_READ,
+    GENERIC_WRITE, HANDLE, HLOCAL,
+};
+use windows::Win32::Security::Authorization::{
+    ConvertSidToStringSidW, GetNamedSecurityInfoW, SE_FILE_OBJECT,
+};
+use windows::Win32::Security::{
+    AccessCheck, DuplicateToken, GetAce, GetFileSecurityW, GetTokenInformation, LookupAccountSidW,
+    SecurityImpersonation, TokenUser, ACCESS_ALLOWED_ACE, ACE_HEADER, ACL,
+    DACL_SECURITY_INFORMATION, GENERIC_MAPPING, GROUP_SECURITY_INFORMATION, INHERITED_ACE,
+    OWNER_SECURITY_INFORMATION, PRIVILEGE_SET, PSECURITY_DESCRIPTOR, PSID, SID_NAME_USE,
+    TOKEN_DUPLICATE, TOKEN_QUERY, TOKEN_USER,
+};
+use windows::Win32::Storage::FileSystem::{
+    DELETE, FILE_ALL_ACCESS, FILE_APPEND_DATA, FILE_ATTRIBUTE_READONLY, FILE_DELETE_CHILD,
+    FILE_EXECUTE, FILE_GENERIC_EXECUTE, FILE_GENERIC_READ, FILE_GENERIC_WRITE, FILE_READ_DATA,
+    FILE_WRITE_DATA, READ_CONTROL, WRITE_DAC, WRITE_OWNER,
+};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+/// Asks AccessCheck for every right the token would be granted
+const MAXIMUM_ALLOWED: u32 = 0x0200_0000;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct EffectiveAccess {
+    pub can_read: bool,
+    /// Modify contents (files) or create entries (folders)
+    pub can_write: bool,
+    pub can_delete: bool,
+    /// Run the file, or traverse into the folder
+    pub can_execute: bool,
+    pub can_change_permissions: bool,
+    pub can_take_ownership: bool,
+    /// The read-only attribute blocks writes even when the ACL allows them
+    pub read_only_attribute: bool,
+    /// Human-readable explanations for each denied capability
+    pub reasons: Vec<String>,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct AccessEntry {
+    /// `DOMAIN\name`, or the SID string when the account can't be resolved
+    pub principal: String,
+    pub sid: String,
+    /// false for a deny entry
+    pub allow: bool,
+    /// Explorer's permission levels the mask covers, strongest first
+    pub rights: Vec<String>,
+    pub mask: u32,
+    /// Inherited from a parent folder rather than set on the item itself
+    pub inherited: bool,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct SecurityInfo {
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    /// Entries in ACL order, which is the order Windows evaluates them
+    pub entries: Vec<AccessEntry>,
+    /// The item has no DACL at all, so everyone has full control
+    pub unrestricted: bool,
+}
+
+/// Closes the token handle when dropped
+struct TokenHandle(HANDLE);
+
+impl Drop for TokenHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
+    }
+}
+
+/// An impersonation copy of the process token, as AccessCheck requires
+fn impersonation_token() -> Result<TokenHandle, String> {
+    unsafe {
+        let mut process_token = HANDLE::default();
+        OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_DUPLICATE | TOKEN_QUERY,
+            &mut process_token,
+        )
+        .map_err(|e| format!("Failed to open process token: {}", e))?;
+        let process_token = TokenHandle(process_token);
+
+        let mut token = HANDLE::default();
+        DuplicateToken(process_token.0, SecurityImpersonation, &mut token)
+            .map_err(|e| format!("Failed to duplicate token: {}", e))?;
+        Ok(TokenHandle(token))
+    }
+}
+
+/// Rights the current user holds on `path` per its security descriptor
+fn granted_access(path: &Path, token: &TokenHandle) -> Result<u32, String> {
+    let wide = to_wide(&crate::paths::long_path(path));
+    let info =
+        (OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION).0;
+    unsafe {
+        let mut needed = 0u32;
+        let _ = GetFileSecurityW(PCWSTR(wide.as_ptr()), info, None, 0, &mut needed);
+        if needed == 0 {
+            return Err(format!(
+                "Failed to read security descriptor of {}: {}",
+                path.display(),
+                std::io::Error::last_os_error()
+            ));
+        }
+        // u64 storage keeps the descriptor pointer-aligned
+        let mut buffer = vec![0u64; (needed as usize).div_ceil(8)];
+        let descriptor = PSECURITY_DESCRIPTOR(buffer.as_mut_ptr() as *mut _);
+        if !GetFileSecurityW(
+            PCWSTR(wide.as_ptr()),
+            info,
+            Some(descriptor),
+            needed,
+            &mut needed,
+        )
+        .as_bool()
+        {
+            return Err(format!(
+                "Failed to read security descriptor of {}: {}",
+                path.display(),
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        let mapping = GENERIC_MAPPING {
+            GenericRead: FILE_GENERIC_READ.0,
+            GenericWrite: FILE_GENERIC_WRITE.0,
+            GenericExecute: FILE_GENERIC_EXECUTE.0,
+            GenericAll: FILE_ALL_ACCESS.0,
+        };
+        let mut privileges = PRIVILEGE_SET::default();
+        let mut privileges_len = std::mem::size_of::<PRIVILEGE_SET>() as u32;
+        let mut granted = 0u32;
+        let mut status = windows::core::BOOL::default();
+        AccessCheck(
+            descriptor,
+            token.0,
+            MAXIMUM_ALLOWED,
+            &mapping,
+            Some(&mut privileges),
+            &mut privileges_len,
+            &mut granted,
+            &mut status,
+        )
+        .map_err(|e| format!("Access check failed for {}: {}", path.display(), e))?;
+        Ok(if status.as_bool() { granted } else { 0 })
+    }
+}
+
+fn has(granted: u32, rights: u32) -> bool {
+    granted & rights == rights
+}
+
+fn effective_access(path: &Path) -> Result<EffectiveAccess, String> {
+    let metadata = std::fs::symlink_metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let is_dir = metadata.is_dir();
+    let read_only_attribute = {
+        use std::os::windows::fs::MetadataExt;
+        metadata.file_attributes() & FILE_ATTRIBUTE_READONLY.0 != 0
+    };
+
+    let token = impersonation_token()?;
+    let granted = granted_access(path, &token)?;
+    // Deleting is also allowed when the parent folder grants "delete subfolders and files"
+    let parent_grants_delete = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .and_then(|p| granted_access(p, &token).ok())
+        .is_some_and(|g| has(g, FILE_DELETE_CHILD.0));
+
+    let can_read = has(granted, FILE_READ_DATA.0);
+    let acl_write = if is_dir {
+        // FILE_ADD_FILE / FILE_ADD_SUBDIRECTORY share bits with WRITE_DATA / APPEND_DATA
+        has(granted, FILE_WRITE_DATA.0) || has(granted, FILE_APPEND_DATA.0)
+    } else {
+        has(granted, FILE_WRITE_DATA.0)
+    };
+    // Explorer ignores the read-only attribute on folders; so does Windows for creation
+    let can_write = acl_write && (is_dir || !read_only_attribute);
+    let can_delete = (has(granted, DELETE.0) || parent_grants_delete) && !read_only_attribute;
+    let can_execute = has(granted, FILE_EXECUTE.0);
+    let can_change_permissions = has(granted, WRITE_DAC.0);
+    let can_take_ownership = has(granted, WRITE_OWNER.0);
+
+    let mut reasons = Vec::new();
+    if !can_read {
+        reasons.push(if is_dir {
+            "You don't have permission to list this folder".to_string()
+        } else {
+            "You don't have permission to read this file".to_string()
+        });
+    }
+    if !acl_write {
+        reasons.push(if is_dir {
+            "You don't have permission to create items in this folder".to_string()
+        } else {
+            "You don't have permission to modify this file".to_string()
+        });
+    } else if !can_write {
+        reasons.push("The file is marked read-only".to_string());
+    }
+    if !has(granted, DELETE.0) && !parent_grants_delete {
+        reasons.push("You don't have permission to delete this item".to_string());
+    } else if read_only_attribute {
+        reasons.push("Read-only items must be unlocked before they can be deleted".to_string());
+    }
+    if !can_change_permissions {
+        reasons.push("Only the owner or an administrator can change permissions".to_string());
+    }
+
+    Ok(EffectiveAccess {
+        can_read,
+        can_write,
+        can_delete,
+        can_execute,
+        can_change_permissions,
+        can_take_ownership,
+        read_only_attribute,
+        reasons,
+    })
+}
+
+/// What the current user can actually do with `path` (Security tab summary)
+#[tauri::command]
+pub async fn get_effective_access(path: String) -> Result<EffectiveAccess, String> {
+    tauri::async_runtime::spawn_blocking(move || effective_access(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+const ACCESS_ALLOWED_ACE_TYPE: u8 = 0;
+const ACCESS_DENIED_ACE_TYPE: u8 = 1;
+
+/// The basic levels of Explorer's Security tab, strongest first
+const RIGHT_LEVELS: [(&str, u32); 5] = [
+    ("Full control", FILE_ALL_ACCESS.0),
+    (
+        "Modify",
+        FILE_GENERIC_READ.0 | FILE_GENERIC_WRITE.0 | FILE_GENERIC_EXECUTE.0 | DELETE.0,
+    ),
+    (
+        "Read & execute",
+        FILE_GENERIC_READ.0 | FILE_GENERIC_EXECUTE.0,
+    ),
+    ("Read", FILE_GENERIC_READ.0),
+    ("Write", FILE_GENERIC_WRITE.0 & !READ_CONTROL.0),
+];
+
+/// Names the permission levels an ACE mask grants; generic bits are mapped to
+/// their file rights first, as inherited ACEs often carry them
+fn rights_names(mask: u32) -> Vec<String> {
+    let mut mask = mask;
+    for (generic, specific) in [
+        (GENERIC_ALL.0, FILE_ALL_ACCESS.0),
+        (GENERIC_READ.0, FILE_GENERIC_READ.0),
+        (GENERIC_WRITE.0, FILE_GENERIC_WRITE.0),
+        (GENERIC_EXECUTE.0, FILE_GENERIC_EXECUTE.0),
+    ] {
+        if mask & generic != 0 {
+            mask = (mask & !generic) | specific;
+        }
+    }
+    let names: Vec<String> = RIGHT_LEVELS
+        .iter()
+        .filter(|(_, rights)| has(mask, *rights))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    if names.is_empty() {
+        vec!["Special permissions".to_string()]
+    } else {
+        names
+    }
+}
+
+/// The SID in its `S-1-5-...` form
+unsafe fn sid_string(sid: PSID) -> String {
+    let mut string = PWSTR::null();
+    if ConvertSidToStringSidW(sid, &mut string).is_err() {
+        return String::new();
+    }
+    let result = string.to_string().unwrap_or_default();
+    let _ = LocalFree(Some(HLOCAL(string.0 as _)));
+    result
+}
+
+/// `DOMAIN\name` for a SID, or None for deleted or unknown accounts
+unsafe fn account_name(sid: PSID) -> Option<String> {
+    let mut name = [0u16; 256];
+    let mut domain = [0u16; 256];
+    let mut name_len = name.len() as u32;
+    let mut domain_len = domain.len() as u32;
+    let mut use_ = SID_NAME_USE::default();
+    LookupAccountSidW(
+        PCWSTR::null(),
+        sid,
+        Some(PWSTR(name.as_mut_ptr())),
+        &mut name_len,
+        Some(PWSTR(domain.as_mut_ptr())),
+        &mut domain_len,
+        &mut use_,
+    )
+    .ok()?;
+    let name = String::from_utf16_lossy(&name[..name_len as usize]);
+    let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+    Some(if domain.is_empty() {
+        name
+    } else {
+        format!("{}\\{}", domain, name)
+    })
+}
+
+unsafe fn principal(sid: PSID) -> Option<String> {
+    if sid.is_invalid() {
+        return None;
+    }
+    account_name(sid).or_else(|| Some(sid_string(sid)))
+}
+
+/// Frees the descriptor GetNamedSecurityInfoW allocated when dropped
+struct LocalDescriptor(PSECURITY_DESCRIPTOR);
+
+impl Drop for LocalDescriptor {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = LocalFree(Some(HLOCAL(self.0 .0)));
+        }
+    }
+}
+
+unsafe fn acl_entries(dacl: *const ACL) -> Vec<AccessEntry> {
+    let mut entries = Vec::new();
+    for index in 0..(*dacl).AceCount as u32 {
+        let mut ace = std::ptr::null_mut();
+        if GetAce(dacl, index, &mut ace).is_err() {
+            continue;
+        }
+        let header = &*(ace as *const ACE_HEADER);
+        // Object and callback ACEs don't occur on files; skip rather than misread them
+        let allow = match header.AceType {
+            ACCESS_ALLOWED_ACE_TYPE => true,
+            ACCESS_DENIED_ACE_TYPE => false,
+            _ => continue,
+        };
+        // ACCESS_DENIED_ACE has the same layout
+        let ace = &*(ace as *const ACCESS_ALLOWED_ACE);
+        let sid = PSID(&ace.SidStart as *const u32 as *mut _);
+        entries.push(AccessEntry {
+            principal: principal(sid).unwrap_or_default(),
+            sid: sid_string(sid),
+            allow,
+            rights: rights_names(ace.Mask),
+            mask: ace.Mask,
+            inherited: header.AceFlags as u32 & INHERITED_ACE.0 != 0,
+        });
+    }
+    entries
+}
+
+fn security_info(path: &Path) -> Result<SecurityInfo, String> {
+    let wide = to_wide(&crate::paths::long_path(path));
+    unsafe {
+        let mut owner = PSID::default();
+        let mut group = PSID::default();
+        let mut dacl: *mut ACL = std::ptr::null_mut();
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        let status = GetNamedSecurityInfoW(
+            PCWSTR(wide.as_ptr()),
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION,
+            Some(&mut owner),
+            Some(&mut group),
+            Some(&mut dacl),
+            None,
+            &mut descriptor,
+        );
+        if status != ERROR_SUCCESS {
+            return Err(format!(
+                "Failed to read security descriptor of {}: {}",
+                path.display(),
+                windows::core::Error::from(status.to_hresult())
+            ));
+        }
+        let _descriptor = LocalDescriptor(descriptor);
+
+        Ok(SecurityInfo {
+            owner: principal(owner),
+            group: principal(group),
+            entries: if dacl.is_null() {
+                Vec::new()
+            } else {
+                acl_entries(dacl)
+            },
+            unrestricted: dacl.is_null(),
+        })
+    }
+}
+
+/// Owner, group and access entries of `path` for the Security tab
+#[tauri::command]
+pub async fn get_security_info(path: String) -> Result<SecurityInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || security_info(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// SID of the user running the app. The elevated helper may run as a different
+/// administrator account, so `%USERNAME%` there would name the wrong user
+pub(crate) fn current_user_sid() -> Result<String, String> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)
+            .map_err(|e| format!("Failed to open process token: {}", e))?;
+        let token = TokenHandle(token);
+
+        let mut needed = 0u32;
+        let _ = GetTokenInformation(token.0, TokenUser, None, 0, &mut needed);
+        let mut buffer = vec![0u64; (needed as usize).div_ceil(8)];
+        GetTokenInformation(
+            token.0,
+            TokenUser,
+            Some(buffer.as_mut_ptr() as *mut _),
+            needed,
+            &mut needed,
+        )
+        .map_err(|e| format!("Failed to read the current user: {}", e))?;
+        let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+        let sid = sid_string(user.User.Sid);
+        if sid.is_empty() {
+            return Err("Failed to read the current user's SID".to_string());
+        }
+        Ok(sid)
+    }
+}
+
+/// Entries below the chosen folders checked after taking ownership, besides the
+/// items themselves
+const OWNERSHIP_SAMPLE: usize = 200;
+
+/// takeown and icacls arguments that make the caller the owner of `path` and
+/// grant `sid` full control. Folders are taken recursively (`/d y` answers yes
+/// for subfolders it can't list yet) and get an inheritable grant applied down
+/// the tree; `/c` keeps icacls going past children it still can't touch
+fn ownership_args(path: &str, is_dir: bool, sid: &str) -> (Vec<String>, Vec<String>) {
+    // "D:" alone would mean the current folder of D:
+    let path = if path.ends_with(':') {
+        format!("{}\\", path)
+    } else {
+        path.to_string()
+    };
+    let mut takeown = vec!["/f".to_string(), path.clone()];
+    let mut icacls = vec![path, "/grant".to_string()];
+    if is_dir {
+        takeown.extend(["/r", "/d", "y"].map(String::from));
+        icacls.push(format!("*{}:(OI)(CI)F", sid));
+        icacls.push("/t".to_string());
+    } else {
+        icacls.push(format!("*{}:F", sid));
+    }
+    icacls.extend(["/c", "/q"].map(String::from));
+    (takeown, icacls)
+}
+
+/// Runs `program` without a console window; its exit code
+fn run_hidden(program: &str, args: &[String]) -> Result<i32, String> {
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    Command::new(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .status()
+        .map(|status| status.code().unwrap_or(-1))
+        .map_err(|e| format!("Failed to run {}: {}", program, e))
+}
+
+/// The elevated helper's half of `take_ownership`. Arguments go to takeown and
+/// icacls directly, so no length limit or `%` expansion of a `cmd` line applies
+pub fn apply_ownership(paths: &[String], sid: &str) -> Result<(), String> {
+    let mut failed = Vec::new();
+    for path in paths {
+        let (takeown, icacls) = ownership_args(path, Path::new(path).is_dir(), sid);
+        // takeown stops at children it can't open; icacls' result covers those too
+        let _ = run_hidden("takeown", &takeown);
+        match run_hidden("icacls", &icacls) {
+            Ok(0) => {}
+            Ok(code) => failed.push(format!("{} (icacls exit code {})", path, code)),
+            Err(e) => failed.push(format!("{} ({})", path, e)),
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Could not grant access to: {}", failed.join(", ")))
+    }
+}
+
+/// `paths`, and up to `sample` entries found breadth-first below them, that the
+/// current user still can't read
+fn still_inaccessible(paths: &[String], sample: usize) -> Vec<String> {
+    let readable = |path: &Path| effective_access(path).is_ok_and(|a| a.can_read);
+    let mut failed: Vec<String> = paths
+        .iter()
+        .filter(|p| !readable(Path::new(p)))
+        .cloned()
+        .collect();
+    let mut queue: VecDeque<PathBuf> = paths
+        .iter()
+        .map(PathBuf::from)
+        .filter(|p| p.is_dir())
+        .collect();
+    let mut checked = 0;
+    while let Some(dir) = queue.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if checked == sample {
+                return failed;
+            }
+            checked += 1;
+            let path = entry.path();
+            if !readable(&path) {
+                failed.push(path.to_string_lossy().to_string());
+            } else if path.is_dir() {
+                queue.push_back(path);
+            }
+        }
+    }
+    failed
+}
+
+/// Makes the current user the owner of `paths` with full control, after a UAC
+/// prompt. Fails listing the items that are still inaccessible afterwards
+#[tauri::command]
+pub async fn take_ownership(window: tauri::Window, paths: Vec<String>) -> Result<(), String> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let sid = current_user_sid()?;
+        log::info!("[PERMISSIONS] Taking ownership of {} item(s)", paths.len());
+        let task = crate::elevation::HelperTask::TakeOwnership {
+            paths: paths.clone(),
+            sid,
+        };
+        crate::elevation::run_helper(crate::get_root_hwnd(&window), &task)?;
+
+        // icacls' exit code only says something failed; confirm the items and a
+        // sample of their contents are readable now
+        let failed = still_inaccessible(&paths, OWNERSHIP_SAMPLE);
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Could not take ownership of: {}",
+                failed.join(", ")
+            ))
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rights_names() {
+        assert_eq!(rights_names(FILE_ALL_ACCESS.0).len(), 5);
+        assert_eq!(rights_names(GENERIC_ALL.0)[0], "Full control");
+        assert_eq!(
+            rights_names(0x1301BF),
+            vec!["Modify", "Read & execute", "Read", "Write"]
+        );
+        assert_eq!(rights_names(0x1200A9), vec!["Read & execute", "Read"]);
+        assert_eq!(
+            rights_names(GENERIC_READ.0 | GENERIC_EXECUTE.0),
+            vec!["Read & execute", "Read"]
+        );
+        assert_eq!(rights_names(DELETE.0), vec!["Special permissions"]);
+    }
+
+    #[test]
+    fn test_ownership_args() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            ownership_args("C:\\Data 100%", true, "S-1-5-21-1"),
+            (
+                args(&["/f", "C:\\Data 100%", "/r", "/d", "y"]),
+                args(&[
+                    "C:\\Data 100%",
+                    "/grant",
+                    "*S-1-5-21-1:(OI)(CI)F",
+                    "/t",
+                    "/c",
+                    "/q"
+                ])
+            )
+        );
+        assert_eq!(
+            ownership_args("C:\\Data\\a.txt", false, "S-1-5-21-1").1,
+            args(&["C:\\Data\\a.txt", "/grant", "*S-1-5-21-1:F", "/c", "/q"])
+        );
+        assert_eq!(ownership_args("D:", true, "S-1-5-21-1").0[1], "D:\\");
+    }
+}
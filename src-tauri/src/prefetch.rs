@@ -0,0 +1,79 @@
+//! Prefetch Module
+//!
+//! Warms the caches for the folders most sessions start in. Shortly after
+//! launch a lowest-priority thread lists Desktop, Downloads and Documents and
+//! renders thumbnails for the first pictures and videos in each, so the first
+//! navigation to them doesn't wait on thumbnail extraction. The listings
+//! themselves aren't cached: no tab watches these folders yet, so the listing
+//! cache would only trust them for half a minute (see `listing`), while the
+//! thumbnail cache is keyed by modification time and stays valid.
+
+use tauri::Manager;
+
+/// Lets the window and the first listing go first
+const STARTUP_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
+const FOLDERS: [&str; 3] = ["desktop", "downloads", "documents"];
+/// Thumbnails rendered per folder; roughly one screen of the grid view
+const THUMBNAILS_PER_FOLDER: usize = 48;
+/// The size the file views request
+const THUMBNAIL_SIZE: u32 = 256;
+
+const IMAGE_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "gif", "webp", "bmp", "heic"];
+const VIDEO_EXTENSIONS: [&str; 9] = [
+    "mp4", "mkv", "avi", "mov", "wmv", "webm", "flv", "mpg", "mpeg",
+];
+
+fn prefetch_folder(app: &tauri::AppHandle, path: &str, show_hidden: bool) -> Result<(), String> {
+    let entries =
+        crate::sta_worker::StaWorker::global().list_files(path.to_string(), show_hidden, None)?;
+
+    let media = entries.iter().filter_map(|entry| {
+        let ext = std::path::Path::new(&entry.path)
+            .extension()?
+            .to_str()?
+            .to_lowercase();
+        let is_video = VIDEO_EXTENSIONS.contains(&ext.as_str());
+        // Warming a OneDrive folder must not download it
+        let is_online_only = entry.cloud_status == Some(crate::cloud::CloudStatus::OnlineOnly);
+        (!is_online_only && (is_video || IMAGE_EXTENSIONS.contains(&ext.as_str())))
+            .then_some((entry, is_video))
+    });
+    for (entry, is_video) in media.take(THUMBNAILS_PER_FOLDER) {
+        let _ = tauri::async_runtime::block_on(crate::get_thumbnail_bytes(
+            entry.path.clone(),
+            THUMBNAIL_SIZE,
+            entry.modified_timestamp,
+            app.state::<crate::ThumbnailCache>(),
+            app.state::<crate::ThumbnailConcurrencyLimit>(),
+            is_video,
+            None,
+        ));
+    }
+    log::debug!("[PREFETCH] Warmed {} ({} items)", path, entries.len());
+    Ok(())
+}
+
+/// Starts the prefetch thread; call once from setup
+pub fn start(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        unsafe {
+            use windows::Win32::System::Threading::{
+                GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_LOWEST,
+            };
+            let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_LOWEST);
+        }
+        std::thread::sleep(STARTUP_DELAY);
+
+        let Ok(paths) = crate::get_system_default_paths() else {
+            return;
+        };
+        let show_hidden = crate::settings::preferences().show_hidden;
+        for folder in FOLDERS {
+            if let Some(path) = paths.get(folder) {
+                if let Err(e) = prefetch_folder(&app, path, show_hidden) {
+                    log::debug!("[PREFETCH] Skipped {}: {}", path, e);
+                }
+            }
+        }
+    });
+}
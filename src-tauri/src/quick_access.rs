@@ -0,0 +1,341 @@
+//! Quick Access Module
+//!
+//! The sidebar's pinned folders, kept in the settings store rather than in the
+//! UI's localStorage so they survive a reinstall or a cleared WebView profile.
+//! The built-in entries (Desktop, Downloads, ...) are told apart by their id
+//! and are hidden rather than removed when unpinned; folders pinned here use
+//! their path as id.
+//!
+//! With the `sync_quick_access` preference, pinning and unpinning also go to
+//! Explorer's own Quick Access through its "pintohome" / "unpinfromhome"
+//! verbs, and folders pinned in Explorer show up in the list.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use windows::core::{w, HSTRING, PCSTR};
+use windows::Win32::Foundation::PROPERTYKEY;
+use windows::Win32::System::Com::{
+    CoInitializeEx, CoTaskMemFree, CoUninitialize, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::UI::Shell::PropertiesSystem::PSGetPropertyKeyFromName;
+use windows::Win32::UI::Shell::{
+    BHID_EnumItems, BHID_SFUIObject, IContextMenu, IEnumShellItems, IShellItem, IShellItem2,
+    SHCreateItemFromParsingName, CMF_NORMAL, CMINVOKECOMMANDINFO, SIGDN_FILESYSPATH,
+    SIGDN_NORMALDISPLAY,
+};
+use windows::Win32::UI::WindowsAndMessaging::{CreatePopupMenu, DestroyMenu, SW_HIDE};
+
+const PINNED_KEY: &str = "pinned_folders";
+/// Explorer's Quick Access, called Home on Windows 11
+const QUICK_ACCESS: &str = "shell:::{679f85cb-0220-4080-b29b-5540cc05aab6}";
+/// Entries the UI creates itself; their paths are filled in from the known folders
+const BUILTIN_IDS: &[&str] = &[
+    "desktop",
+    "home",
+    "downloads",
+    "documents",
+    "pictures",
+    "recycle-bin",
+];
+const CONTEXT_MENU_FIRST_ID: u32 = 1;
+const CONTEXT_MENU_LAST_ID: u32 = 0x7FFF;
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug, PartialEq)]
+#[serde(default)]
+#[ts(export)]
+pub struct PinnedFolder {
+    pub id: String,
+    pub name: String,
+    /// "" for This PC, "shell:" paths for virtual folders
+    pub path: String,
+    /// False for a built-in entry the user unpinned
+    pub enabled: bool,
+}
+
+impl Default for PinnedFolder {
+    fn default() -> Self {
+        PinnedFolder {
+            id: String::new(),
+            name: String::new(),
+            path: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    a.trim_end_matches('\\')
+        .eq_ignore_ascii_case(b.trim_end_matches('\\'))
+}
+
+fn folder_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Adds `path` at the end, or shows it again if it was a hidden built-in; false when already pinned
+fn pin(folders: &mut Vec<PinnedFolder>, path: &str, name: &str) -> bool {
+    match folders.iter_mut().find(|f| same_path(&f.path, path)) {
+        Some(folder) if folder.enabled => false,
+        Some(folder) => {
+            folder.enabled = true;
+            true
+        }
+        None => {
+            folders.push(PinnedFolder {
+                id: path.to_string(),
+                name: name.to_string(),
+                path: path.to_string(),
+                enabled: true,
+            });
+            true
+        }
+    }
+}
+
+/// Removes `path`, or hides it if it is a built-in; false when it wasn't pinned
+fn unpin(folders: &mut Vec<PinnedFolder>, path: &str) -> bool {
+    let Some(index) = folders
+        .iter()
+        .position(|f| f.enabled && same_path(&f.path, path))
+    else {
+        return false;
+    };
+    if BUILTIN_IDS.contains(&folders[index].id.as_str()) {
+        folders[index].enabled = false;
+    } else {
+        folders.remove(index);
+    }
+    true
+}
+
+fn stored() -> Option<Vec<PinnedFolder>> {
+    crate::settings::SettingsStore::global().get(PINNED_KEY)
+}
+
+fn store(folders: &[PinnedFolder]) -> Result<(), String> {
+    crate::settings::SettingsStore::global().set(PINNED_KEY, &folders)
+}
+
+/// Runs `f` with COM initialized; shell verbs want an apartment thread
+fn with_com<T>(f: impl FnOnce() -> T) -> T {
+    unsafe {
+        // Only balance what we initialized
+        let com_initialized = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
+        let result = f();
+        if com_initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+unsafe fn display_name(
+    item: &IShellItem,
+    kind: windows::Win32::UI::Shell::SIGDN,
+) -> Option<String> {
+    let name = item.GetDisplayName(kind).ok()?;
+    let result = name.to_string().ok();
+    CoTaskMemFree(Some(name.as_ptr() as *const _));
+    result
+}
+
+/// Items directly inside Explorer's Quick Access
+unsafe fn quick_access_items() -> Result<Vec<IShellItem>, String> {
+    let root: IShellItem = SHCreateItemFromParsingName(&HSTRING::from(QUICK_ACCESS), None)
+        .map_err(|e| format!("Quick Access is not available: {}", e))?;
+    let items: IEnumShellItems = root
+        .BindToHandler(None, &BHID_EnumItems)
+        .map_err(|e| format!("Failed to list Quick Access: {}", e))?;
+    let mut result = Vec::new();
+    let mut item: [Option<IShellItem>; 1] = [None];
+    let mut fetched = 0;
+    while items.Next(&mut item, Some(&mut fetched)).is_ok() && fetched > 0 {
+        result.extend(item[0].take());
+    }
+    Ok(result)
+}
+
+/// (name, path) of the folders pinned in Explorer's Quick Access
+unsafe fn explorer_pinned() -> Result<Vec<(String, String)>, String> {
+    let mut key = PROPERTYKEY::default();
+    PSGetPropertyKeyFromName(w!("System.Home.IsPinned"), &mut key)
+        .map_err(|e| format!("Pinned state is not available: {}", e))?;
+    Ok(quick_access_items()?
+        .into_iter()
+        .filter(|item| {
+            windows::core::Interface::cast::<IShellItem2>(item)
+                .and_then(|item| item.GetBool(&key))
+                .is_ok_and(|pinned| pinned.as_bool())
+        })
+        .filter_map(|item| {
+            // Recent files and virtual folders have no file system path
+            let path = display_name(&item, SIGDN_FILESYSPATH)?;
+            let name =
+                display_name(&item, SIGDN_NORMALDISPLAY).unwrap_or_else(|| folder_name(&path));
+            std::path::Path::new(&path).is_dir().then_some((name, path))
+        })
+        .collect())
+}
+
+/// Invokes `verb` from the context menu of `item`
+unsafe fn invoke_verb(item: &IShellItem, verb: &str) -> Result<(), String> {
+    let menu: IContextMenu = item
+        .BindToHandler(None, &BHID_SFUIObject)
+        .map_err(|e| format!("Failed to get the context menu: {}", e))?;
+    let hmenu = CreatePopupMenu().map_err(|e| format!("CreatePopupMenu failed: {}", e))?;
+    // Verbs from shell extensions only answer once the menu has been filled
+    let result = menu
+        .QueryContextMenu(
+            hmenu,
+            0,
+            CONTEXT_MENU_FIRST_ID,
+            CONTEXT_MENU_LAST_ID,
+            CMF_NORMAL,
+        )
+        .ok()
+        .and_then(|()| {
+            let verb = format!("{}\0", verb);
+            menu.InvokeCommand(&CMINVOKECOMMANDINFO {
+                cbSize: std::mem::size_of::<CMINVOKECOMMANDINFO>() as u32,
+                lpVerb: PCSTR(verb.as_ptr()),
+                nShow: SW_HIDE.0,
+                ..Default::default()
+            })
+        });
+    let _ = DestroyMenu(hmenu);
+    result.map_err(|e| format!("'{}' failed: {}", verb, e))
+}
+
+fn explorer_pin(path: &str) -> Result<(), String> {
+    with_com(|| unsafe {
+        let item: IShellItem = SHCreateItemFromParsingName(&HSTRING::from(path), None)
+            .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        invoke_verb(&item, "pintohome")
+    })
+}
+
+fn explorer_unpin(path: &str) -> Result<(), String> {
+    with_com(|| unsafe {
+        // The verb lives on the Quick Access entry, not on the folder itself
+        let Some(item) = quick_access_items()?.into_iter().find(|item| {
+            display_name(item, SIGDN_FILESYSPATH).is_some_and(|p| same_path(&p, path))
+        }) else {
+            return Ok(());
+        };
+        invoke_verb(&item, "unpinfromhome")
+    })
+}
+
+fn sync_enabled() -> bool {
+    crate::settings::preferences().sync_quick_access
+}
+
+/// The pinned folders, or None before the UI has saved any (it then sends its
+/// defaults with `set_pinned_folders`). Folders pinned in Explorer are added
+/// when `sync_quick_access` is on.
+#[tauri::command]
+pub async fn get_pinned_folders() -> Result<Option<Vec<PinnedFolder>>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let Some(mut folders) = stored() else {
+            return Ok(None);
+        };
+        if sync_enabled() {
+            match with_com(|| unsafe { explorer_pinned() }) {
+                Ok(pinned) => {
+                    let mut added = false;
+                    for (name, path) in pinned {
+                        // Unpinned built-ins stay hidden; Explorer pins Desktop and others too
+                        if !folders.iter().any(|f| same_path(&f.path, &path)) {
+                            added |= pin(&mut folders, &path, &name);
+                        }
+                    }
+                    if added {
+                        store(&folders)?;
+                    }
+                }
+                Err(e) => log::warn!("[QUICK_ACCESS] {}", e),
+            }
+        }
+        Ok(Some(folders))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Replaces the whole list, e.g. after the user reordered or renamed entries
+#[tauri::command]
+pub fn set_pinned_folders(folders: Vec<PinnedFolder>) -> Result<(), String> {
+    store(&folders)
+}
+
+/// Pins `path` to the sidebar (and Explorer's Quick Access when syncing); returns the new list
+#[tauri::command]
+pub async fn pin_folder(path: String, name: Option<String>) -> Result<Vec<PinnedFolder>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut folders = stored().unwrap_or_default();
+        let name = name.unwrap_or_else(|| folder_name(&path));
+        if pin(&mut folders, &path, &name) {
+            store(&folders)?;
+            log::info!("[QUICK_ACCESS] Pinned {}", path);
+        }
+        if sync_enabled() && !path.is_empty() && !path.starts_with("shell:") {
+            if let Err(e) = explorer_pin(&crate::expand_env_vars(&path)) {
+                log::warn!("[QUICK_ACCESS] {}", e);
+            }
+        }
+        Ok(folders)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Unpins `path` from the sidebar (and Explorer's Quick Access when syncing); returns the new list
+#[tauri::command]
+pub async fn unpin_folder(path: String) -> Result<Vec<PinnedFolder>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut folders = stored().unwrap_or_default();
+        if unpin(&mut folders, &path) {
+            store(&folders)?;
+            log::info!("[QUICK_ACCESS] Unpinned {}", path);
+        }
+        if sync_enabled() && !path.is_empty() && !path.starts_with("shell:") {
+            if let Err(e) = explorer_unpin(&crate::expand_env_vars(&path)) {
+                log::warn!("[QUICK_ACCESS] {}", e);
+            }
+        }
+        Ok(folders)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_and_unpin() {
+        let mut folders = vec![PinnedFolder {
+            id: "downloads".to_string(),
+            name: "Downloads".to_string(),
+            path: "C:\\Users\\me\\Downloads".to_string(),
+            enabled: true,
+        }];
+        assert!(pin(&mut folders, "D:\\Work", "Work"));
+        assert!(!pin(&mut folders, "d:\\work\\", "Work"));
+        assert_eq!(folders.len(), 2);
+
+        // Built-ins are hidden, other folders removed
+        assert!(unpin(&mut folders, "C:\\Users\\me\\Downloads"));
+        assert!(!folders[0].enabled);
+        assert!(unpin(&mut folders, "D:\\Work"));
+        assert_eq!(folders.len(), 1);
+        assert!(!unpin(&mut folders, "D:\\Work"));
+
+        assert!(pin(&mut folders, "C:\\Users\\me\\Downloads", "Downloads"));
+        assert!(folders[0].enabled);
+    }
+}
@@ -0,0 +1,133 @@
+//! Recent Module
+//!
+//! Files the user opened lately, read from the Windows Recent Items folder
+//! that Explorer and the Office/Shell "recent" lists also feed. Each item there
+//! is a shortcut named after its target, so the targets are resolved with the
+//! same IShellLink code as the Shortcut properties tab. Together with
+//! `frecency::get_frequent_folders` this makes up the Home view.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+use windows::Win32::System::Com::{
+    CoInitializeEx, CoTaskMemFree, CoUninitialize, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::UI::Shell::{FOLDERID_Recent, SHGetKnownFolderPath, KF_FLAG_DEFAULT};
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct RecentFile {
+    pub entry: crate::FileEntry,
+    /// Unix seconds of the last time the file was opened
+    #[ts(type = "number")]
+    pub opened_at: i64,
+}
+
+fn recent_folder() -> Option<PathBuf> {
+    unsafe {
+        let path_ptr = SHGetKnownFolderPath(&FOLDERID_Recent, KF_FLAG_DEFAULT, None).ok()?;
+        let path = path_ptr.to_string().ok().map(PathBuf::from);
+        CoTaskMemFree(Some(path_ptr.as_ptr() as *const _));
+        path
+    }
+}
+
+/// The .lnk files of the Recent folder with their modification time, newest first
+fn recent_links(folder: &Path) -> Vec<(PathBuf, i64)> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut links: Vec<(PathBuf, i64)> = entries
+        .flatten()
+        .filter(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"))
+        })
+        .filter_map(|e| {
+            let modified = e.metadata().ok()?.modified().ok()?;
+            let secs = modified
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs() as i64;
+            Some((e.path(), secs))
+        })
+        .collect();
+    links.sort_by_key(|link| std::cmp::Reverse(link.1));
+    links
+}
+
+/// The first `limit` distinct targets that are existing files; `targets` is
+/// consumed lazily so only as many shortcuts as needed get resolved
+fn distinct_files(
+    targets: impl Iterator<Item = (String, i64)>,
+    limit: usize,
+    is_file: impl Fn(&Path) -> bool,
+) -> Vec<(String, i64)> {
+    let mut seen = HashSet::new();
+    targets
+        .filter(|(target, _)| !target.is_empty())
+        // Folders opened from Explorer land here too; the frequent folders list covers them
+        .filter(|(target, _)| is_file(Path::new(target)))
+        .filter(|(target, _)| seen.insert(target.to_lowercase()))
+        .take(limit)
+        .collect()
+}
+
+/// Recently opened files, newest first, skipping ones that were deleted since
+#[tauri::command]
+pub async fn get_recent_files(limit: usize) -> Result<Vec<RecentFile>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let folder = recent_folder().ok_or("The Recent Items folder is not available")?;
+        let links = recent_links(&folder);
+        unsafe {
+            // IShellLink needs COM; only balance what we initialized
+            let com_initialized = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
+            let targets = links.into_iter().filter_map(|(link, opened_at)| {
+                let info =
+                    crate::shortcuts::resolve_shortcut(link.to_string_lossy().to_string()).ok()?;
+                Some((info.target_path, opened_at))
+            });
+            let files = distinct_files(targets, limit, Path::is_file);
+            if com_initialized {
+                CoUninitialize();
+            }
+            Ok(files
+                .into_iter()
+                .filter_map(|(target, opened_at)| {
+                    let entry = crate::get_file_entry(Path::new(&target)).ok()?;
+                    Some(RecentFile { entry, opened_at })
+                })
+                .collect())
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distinct_files() {
+        let targets = vec![
+            ("C:\\Docs\\report.docx".to_string(), 30),
+            ("".to_string(), 25),
+            ("C:\\Docs".to_string(), 20),
+            ("c:\\docs\\REPORT.docx".to_string(), 15),
+            ("C:\\Docs\\notes.txt".to_string(), 10),
+            ("C:\\Docs\\old.txt".to_string(), 5),
+        ];
+        let is_file = |p: &Path| p.extension().is_some();
+        let files = distinct_files(targets.into_iter(), 2, is_file);
+        assert_eq!(
+            files,
+            vec![
+                ("C:\\Docs\\report.docx".to_string(), 30),
+                ("C:\\Docs\\notes.txt".to_string(), 10),
+            ]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Reputation Module
+//!
+//! Opt-in VirusTotal lookup for suspicious downloads. Only the file's SHA-256
+//! leaves the machine, never its contents, and nothing is sent until the
+//! user has stored an API key of their own. Requests go through WinHTTP so
+//! the system proxy configuration applies.
+
+use crate::settings::SettingsStore;
+use serde::Serialize;
+use std::ffi::c_void;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use ts_rs::TS;
+use windows::core::{w, HSTRING, PCWSTR};
+use windows::Win32::Networking::WinHttp::{
+    WinHttpCloseHandle, WinHttpConnect, WinHttpOpen, WinHttpOpenRequest, WinHttpQueryDataAvailable,
+    WinHttpQueryHeaders, WinHttpReadData, WinHttpReceiveResponse, WinHttpSendRequest,
+    WinHttpSetTimeouts, INTERNET_DEFAULT_HTTPS_PORT, WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+    WINHTTP_FLAG_SECURE, WINHTTP_QUERY_FLAG_NUMBER, WINHTTP_QUERY_STATUS_CODE,
+};
+
+/// Settings key holding the user's VirusTotal API key
+pub(crate) const API_KEY_SETTING: &str = "virustotal.api_key";
+const API_HOST: &str = "www.virustotal.com";
+const TIMEOUT_MS: i32 = 15_000;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct FileReputation {
+    pub sha256: String,
+    /// False when VirusTotal has never seen this file
+    pub known: bool,
+    pub malicious: u32,
+    pub suspicious: u32,
+    pub harmless: u32,
+    pub undetected: u32,
+    /// Unix seconds of the most recent scan
+    #[ts(type = "number | null")]
+    pub last_analysis_date: Option<i64>,
+    /// Report page for the file
+    pub permalink: String,
+}
+
+/// WinHTTP handle closed on drop
+struct InternetHandle(*mut c_void);
+
+impl InternetHandle {
+    fn new(raw: *mut c_void, what: &str) -> Result<Self, String> {
+        if raw.is_null() {
+            Err(format!(
+                "{} failed: {}",
+                what,
+                std::io::Error::last_os_error()
+            ))
+        } else {
+            Ok(Self(raw))
+        }
+    }
+}
+
+impl Drop for InternetHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WinHttpCloseHandle(self.0);
+        }
+    }
+}
+
+/// HTTPS GET against the VirusTotal API; returns the status code and body
+fn api_get(path: &str, api_key: &str) -> Result<(u32, Vec<u8>), String> {
+    unsafe {
+        let session = InternetHandle::new(
+            WinHttpOpen(
+                w!("Quick Explorer"),
+                WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+                PCWSTR::null(),
+                PCWSTR::null(),
+                0,
+            ),
+            "WinHttpOpen",
+        )?;
+        let _ = WinHttpSetTimeouts(session.0, TIMEOUT_MS, TIMEOUT_MS, TIMEOUT_MS, TIMEOUT_MS);
+        let connection = InternetHandle::new(
+            WinHttpConnect(
+                session.0,
+                &HSTRING::from(API_HOST),
+                INTERNET_DEFAULT_HTTPS_PORT,
+                0,
+            ),
+            "WinHttpConnect",
+        )?;
+        let request = InternetHandle::new(
+            WinHttpOpenRequest(
+                connection.0,
+                w!("GET"),
+                &HSTRING::from(path),
+                PCWSTR::null(),
+                PCWSTR::null(),
+                std::ptr::null(),
+                WINHTTP_FLAG_SECURE,
+            ),
+            "WinHttpOpenRequest",
+        )?;
+
+        let headers: Vec<u16> = format!("x-apikey: {}\r\naccept: application/json\r\n", api_key)
+            .encode_utf16()
+            .collect();
+        WinHttpSendRequest(request.0, Some(&headers), None, 0, 0, 0)
+            .map_err(|e| format!("Could not reach VirusTotal: {}", e))?;
+        WinHttpReceiveResponse(request.0, std::ptr::null_mut())
+            .map_err(|e| format!("No response from VirusTotal: {}", e))?;
+
+        let mut status = 0u32;
+        let mut status_len = std::mem::size_of::<u32>() as u32;
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            PCWSTR::null(),
+            Some(&mut status as *mut u32 as *mut c_void),
+            &mut status_len,
+            std::ptr::null_mut(),
+        )
+        .map_err(|e| format!("Failed to read response status: {}", e))?;
+
+        let mut body = Vec::new();
+        loop {
+            let mut available = 0u32;
+            WinHttpQueryDataAvailable(request.0, &mut available)
+                .map_err(|e| format!("Failed to read response: {}", e))?;
+            if available == 0 {
+                break;
+            }
+            let start = body.len();
+            body.resize(start + available as usize, 0);
+            let mut read = 0u32;
+            WinHttpReadData(
+                request.0,
+                body[start..].as_mut_ptr() as *mut c_void,
+                available,
+                &mut read,
+            )
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+            body.truncate(start + read as usize);
+        }
+        Ok((status, body))
+    }
+}
+
+fn parse_report(sha256: String, body: &[u8]) -> Result<FileReputation, String> {
+    let json: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| format!("Unexpected VirusTotal reply: {}", e))?;
+    let attributes = &json["data"]["attributes"];
+    let stats = &attributes["last_analysis_stats"];
+    let count = |key: &str| stats[key].as_u64().unwrap_or(0) as u32;
+    Ok(FileReputation {
+        permalink: format!("https://www.virustotal.com/gui/file/{}", sha256),
+        sha256,
+        known: true,
+        malicious: count("malicious"),
+        suspicious: count("suspicious"),
+        harmless: count("harmless"),
+        undetected: count("undetected"),
+        last_analysis_date: attributes["last_analysis_date"].as_i64(),
+    })
+}
+
+fn lookup(path: &Path, api_key: &str) -> Result<FileReputation, String> {
+    if path.is_dir() {
+        return Err("Folders cannot be checked".to_string());
+    }
+    let sha256 = crate::hashing::sha256_file(path, None, &AtomicBool::new(false))?;
+    log::info!("[REPUTATION] Looking up {} ({})", path.display(), sha256);
+
+    let (status, body) = api_get(&format!("/api/v3/files/{}", sha256), api_key)?;
+    match status {
+        200 => parse_report(sha256, &body),
+        404 => Ok(FileReputation {
+            permalink: format!("https://www.virustotal.com/gui/file/{}", sha256),
+            sha256,
+            known: false,
+            malicious: 0,
+            suspicious: 0,
+            harmless: 0,
+            undetected: 0,
+            last_analysis_date: None,
+        }),
+        401 | 403 => Err("VirusTotal rejected the API key".to_string()),
+        429 => Err("VirusTotal quota exceeded, try again later".to_string()),
+        other => Err(format!("VirusTotal returned HTTP {}", other)),
+    }
+}
+
+/// Hashes `path` and asks VirusTotal how many engines flag it. Requires an API key
+/// stored with `set_virustotal_api_key`.
+#[tauri::command]
+pub async fn check_file_reputation(path: String) -> Result<FileReputation, String> {
+    let api_key = SettingsStore::global()
+        .get::<String>(API_KEY_SETTING)
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| "Add a VirusTotal API key in Settings first".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || lookup(Path::new(&path), &api_key))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Stores the user's VirusTotal API key; an empty key turns lookups off
+#[tauri::command]
+pub fn set_virustotal_api_key(api_key: String) -> Result<(), String> {
+    SettingsStore::global().set(API_KEY_SETTING, &api_key.trim())
+}
+
+#[tauri::command]
+pub fn has_virustotal_api_key() -> bool {
+    SettingsStore::global()
+        .get::<String>(API_KEY_SETTING)
+        .is_some_and(|k| !k.is_empty())
+}
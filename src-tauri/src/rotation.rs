@@ -0,0 +1,354 @@
+//! Rotation Module
+//!
+//! Rotates pictures in place. JPEGs are never re-encoded: the rotation is
+//! folded into the EXIF orientation tag, which Windows, browsers and image
+//! viewers apply when displaying the file, so no quality is lost no matter
+//! how often a picture is turned. Other formats are decoded, rotated and
+//! written back in the same format.
+
+use std::path::Path;
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const ORIENTATION_TAG: u16 = 0x0112;
+/// TIFF field type of the orientation value
+const SHORT: u16 = 3;
+
+/// (clockwise rotation in quarter turns, mirrored first) for EXIF orientations 1-8
+const ORIENTATIONS: [(u8, bool); 8] = [
+    (0, false),
+    (0, true),
+    (2, false),
+    (2, true),
+    (3, true),
+    (1, false),
+    (1, true),
+    (3, false),
+];
+
+/// The orientation showing a picture stored with `current` turned by `quarter_turns` more
+fn rotate_orientation(current: u16, quarter_turns: u8) -> u16 {
+    let (turns, mirrored) = ORIENTATIONS
+        .get((current as usize).wrapping_sub(1))
+        .copied()
+        .unwrap_or((0, false));
+    let target = ((turns + quarter_turns) % 4, mirrored);
+    ORIENTATIONS
+        .iter()
+        .position(|&o| o == target)
+        .map(|i| i as u16 + 1)
+        .unwrap_or(1)
+}
+
+fn read_u16(data: &[u8], at: usize, le: bool) -> Option<u16> {
+    let bytes: [u8; 2] = data.get(at..at + 2)?.try_into().ok()?;
+    Some(if le {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
+
+fn read_u32(data: &[u8], at: usize, le: bool) -> Option<u32> {
+    let bytes: [u8; 4] = data.get(at..at + 4)?.try_into().ok()?;
+    Some(if le {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+fn u16_bytes(value: u16, le: bool) -> [u8; 2] {
+    if le {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    }
+}
+
+fn u32_bytes(value: u32, le: bool) -> [u8; 4] {
+    if le {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    }
+}
+
+fn orientation_entry(value: u16, le: bool) -> [u8; 12] {
+    let mut entry = [0u8; 12];
+    entry[0..2].copy_from_slice(&u16_bytes(ORIENTATION_TAG, le));
+    entry[2..4].copy_from_slice(&u16_bytes(SHORT, le));
+    entry[4..8].copy_from_slice(&u32_bytes(1, le));
+    entry[8..10].copy_from_slice(&u16_bytes(value, le));
+    entry
+}
+
+struct Segment {
+    marker: u8,
+    /// Offset of the 0xFF of the marker
+    start: usize,
+    /// Offset just past the segment
+    end: usize,
+}
+
+/// Marker segments before the image data
+fn segments(data: &[u8]) -> Result<Vec<Segment>, String> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err("Not a JPEG file".to_string());
+    }
+    let mut segments = Vec::new();
+    let mut pos = 2;
+    loop {
+        if data.get(pos) != Some(&0xFF) {
+            return Err("Corrupt JPEG marker".to_string());
+        }
+        let marker = *data.get(pos + 1).ok_or("Truncated JPEG")?;
+        match marker {
+            // Fill byte
+            0xFF => pos += 1,
+            // Start of scan / end of image: the headers are over
+            0xDA | 0xD9 => return Ok(segments),
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            _ => {
+                let len = read_u16(data, pos + 2, false).ok_or("Truncated JPEG")? as usize;
+                let end = pos + 2 + len;
+                if len < 2 || end > data.len() {
+                    return Err("Corrupt JPEG segment".to_string());
+                }
+                segments.push(Segment {
+                    marker,
+                    start: pos,
+                    end,
+                });
+                pos = end;
+            }
+        }
+    }
+}
+
+/// APP1 segment holding only an orientation tag
+fn exif_segment(orientation: u16) -> Vec<u8> {
+    let mut tiff = b"II*\0".to_vec();
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    tiff.extend_from_slice(&1u16.to_le_bytes());
+    tiff.extend_from_slice(&orientation_entry(orientation, true));
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+
+    let len = (2 + EXIF_HEADER.len() + tiff.len()) as u16;
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&len.to_be_bytes());
+    segment.extend_from_slice(EXIF_HEADER);
+    segment.extend_from_slice(&tiff);
+    segment
+}
+
+/// Turns the EXIF orientation of a JPEG by `quarter_turns` clockwise, adding the tag
+/// (or the whole EXIF block) when the file has none. The image data is left untouched.
+fn rotate_jpeg(data: &mut Vec<u8>, quarter_turns: u8) -> Result<(), String> {
+    let segments = segments(data)?;
+    let exif = segments
+        .iter()
+        .find(|s| s.marker == 0xE1 && data[s.start + 4..s.end].starts_with(EXIF_HEADER));
+    let Some(exif) = exif else {
+        // APP1 goes after the JFIF header when there is one
+        let at = match segments.first() {
+            Some(s) if s.marker == 0xE0 => s.end,
+            _ => 2,
+        };
+        let segment = exif_segment(rotate_orientation(1, quarter_turns));
+        data.splice(at..at, segment);
+        return Ok(());
+    };
+
+    let tiff_start = exif.start + 4 + EXIF_HEADER.len();
+    let tiff = &mut data[tiff_start..exif.end];
+    let le = match tiff.get(0..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => return Err("Corrupt EXIF header".to_string()),
+    };
+    let ifd0 = read_u32(tiff, 4, le).ok_or("Corrupt EXIF header")? as usize;
+    let count = read_u16(tiff, ifd0, le).ok_or("Corrupt EXIF directory")? as usize;
+    let entries = ifd0 + 2;
+    if entries + count * 12 + 4 > tiff.len() {
+        return Err("Corrupt EXIF directory".to_string());
+    }
+
+    for i in 0..count {
+        let entry = entries + i * 12;
+        if read_u16(tiff, entry, le) == Some(ORIENTATION_TAG) {
+            let current = read_u16(tiff, entry + 8, le).unwrap_or(1);
+            let rotated = rotate_orientation(current, quarter_turns);
+            tiff[entry..entry + 12].copy_from_slice(&orientation_entry(rotated, le));
+            return Ok(());
+        }
+    }
+
+    // No orientation tag: write a copy of IFD0 with the tag added at the end of the EXIF
+    // block and point the header at it. Offsets are relative to the TIFF header, so
+    // everything the old entries refer to stays where it is.
+    let mut ifd = Vec::with_capacity(2 + (count + 1) * 12 + 4);
+    ifd.extend_from_slice(&u16_bytes(count as u16 + 1, le));
+    let mut inserted = false;
+    for i in 0..count {
+        let entry = &tiff[entries + i * 12..entries + (i + 1) * 12];
+        // Entries are sorted by tag
+        if !inserted && read_u16(entry, 0, le).unwrap_or(0) > ORIENTATION_TAG {
+            ifd.extend_from_slice(&orientation_entry(rotate_orientation(1, quarter_turns), le));
+            inserted = true;
+        }
+        ifd.extend_from_slice(entry);
+    }
+    if !inserted {
+        ifd.extend_from_slice(&orientation_entry(rotate_orientation(1, quarter_turns), le));
+    }
+    let next_ifd = entries + count * 12;
+    ifd.extend_from_slice(&tiff[next_ifd..next_ifd + 4]);
+
+    // IFDs start on a word boundary
+    let padding = tiff.len() % 2;
+    let new_offset = (tiff.len() + padding) as u32;
+    let new_len = exif.end - exif.start - 2 + padding + ifd.len();
+    if new_len > u16::MAX as usize {
+        return Err("EXIF block is too large to extend".to_string());
+    }
+    tiff[4..8].copy_from_slice(&u32_bytes(new_offset, le));
+
+    let mut appended = vec![0u8; padding];
+    appended.extend_from_slice(&ifd);
+    let end = exif.end;
+    let len_at = exif.start + 2;
+    data.splice(end..end, appended);
+    data[len_at..len_at + 2].copy_from_slice(&(new_len as u16).to_be_bytes());
+    Ok(())
+}
+
+/// Decodes, rotates and re-encodes formats without an orientation tag
+fn rotate_pixels(path: &Path, data: &[u8], quarter_turns: u8) -> Result<Vec<u8>, String> {
+    let format = image::ImageFormat::from_path(path)
+        .ok()
+        .filter(|f| matches!(f, image::ImageFormat::Png | image::ImageFormat::Bmp))
+        .ok_or_else(|| format!("Rotating {} files is not supported", path.display()))?;
+    let img = image::load_from_memory_with_format(data, format)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let rotated = match quarter_turns {
+        1 => img.rotate90(),
+        2 => img.rotate180(),
+        3 => img.rotate270(),
+        _ => img,
+    };
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    rotated
+        .write_to(&mut cursor, format)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+fn rotate_file(path: &Path, degrees: i32) -> Result<(), String> {
+    if degrees % 90 != 0 {
+        return Err(format!(
+            "Can only rotate by multiples of 90°, not {}°",
+            degrees
+        ));
+    }
+    let quarter_turns = (degrees.rem_euclid(360) / 90) as u8;
+    if quarter_turns == 0 {
+        return Ok(());
+    }
+
+    let mut data = std::fs::read(path).map_err(|e| format!("Failed to read image: {}", e))?;
+    let is_jpeg = path
+        .extension()
+        .map(|e| {
+            let ext = e.to_string_lossy().to_lowercase();
+            ext == "jpg" || ext == "jpeg" || ext == "jfif"
+        })
+        .unwrap_or(false);
+    if is_jpeg {
+        rotate_jpeg(&mut data, quarter_turns)?;
+    } else {
+        data = rotate_pixels(path, &data, quarter_turns)?;
+    }
+    // Written over the original rather than replaced, so its ACL and creation time stay
+    std::fs::write(path, &data).map_err(|e| format!("Failed to save image: {}", e))
+}
+
+/// Rotates a picture clockwise by `degrees` (a multiple of 90, negative for
+/// counter-clockwise) and returns its updated entry
+#[tauri::command]
+pub async fn rotate_image(
+    path: String,
+    degrees: i32,
+    thumbnails: tauri::State<'_, crate::ThumbnailCache>,
+) -> Result<crate::FileEntry, String> {
+    let expanded_path = crate::expand_env_vars(&path);
+    let target = expanded_path.clone();
+    tauri::async_runtime::spawn_blocking(move || rotate_file(Path::new(&target), degrees))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+
+    // Cache keys are "kind:path:size:modified"
+    if let Ok(mut cache) = thumbnails.0.lock() {
+        let needle = format!(":{}:", expanded_path);
+        let stale: Vec<String> = cache
+            .iter()
+            .filter(|(key, _)| key.contains(&needle))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            cache.pop(&key);
+        }
+    }
+
+    log::info!("[ROTATE] Rotated {} by {}°", expanded_path, degrees);
+    crate::get_file_entry(Path::new(&expanded_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orientation(data: &[u8]) -> Option<u16> {
+        let exif = segments(data)
+            .ok()?
+            .into_iter()
+            .find(|s| s.marker == 0xE1)?;
+        let tiff = &data[exif.start + 10..exif.end];
+        let le = &tiff[0..2] == b"II";
+        let ifd0 = read_u32(tiff, 4, le)? as usize;
+        let count = read_u16(tiff, ifd0, le)? as usize;
+        (0..count)
+            .map(|i| ifd0 + 2 + i * 12)
+            .find(|&e| read_u16(tiff, e, le) == Some(ORIENTATION_TAG))
+            .and_then(|e| read_u16(tiff, e + 8, le))
+    }
+
+    #[test]
+    fn test_rotate_jpeg() {
+        assert_eq!(rotate_orientation(1, 1), 6);
+        assert_eq!(rotate_orientation(6, 1), 3);
+        assert_eq!(rotate_orientation(8, 1), 1);
+        assert_eq!(rotate_orientation(2, 1), 7);
+
+        // SOI, a JFIF APP0, then the scan
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x4A, 0x46];
+        data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        rotate_jpeg(&mut data, 1).unwrap();
+        assert_eq!(orientation(&data), Some(6));
+        rotate_jpeg(&mut data, 3).unwrap();
+        assert_eq!(orientation(&data), Some(1));
+        assert!(data.ends_with(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]));
+
+        // Big-endian EXIF with only a Make tag
+        let mut tiff = b"MM\0*\0\0\0\x08\0\x01".to_vec();
+        tiff.extend_from_slice(&[0x01, 0x0F, 0, 2, 0, 0, 0, 4, b'C', b'a', b'm', 0]);
+        tiff.extend_from_slice(&[0, 0, 0, 0]);
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        data.extend_from_slice(&((2 + EXIF_HEADER.len() + tiff.len()) as u16).to_be_bytes());
+        data.extend_from_slice(EXIF_HEADER);
+        data.extend_from_slice(&tiff);
+        data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        rotate_jpeg(&mut data, 2).unwrap();
+        assert_eq!(orientation(&data), Some(3));
+        assert!(segments(&data).is_ok());
+    }
+}
@@ -0,0 +1,94 @@
+//! Archive Search Module
+//!
+//! Matches entry names inside ZIP and 7Z archives met during a live search.
+//! Only the archive's table of contents is read (see
+//! `extraction::list_archive_entries`); nothing is decompressed.
+
+use super::filters::ParsedQuery;
+use super::fuzzy;
+use super::live_search::SearchOptions;
+use super::pattern::{MatchSpan, Pattern};
+use serde::Serialize;
+use std::path::Path;
+use ts_rs::TS;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct ArchiveMatch {
+    /// The archive file on disk
+    pub archive_path: String,
+    /// Path of the match inside the archive, `/`-separated
+    pub entry_path: String,
+    pub name: String,
+    pub is_dir: bool,
+    #[ts(type = "number")]
+    pub size: u64,
+    /// Highlight ranges within `name`
+    pub spans: Vec<MatchSpan>,
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    crate::extraction::is_listable_archive(path)
+}
+
+/// Entries of `archive` whose name matches the search. `query` is only used for fuzzy
+/// matching; otherwise the compiled `pattern` decides.
+pub fn search_archive(
+    archive: &Path,
+    query: &str,
+    pattern: &Pattern,
+    options: &SearchOptions,
+    parsed: &ParsedQuery,
+) -> Vec<ArchiveMatch> {
+    let entries = match crate::extraction::list_archive_entries(archive) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::debug!("[SEARCH] Skipping archive {}: {}", archive.display(), e);
+            return Vec::new();
+        }
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let entry_path = entry.name.trim_end_matches('/');
+            let name = entry_path.rsplit('/').next().unwrap_or(entry_path);
+            if name.is_empty() {
+                return None;
+            }
+            if (entry.is_dir && !options.include_folders)
+                || (!entry.is_dir && !options.include_files)
+            {
+                return None;
+            }
+            if !parsed.filters.is_empty()
+                && !parsed.matches_attributes(
+                    Path::new(entry_path),
+                    entry.is_dir,
+                    Some(entry.size),
+                    None,
+                )
+            {
+                return None;
+            }
+
+            let spans = if options.fuzzy && !options.regex {
+                let candidate = entry_path.replace('/', "\\");
+                let (_, positions) = fuzzy::fuzzy_match(query, &candidate)?;
+                fuzzy::file_name_spans(&candidate, &positions)
+            } else if pattern.is_match(name) {
+                pattern.spans(name)
+            } else {
+                return None;
+            };
+            Some(ArchiveMatch {
+                archive_path: archive.to_string_lossy().to_string(),
+                entry_path: entry_path.to_string(),
+                name: name.to_string(),
+                is_dir: entry.is_dir,
+                size: entry.size,
+                spans,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,409 @@
+//! Content Search Module
+//!
+//! Grep-style matching inside files for the live search. Files are streamed
+//! line by line so memory stays bounded regardless of file size; binaries are
+//! skipped with a NUL-byte heuristic and text encoding is sniffed from the BOM
+//! (UTF-8, UTF-16 LE/BE) with a Windows-1252 fallback for legacy ANSI files.
+//! Lines are tested with the search's compiled `Pattern` (substring or regex),
+//! and each file gets a time budget so one pathological file cannot stall a search.
+
+use super::pattern::{self, MatchSpan, Pattern};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+/// Bytes inspected up front for the binary heuristic and BOM sniffing
+const SNIFF_LEN: usize = 8 * 1024;
+/// Longest line kept in memory; the remainder of a longer line is skipped
+const MAX_LINE_BYTES: usize = 64 * 1024;
+/// Characters of context kept on each side of the match in the excerpt
+const EXCERPT_CONTEXT: usize = 80;
+/// A single file is abandoned after this long (huge one-line files with a complex regex)
+const FILE_TIME_BUDGET: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct ContentMatch {
+    pub path: String,
+    /// 1-based line number
+    #[ts(type = "number")]
+    pub line_number: u64,
+    /// The matched line, trimmed to a window around the first hit
+    pub excerpt: String,
+    /// Where the hits fall inside `excerpt`
+    pub spans: Vec<MatchSpan>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Not valid UTF-8: decoded as Windows-1252
+    Ansi,
+}
+
+/// Returns None for files that look binary
+pub fn detect_encoding(head: &[u8]) -> Option<TextEncoding> {
+    if head.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return Some(TextEncoding::Utf8);
+    }
+    if head.starts_with(&[0xFF, 0xFE]) {
+        return Some(TextEncoding::Utf16Le);
+    }
+    if head.starts_with(&[0xFE, 0xFF]) {
+        return Some(TextEncoding::Utf16Be);
+    }
+    // Text files practically never contain NUL without a UTF-16 BOM
+    if head.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => Some(TextEncoding::Utf8),
+        // A multi-byte sequence cut off at the end of a full sniff window is still UTF-8
+        Err(e) if e.error_len().is_none() && head.len() >= SNIFF_LEN => Some(TextEncoding::Utf8),
+        Err(_) => Some(TextEncoding::Ansi),
+    }
+}
+
+/// Windows-1252 code points for 0x80..=0x9F (0 = undefined, kept as U+FFFD)
+const CP1252_HIGH: [u16; 32] = [
+    0x20AC, 0, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0, 0x017D, 0, 0, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014, 0x02DC,
+    0x2122, 0x0161, 0x203A, 0x0153, 0, 0x017E, 0x0178,
+];
+
+fn decode_ansi(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => {
+                char::from_u32(CP1252_HIGH[(b - 0x80) as usize] as u32).unwrap_or('\u{FFFD}')
+            }
+            _ => b as char,
+        })
+        .collect()
+}
+
+fn decode_line(bytes: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Ansi => decode_ansi(bytes),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Cuts `line` down to a window around the first of `hits` (byte ranges in `line`,
+/// char-boundary safe) and maps the hits that fall inside it onto the excerpt
+fn make_excerpt(line: &str, hits: &[(usize, usize)]) -> (String, Vec<MatchSpan>) {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let (mut from, mut to) = (0, line.len());
+    let (mut prefix, mut suffix) = ("", "");
+
+    let total_chars = line.chars().count();
+    if total_chars > EXCERPT_CONTEXT * 2 {
+        let first_hit = hits.first().map_or(0, |h| h.0.min(line.len()));
+        let hit_char = line[..first_hit].chars().count();
+        let start = hit_char.saturating_sub(EXCERPT_CONTEXT);
+        from = line
+            .char_indices()
+            .nth(start)
+            .map_or(line.len(), |(i, _)| i);
+        to = line[from..]
+            .char_indices()
+            .nth(EXCERPT_CONTEXT * 2)
+            .map_or(line.len(), |(i, _)| from + i);
+        if start > 0 {
+            prefix = "…";
+        }
+        if start + EXCERPT_CONTEXT * 2 < total_chars {
+            suffix = "…";
+        }
+    }
+
+    // Trim the window itself so the spans stay aligned with the trimmed text
+    let window = &line[from..to];
+    from += window.len() - window.trim_start().len();
+    to = from + line[from..to].trim_end().len();
+    let body = &line[from..to];
+
+    let clipped: Vec<(usize, usize)> = hits
+        .iter()
+        .map(|&(s, e)| (s.clamp(from, to) - from, e.clamp(from, to) - from))
+        .filter(|(s, e)| s < e)
+        .collect();
+    let offset = prefix.encode_utf16().count() as u32;
+    let spans = pattern::byte_ranges_to_spans(body, &clipped)
+        .into_iter()
+        .map(|span| MatchSpan {
+            start: span.start + offset,
+            end: span.end + offset,
+        })
+        .collect();
+    (format!("{}{}{}", prefix, body, suffix), spans)
+}
+
+/// Reads one `\n`-terminated line into `buf`, dropping anything past MAX_LINE_BYTES.
+/// Returns false at end of file.
+fn read_bounded_line<R: BufRead>(reader: &mut R, buf: &mut Vec<u8>) -> std::io::Result<bool> {
+    buf.clear();
+    let mut read_any = false;
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            return Ok(read_any);
+        }
+        read_any = true;
+        let (chunk, done) = match available.iter().position(|&b| b == b'\n') {
+            Some(i) => (&available[..=i], true),
+            None => (available, false),
+        };
+        let room = MAX_LINE_BYTES.saturating_sub(buf.len());
+        buf.extend_from_slice(&chunk[..chunk.len().min(room)]);
+        let consumed = chunk.len();
+        reader.consume(consumed);
+        if done {
+            return Ok(true);
+        }
+    }
+}
+
+/// UTF-16 has no cheap byte-level line splitting, so decode in fixed-size
+/// chunks and carry the unfinished line over to the next chunk.
+fn search_utf16(
+    reader: &mut impl Read,
+    big_endian: bool,
+    pattern: &Pattern,
+    max_hits: usize,
+    should_stop: &dyn Fn() -> bool,
+    mut on_line: impl FnMut(u64, &str, &[(usize, usize)]),
+) -> std::io::Result<usize> {
+    let mut chunk = vec![0u8; 64 * 1024];
+    let mut pending = String::new();
+    let mut carry: Vec<u8> = Vec::new();
+    let mut line_number = 0u64;
+    let mut hits = 0usize;
+
+    let mut check_line = |line: &str, line_number: u64, hits: &mut usize| {
+        let line_hits = pattern.find_all(line);
+        if !line_hits.is_empty() {
+            on_line(line_number, line, &line_hits);
+            *hits += 1;
+        }
+    };
+
+    loop {
+        if should_stop() || hits >= max_hits {
+            return Ok(hits);
+        }
+        let n = reader.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+
+        let mut bytes = std::mem::take(&mut carry);
+        bytes.extend_from_slice(&chunk[..n]);
+        // Hold back an odd trailing byte and a dangling high surrogate for the next chunk
+        let mut keep = bytes.len() % 2;
+        if bytes.len() >= keep + 2 {
+            let end = bytes.len() - keep;
+            let pair = [bytes[end - 2], bytes[end - 1]];
+            let unit = if big_endian {
+                u16::from_be_bytes(pair)
+            } else {
+                u16::from_le_bytes(pair)
+            };
+            if (0xD800..=0xDBFF).contains(&unit) {
+                keep += 2;
+            }
+        }
+        carry = bytes.split_off(bytes.len() - keep);
+        let units = bytes.chunks_exact(2).map(|p| {
+            if big_endian {
+                u16::from_be_bytes([p[0], p[1]])
+            } else {
+                u16::from_le_bytes([p[0], p[1]])
+            }
+        });
+        pending.extend(char::decode_utf16(units).map(|r| r.unwrap_or('\u{FFFD}')));
+
+        while let Some(nl) = pending.find('\n') {
+            line_number += 1;
+            let line: String = pending.drain(..=nl).collect();
+            check_line(&line, line_number, &mut hits);
+            if hits >= max_hits {
+                return Ok(hits);
+            }
+        }
+        if pending.len() > MAX_LINE_BYTES {
+            let mut cut = MAX_LINE_BYTES;
+            while !pending.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            pending.truncate(cut);
+        }
+    }
+
+    if !pending.is_empty() {
+        line_number += 1;
+        check_line(&pending, line_number, &mut hits);
+    }
+    Ok(hits)
+}
+
+/// Searches `path` for `pattern`.
+/// Returns an empty list for binaries, unreadable files and files without hits.
+pub fn search_file(
+    path: &Path,
+    pattern: &Pattern,
+    max_hits: usize,
+    cancelled: &AtomicBool,
+) -> Vec<ContentMatch> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut reader = BufReader::with_capacity(SNIFF_LEN, file);
+    let encoding = match reader.fill_buf() {
+        Ok(head) => detect_encoding(&head[..head.len().min(SNIFF_LEN)]),
+        Err(_) => None,
+    };
+    let Some(encoding) = encoding else {
+        return Vec::new();
+    };
+
+    let deadline = Instant::now() + FILE_TIME_BUDGET;
+    let should_stop = || {
+        if Instant::now() >= deadline {
+            log::warn!(
+                "[SEARCH] Gave up on {} after {:?}",
+                path.display(),
+                FILE_TIME_BUDGET
+            );
+            return true;
+        }
+        cancelled.load(Ordering::Relaxed)
+    };
+
+    let path_string = path.to_string_lossy().to_string();
+    let mut matches = Vec::new();
+    let mut push = |line_number: u64, line: &str, hits: &[(usize, usize)]| {
+        let (excerpt, spans) = make_excerpt(line, hits);
+        matches.push(ContentMatch {
+            path: path_string.clone(),
+            line_number,
+            excerpt,
+            spans,
+        });
+    };
+
+    match encoding {
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            reader.consume(2); // BOM
+            let _ = search_utf16(
+                &mut reader,
+                encoding == TextEncoding::Utf16Be,
+                pattern,
+                max_hits,
+                &should_stop,
+                &mut push,
+            );
+        }
+        TextEncoding::Utf8 | TextEncoding::Ansi => {
+            let mut buf = Vec::new();
+            let mut line_number = 0u64;
+            let mut hits = 0usize;
+            while let Ok(true) = read_bounded_line(&mut reader, &mut buf) {
+                line_number += 1;
+                if line_number.is_multiple_of(1024) && should_stop() {
+                    break;
+                }
+                let bytes = if line_number == 1 {
+                    buf.strip_prefix(&[0xEF, 0xBB, 0xBF][..]).unwrap_or(&buf)
+                } else {
+                    &buf[..]
+                };
+                // A NUL past the sniff window means we mis-detected a binary file
+                if bytes.contains(&0) {
+                    return Vec::new();
+                }
+                let line = decode_line(bytes, encoding);
+                let line_hits = pattern.find_all(&line);
+                if !line_hits.is_empty() {
+                    push(line_number, &line, &line_hits);
+                    hits += 1;
+                    if hits >= max_hits {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(detect_encoding(b"hello world"), Some(TextEncoding::Utf8));
+        assert_eq!(
+            detect_encoding(&[0xEF, 0xBB, 0xBF, b'a']),
+            Some(TextEncoding::Utf8)
+        );
+        assert_eq!(
+            detect_encoding(&[0xFF, 0xFE, b'a', 0]),
+            Some(TextEncoding::Utf16Le)
+        );
+        assert_eq!(detect_encoding(b"caf\xe9"), Some(TextEncoding::Ansi));
+        assert_eq!(detect_encoding(b"MZ\x90\x00\x03"), None);
+    }
+
+    #[test]
+    fn test_decode_ansi() {
+        assert_eq!(decode_ansi(b"caf\xe9 \x80"), "café €");
+    }
+
+    #[test]
+    fn test_make_excerpt_trims_long_lines() {
+        let line = format!("{}needle{}", "a".repeat(500), "b".repeat(500));
+        let (excerpt, spans) = make_excerpt(&line, &[(500, 506)]);
+        assert!(excerpt.contains("needle"));
+        assert!(excerpt.starts_with('…') && excerpt.ends_with('…'));
+        assert!(excerpt.chars().count() <= EXCERPT_CONTEXT * 2 + 2);
+        let span = spans[0];
+        let units: Vec<u16> = excerpt.encode_utf16().collect();
+        assert_eq!(
+            String::from_utf16(&units[span.start as usize..span.end as usize]).unwrap(),
+            "needle"
+        );
+
+        let (excerpt, spans) = make_excerpt("   short needle line\r\n", &[(9, 15)]);
+        assert_eq!(excerpt, "short needle line");
+        assert_eq!(spans, vec![MatchSpan { start: 6, end: 12 }]);
+    }
+
+    #[test]
+    fn test_utf16_search() {
+        let text = "first line\r\nsecond NEEDLE line\r\n";
+        let bytes: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        let pattern = Pattern::new("needle", false, false).unwrap();
+        let mut found = Vec::new();
+        let hits = search_utf16(
+            &mut &bytes[..],
+            false,
+            &pattern,
+            10,
+            &|| false,
+            |n, line, _| found.push((n, line.trim().to_string())),
+        )
+        .unwrap();
+        assert_eq!(hits, 1);
+        assert_eq!(found, vec![(2, "second NEEDLE line".to_string())]);
+    }
+}
@@ -0,0 +1,114 @@
+//! Duplicates Module
+//!
+//! Groups the files a live search collected by identical content. Files are
+//! first bucketed by size, then by a hash of their first 64 KB, and only the
+//! survivors are hashed in full (SHA-256), so unique files are rarely read
+//! beyond their first chunk.
+
+use crate::FileEntry;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use ts_rs::TS;
+
+/// Prefix hashed to split same-size buckets cheaply
+const QUICK_HASH_LEN: u64 = 64 * 1024;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct DuplicateGroup {
+    #[ts(type = "number")]
+    pub size: u64,
+    /// SHA-256 shared by every file in the group
+    pub hash: String,
+    pub entries: Vec<FileEntry>,
+}
+
+/// Splits `paths` into buckets by `key`, dropping buckets that can no longer hold a duplicate
+fn regroup<K, F>(paths: Vec<PathBuf>, cancelled: &AtomicBool, key: F) -> Vec<(K, Vec<PathBuf>)>
+where
+    K: std::hash::Hash + Eq + Send,
+    F: Fn(&Path) -> Option<K> + Sync,
+{
+    let keyed: Vec<(K, PathBuf)> = paths
+        .into_par_iter()
+        .filter_map(|path| {
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            key(&path).map(|k| (k, path))
+        })
+        .collect();
+
+    let mut buckets: HashMap<K, Vec<PathBuf>> = HashMap::new();
+    for (k, path) in keyed {
+        buckets.entry(k).or_default().push(path);
+    }
+    buckets.into_iter().filter(|(_, v)| v.len() > 1).collect()
+}
+
+/// Finds groups of identical files among `candidates` (path, size). With `target`, only
+/// the group containing that file is reported. Empty files are ignored.
+pub fn find_groups(
+    candidates: Vec<(PathBuf, u64)>,
+    target: Option<&Path>,
+    cancelled: &AtomicBool,
+    mut on_group: impl FnMut(DuplicateGroup),
+) -> usize {
+    let target_size = target.and_then(|t| t.metadata().ok()).map(|m| m.len());
+
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for (path, size) in candidates {
+        if size == 0 || target_size.is_some_and(|t| t != size) {
+            continue;
+        }
+        by_size.entry(size).or_default().push(path);
+    }
+    if let (Some(target), Some(size)) = (target, target_size) {
+        // The file the user asked about may sit outside the searched tree
+        let bucket = by_size.entry(size).or_default();
+        if !bucket.iter().any(|p| p == target) {
+            bucket.push(target.to_path_buf());
+        }
+    }
+
+    let mut found = 0;
+    for (size, paths) in by_size {
+        if paths.len() < 2 || cancelled.load(Ordering::Relaxed) {
+            continue;
+        }
+        let quick = if size > QUICK_HASH_LEN {
+            regroup(paths, cancelled, |p| {
+                crate::hashing::sha256_file(p, Some(QUICK_HASH_LEN), cancelled).ok()
+            })
+        } else {
+            // Small files: the prefix is the whole file, go straight to the full hash
+            vec![(String::new(), paths)]
+        };
+
+        for (_, paths) in quick {
+            for (hash, paths) in regroup(paths, cancelled, |p| {
+                crate::hashing::sha256_file(p, None, cancelled).ok()
+            }) {
+                if target.is_some_and(|t| !paths.iter().any(|p| p == t)) {
+                    continue;
+                }
+                let entries: Vec<FileEntry> = paths
+                    .iter()
+                    .filter_map(|p| crate::get_file_entry(p).ok())
+                    .collect();
+                if entries.len() > 1 {
+                    found += 1;
+                    on_group(DuplicateGroup {
+                        size,
+                        hash,
+                        entries,
+                    });
+                }
+            }
+        }
+    }
+    found
+}
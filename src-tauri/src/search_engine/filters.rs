@@ -0,0 +1,400 @@
+//! Search Filters Module
+//!
+//! Parses Explorer-style filter tokens out of a search query so the live search
+//! can apply them during the walk:
+//!
+//! - `size:>10MB`, `size:<=500KB`, `size:1MB..5MB`, `size:large` (Explorer buckets)
+//! - `modified:lastweek`, `date:today`, `modified:>2024-01-31`, `modified:2024-01-01..2024-02-01`
+//! - `ext:pdf`, `ext:jpg,png`, `type:.docx`
+//! - `kind:image|video|audio|document|archive|executable|folder|text`
+//!
+//! Anything that isn't a recognized filter stays part of the text query.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+    Executable,
+    Text,
+    Folder,
+}
+
+impl Kind {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "image" | "images" | "picture" | "pictures" | "photo" => Some(Kind::Image),
+            "video" | "videos" | "movie" => Some(Kind::Video),
+            "audio" | "music" | "sound" => Some(Kind::Audio),
+            "document" | "documents" | "doc" | "docs" => Some(Kind::Document),
+            "archive" | "archives" | "compressed" | "zip" => Some(Kind::Archive),
+            "executable" | "program" | "programs" | "app" => Some(Kind::Executable),
+            "text" | "code" => Some(Kind::Text),
+            "folder" | "folders" | "dir" | "directory" => Some(Kind::Folder),
+            _ => None,
+        }
+    }
+
+    /// Extensions that make up the kind; empty for folders
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Kind::Image => &[
+                "jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff", "ico", "svg", "heic",
+                "avif", "raw", "cr2", "nef", "arw", "dng",
+            ],
+            Kind::Video => &[
+                "mp4", "mkv", "avi", "mov", "wmv", "webm", "flv", "m4v", "mpg", "mpeg", "ts", "3gp",
+            ],
+            Kind::Audio => &[
+                "mp3", "wav", "flac", "aac", "ogg", "m4a", "wma", "opus", "aiff",
+            ],
+            Kind::Document => &[
+                "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "odp", "rtf",
+                "txt", "md", "epub",
+            ],
+            Kind::Archive => &[
+                "zip", "7z", "rar", "tar", "gz", "tgz", "bz2", "xz", "zst", "cab", "iso",
+            ],
+            Kind::Executable => &[
+                "exe", "msi", "bat", "cmd", "ps1", "com", "scr", "appx", "msix",
+            ],
+            Kind::Text => &[
+                "txt", "md", "log", "csv", "json", "xml", "yaml", "yml", "toml", "ini", "cfg",
+                "rs", "ts", "tsx", "js", "jsx", "py", "c", "cpp", "h", "cs", "java", "go", "html",
+                "css", "sql", "sh", "ps1",
+            ],
+            Kind::Folder => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Inclusive byte range
+    Size {
+        min: u64,
+        max: u64,
+    },
+    /// Half-open range of Unix seconds [from, to)
+    Modified {
+        from: i64,
+        to: i64,
+    },
+    /// Lowercase extensions without the dot
+    Extension(Vec<String>),
+    Kind(Kind),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedQuery {
+    /// The query with all filter tokens removed
+    pub text: String,
+    pub filters: Vec<Filter>,
+}
+
+impl ParsedQuery {
+    /// Size and date filters need a stat call; extension and kind filters don't
+    pub fn needs_metadata(&self) -> bool {
+        self.filters
+            .iter()
+            .any(|f| matches!(f, Filter::Size { .. } | Filter::Modified { .. }))
+    }
+
+    /// Applies every filter; `metadata` may be None when `needs_metadata()` is false
+    pub fn matches(&self, path: &Path, is_dir: bool, metadata: Option<&std::fs::Metadata>) -> bool {
+        let size = metadata.map(|m| m.len());
+        let modified = metadata
+            .and_then(|m| m.modified().ok())
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp());
+        self.matches_attributes(path, is_dir, size, modified)
+    }
+
+    /// Same as `matches` for entries that have no file system metadata (e.g. inside an archive);
+    /// `modified` is in Unix seconds
+    pub fn matches_attributes(
+        &self,
+        path: &Path,
+        is_dir: bool,
+        size: Option<u64>,
+        modified: Option<i64>,
+    ) -> bool {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        self.filters.iter().all(|filter| match filter {
+            Filter::Size { min, max } => !is_dir && size.is_some_and(|s| s >= *min && s <= *max),
+            Filter::Modified { from, to } => modified.is_some_and(|ts| ts >= *from && ts < *to),
+            Filter::Extension(exts) => !is_dir && exts.contains(&ext),
+            Filter::Kind(Kind::Folder) => is_dir,
+            Filter::Kind(kind) => !is_dir && kind.extensions().contains(&ext.as_str()),
+        })
+    }
+}
+
+/// Parses "10MB", "1.5 GB", "512" (bytes) into a byte count
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim() {
+        "" | "b" => 1,
+        "k" | "kb" => 1024,
+        "m" | "mb" => 1024 * 1024,
+        "g" | "gb" => 1024 * 1024 * 1024,
+        "t" | "tb" => 1024u64.pow(4),
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+fn parse_size_filter(value: &str) -> Option<Filter> {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+
+    // Explorer's named buckets
+    let bucket = match value {
+        "empty" => Some((0, 0)),
+        "tiny" => Some((0, 16 * KB)),
+        "small" => Some((16 * KB, MB)),
+        "medium" => Some((MB, 128 * MB)),
+        "large" => Some((128 * MB, GB)),
+        "huge" => Some((GB, 4 * GB)),
+        "gigantic" => Some((4 * GB, u64::MAX)),
+        _ => None,
+    };
+    if let Some((min, max)) = bucket {
+        return Some(Filter::Size { min, max });
+    }
+
+    if let Some((lo, hi)) = value.split_once("..") {
+        return Some(Filter::Size {
+            min: parse_size(lo)?,
+            max: parse_size(hi)?,
+        });
+    }
+
+    let (min, max) = if let Some(v) = value.strip_prefix(">=") {
+        (parse_size(v)?, u64::MAX)
+    } else if let Some(v) = value.strip_prefix("<=") {
+        (0, parse_size(v)?)
+    } else if let Some(v) = value.strip_prefix('>') {
+        (parse_size(v)?.saturating_add(1), u64::MAX)
+    } else if let Some(v) = value.strip_prefix('<') {
+        (0, parse_size(v)?.saturating_sub(1))
+    } else {
+        let exact = parse_size(value.strip_prefix('=').unwrap_or(value))?;
+        (exact, exact)
+    };
+    Some(Filter::Size { min, max })
+}
+
+fn local_midnight(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest()
+        .map(|d| d.timestamp())
+        .unwrap_or(0)
+}
+
+fn first_of_month(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_default()
+}
+
+/// Date keywords and ranges relative to `today`
+fn parse_date_filter(value: &str, today: NaiveDate) -> Option<Filter> {
+    let range = |from: NaiveDate, to: NaiveDate| {
+        Some(Filter::Modified {
+            from: local_midnight(from),
+            to: local_midnight(to),
+        })
+    };
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month_start = first_of_month(today.year(), today.month());
+    let prev_month_start = if today.month() == 1 {
+        first_of_month(today.year() - 1, 12)
+    } else {
+        first_of_month(today.year(), today.month() - 1)
+    };
+    let year_start = first_of_month(today.year(), 1);
+    let tomorrow = today + Duration::days(1);
+
+    match value {
+        "today" => return range(today, tomorrow),
+        "yesterday" => return range(today - Duration::days(1), today),
+        "thisweek" => return range(week_start, tomorrow),
+        "lastweek" => return range(week_start - Duration::days(7), week_start),
+        "thismonth" => return range(month_start, tomorrow),
+        "lastmonth" => return range(prev_month_start, month_start),
+        "thisyear" => return range(year_start, tomorrow),
+        "lastyear" => return range(first_of_month(today.year() - 1, 1), year_start),
+        _ => {}
+    }
+
+    let parse_day = |s: &str| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok();
+    if let Some((lo, hi)) = value.split_once("..") {
+        // Inclusive of the end day
+        return range(parse_day(lo)?, parse_day(hi)? + Duration::days(1));
+    }
+    if let Some(v) = value.strip_prefix(">=") {
+        return Some(Filter::Modified {
+            from: local_midnight(parse_day(v)?),
+            to: i64::MAX,
+        });
+    }
+    if let Some(v) = value.strip_prefix('>') {
+        return Some(Filter::Modified {
+            from: local_midnight(parse_day(v)? + Duration::days(1)),
+            to: i64::MAX,
+        });
+    }
+    if let Some(v) = value.strip_prefix("<=") {
+        return Some(Filter::Modified {
+            from: i64::MIN,
+            to: local_midnight(parse_day(v)? + Duration::days(1)),
+        });
+    }
+    if let Some(v) = value.strip_prefix('<') {
+        return Some(Filter::Modified {
+            from: i64::MIN,
+            to: local_midnight(parse_day(v)?),
+        });
+    }
+    let day = parse_day(value.strip_prefix('=').unwrap_or(value))?;
+    range(day, day + Duration::days(1))
+}
+
+fn parse_token(key: &str, value: &str, today: NaiveDate) -> Option<Filter> {
+    match key {
+        "size" => parse_size_filter(value),
+        "modified" | "datemodified" | "date" => parse_date_filter(value, today),
+        "ext" | "type" => {
+            let exts: Vec<String> = value
+                .split(',')
+                .map(|e| {
+                    e.trim()
+                        .trim_start_matches("*.")
+                        .trim_start_matches('.')
+                        .to_string()
+                })
+                .filter(|e| !e.is_empty())
+                .collect();
+            if exts.is_empty() {
+                None
+            } else {
+                Some(Filter::Extension(exts))
+            }
+        }
+        "kind" => Kind::parse(value).map(Filter::Kind),
+        _ => None,
+    }
+}
+
+pub fn parse_query(query: &str) -> ParsedQuery {
+    parse_query_at(query, Local::now().date_naive())
+}
+
+fn parse_query_at(query: &str, today: NaiveDate) -> ParsedQuery {
+    let mut text = Vec::new();
+    let mut filters = Vec::new();
+
+    for token in query.split_whitespace() {
+        let parsed = token.split_once(':').and_then(|(key, value)| {
+            parse_token(&key.to_lowercase(), &value.to_lowercase(), today)
+        });
+        match parsed {
+            Some(filter) => filters.push(filter),
+            None => text.push(token),
+        }
+    }
+
+    ParsedQuery {
+        text: text.join(" "),
+        filters,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_filters_are_removed_from_text() {
+        let parsed = parse_query_at("invoice size:>10MB ext:pdf 2024", day(2024, 5, 15));
+        assert_eq!(parsed.text, "invoice 2024");
+        assert_eq!(
+            parsed.filters,
+            vec![
+                Filter::Size {
+                    min: 10 * 1024 * 1024 + 1,
+                    max: u64::MAX
+                },
+                Filter::Extension(vec!["pdf".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_tokens_stay_text() {
+        let parsed = parse_query_at("C:\\temp foo:bar kind:nothing", day(2024, 5, 15));
+        assert_eq!(parsed.text, "C:\\temp foo:bar kind:nothing");
+        assert!(parsed.filters.is_empty());
+    }
+
+    #[test]
+    fn test_size_syntax() {
+        assert_eq!(parse_size("1.5KB"), Some(1536));
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("10XB"), None);
+        assert_eq!(
+            parse_size_filter("1mb..2mb"),
+            Some(Filter::Size {
+                min: 1024 * 1024,
+                max: 2 * 1024 * 1024
+            })
+        );
+        assert_eq!(
+            parse_size_filter("empty"),
+            Some(Filter::Size { min: 0, max: 0 })
+        );
+    }
+
+    #[test]
+    fn test_last_week_is_previous_monday_to_monday() {
+        // 2024-05-15 is a Wednesday
+        let parsed = parse_query_at("modified:lastweek", day(2024, 5, 15));
+        assert_eq!(
+            parsed.filters,
+            vec![Filter::Modified {
+                from: local_midnight(day(2024, 5, 6)),
+                to: local_midnight(day(2024, 5, 13)),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_kind_and_extension_matching() {
+        let parsed = parse_query_at("kind:image", day(2024, 5, 15));
+        assert!(parsed.matches(Path::new("C:\\a\\photo.JPG"), false, None));
+        assert!(!parsed.matches(Path::new("C:\\a\\notes.txt"), false, None));
+        assert!(!parsed.needs_metadata());
+
+        let folders = parse_query_at("kind:folder", day(2024, 5, 15));
+        assert!(folders.matches(Path::new("C:\\a"), true, None));
+        assert!(!folders.matches(Path::new("C:\\a.txt"), false, None));
+    }
+}
@@ -0,0 +1,288 @@
+//! Folder Index Module
+//!
+//! Keeps ART name indices (see `index_manager`) of the user's most visited
+//! folder trees warm in the background and patches them from a directory
+//! watcher, so the in-folder search box can answer from memory as the user
+//! types instead of walking the tree on every keystroke.
+
+use super::index_manager::IndexManager;
+use crate::watcher::{ChangeKind, DirectoryWatcher, FsChange};
+use crate::FileEntry;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::OnceLock;
+use std::time::Duration;
+use ts_rs::TS;
+
+/// How many of the top frecency folders get a live index
+const MAX_INDEXED_TREES: usize = 5;
+/// Let the app finish starting up before walking anything
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+/// How often the set of indexed trees is re-evaluated against frecency
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Persisted indices older than this are rebuilt instead of trusted
+const MAX_INDEX_AGE: Duration = Duration::from_secs(3 * 24 * 3600);
+/// Changes are collected until the tree has been quiet this long
+const DEBOUNCE: Duration = Duration::from_millis(500);
+const DEFAULT_QUICK_SEARCH_LIMIT: usize = 200;
+
+enum IndexEvent {
+    /// Children of this folder changed
+    Folder(PathBuf),
+    /// The watcher lost events; the whole tree must be walked again
+    Tree(String),
+}
+
+struct FolderIndexService {
+    /// Watched tree root -> watcher
+    watchers: Mutex<HashMap<String, DirectoryWatcher>>,
+    events: Sender<IndexEvent>,
+}
+
+static SERVICE: OnceLock<FolderIndexService> = OnceLock::new();
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct QuickSearchResult {
+    /// False when no index covers the folder yet; the caller should fall back to `start_search`
+    pub indexed: bool,
+    pub entries: Vec<FileEntry>,
+}
+
+/// Starts the background indexer. Called once from app setup.
+pub fn start() {
+    let (events, receiver) = mpsc::channel();
+    if SERVICE
+        .set(FolderIndexService {
+            watchers: Mutex::new(HashMap::new()),
+            events,
+        })
+        .is_err()
+    {
+        return;
+    }
+
+    std::thread::spawn(move || apply_events(receiver));
+    std::thread::spawn(|| {
+        std::thread::sleep(STARTUP_DELAY);
+        loop {
+            refresh_trees();
+            std::thread::sleep(REFRESH_INTERVAL);
+        }
+    });
+}
+
+/// Resolves the on-disk casing of a frecency key (they are stored lowercased), which is
+/// what the index and the watcher paths need to line up with the paths the UI sends.
+fn real_path(key: &str) -> Option<String> {
+    let canonical = std::fs::canonicalize(key).ok()?;
+    if !canonical.is_dir() {
+        return None;
+    }
+    let s = canonical.to_string_lossy().to_string();
+    // canonicalize returns a verbatim path; only drive-letter paths can drop the prefix safely
+    match s.strip_prefix(r"\\?\") {
+        Some(rest) if !rest.starts_with("UNC\\") => Some(rest.to_string()),
+        Some(_) => None,
+        None => Some(s),
+    }
+}
+
+/// Top frecency folders that still exist, excluding drive roots and folders nested in a
+/// higher-ranked pick (the parent's index already covers them).
+fn pick_trees() -> Vec<String> {
+    let mut ranked: Vec<(String, f64)> = crate::frecency::FrecencyStore::global()
+        .snapshot()
+        .into_iter()
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut picked: Vec<String> = Vec::new();
+    for (key, _) in ranked {
+        if picked.len() >= MAX_INDEXED_TREES {
+            break;
+        }
+        // A whole drive is the deep search's job, not an as-you-type index
+        if Path::new(&key).parent().is_none() {
+            continue;
+        }
+        let Some(path) = real_path(&key) else {
+            continue;
+        };
+        let nested = picked
+            .iter()
+            .any(|p| Path::new(&path).starts_with(p) || Path::new(p).starts_with(&path));
+        if !nested {
+            picked.push(path);
+        }
+    }
+    picked
+}
+
+fn refresh_trees() {
+    let Some(service) = SERVICE.get() else {
+        return;
+    };
+    let trees = pick_trees();
+    let manager = IndexManager::global();
+
+    // Stop watching trees that fell out of the top list
+    service
+        .watchers
+        .lock()
+        .retain(|root, _| trees.contains(root));
+
+    for root in trees {
+        let fresh = manager.load_covering_index(&root)
+            && manager
+                .index_age(&root)
+                .is_some_and(|age| age < MAX_INDEX_AGE);
+        if !fresh {
+            log::info!("[FOLDER-INDEX] Indexing {}", root);
+            match manager.build_index(&root, &|| false) {
+                Ok(count) => log::info!("[FOLDER-INDEX] Indexed {} ({} items)", root, count),
+                Err(e) => {
+                    log::warn!("[FOLDER-INDEX] Could not index {}: {}", root, e);
+                    continue;
+                }
+            }
+        }
+
+        if service.watchers.lock().contains_key(&root) {
+            continue;
+        }
+        let events = service.events.clone();
+        let tree = root.clone();
+        match DirectoryWatcher::start(Path::new(&root), true, move |changes| {
+            for event in folders_to_update(&tree, changes) {
+                let _ = events.send(event);
+            }
+        }) {
+            Ok(watcher) => {
+                service.watchers.lock().insert(root, watcher);
+            }
+            Err(e) => log::warn!("[FOLDER-INDEX] {}", e),
+        }
+    }
+}
+
+/// Maps raw watcher changes to the folders whose child lists changed
+fn folders_to_update(tree: &str, changes: Vec<FsChange>) -> Vec<IndexEvent> {
+    let mut events = Vec::new();
+    for change in changes {
+        match change.kind {
+            // Content edits do not change names
+            ChangeKind::Modified => {}
+            ChangeKind::Rescan => events.push(IndexEvent::Tree(tree.to_string())),
+            ChangeKind::Created | ChangeKind::Removed | ChangeKind::Renamed => {
+                if let Some(parent) = change.path.parent() {
+                    events.push(IndexEvent::Folder(parent.to_path_buf()));
+                }
+                if let Some(parent) = change.old_path.as_ref().and_then(|p| p.parent()) {
+                    events.push(IndexEvent::Folder(parent.to_path_buf()));
+                }
+                // A folder moved or copied in arrives with its whole subtree
+                if change.kind != ChangeKind::Removed && change.path.is_dir() {
+                    events.push(IndexEvent::Folder(change.path));
+                }
+            }
+        }
+    }
+    events
+}
+
+fn apply_events(receiver: Receiver<IndexEvent>) {
+    let manager = IndexManager::global();
+    while let Ok(first) = receiver.recv() {
+        let mut folders = HashSet::new();
+        let mut trees = HashSet::new();
+        let mut pending = Some(first);
+        while let Some(event) = pending {
+            match event {
+                IndexEvent::Folder(path) => {
+                    folders.insert(path);
+                }
+                IndexEvent::Tree(root) => {
+                    trees.insert(root);
+                }
+            }
+            pending = receiver.recv_timeout(DEBOUNCE).ok();
+        }
+
+        for root in &trees {
+            log::info!("[FOLDER-INDEX] Watcher overflowed, re-indexing {}", root);
+            if let Err(e) = manager.build_index(root, &|| false) {
+                log::warn!("[FOLDER-INDEX] Could not re-index {}: {}", root, e);
+            }
+        }
+
+        // Expand new folders into their subtrees so their contents become searchable too
+        let mut stack: Vec<PathBuf> = folders
+            .into_iter()
+            .filter(|f| !trees.iter().any(|t| f.starts_with(t)))
+            .collect();
+        let mut seen = HashSet::new();
+        while let Some(folder) = stack.pop() {
+            if !seen.insert(folder.clone()) {
+                continue;
+            }
+            let Ok(read_dir) = std::fs::read_dir(&folder) else {
+                continue;
+            };
+            let entries: Vec<FileEntry> = read_dir
+                .filter_map(Result::ok)
+                .filter_map(|e| crate::get_file_entry(&e.path()).ok())
+                .collect();
+            let is_new = |e: &&FileEntry| e.is_dir && !manager.contains_path(&e.path);
+            stack.extend(
+                entries
+                    .iter()
+                    .filter(is_new)
+                    .map(|e| PathBuf::from(&e.path)),
+            );
+            manager.update_folder_entries(&folder.to_string_lossy(), &entries);
+        }
+    }
+}
+
+/// Searches the background index for names under `root`. Returns `indexed: false` when no
+/// index covers `root`, so the UI knows to fall back to a live walk.
+#[tauri::command]
+pub async fn quick_search(
+    root: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<QuickSearchResult, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = IndexManager::global();
+        if !manager.load_covering_index(&root) {
+            return QuickSearchResult {
+                indexed: false,
+                entries: Vec::new(),
+            };
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_QUICK_SEARCH_LIMIT);
+        let entries = manager
+            .search(&root, &query, &|| false)
+            .into_iter()
+            // The index can briefly trail the disk; drop entries that are already gone
+            .filter_map(|(path, _)| crate::get_file_entry(Path::new(&path)).ok())
+            .take(limit)
+            .collect();
+        QuickSearchResult {
+            indexed: true,
+            entries,
+        }
+    })
+    .await
+    .map_err(|e| format!("Quick search failed: {}", e))
+}
@@ -0,0 +1,169 @@
+//! Fuzzy Matching Module
+//!
+//! Subsequence scoring for the live search. Every whitespace-separated token of
+//! the query must appear, in order, somewhere in the candidate's path relative to
+//! the search root, so "dwnl inv" matches `Downloads\Invoices`. Consecutive
+//! characters, word starts and hits in the file name itself score higher.
+
+use super::pattern::{self, MatchSpan};
+
+/// Bonus for a character that directly follows the previous match
+const CONSECUTIVE_BONUS: i64 = 20;
+/// Bonus for matching the first character of a word (after a separator or camelCase hump)
+const WORD_START_BONUS: i64 = 15;
+/// Bonus per token that matched inside the last path component
+const FILE_NAME_BONUS: i64 = 25;
+/// Penalty for opening a gap between two matched characters...
+const GAP_START_PENALTY: i64 = 5;
+/// ...plus this much per skipped character
+const GAP_PENALTY: i64 = 1;
+const MATCH_SCORE: i64 = 10;
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '\\' | '/' | ' ' | '_' | '-' | '.')
+}
+
+fn is_word_start(chars: &[char], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
+    }
+    let prev = chars[idx - 1];
+    is_separator(prev) || (prev.is_lowercase() && chars[idx].is_uppercase())
+}
+
+/// Scores one token against `candidate`, trying every possible starting point
+/// and keeping the best greedy alignment. Returns (score, matched char indices).
+fn score_token(token: &[char], candidate: &[char], lower: &[char]) -> Option<(i64, Vec<usize>)> {
+    let first = *token.first()?;
+    let mut best: Option<(i64, Vec<usize>)> = None;
+
+    for start in (0..lower.len()).filter(|&i| lower[i] == first) {
+        let mut score = 0;
+        let mut prev: Option<usize> = None;
+        let mut pos = start;
+        let mut positions = Vec::with_capacity(token.len());
+
+        for &tc in token {
+            let Some(offset) = lower[pos..].iter().position(|&c| c == tc) else {
+                break;
+            };
+            let idx = pos + offset;
+            score += MATCH_SCORE;
+            if is_word_start(candidate, idx) {
+                score += WORD_START_BONUS;
+            }
+            match prev {
+                Some(p) if idx == p + 1 => score += CONSECUTIVE_BONUS,
+                Some(p) => score -= GAP_START_PENALTY + GAP_PENALTY * (idx - p - 1) as i64,
+                None => {}
+            }
+            prev = Some(idx);
+            pos = idx + 1;
+            positions.push(idx);
+        }
+
+        if positions.len() == token.len() {
+            let is_better = match best {
+                Some((b, _)) => score > b,
+                None => true,
+            };
+            if is_better {
+                best = Some((score, positions));
+            }
+        }
+    }
+    best
+}
+
+/// Returns None unless every token of `query` matches `candidate`; otherwise the
+/// score and the sorted char indices of `candidate` that matched
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = candidate_chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let name_start = candidate_chars
+        .iter()
+        .rposition(|&c| c == '\\' || c == '/')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    let mut total = 0;
+    let mut matched = Vec::new();
+    for token in query.split_whitespace() {
+        let token: Vec<char> = token.to_lowercase().chars().collect();
+        let (score, positions) = score_token(&token, &candidate_chars, &lower)?;
+        total += score;
+        if positions.last().is_some_and(|&end| end >= name_start) {
+            total += FILE_NAME_BONUS;
+        }
+        matched.extend(positions);
+    }
+    matched.sort_unstable();
+    matched.dedup();
+    // Prefer shorter paths when everything else is equal
+    Some((total - candidate_chars.len() as i64 / 4, matched))
+}
+
+/// Highlight spans (relative to the file name) for the matched characters of
+/// `candidate` that fall inside its last path component
+pub fn file_name_spans(candidate: &str, positions: &[usize]) -> Vec<MatchSpan> {
+    let name_start = candidate
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| *c == '\\' || *c == '/')
+        .last()
+        .map_or(0, |(i, _)| i + 1);
+    let name: String = candidate.chars().skip(name_start).collect();
+    let in_name: Vec<usize> = positions
+        .iter()
+        .filter(|&&p| p >= name_start)
+        .map(|p| p - name_start)
+        .collect();
+    pattern::char_positions_to_spans(&name, &in_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+        fuzzy_match(query, candidate).map(|(score, _)| score)
+    }
+
+    #[test]
+    fn test_subsequence_tokens_match_across_path() {
+        assert!(fuzzy_score("dwnl inv", "Downloads\\Invoices").is_some());
+        assert!(fuzzy_score("dwnl xyz", "Downloads\\Invoices").is_none());
+        assert!(fuzzy_score("inv", "Downloads").is_none());
+    }
+
+    #[test]
+    fn test_ranking_prefers_word_starts_and_file_names() {
+        let target = fuzzy_score("dwnl inv", "Downloads\\Invoices").unwrap();
+        let noise = fuzzy_score("dwnl inv", "Dev\\work\\node_lib\\install\\inventory.txt").unwrap();
+        assert!(target > noise);
+
+        let exact = fuzzy_score("report", "Work\\report.pdf").unwrap();
+        let scattered = fuzzy_score("report", "Recent\\public_photos\\art.jpg").unwrap_or(i64::MIN);
+        assert!(exact > scattered);
+    }
+
+    #[test]
+    fn test_match_positions() {
+        let (_, positions) = fuzzy_match("dl inv", "Downloads\\Invoices").unwrap();
+        assert_eq!(positions, vec![0, 4, 10, 11, 12]);
+        assert_eq!(
+            file_name_spans("Downloads\\Invoices", &positions),
+            vec![MatchSpan { start: 0, end: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_camel_case_word_start() {
+        let hump = fuzzy_score("mf", "MyFile.txt").unwrap();
+        let inner = fuzzy_score("mf", "amfile.txt").unwrap();
+        assert!(hump > inner);
+    }
+}
@@ -314,34 +314,70 @@ impl IndexManager {
         }
     }
 
-    pub fn ensure_indexed(&self, root_path: String, nav_id: String, window: tauri::Window) -> Result<bool, String> {
+    /// Makes sure an index covering `root_path` is in memory, loading it (or a parent's) from disk
+    /// if needed. Returns false if no such index exists yet.
+    pub fn load_covering_index(&self, root_path: &str) -> bool {
         // 1. Check if already in memory (exact match)
         {
             let indices = self.indices.read();
-            if indices.contains_key(&root_path) {
-                return Ok(false);
+            if indices.contains_key(root_path) {
+                return true;
             }
         }
 
         // 2. Check if any parent is in memory
-        if let Some(parent_key) = self.find_closest_index(&root_path) {
+        if let Some(parent_key) = self.find_closest_index(root_path) {
             info!("Found existing parent index {} in memory for {}", parent_key, root_path);
-            return Ok(false);
+            return true;
         }
 
         // 3. Try loading exact path from SSD
-        if self.load_index(&root_path) {
-            return Ok(false);
+        if self.load_index(root_path) {
+            return true;
         }
 
         // 4. Try loading parent path from SSD
-        if let Some(parent_key) = self.check_parent_indices_on_disk(&root_path) {
+        if let Some(parent_key) = self.check_parent_indices_on_disk(root_path) {
             info!("Found existing parent index {} on disk for {}", parent_key, root_path);
             if self.load_index(&parent_key) {
-                return Ok(false);
+                return true;
             }
         }
 
+        false
+    }
+
+    /// Time since the index covering `root_path` was last fully built
+    pub fn index_age(&self, root_path: &str) -> Option<Duration> {
+        let key = if self.indexed_at.read().contains_key(root_path) {
+            root_path.to_string()
+        } else {
+            self.find_closest_index(root_path)?
+        };
+        let indexed_at = *self.indexed_at.read().get(&key)?;
+        indexed_at.elapsed().ok()
+    }
+
+    /// Whether `full_path` is already part of the index covering it
+    pub fn contains_path(&self, full_path: &str) -> bool {
+        let Some(key) = self.find_closest_index(full_path) else {
+            return false;
+        };
+        let Ok(rel) = Path::new(full_path).strip_prefix(&key) else {
+            return false;
+        };
+        self.path_sets
+            .read()
+            .get(&key)
+            .is_some_and(|set| set.contains(rel.to_string_lossy().as_ref()))
+    }
+
+    pub fn ensure_indexed(&self, root_path: String, nav_id: String, window: tauri::Window) -> Result<bool, String> {
+        // 1-4. Reuse an index from memory or disk
+        if self.load_covering_index(&root_path) {
+            return Ok(false);
+        }
+
         // 5. Hardware check: If SSD, we don't necessarily NEED to index (we use jwalk), 
         // but for HDDs, we must index now.
         if crate::is_ssd(&root_path) {
@@ -355,7 +391,6 @@ impl IndexManager {
 
 
     fn index_hdd_path(&self, root_path: String, nav_id: Option<String>, is_background: bool, window: tauri::Window) -> Result<(), String> {
-        let mut art = ART::new(500); // Max results limit
         let start_time = Instant::now();
 
         // Notify frontend that we are indexing (only if not a background task to avoid label flickering)
@@ -363,18 +398,6 @@ impl IndexManager {
             let _ = window.emit("deep-search-detail-status", "Indexing...");
         }
         let _ = window.emit("deep-search-status", "Indexing HDD...");
-        
-        let mut stack = vec![PathBuf::from(&root_path)];
-        let mut count = 0;
-        let mut path_set = HashSet::new();
-
-        // Perform indexing directly in the current (worker) thread.
-        // We set thread priority now.
-        #[cfg(target_os = "windows")]
-        unsafe {
-            use windows::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_LOWEST};
-            let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_LOWEST);
-        }
 
         let is_cancelled = || {
             use std::sync::atomic::Ordering;
@@ -395,16 +418,74 @@ impl IndexManager {
             false
         };
 
+        let count = self.build_index(&root_path, &is_cancelled)?;
+
+        info!("Finished indexing {} ({} items in {:?})", root_path, count, start_time.elapsed());
+        let _ = window.emit("deep-search-status", "Search ready");
+        
+        if !is_background {
+            let _ = window.emit("deep-search-detail-status", "Indexing finished");
+        }
+        
+        Ok(())
+    }
+
+    /// Walks `root_path` at lowest thread priority, then stores and persists the new index.
+    /// Used directly by the background folder index, which has no window to report to.
+    pub fn build_index(&self, root_path: &str, is_cancelled: &dyn Fn() -> bool) -> Result<usize, String> {
+        // Perform indexing directly in the current (worker) thread.
+        // We set thread priority now.
+        #[cfg(target_os = "windows")]
+        unsafe {
+            use windows::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_LOWEST};
+            let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_LOWEST);
+        }
+
+        let walked = Self::walk_into_art(root_path, is_cancelled);
+
+        // Restore priority
+        #[cfg(target_os = "windows")]
+        unsafe {
+            use windows::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_NORMAL};
+            let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_NORMAL);
+        }
+
+        let Some((art, path_set)) = walked else {
+            warn!("Indexing aborted for {}", root_path);
+            return Err("Indexing cancelled".to_string());
+        };
+
+        let count = path_set.len();
+        self.indices.write().insert(root_path.to_string(), art);
+        self.path_sets.write().insert(root_path.to_string(), path_set);
+        self.save_index(root_path);
+        self.save_meta(root_path);
+        Ok(count)
+    }
+
+    /// Returns None if cancelled part-way through
+    fn walk_into_art(root_path: &str, is_cancelled: &dyn Fn() -> bool) -> Option<(ART, HashSet<String>)> {
+        // Whole NTFS drives index from the file table in seconds when running elevated
+        if crate::mft::is_volume_root(Path::new(root_path)) {
+            match crate::mft::read_volume(Path::new(root_path), is_cancelled) {
+                Ok(volume) => return Some(Self::volume_into_art(&volume)),
+                Err(e) => debug!("Walking {} (file table unavailable: {})", root_path, e),
+            }
+        }
+
+        let mut art = ART::new(500); // Max results limit
+        let mut stack = vec![PathBuf::from(root_path)];
+        let mut path_set = HashSet::new();
+        // is_dir() follows junctions, so guard against loops like "Application Data"
+        let mut visited = crate::links::VisitedDirs::new();
+
         while let Some(current_dir) = stack.pop() {
             if is_cancelled() {
-                // Restore priority before returning
-                #[cfg(target_os = "windows")]
-                unsafe {
-                    use windows::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_NORMAL};
-                    let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_NORMAL);
-                }
-                warn!("Indexing aborted for {}", root_path);
-                return Err("Indexing cancelled".to_string());
+                return None;
+            }
+            if !visited.first_visit(&current_dir) {
+                debug!("Skipping already indexed folder (link cycle): {:?}", current_dir);
+                continue;
             }
 
             if let Ok(entries) = fs::read_dir(&current_dir) {
@@ -413,31 +494,21 @@ impl IndexManager {
                     loop_count += 1;
                     
                     // Frequent cancellation check (every 50 items)
-                    if loop_count % 50 == 0 {
-                        if is_cancelled() {
-                            // Restore priority before returning
-                            #[cfg(target_os = "windows")]
-                            unsafe {
-                                use windows::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_NORMAL};
-                                let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_NORMAL);
-                            }
-                            warn!("Indexing aborted for {} (inner loop check)", root_path);
-                            return Err("Indexing cancelled".to_string());
-                        }
+                    if loop_count % 50 == 0 && is_cancelled() {
+                        return None;
                     }
 
                     let path = entry.path();
                     if let Some(path_str) = path.to_str() {
                         // Use relative path for ART index
-                        let rel_path = if let Ok(rel) = path.strip_prefix(&root_path) {
+                        let rel_path = if let Ok(rel) = path.strip_prefix(root_path) {
                             rel.to_string_lossy().to_string()
                         } else {
                             path_str.to_string()
                         };
                         
                         art.insert(&rel_path, 1.0);
-                        path_set.insert(rel_path.clone());
-                        count += 1;
+                        path_set.insert(rel_path);
 
                         if path.is_dir() {
                             stack.push(path);
@@ -447,25 +518,23 @@ impl IndexManager {
             }
         }
 
-        // Restore priority
-        #[cfg(target_os = "windows")]
-        unsafe {
-            use windows::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_NORMAL};
-            let _ = SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_NORMAL);
-        }
+        Some((art, path_set))
+    }
 
-        info!("Finished indexing {} ({} items in {:?})", root_path, count, start_time.elapsed());
-        self.indices.write().insert(root_path.clone(), art);
-        self.path_sets.write().insert(root_path.clone(), path_set);
-        self.save_index(&root_path);
-        self.save_meta(&root_path);
-        let _ = window.emit("deep-search-status", "Search ready");
-        
-        if !is_background {
-            let _ = window.emit("deep-search-detail-status", "Indexing finished");
-        }
-        
-        Ok(())
+    fn volume_into_art(volume: &crate::mft::MftVolume) -> (ART, HashSet<String>) {
+        let mut art = ART::new(500); // Max results limit
+        let mut path_set = HashSet::new();
+        volume.walk(String::new(), |parent, file| {
+            let rel_path = if parent.is_empty() {
+                file.name.to_string()
+            } else {
+                format!("{}\\{}", parent, file.name)
+            };
+            art.insert(&rel_path, 1.0);
+            path_set.insert(rel_path.clone());
+            file.is_dir.then_some(rel_path)
+        });
+        (art, path_set)
     }
 
     pub fn update_folder_entries(&self, folder_path: &str, entries: &[FileEntry]) {
@@ -723,6 +792,13 @@ mod tests {
                 modified_timestamp: 0,
                 created_timestamp: 0,
                 dimensions: None,
+                url_target: None,
+                recycle_info: None,
+                is_symlink: false,
+                is_junction: false,
+                link_target: None,
+                attributes: Default::default(),
+                cloud_status: None,
             }
         ];
         
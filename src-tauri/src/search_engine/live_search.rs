@@ -0,0 +1,603 @@
+//! Live Search Module
+//!
+//! `start_search` walks a directory tree in parallel (jwalk) and streams name
+//! matches to the frontend in small batches. Every search gets its own id and
+//! cancellation flag, so several tabs can search at once and `cancel_search`
+//! only stops the one it names. With `search_content` set, file contents are
+//! grepped instead of names (see `content_search`); with `fuzzy` set, results
+//! are ranked by `fuzzy::fuzzy_match` plus folder frecency across the whole
+//! walk, and each batch carries the best `MAX_RANKED` found so far.
+//! With `regex` set, the query text is a regular expression (see `pattern`),
+//! compiled once in `start_search` and used for names and contents alike.
+//! With `glob` set, or when a name query contains `*` or `?`, the query is a
+//! wildcard pattern matched against the whole name (`*.pdf`, `IMG_????.jpg`).
+//! With `find_duplicates` set, matching files are collected instead of
+//! streamed and reported as groups of identical content (see `duplicates`).
+//! Filter tokens in the query (`size:>10MB`, `kind:image`, ...) are parsed by
+//! `filters` and applied to each entry as the walk reaches it. With
+//! `search_archives` set, entry names inside ZIP/7Z files are matched too and
+//! reported separately as `archive_matches` (see `archive_search`).
+
+use super::archive_search::{self, ArchiveMatch};
+use super::content_search::{self, ContentMatch};
+use super::duplicates::{self, DuplicateGroup};
+use super::pattern::{self, MatchSpan, Pattern};
+use super::{filters, fuzzy};
+use crate::FileEntry;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tauri::Emitter;
+use ts_rs::TS;
+
+/// Flush a batch when it reaches this many entries...
+const BATCH_SIZE: usize = 50;
+/// ...or when this much time has passed since the previous one
+const BATCH_INTERVAL_MS: u128 = 100;
+/// Files handed to the rayon pool at once in content mode
+const CONTENT_CHUNK: usize = 32;
+/// Stop reporting lines from a single file after this many hits
+const MAX_HITS_PER_FILE: usize = 100;
+/// Fuzzy searches keep (and re-send) only this many of the best results
+const MAX_RANKED: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// Mirrors the "show hidden files" setting; hidden folders are not descended into
+    pub show_hidden: bool,
+    pub case_sensitive: bool,
+    pub include_files: bool,
+    pub include_folders: bool,
+    /// Stop after this many matches
+    pub max_results: Option<usize>,
+    /// Depth limit relative to the root (1 = direct children only)
+    pub max_depth: Option<usize>,
+    /// Match file contents instead of names (folders are never reported)
+    pub search_content: bool,
+    /// Content mode skips files larger than this (bytes)
+    pub max_file_size: u64,
+    /// Subsequence matching on the path relative to the root, ranked by score
+    /// plus the frecency of the containing folder
+    pub fuzzy: bool,
+    /// Also match entry names inside ZIP/7Z archives found during the walk (name mode only)
+    pub search_archives: bool,
+    /// Treat the query text as a regular expression; takes precedence over `fuzzy`
+    pub regex: bool,
+    /// Treat the query text as a wildcard pattern for the whole name (name mode only).
+    /// Implied when the text contains `*` or `?`; `regex` takes precedence
+    pub glob: bool,
+    /// Report groups of matching files with identical content instead of the matches
+    /// themselves (name mode only; the query may be empty)
+    pub find_duplicates: bool,
+    /// With `find_duplicates`, only report the group containing this file
+    pub duplicates_of: Option<String>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            show_hidden: false,
+            case_sensitive: false,
+            include_files: true,
+            include_folders: true,
+            max_results: None,
+            max_depth: None,
+            search_content: false,
+            max_file_size: 50 * 1024 * 1024,
+            fuzzy: false,
+            search_archives: false,
+            regex: false,
+            glob: false,
+            find_duplicates: false,
+            duplicates_of: None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct SearchResultBatch {
+    pub search_id: String,
+    pub entries: Vec<FileEntry>,
+    /// Relevance of each entry (same order as `entries`, highest first);
+    /// empty unless the search is fuzzy
+    #[ts(type = "Array<number>")]
+    pub scores: Vec<i64>,
+    /// Set by fuzzy searches: `entries` is the whole ranking so far and replaces
+    /// the entries of earlier batches
+    pub replaces_previous: bool,
+    /// Highlight ranges within each entry's name (same order as `entries`)
+    pub name_spans: Vec<Vec<MatchSpan>>,
+    /// Line hits when the search runs in content mode
+    pub content_matches: Vec<ContentMatch>,
+    /// Entries inside archives, when the search includes archives
+    pub archive_matches: Vec<ArchiveMatch>,
+    /// Identical-content groups, when the search looks for duplicates
+    pub duplicate_groups: Vec<DuplicateGroup>,
+}
+
+#[derive(Serialize, Clone, TS)]
+#[ts(export)]
+pub struct SearchFinished {
+    pub search_id: String,
+    #[ts(type = "number")]
+    pub total_matches: u64,
+    pub cancelled: bool,
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+}
+
+static NEXT_SEARCH_ID: AtomicU64 = AtomicU64::new(1);
+static ACTIVE_SEARCHES: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn active_searches() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    ACTIVE_SEARCHES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn is_hidden(path: &std::path::Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    // 0x2 is FILE_ATTRIBUTE_HIDDEN
+    std::fs::symlink_metadata(path)
+        .map(|m| m.file_attributes() & 0x2 != 0)
+        .unwrap_or(false)
+}
+
+/// A fuzzy match; ordered by score, earlier matches first among equal scores
+struct Ranked {
+    score: i64,
+    seq: u64,
+    entry: FileEntry,
+    spans: Vec<MatchSpan>,
+}
+
+impl Ranked {
+    fn key(&self) -> (i64, Reverse<u64>) {
+        (self.score, Reverse(self.seq))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Ranked {}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Accumulates matches and emits them as `search-results` batches
+struct ResultStream {
+    search_id: String,
+    window: tauri::Window,
+    buffer: Vec<FileEntry>,
+    name_spans: Vec<Vec<MatchSpan>>,
+    /// Best fuzzy matches of the whole walk, worst on top so it is evicted first
+    ranked: BinaryHeap<Reverse<Ranked>>,
+    /// Fuzzy matches seen since the last batch
+    ranked_pending: usize,
+    ranked_seen: u64,
+    content_buffer: Vec<ContentMatch>,
+    archive_buffer: Vec<ArchiveMatch>,
+    duplicate_buffer: Vec<DuplicateGroup>,
+    last_emit: Instant,
+}
+
+impl ResultStream {
+    fn push(&mut self, entry: FileEntry, spans: Vec<MatchSpan>) {
+        self.buffer.push(entry);
+        self.name_spans.push(spans);
+        self.maybe_flush();
+    }
+
+    fn push_ranked(&mut self, entry: FileEntry, score: i64, spans: Vec<MatchSpan>) {
+        self.ranked.push(Reverse(Ranked {
+            score,
+            seq: self.ranked_seen,
+            entry,
+            spans,
+        }));
+        if self.ranked.len() > MAX_RANKED {
+            self.ranked.pop();
+        }
+        self.ranked_seen += 1;
+        self.ranked_pending += 1;
+        self.maybe_flush();
+    }
+
+    fn push_content(&mut self, hit: ContentMatch) {
+        self.content_buffer.push(hit);
+        self.maybe_flush();
+    }
+
+    fn push_archive(&mut self, hit: ArchiveMatch) {
+        self.archive_buffer.push(hit);
+        self.maybe_flush();
+    }
+
+    fn push_duplicates(&mut self, group: DuplicateGroup) {
+        self.duplicate_buffer.push(group);
+        self.maybe_flush();
+    }
+
+    fn pending(&self) -> usize {
+        self.buffer.len()
+            + self.ranked_pending
+            + self.content_buffer.len()
+            + self.archive_buffer.len()
+            + self.duplicate_buffer.len()
+    }
+
+    fn maybe_flush(&mut self) {
+        if self.pending() >= BATCH_SIZE || self.last_emit.elapsed().as_millis() >= BATCH_INTERVAL_MS
+        {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.pending() == 0 {
+            return;
+        }
+        let mut entries = std::mem::take(&mut self.buffer);
+        let mut name_spans = std::mem::take(&mut self.name_spans);
+        let mut scores = Vec::new();
+        let replaces_previous = self.ranked_pending > 0;
+        if replaces_previous {
+            // A later match can outrank anything sent so far, so send the whole ranking
+            let mut ranked: Vec<&Ranked> = self.ranked.iter().map(|r| &r.0).collect();
+            ranked.sort_by(|a, b| b.cmp(a));
+            for r in ranked {
+                entries.push(r.entry.clone());
+                scores.push(r.score);
+                name_spans.push(r.spans.clone());
+            }
+            self.ranked_pending = 0;
+        }
+        let _ = self.window.emit(
+            "search-results",
+            SearchResultBatch {
+                search_id: self.search_id.clone(),
+                entries,
+                scores,
+                replaces_previous,
+                name_spans,
+                content_matches: std::mem::take(&mut self.content_buffer),
+                archive_matches: std::mem::take(&mut self.archive_buffer),
+                duplicate_groups: std::mem::take(&mut self.duplicate_buffer),
+            },
+        );
+        self.last_emit = Instant::now();
+    }
+}
+
+/// Greps a chunk of files in parallel and streams the hits; returns how many were found
+fn scan_content_chunk(
+    files: &mut Vec<std::path::PathBuf>,
+    pattern: &Pattern,
+    cancelled: &AtomicBool,
+    stream: &mut ResultStream,
+) -> u64 {
+    let hits: Vec<Vec<ContentMatch>> = files
+        .par_iter()
+        .map(|path| {
+            if cancelled.load(Ordering::Relaxed) {
+                return Vec::new();
+            }
+            content_search::search_file(path, pattern, MAX_HITS_PER_FILE, cancelled)
+        })
+        .collect();
+    files.clear();
+
+    let mut count = 0;
+    for hit in hits.into_iter().flatten() {
+        stream.push_content(hit);
+        count += 1;
+    }
+    count
+}
+
+/// Extra score for results inside (or being) folders the user visits often
+fn frecency_boost(frecency: &HashMap<String, f64>, path: &std::path::Path, is_dir: bool) -> i64 {
+    let lookup = |p: &std::path::Path| {
+        frecency
+            .get(&crate::frecency::folder_key(&p.to_string_lossy()))
+            .copied()
+            .unwrap_or(0.0)
+    };
+    let own = if is_dir { lookup(path) } else { 0.0 };
+    let parent = path.parent().map(lookup).unwrap_or(0.0);
+    (own.max(parent) * 0.5) as i64
+}
+
+fn run_search(
+    search_id: String,
+    root: String,
+    query: String,
+    pattern: Pattern,
+    options: SearchOptions,
+    cancelled: Arc<AtomicBool>,
+    window: tauri::Window,
+) {
+    use jwalk::WalkDir;
+
+    let start = Instant::now();
+    // Filter tokens (size:, modified:, ext:, kind:) are applied here, during the walk
+    let parsed = filters::parse_query(&query);
+    let query = parsed.text.clone();
+    let fuzzy = options.fuzzy && !options.regex && !options.glob;
+    let mut total_matches: u64 = 0;
+    let mut stream = ResultStream {
+        search_id: search_id.clone(),
+        window: window.clone(),
+        buffer: Vec::new(),
+        name_spans: Vec::new(),
+        ranked: BinaryHeap::new(),
+        ranked_pending: 0,
+        ranked_seen: 0,
+        content_buffer: Vec::new(),
+        archive_buffer: Vec::new(),
+        duplicate_buffer: Vec::new(),
+        last_emit: Instant::now(),
+    };
+    let mut pending_files = Vec::with_capacity(CONTENT_CHUNK);
+    let find_duplicates = options.find_duplicates && !options.search_content;
+    let mut duplicate_candidates = Vec::new();
+    let frecency = if fuzzy {
+        crate::frecency::FrecencyStore::global().snapshot()
+    } else {
+        HashMap::new()
+    };
+    let limit_reached = |total: u64| options.max_results.is_some_and(|max| total >= max as u64);
+
+    let mut walker = WalkDir::new(&root).skip_hidden(false);
+    if let Some(depth) = options.max_depth {
+        walker = walker.max_depth(depth);
+    }
+
+    let read_dir_cancelled = Arc::clone(&cancelled);
+    let show_hidden = options.show_hidden;
+    let walker = walker.process_read_dir(move |_, _, _, children| {
+        if read_dir_cancelled.load(Ordering::Relaxed) {
+            // Abort the read if cancelled
+            children.clear();
+            return;
+        }
+        if !show_hidden {
+            // Dropping hidden folders here also prunes everything beneath them
+            children.retain(|child| {
+                child
+                    .as_ref()
+                    .map(|e| !is_hidden(&e.path()))
+                    .unwrap_or(true)
+            });
+        }
+    });
+
+    for entry in walker {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let dir_entry = match entry {
+            Ok(e) => e,
+            Err(err) => {
+                log::warn!("[SEARCH] Walk error: {}", err);
+                continue;
+            }
+        };
+        // The root itself is not a result
+        if dir_entry.depth() == 0 {
+            continue;
+        }
+
+        let is_dir = dir_entry.file_type().is_dir();
+        let path = dir_entry.path();
+
+        // Filters apply to the entries inside, not to the archive itself
+        if options.search_archives
+            && !options.search_content
+            && !is_dir
+            && archive_search::is_archive(&path)
+        {
+            for hit in archive_search::search_archive(&path, &query, &pattern, &options, &parsed) {
+                stream.push_archive(hit);
+                total_matches += 1;
+            }
+            if limit_reached(total_matches) {
+                break;
+            }
+        }
+
+        if !parsed.filters.is_empty() {
+            let metadata = if parsed.needs_metadata() {
+                dir_entry.metadata().ok()
+            } else {
+                None
+            };
+            if !parsed.matches(&path, is_dir, metadata.as_ref()) {
+                continue;
+            }
+        }
+
+        if options.search_content {
+            let too_large = dir_entry
+                .metadata()
+                .map(|m| m.len() > options.max_file_size)
+                .unwrap_or(true);
+            if is_dir || too_large {
+                continue;
+            }
+            pending_files.push(path);
+            if pending_files.len() >= CONTENT_CHUNK {
+                total_matches +=
+                    scan_content_chunk(&mut pending_files, &pattern, &cancelled, &mut stream);
+                if limit_reached(total_matches) {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        if (is_dir && !options.include_folders) || (!is_dir && !options.include_files) {
+            continue;
+        }
+
+        let (score, spans) = if fuzzy {
+            let relative = path.strip_prefix(&root).unwrap_or(&path).to_string_lossy();
+            let Some((score, positions)) = fuzzy::fuzzy_match(&query, &relative) else {
+                continue;
+            };
+            (
+                Some(score + frecency_boost(&frecency, &path, is_dir)),
+                fuzzy::file_name_spans(&relative, &positions),
+            )
+        } else {
+            let name = dir_entry.file_name().to_string_lossy();
+            if !pattern.is_match(&name) {
+                continue;
+            }
+            (None, pattern.spans(&name))
+        };
+
+        if find_duplicates {
+            if !is_dir {
+                if let Ok(metadata) = dir_entry.metadata() {
+                    duplicate_candidates.push((path, metadata.len()));
+                }
+            }
+            continue;
+        }
+
+        if let Ok(file_entry) = crate::get_file_entry(&path) {
+            match score {
+                Some(score) => stream.push_ranked(file_entry, score, spans),
+                None => stream.push(file_entry, spans),
+            }
+            total_matches += 1;
+            if limit_reached(total_matches) {
+                break;
+            }
+        }
+    }
+
+    if !pending_files.is_empty()
+        && !cancelled.load(Ordering::Relaxed)
+        && !limit_reached(total_matches)
+    {
+        total_matches += scan_content_chunk(&mut pending_files, &pattern, &cancelled, &mut stream);
+    }
+
+    if find_duplicates && !cancelled.load(Ordering::Relaxed) {
+        let target = options.duplicates_of.as_deref().map(std::path::Path::new);
+        total_matches += duplicates::find_groups(duplicate_candidates, target, &cancelled, |g| {
+            stream.push_duplicates(g)
+        }) as u64;
+    }
+
+    stream.flush();
+
+    let was_cancelled = cancelled.load(Ordering::Relaxed);
+    if let Ok(mut searches) = active_searches().lock() {
+        searches.remove(&search_id);
+    }
+
+    log::info!(
+        "[SEARCH] {} finished: {} matches in {:?} (cancelled: {})",
+        search_id,
+        total_matches,
+        start.elapsed(),
+        was_cancelled
+    );
+
+    let _ = window.emit(
+        "search-finished",
+        SearchFinished {
+            search_id,
+            total_matches,
+            cancelled: was_cancelled,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        },
+    );
+}
+
+/// Starts a recursive name search under `root` and returns its id immediately.
+/// Matches arrive as `search-results` events followed by one `search-finished`.
+#[tauri::command]
+pub fn start_search(
+    window: tauri::Window,
+    root: String,
+    query: String,
+    options: Option<SearchOptions>,
+) -> Result<String, String> {
+    let root = crate::paths::expand_path_str(&root)?;
+    if !std::path::Path::new(&root).is_dir() {
+        return Err(format!("Not a folder: {}", root));
+    }
+    let query = query.trim().to_string();
+    let mut options = options.unwrap_or_default();
+    // "Find duplicates of this file" has nothing to type; every file is a candidate
+    if query.is_empty() && !options.find_duplicates {
+        return Err("Search query is empty".to_string());
+    }
+
+    let text = filters::parse_query(&query).text;
+    if options.search_content && text.is_empty() {
+        return Err("Content search needs some text to look for".to_string());
+    }
+    options.glob = !options.search_content
+        && !options.regex
+        && (options.glob || pattern::looks_like_glob(&text));
+    // Compiled once here so a bad regex is reported to the caller instead of the event stream
+    let pattern = if options.glob {
+        Pattern::glob(&text, options.case_sensitive)?
+    } else {
+        Pattern::new(&text, options.case_sensitive, options.regex)?
+    };
+    let search_id = format!("search-{}", NEXT_SEARCH_ID.fetch_add(1, Ordering::Relaxed));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    active_searches()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(search_id.clone(), Arc::clone(&cancelled));
+
+    log::info!(
+        "[SEARCH] {} started in {} for '{}' ({:?})",
+        search_id,
+        root,
+        query,
+        options
+    );
+
+    let id = search_id.clone();
+    std::thread::spawn(move || run_search(id, root, query, pattern, options, cancelled, window));
+
+    Ok(search_id)
+}
+
+/// Cancels one search, or every running search when `search_id` is None
+#[tauri::command]
+pub fn cancel_search(search_id: Option<String>) {
+    if let Ok(searches) = active_searches().lock() {
+        for (id, flag) in searches.iter() {
+            if search_id.as_ref().is_none_or(|target| target == id) {
+                flag.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
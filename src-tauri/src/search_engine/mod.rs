@@ -1,2 +1,13 @@
+pub mod archive_search;
 pub mod art_core;
+pub mod content_search;
+pub mod duplicates;
+pub mod filters;
+pub mod folder_index;
+pub mod fuzzy;
 pub mod index_manager;
+pub mod live_search;
+pub mod pattern;
+pub mod saved_searches;
+pub mod volume_index;
+pub mod windows_search;
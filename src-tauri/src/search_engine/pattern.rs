@@ -0,0 +1,354 @@
+//! Search Pattern Module
+//!
+//! The text part of a search query, compiled once per search and shared by the
+//! name matcher, the content grep and the archive scan. Plain queries are
+//! substring matches; in regex mode the `regex` crate is used, whose matching
+//! time is linear in the input, so a hostile pattern cannot backtrack forever.
+//! Compilation is still capped so a huge pattern cannot exhaust memory.
+//! Wildcard patterns (`*.txt`, `IMG_????.jpg`) are translated to an anchored
+//! regex, so they go through the same engine and limits.
+//! Matches can be reported as `MatchSpan`s so the UI highlights exactly what
+//! the backend matched.
+
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Longest regex accepted from the search box
+const MAX_REGEX_LEN: usize = 1024;
+/// Cap on the compiled program...
+const REGEX_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+/// ...and on the lazy DFA cache built while matching
+const REGEX_DFA_SIZE_LIMIT: usize = 8 * 1024 * 1024;
+/// Guards against deeply nested groups such as `((((((a))))))...`
+const REGEX_NEST_LIMIT: u32 = 64;
+/// Highlighting more than this many hits in one string is just noise
+const MAX_SPANS: usize = 32;
+
+/// A highlighted range, in UTF-16 code units so it slices JavaScript strings directly
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[ts(export)]
+pub struct MatchSpan {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Converts sorted, non-overlapping byte ranges of `text` into UTF-16 spans
+pub fn byte_ranges_to_spans(text: &str, ranges: &[(usize, usize)]) -> Vec<MatchSpan> {
+    let mut spans = Vec::with_capacity(ranges.len());
+    let mut units = 0u32;
+    let mut consumed = 0usize;
+    for &(start, end) in ranges {
+        units += text[consumed..start].encode_utf16().count() as u32;
+        let span_start = units;
+        units += text[start..end].encode_utf16().count() as u32;
+        consumed = end;
+        spans.push(MatchSpan {
+            start: span_start,
+            end: units,
+        });
+    }
+    spans
+}
+
+/// Converts sorted char indices of `text` into UTF-16 spans, merging adjacent characters
+pub fn char_positions_to_spans(text: &str, positions: &[usize]) -> Vec<MatchSpan> {
+    let mut spans: Vec<MatchSpan> = Vec::new();
+    let mut wanted = positions.iter().peekable();
+    let mut units = 0u32;
+    for (i, c) in text.chars().enumerate() {
+        let width = c.len_utf16() as u32;
+        if wanted.next_if(|&&p| p == i).is_some() {
+            match spans.last_mut() {
+                Some(last) if last.end == units => last.end += width,
+                _ => spans.push(MatchSpan {
+                    start: units,
+                    end: units + width,
+                }),
+            }
+        }
+        units += width;
+    }
+    spans
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `needle` is already lowercased when the match is case-insensitive
+    Literal {
+        needle: String,
+        case_sensitive: bool,
+    },
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn new(text: &str, case_sensitive: bool, regex: bool) -> Result<Self, String> {
+        if !regex {
+            let needle = if case_sensitive {
+                text.to_string()
+            } else {
+                text.to_lowercase()
+            };
+            return Ok(Pattern::Literal {
+                needle,
+                case_sensitive,
+            });
+        }
+
+        if text.len() > MAX_REGEX_LEN {
+            return Err(format!(
+                "Regular expression is too long (max {} characters)",
+                MAX_REGEX_LEN
+            ));
+        }
+        build_regex(text, case_sensitive)
+    }
+
+    /// A wildcard pattern matched against the whole text: `*` is any run of characters,
+    /// `?` one character and `[abc]` / `[a-z]` / `[!abc]` a character class
+    pub fn glob(text: &str, case_sensitive: bool) -> Result<Self, String> {
+        if text.len() > MAX_REGEX_LEN {
+            return Err(format!(
+                "Wildcard pattern is too long (max {} characters)",
+                MAX_REGEX_LEN
+            ));
+        }
+        build_regex(&glob_to_regex(text), case_sensitive)
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        match self {
+            Pattern::Literal {
+                needle,
+                case_sensitive: true,
+            } => text.contains(needle.as_str()),
+            Pattern::Regex(re) => re.is_match(text),
+            Pattern::Literal { .. } => self.find(text).is_some(),
+        }
+    }
+
+    /// Byte ranges of the non-empty matches in `text`, at most MAX_SPANS of them
+    pub fn find_all(&self, text: &str) -> Vec<(usize, usize)> {
+        match self {
+            Pattern::Regex(re) => re
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .take(MAX_SPANS)
+                .collect(),
+            Pattern::Literal { needle, .. } if needle.is_empty() => Vec::new(),
+            Pattern::Literal { .. } => {
+                let mut ranges = Vec::new();
+                let mut from = 0;
+                while ranges.len() < MAX_SPANS {
+                    let Some((start, end)) = self.find(&text[from..]) else {
+                        break;
+                    };
+                    ranges.push((from + start, from + end));
+                    from += end.max(start + 1);
+                    // A match can end mid-character only if lowercasing split it; realign
+                    while from < text.len() && !text.is_char_boundary(from) {
+                        from += 1;
+                    }
+                    if from >= text.len() {
+                        break;
+                    }
+                }
+                ranges
+            }
+        }
+    }
+
+    /// Highlight spans for every match in `text`
+    pub fn spans(&self, text: &str) -> Vec<MatchSpan> {
+        byte_ranges_to_spans(text, &self.find_all(text))
+    }
+
+    /// Byte range of the first match in `text`
+    pub fn find(&self, text: &str) -> Option<(usize, usize)> {
+        match self {
+            Pattern::Literal {
+                needle,
+                case_sensitive,
+            } => find_literal(text, needle, *case_sensitive),
+            Pattern::Regex(re) => re.find(text).map(|m| (m.start(), m.end())),
+        }
+    }
+}
+
+fn build_regex(text: &str, case_sensitive: bool) -> Result<Pattern, String> {
+    RegexBuilder::new(text)
+        .case_insensitive(!case_sensitive)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .nest_limit(REGEX_NEST_LIMIT)
+        .build()
+        .map(Pattern::Regex)
+        .map_err(|e| format!("Invalid regular expression: {}", e))
+}
+
+/// Whether a name query reads as a wildcard pattern; names can't contain `*` or `?`
+pub fn looks_like_glob(text: &str) -> bool {
+    text.contains(['*', '?'])
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut out = String::from("^");
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '[' => {
+                let negated = matches!(chars.get(i + 1), Some('!' | '^'));
+                let start = i + 1 + negated as usize;
+                // A ']' right after the '[' (or "[!") is part of the class
+                let Some(close) = (start + 1..chars.len()).find(|&j| chars[j] == ']') else {
+                    out.push_str(r"\[");
+                    i += 1;
+                    continue;
+                };
+                out.push('[');
+                if negated {
+                    out.push('^');
+                }
+                for &c in &chars[start..close] {
+                    if matches!(c, '\\' | '[' | ']' | '&' | '~' | '^') {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push(']');
+                i = close;
+            }
+            c => out.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+        i += 1;
+    }
+    out.push('$');
+    out
+}
+
+fn find_literal(line: &str, needle: &str, case_sensitive: bool) -> Option<(usize, usize)> {
+    if case_sensitive {
+        return line.find(needle).map(|pos| (pos, pos + needle.len()));
+    }
+    if line.is_ascii() {
+        return line
+            .to_ascii_lowercase()
+            .find(needle)
+            .map(|pos| (pos, pos + needle.len()));
+    }
+    // Lowercasing can change byte lengths ('İ' -> "i̇"); keep a map from each byte
+    // of the lowered line back to the start of the original character
+    let mut lower = String::with_capacity(line.len());
+    let mut origin = Vec::with_capacity(line.len());
+    for (i, c) in line.char_indices() {
+        let before = lower.len();
+        lower.extend(c.to_lowercase());
+        origin.resize(origin.len() + lower.len() - before, i);
+    }
+    let pos = lower.find(needle)?;
+    let end = origin
+        .get(pos + needle.len())
+        .copied()
+        .unwrap_or(line.len());
+    Some((origin[pos], end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_find_case_insensitive() {
+        let pattern = Pattern::new("World", false, false).unwrap();
+        assert_eq!(pattern.find("Hello World"), Some((6, 11)));
+        let pattern = Pattern::new("world", true, false).unwrap();
+        assert_eq!(pattern.find("Hello World"), None);
+        // 'İ' lowercases to two chars; the hit must still map to the right byte offsets
+        let pattern = Pattern::new("needle", false, false).unwrap();
+        assert_eq!(pattern.find("İx needle!"), Some((4, 10)));
+    }
+
+    #[test]
+    fn test_regex_mode() {
+        let pattern = Pattern::new(r"^IMG_\d{4}\.jpe?g$", false, true).unwrap();
+        assert!(pattern.is_match("img_0042.JPG"));
+        assert!(!pattern.is_match("img_42.jpg"));
+        assert_eq!(
+            Pattern::new(r"\d+", true, true).unwrap().find("v12.3"),
+            Some((1, 3))
+        );
+    }
+
+    #[test]
+    fn test_spans_are_utf16() {
+        let pattern = Pattern::new("ab", false, false).unwrap();
+        // '😀' is two UTF-16 units, 'é' is one
+        assert_eq!(
+            pattern.spans("😀AB é ab"),
+            vec![
+                MatchSpan { start: 2, end: 4 },
+                MatchSpan { start: 7, end: 9 }
+            ]
+        );
+        assert_eq!(
+            char_positions_to_spans("a😀bc", &[1, 2, 3]),
+            vec![MatchSpan { start: 1, end: 5 }]
+        );
+        assert_eq!(
+            char_positions_to_spans("abcd", &[0, 2]),
+            vec![
+                MatchSpan { start: 0, end: 1 },
+                MatchSpan { start: 2, end: 3 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glob() {
+        let pattern = Pattern::glob("*.TXT", false).unwrap();
+        assert!(pattern.is_match("notes.txt"));
+        assert!(!pattern.is_match("notes.txt.bak"));
+        let pattern = Pattern::glob("IMG_????.jp[e!]g", true).unwrap();
+        assert!(pattern.is_match("IMG_0042.jpeg"));
+        assert!(pattern.is_match("IMG_0042.jp!g"));
+        assert!(!pattern.is_match("img_0042.jpeg"));
+        let pattern = Pattern::glob("report[!0-9].(draft)", false).unwrap();
+        assert!(pattern.is_match("reportA.(draft)"));
+        assert!(!pattern.is_match("report1.(draft)"));
+        // An unclosed class is a literal bracket
+        assert!(Pattern::glob("a[b", false).unwrap().is_match("a[b"));
+        assert!(looks_like_glob("*.pdf") && !looks_like_glob("report"));
+    }
+
+    #[test]
+    fn test_glob_bracket_in_class() {
+        // A ']' first in the class, negated or not, is a literal member
+        let pattern = Pattern::glob("a[]]b", false).unwrap();
+        assert!(pattern.is_match("a]b") && !pattern.is_match("axb"));
+        let pattern = Pattern::glob("a[!]]b", false).unwrap();
+        assert!(pattern.is_match("axb") && !pattern.is_match("a]b"));
+        // Nothing closes these, so the brackets are plain text
+        assert!(Pattern::glob("a[!]", false).unwrap().is_match("a[!]"));
+        assert!(Pattern::glob("a[]", false).unwrap().is_match("a[]"));
+    }
+
+    #[test]
+    fn test_regex_guards() {
+        assert!(Pattern::new("(unclosed", false, true).is_err());
+        assert!(Pattern::new(&"a".repeat(MAX_REGEX_LEN + 1), false, true).is_err());
+        assert!(Pattern::new(
+            &format!("{}a{}", "(".repeat(100), ")".repeat(100)),
+            false,
+            true
+        )
+        .is_err());
+        // Classic catastrophic-backtracking pattern finishes instantly with a linear-time engine
+        let evil = Pattern::new("(a+)+$", false, true).unwrap();
+        assert!(!evil.is_match(&format!("{}!", "a".repeat(10_000))));
+    }
+}
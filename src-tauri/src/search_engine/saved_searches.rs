@@ -0,0 +1,106 @@
+//! Saved Searches Module
+//!
+//! Named search definitions ("smart folders") kept in the settings store under
+//! `saved_searches`. Running one simply starts a live search with the stored
+//! root, query and options.
+
+use super::live_search::{self, SearchOptions};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+const SETTINGS_KEY: &str = "saved_searches";
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct SavedSearch {
+    pub id: String,
+    pub name: String,
+    pub root: String,
+    /// Full query text, including filter tokens such as `kind:image`
+    pub query: String,
+    #[ts(type = "Record<string, unknown>")]
+    pub options: SearchOptions,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+fn load() -> Vec<SavedSearch> {
+    crate::settings::SettingsStore::global()
+        .get(SETTINGS_KEY)
+        .unwrap_or_default()
+}
+
+fn store(searches: &[SavedSearch]) -> Result<(), String> {
+    crate::settings::SettingsStore::global().set(SETTINGS_KEY, &searches)
+}
+
+#[tauri::command]
+pub fn list_saved_searches() -> Vec<SavedSearch> {
+    load()
+}
+
+/// Saves a search; an existing entry with the same name (case-insensitive) is replaced
+#[tauri::command]
+pub fn save_search(
+    name: String,
+    root: String,
+    query: String,
+    options: Option<SearchOptions>,
+) -> Result<SavedSearch, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("A saved search needs a name".to_string());
+    }
+    if query.trim().is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+
+    let mut searches = load();
+    let now = chrono::Utc::now();
+    let existing = searches
+        .iter()
+        .position(|s| s.name.eq_ignore_ascii_case(&name));
+
+    let saved = SavedSearch {
+        id: existing
+            .map(|i| searches[i].id.clone())
+            .unwrap_or_else(|| format!("saved-{}", now.timestamp_millis())),
+        name,
+        root,
+        query: query.trim().to_string(),
+        options: options.unwrap_or_default(),
+        created_at: now.timestamp(),
+    };
+
+    match existing {
+        Some(i) => searches[i] = saved.clone(),
+        None => searches.push(saved.clone()),
+    }
+    store(&searches)?;
+
+    log::info!("[SEARCH] Saved search '{}' ({})", saved.name, saved.id);
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn delete_saved_search(id: String) -> Result<(), String> {
+    let mut searches = load();
+    let before = searches.len();
+    searches.retain(|s| s.id != id);
+    if searches.len() == before {
+        return Err(format!("Saved search not found: {}", id));
+    }
+    store(&searches)
+}
+
+/// Starts the saved search and returns the live search id (see `start_search`)
+#[tauri::command]
+pub fn run_saved_search(window: tauri::Window, id: String) -> Result<String, String> {
+    let saved = load()
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("Saved search not found: {}", id))?;
+
+    live_search::start_search(window, saved.root, saved.query, Some(saved.options))
+}
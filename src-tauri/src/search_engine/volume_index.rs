@@ -0,0 +1,516 @@
+//! Volume Index Module
+//!
+//! Everything-style instant search over whole NTFS drives. Each drive listed in
+//! the `instant_search_drives` preference gets an in-memory table of every name
+//! on the volume, read from the master file table (see `mft`) and kept current
+//! by polling the volume's USN change journal, which logs every create, delete
+//! and rename. `instant_search` scans those tables, which takes milliseconds
+//! even with millions of files.
+//!
+//! Tables are persisted together with the journal position they are current
+//! to, so a restart only replays what changed since. When the journal was
+//! recreated or has wrapped past that position the volume is read again. Both
+//! the file table and the journal need the raw volume, so drives only get an
+//! index while the app runs elevated.
+
+use super::folder_index::QuickSearchResult;
+use super::pattern::{self, Pattern};
+use crate::mft::{self, RECORD_NUMBER_MASK, ROOT_RECORD};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::os::windows::io::AsRawHandle;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::Ioctl::{
+    FSCTL_QUERY_USN_JOURNAL, FSCTL_READ_USN_JOURNAL, READ_USN_JOURNAL_DATA_V0, USN_JOURNAL_DATA_V0,
+    USN_REASON_FILE_CREATE, USN_REASON_FILE_DELETE, USN_REASON_RENAME_NEW_NAME, USN_RECORD_V2,
+};
+use windows::Win32::System::IO::DeviceIoControl;
+
+/// Let the app finish starting up before reading a whole volume
+const STARTUP_DELAY: Duration = Duration::from_secs(20);
+/// How often the journals are read
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Changed tables are written back at most this often
+const SAVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// A drive that couldn't be read (not elevated, not NTFS) is tried again after this
+const RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Size of the buffer journal records are read into
+const JOURNAL_BUFFER_BYTES: usize = 64 * 1024;
+/// Deeper parent chains than this can only be a loop in a damaged table
+const MAX_DEPTH: usize = 512;
+const DEFAULT_LIMIT: usize = 200;
+/// Journal reasons that change the name table; an old name is always followed by the new one
+const REASON_MASK: u32 =
+    USN_REASON_FILE_CREATE | USN_REASON_FILE_DELETE | USN_REASON_RENAME_NEW_NAME;
+const FILE_ATTRIBUTE_DIRECTORY: u32 = 0x10;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct NameRecord {
+    name: Box<str>,
+    /// Record number of the containing folder
+    parent: u64,
+    is_dir: bool,
+}
+
+/// One create, delete or rename read from the journal
+#[derive(Debug)]
+struct JournalChange {
+    record: u64,
+    parent: u64,
+    name: String,
+    is_dir: bool,
+    reason: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VolumeIndex {
+    /// Drive root, e.g. "C:\"
+    root: String,
+    journal_id: u64,
+    /// First journal entry not applied yet
+    next_usn: i64,
+    /// Record number -> name
+    records: HashMap<u64, NameRecord>,
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    saved_at: Option<Instant>,
+}
+
+static VOLUMES: RwLock<Vec<VolumeIndex>> = RwLock::new(Vec::new());
+
+impl VolumeIndex {
+    fn apply(&mut self, change: JournalChange) {
+        if change.reason & USN_REASON_FILE_DELETE != 0 {
+            self.records.remove(&change.record);
+        } else {
+            self.records.insert(
+                change.record,
+                NameRecord {
+                    name: change.name.into_boxed_str(),
+                    parent: change.parent,
+                    is_dir: change.is_dir,
+                },
+            );
+        }
+        self.dirty = true;
+    }
+
+    /// Full path of `record`; None when a folder on the way is missing
+    fn path(&self, mut record: u64) -> Option<String> {
+        let mut names = Vec::new();
+        while record != ROOT_RECORD as u64 {
+            if names.len() > MAX_DEPTH {
+                return None;
+            }
+            let entry = self.records.get(&record)?;
+            names.push(&*entry.name);
+            record = entry.parent;
+        }
+        names.reverse();
+        Some(format!("{}{}", self.root, names.join("\\")))
+    }
+
+    /// Records whose name matches, shortest names first
+    fn search(&self, pattern: &Pattern, limit: usize) -> Vec<(&str, u64)> {
+        let mut matches: Vec<(&str, u64)> = self
+            .records
+            .iter()
+            .filter(|(&record, entry)| {
+                record != ROOT_RECORD as u64 && pattern.is_match(&entry.name)
+            })
+            .map(|(&record, entry)| (&*entry.name, record))
+            .collect();
+        let by_length = |a: &(&str, u64), b: &(&str, u64)| (a.0.len(), a.0).cmp(&(b.0.len(), b.0));
+        if matches.len() > limit && limit > 0 {
+            matches.select_nth_unstable_by(limit - 1, by_length);
+            matches.truncate(limit);
+        }
+        matches.sort_unstable_by(by_length);
+        matches.truncate(limit);
+        matches
+    }
+}
+
+fn index_file(root: &str) -> PathBuf {
+    let safe_name = root.replace(|c: char| !c.is_alphanumeric(), "_");
+    crate::paths::app_data_dir()
+        .join("indices")
+        .join(format!("{}.volume", safe_name))
+}
+
+fn open_volume(root: &str) -> Result<File, String> {
+    let device = mft::volume_device(Path::new(root)).ok_or("Not a drive root")?;
+    File::open(&device).map_err(|e| format!("Failed to open {}: {}", device, e))
+}
+
+fn query_journal(volume: &File) -> Result<USN_JOURNAL_DATA_V0, String> {
+    let mut data = USN_JOURNAL_DATA_V0::default();
+    unsafe {
+        DeviceIoControl(
+            HANDLE(volume.as_raw_handle()),
+            FSCTL_QUERY_USN_JOURNAL,
+            None,
+            0,
+            Some(&mut data as *mut _ as *mut _),
+            std::mem::size_of::<USN_JOURNAL_DATA_V0>() as u32,
+            None,
+            None,
+        )
+    }
+    .map_err(|e| format!("No change journal: {}", e))?;
+    Ok(data)
+}
+
+/// Decodes the USN_RECORD_V2 entries that follow the next USN in a journal read
+fn parse_journal_records(data: &[u8]) -> Vec<JournalChange> {
+    let header = std::mem::size_of::<USN_RECORD_V2>();
+    let mut changes = Vec::new();
+    let mut offset = 0;
+    while offset + header <= data.len() {
+        let record =
+            unsafe { std::ptr::read_unaligned(data[offset..].as_ptr() as *const USN_RECORD_V2) };
+        let length = record.RecordLength as usize;
+        if length < header || offset + length > data.len() {
+            break;
+        }
+        // Version 3 records carry 128-bit references, which only ReFS hands out
+        if record.MajorVersion == 2 {
+            let start = offset + record.FileNameOffset as usize;
+            let name: Vec<u16> = data
+                .get(start..start + record.FileNameLength as usize)
+                .unwrap_or_default()
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            changes.push(JournalChange {
+                record: record.FileReferenceNumber & RECORD_NUMBER_MASK,
+                parent: record.ParentFileReferenceNumber & RECORD_NUMBER_MASK,
+                name: String::from_utf16_lossy(&name),
+                is_dir: record.FileAttributes & FILE_ATTRIBUTE_DIRECTORY != 0,
+                reason: record.Reason,
+            });
+        }
+        offset += length;
+    }
+    changes
+}
+
+/// Applies every journal entry written since the index was last current
+fn catch_up(index: &mut VolumeIndex, volume: &File) -> Result<(), String> {
+    let mut buffer = vec![0u64; JOURNAL_BUFFER_BYTES / 8];
+    loop {
+        let request = READ_USN_JOURNAL_DATA_V0 {
+            StartUsn: index.next_usn,
+            ReasonMask: REASON_MASK,
+            ReturnOnlyOnClose: 0,
+            Timeout: 0,
+            BytesToWaitFor: 0,
+            UsnJournalID: index.journal_id,
+        };
+        let mut returned = 0u32;
+        unsafe {
+            DeviceIoControl(
+                HANDLE(volume.as_raw_handle()),
+                FSCTL_READ_USN_JOURNAL,
+                Some(&request as *const _ as *const _),
+                std::mem::size_of::<READ_USN_JOURNAL_DATA_V0>() as u32,
+                Some(buffer.as_mut_ptr() as *mut _),
+                JOURNAL_BUFFER_BYTES as u32,
+                Some(&mut returned),
+                None,
+            )
+        }
+        .map_err(|e| format!("Failed to read the change journal: {}", e))?;
+
+        let data =
+            unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, returned as usize) };
+        let Some(next_usn) = data
+            .get(..8)
+            .map(|b| i64::from_le_bytes(b.try_into().unwrap()))
+        else {
+            return Ok(());
+        };
+        for change in parse_journal_records(&data[8..]) {
+            index.apply(change);
+        }
+        if next_usn == index.next_usn {
+            return Ok(());
+        }
+        index.next_usn = next_usn;
+    }
+}
+
+/// Reads the whole volume; the journal position is taken first so nothing changed
+/// while the table is read gets lost
+fn build(root: &str, volume: &File) -> Result<VolumeIndex, String> {
+    let journal = query_journal(volume)?;
+    let started = Instant::now();
+    let table = mft::read_volume(Path::new(root), &|| false)?;
+    let records = table
+        .files()
+        .map(|(number, file)| {
+            (
+                number,
+                NameRecord {
+                    name: file.name.clone(),
+                    parent: file.parent,
+                    is_dir: file.is_dir,
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    log::info!(
+        "[VOLUME_INDEX] Read {} names from {} in {:?}",
+        records.len(),
+        root,
+        started.elapsed()
+    );
+    Ok(VolumeIndex {
+        root: root.to_string(),
+        journal_id: journal.UsnJournalID,
+        next_usn: journal.NextUsn,
+        records,
+        dirty: true,
+        saved_at: None,
+    })
+}
+
+/// The persisted table of `root`, if its journal still reaches back to where it stopped
+fn load(root: &str, volume: &File) -> Option<VolumeIndex> {
+    let data = std::fs::read(index_file(root)).ok()?;
+    let index: VolumeIndex = bincode::deserialize(&data).ok()?;
+    let journal = query_journal(volume).ok()?;
+    (index.root == root
+        && index.journal_id == journal.UsnJournalID
+        && index.next_usn >= journal.FirstUsn)
+        .then_some(index)
+}
+
+fn save(index: &mut VolumeIndex) {
+    let file = index_file(&index.root);
+    let _ = std::fs::create_dir_all(file.parent().unwrap_or(Path::new(".")));
+    match bincode::serialize(&*index) {
+        Ok(data) => match std::fs::write(&file, data) {
+            Ok(()) => {
+                index.dirty = false;
+                index.saved_at = Some(Instant::now());
+            }
+            Err(e) => log::error!("[VOLUME_INDEX] Failed to save {:?}: {}", file, e),
+        },
+        Err(e) => log::error!("[VOLUME_INDEX] Failed to serialize {}: {}", index.root, e),
+    }
+}
+
+/// Opens or builds the index of `root` and brings it up to date
+fn open_index(root: &str) -> Result<(VolumeIndex, File), String> {
+    let volume = open_volume(root)?;
+    let mut index = match load(root, &volume) {
+        Some(index) => index,
+        None => build(root, &volume)?,
+    };
+    catch_up(&mut index, &volume)?;
+    save(&mut index);
+    Ok((index, volume))
+}
+
+/// Normalizes a preference entry such as "c", "C:" or "c:\" to "C:\"
+fn drive_root(drive: &str) -> Option<String> {
+    match drive.trim().trim_end_matches(['\\', '/']).as_bytes() {
+        [l] | [l, b':'] if l.is_ascii_alphabetic() => {
+            Some(format!("{}:\\", (*l as char).to_ascii_uppercase()))
+        }
+        _ => None,
+    }
+}
+
+fn configured_roots() -> Vec<String> {
+    crate::settings::preferences()
+        .instant_search_drives
+        .iter()
+        .filter_map(|d| drive_root(d))
+        .collect()
+}
+
+/// Starts the background indexer. Called once from app setup.
+pub fn start() {
+    std::thread::spawn(|| {
+        std::thread::sleep(STARTUP_DELAY);
+        let mut volumes: HashMap<String, File> = HashMap::new();
+        let mut failed: HashMap<String, Instant> = HashMap::new();
+        loop {
+            let roots = configured_roots();
+
+            // Drives taken out of the preference are dropped; their saved tables stay on disk
+            volumes.retain(|root, _| roots.contains(root));
+            VOLUMES.write().retain(|index| roots.contains(&index.root));
+
+            for root in &roots {
+                if volumes.contains_key(root)
+                    || failed
+                        .get(root)
+                        .is_some_and(|at| at.elapsed() < RETRY_INTERVAL)
+                {
+                    continue;
+                }
+                match open_index(root) {
+                    Ok((index, volume)) => {
+                        failed.remove(root);
+                        volumes.insert(root.clone(), volume);
+                        VOLUMES.write().push(index);
+                    }
+                    Err(e) => {
+                        log::warn!("[VOLUME_INDEX] No index for {}: {}", root, e);
+                        failed.insert(root.clone(), Instant::now());
+                    }
+                }
+            }
+
+            let mut broken = Vec::new();
+            for index in VOLUMES.write().iter_mut() {
+                let Some(volume) = volumes.get(&index.root) else {
+                    continue;
+                };
+                if let Err(e) = catch_up(index, volume) {
+                    // The journal was deleted or wrapped; read the volume again
+                    log::warn!("[VOLUME_INDEX] Rebuilding {}: {}", index.root, e);
+                    broken.push(index.root.clone());
+                } else if index.dirty
+                    && index
+                        .saved_at
+                        .is_none_or(|at| at.elapsed() >= SAVE_INTERVAL)
+                {
+                    save(index);
+                }
+            }
+            for root in broken {
+                volumes.remove(&root);
+                let _ = std::fs::remove_file(index_file(&root));
+                VOLUMES.write().retain(|index| index.root != root);
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Searches the names of every indexed drive. Plain queries match anywhere in the
+/// name, wildcard queries the whole name. Returns `indexed: false` when no drive
+/// has an index yet.
+#[tauri::command]
+pub async fn instant_search(
+    query: String,
+    limit: Option<usize>,
+) -> Result<QuickSearchResult, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let pattern = if pattern::looks_like_glob(&query) {
+        Pattern::glob(&query, false)?
+    } else {
+        Pattern::new(&query, false, false)?
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        let started = Instant::now();
+        let paths: Vec<String> = {
+            let volumes = VOLUMES.read();
+            if volumes.is_empty() {
+                return QuickSearchResult {
+                    indexed: false,
+                    entries: Vec::new(),
+                };
+            }
+            volumes
+                .iter()
+                .flat_map(|index| {
+                    index
+                        .search(&pattern, limit)
+                        .into_iter()
+                        .filter_map(|(_, record)| index.path(record))
+                })
+                .take(limit)
+                .collect()
+        };
+        log::debug!(
+            "[VOLUME_INDEX] '{}': {} results in {:?}",
+            query,
+            paths.len(),
+            started.elapsed()
+        );
+        let entries = paths
+            .iter()
+            // The journal is polled, so a result may be a moment out of date
+            .filter_map(|path| crate::get_file_entry(Path::new(path)).ok())
+            .collect();
+        QuickSearchResult {
+            indexed: true,
+            entries,
+        }
+    })
+    .await
+    .map_err(|e| format!("Instant search failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(record: u64, parent: u64, name: &str, reason: u32) -> JournalChange {
+        JournalChange {
+            record,
+            parent,
+            name: name.to_string(),
+            is_dir: record < 100,
+            reason,
+        }
+    }
+
+    #[test]
+    fn test_journal_changes() {
+        let mut index = VolumeIndex {
+            root: "C:\\".to_string(),
+            journal_id: 1,
+            next_usn: 0,
+            records: HashMap::new(),
+            dirty: false,
+            saved_at: None,
+        };
+        index.apply(change(
+            40,
+            ROOT_RECORD as u64,
+            "Projects",
+            USN_REASON_FILE_CREATE,
+        ));
+        index.apply(change(200, 40, "report.txt", USN_REASON_FILE_CREATE));
+        index.apply(change(201, 40, "notes.txt", USN_REASON_FILE_CREATE));
+        index.apply(change(
+            201,
+            40,
+            "old report.txt",
+            USN_REASON_RENAME_NEW_NAME,
+        ));
+        index.apply(change(200, 40, "report.txt", USN_REASON_FILE_DELETE));
+
+        let pattern = Pattern::new("REPORT", false, false).unwrap();
+        let found = index.search(&pattern, 10);
+        assert_eq!(found, vec![("old report.txt", 201)]);
+        assert_eq!(
+            index.path(201).as_deref(),
+            Some("C:\\Projects\\old report.txt")
+        );
+        // Orphans whose folder isn't known resolve to nothing
+        index.apply(change(300, 99, "lost.txt", USN_REASON_FILE_CREATE));
+        assert_eq!(index.path(300), None);
+
+        assert_eq!(drive_root("d").as_deref(), Some("D:\\"));
+        assert_eq!(drive_root("d:/").as_deref(), Some("D:\\"));
+        assert_eq!(drive_root("\\\\NAS"), None);
+    }
+}
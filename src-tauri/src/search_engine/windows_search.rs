@@ -0,0 +1,314 @@
+//! Windows Search Module
+//!
+//! Queries the index the Windows Search service already keeps (the
+//! `SystemIndex` catalog) instead of building one of our own. It covers the
+//! libraries, the user profile and whatever else the user added to the
+//! indexing options, and it matches file contents as well as names.
+//!
+//! `ISearchQueryHelper` turns the text part of the query into the catalog's
+//! SQL dialect; the `kind:`, `ext:`, `size:` and `modified:` filters the live
+//! search understands (see `filters`) become WHERE restrictions. The SQL runs
+//! through the Search.CollatorDSO OLE DB provider, and the paths it returns are
+//! turned back into `FileEntry` records.
+
+use super::filters::{self, Filter, Kind};
+use crate::FileEntry;
+use std::path::Path;
+use windows::core::{w, IUnknown, Interface, GUID, HSTRING, PWSTR};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_INPROC_SERVER,
+    CLSCTX_LOCAL_SERVER, COINIT_MULTITHREADED,
+};
+use windows::Win32::System::Search::{
+    CSearchManager, IAccessor, ICommandText, IDBCreateCommand, IDBCreateSession, IDBInitialize,
+    IDataInitialize, IRowset, ISearchManager, DBACCESSOR_ROWDATA, DBBINDING,
+    DBMEMOWNER_CLIENTOWNED, DBPARAMIO_NOTPARAM, DBPART_LENGTH, DBPART_STATUS, DBPART_VALUE,
+    DBSTATUS_S_OK, DBTYPE_WSTR, DB_NULL_HCHAPTER, HACCESSOR, MSDAINITIALIZE,
+};
+
+/// The command dialect the search provider expects
+const DBGUID_DEFAULT: GUID = GUID::from_u128(0xc8b521fb_5cf3_11ce_ade5_00aa0044773d);
+/// Longest path a result column can hold, in UTF-16 units
+const MAX_PATH_CHARS: usize = 32 * 1024;
+const DEFAULT_LIMIT: usize = 200;
+
+/// Row buffer for the one column that is read, System.ItemPathDisplay
+#[repr(C)]
+struct PathColumn {
+    status: u32,
+    /// Bytes, without the terminator
+    length: usize,
+    value: [u16; MAX_PATH_CHARS],
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Timestamp literal for Unix seconds; the index stores dates in UTC
+fn sql_date(seconds: i64) -> String {
+    let date = chrono::DateTime::from_timestamp(seconds, 0).unwrap_or_default();
+    sql_string(&date.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn extension_clause<S: AsRef<str>>(extensions: &[S]) -> String {
+    let list: Vec<String> = extensions
+        .iter()
+        .map(|e| sql_string(&format!(".{}", e.as_ref())))
+        .collect();
+    format!("System.FileExtension IN ({})", list.join(", "))
+}
+
+/// WHERE restrictions for the scope and the query's filters, in the
+/// "AND ..." form `SetQueryWhereRestrictions` appends to the text query
+fn where_restrictions(scope: Option<&str>, filters: &[Filter]) -> String {
+    let mut clauses = Vec::new();
+    if let Some(scope) = scope {
+        clauses.push(format!("SCOPE={}", sql_string(&format!("file:{}", scope))));
+    }
+    for filter in filters {
+        match filter {
+            Filter::Size { min, max } => {
+                clauses.push(format!("System.Size >= {}", min));
+                if *max != u64::MAX {
+                    clauses.push(format!("System.Size <= {}", max));
+                }
+            }
+            Filter::Modified { from, to } => {
+                if *from != i64::MIN {
+                    clauses.push(format!("System.DateModified >= {}", sql_date(*from)));
+                }
+                if *to != i64::MAX {
+                    clauses.push(format!("System.DateModified < {}", sql_date(*to)));
+                }
+            }
+            Filter::Extension(extensions) => clauses.push(extension_clause(extensions)),
+            Filter::Kind(Kind::Folder) => clauses.push("System.Kind = 'folder'".to_string()),
+            // Same extensions as the live search, rather than the index's own kind names
+            Filter::Kind(kind) => clauses.push(extension_clause(kind.extensions())),
+        }
+    }
+    clauses
+        .iter()
+        .map(|c| format!("AND {}", c))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Takes ownership of a string the search API allocated
+unsafe fn take_string(value: PWSTR) -> Result<String, String> {
+    let result = value.to_string().map_err(|e| e.to_string());
+    CoTaskMemFree(Some(value.as_ptr() as *const _));
+    result
+}
+
+/// Builds the SQL for `query` and the connection string of the catalog
+unsafe fn build_sql(
+    query: &str,
+    scope: Option<&str>,
+    limit: usize,
+) -> Result<(String, String), String> {
+    let parsed = filters::parse_query(query);
+    let manager: ISearchManager = CoCreateInstance(&CSearchManager, None, CLSCTX_LOCAL_SERVER)
+        .map_err(|e| format!("Windows Search is not available: {}", e))?;
+    let helper = manager
+        .GetCatalog(w!("SystemIndex"))
+        .and_then(|catalog| catalog.GetQueryHelper())
+        .map_err(|e| format!("Failed to open the search index: {}", e))?;
+
+    helper
+        .SetQuerySelectColumns(w!("System.ItemPathDisplay"))
+        .and_then(|_| {
+            helper.SetQueryWhereRestrictions(&HSTRING::from(where_restrictions(
+                scope,
+                &parsed.filters,
+            )))
+        })
+        .and_then(|_| helper.SetQuerySorting(w!("System.Search.Rank DESC")))
+        .and_then(|_| helper.SetQueryMaxResults(limit.min(i32::MAX as usize) as i32))
+        .map_err(|e| format!("Failed to set up the search: {}", e))?;
+    let sql = helper
+        .GenerateSQLFromUserQuery(&HSTRING::from(parsed.text.trim()))
+        .map_err(|e| format!("Invalid search query: {}", e))?;
+    let sql = take_string(sql)?;
+    let connection = take_string(helper.ConnectionString().map_err(|e| e.to_string())?)?;
+    Ok((sql, connection))
+}
+
+/// Runs `sql` against the catalog and returns the paths of the rows
+unsafe fn run_sql(sql: &str, connection: &str, limit: usize) -> Result<Vec<String>, String> {
+    let initialize: IDataInitialize = CoCreateInstance(&MSDAINITIALIZE, None, CLSCTX_INPROC_SERVER)
+        .map_err(|e| format!("OLE DB is not available: {}", e))?;
+    let mut source: Option<IUnknown> = None;
+    initialize
+        .GetDataSource(
+            None,
+            CLSCTX_INPROC_SERVER.0,
+            &HSTRING::from(connection),
+            &IDBInitialize::IID,
+            &mut source,
+        )
+        .map_err(|e| format!("Failed to connect to the search index: {}", e))?;
+    let source: IDBInitialize = source
+        .ok_or("The search provider returned no data source")?
+        .cast()
+        .map_err(|e| e.to_string())?;
+    source
+        .Initialize()
+        .map_err(|e| format!("Failed to connect to the search index: {}", e))?;
+
+    let command: ICommandText = source
+        .cast::<IDBCreateSession>()
+        .and_then(|s| s.CreateSession(None, &IDBCreateCommand::IID))
+        .and_then(|s| s.cast::<IDBCreateCommand>())
+        .and_then(|s| s.CreateCommand(None, &ICommandText::IID))
+        .and_then(|c| c.cast())
+        .map_err(|e| format!("Failed to open a search session: {}", e))?;
+    command
+        .SetCommandText(&DBGUID_DEFAULT, &HSTRING::from(sql))
+        .map_err(|e| e.to_string())?;
+    let mut rowset: Option<IUnknown> = None;
+    command
+        .Execute(None, &IRowset::IID, None, None, Some(&mut rowset))
+        .map_err(|e| format!("Search failed: {}", e))?;
+    let rowset: IRowset = rowset
+        .ok_or("The search returned no rows")?
+        .cast()
+        .map_err(|e| e.to_string())?;
+
+    let accessor: IAccessor = rowset.cast().map_err(|e| e.to_string())?;
+    let binding = DBBINDING {
+        iOrdinal: 1,
+        obValue: std::mem::offset_of!(PathColumn, value),
+        obLength: std::mem::offset_of!(PathColumn, length),
+        obStatus: std::mem::offset_of!(PathColumn, status),
+        dwPart: (DBPART_VALUE.0 | DBPART_LENGTH.0 | DBPART_STATUS.0) as u32,
+        dwMemOwner: DBMEMOWNER_CLIENTOWNED.0 as u32,
+        eParamIO: DBPARAMIO_NOTPARAM.0 as u32,
+        cbMaxLen: MAX_PATH_CHARS * 2,
+        wType: DBTYPE_WSTR.0 as u16,
+        ..Default::default()
+    };
+    let mut handle = HACCESSOR::default();
+    accessor
+        .CreateAccessor(
+            DBACCESSOR_ROWDATA.0 as u32,
+            1,
+            &binding,
+            std::mem::size_of::<PathColumn>(),
+            &mut handle,
+            None,
+        )
+        .map_err(|e| format!("Failed to read the search results: {}", e))?;
+
+    let mut column = Box::new(PathColumn {
+        status: 0,
+        length: 0,
+        value: [0; MAX_PATH_CHARS],
+    });
+    let mut paths = Vec::new();
+    while paths.len() < limit {
+        // One row at a time: the provider fills the HROW array the slice points to
+        let mut row = 0usize;
+        let mut rows = [&mut row as *mut usize];
+        let mut obtained = 0usize;
+        if rowset
+            .GetNextRows(DB_NULL_HCHAPTER as usize, 0, &mut obtained, &mut rows)
+            .is_err()
+            || obtained == 0
+        {
+            break;
+        }
+        let read = rowset.GetData(row, handle, &mut *column as *mut PathColumn as *mut _);
+        let _ = rowset.ReleaseRows(
+            1,
+            &row,
+            std::ptr::null(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        if read.is_ok() && column.status == DBSTATUS_S_OK.0 as u32 {
+            let chars = (column.length / 2).min(MAX_PATH_CHARS);
+            paths.push(String::from_utf16_lossy(&column.value[..chars]));
+        }
+    }
+    let _ = accessor.ReleaseAccessor(handle, None);
+    Ok(paths)
+}
+
+fn query_index(query: &str, scope: Option<&str>, limit: usize) -> Result<Vec<String>, String> {
+    unsafe {
+        // Callers may be on a tokio worker without COM; only balance what we initialized
+        let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        let result = build_sql(query, scope, limit)
+            .and_then(|(sql, connection)| run_sql(&sql, &connection, limit));
+        if com_initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+/// Searches the Windows Search index for names and contents matching `query`, which
+/// may contain the same `kind:`, `ext:`, `size:` and `modified:` filters as the
+/// live search. `scope` limits the results to one folder tree; None searches
+/// everything that is indexed.
+#[tauri::command]
+pub async fn windows_search(
+    query: String,
+    scope: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<FileEntry>, String> {
+    if query.trim().is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let scope = scope
+        .map(|s| crate::expand_env_vars(&s))
+        .filter(|s| !s.is_empty());
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        let paths = query_index(&query, scope.as_deref(), limit)?;
+        log::info!(
+            "[WINDOWS_SEARCH] '{}' in {:?}: {} results",
+            query,
+            scope,
+            paths.len()
+        );
+        Ok(paths
+            .iter()
+            // The index updates in the background, so results can be stale
+            .filter_map(|path| crate::get_file_entry(Path::new(path)).ok())
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_where_restrictions() {
+        assert_eq!(where_restrictions(None, &[]), "");
+        let filters = [
+            Filter::Kind(Kind::Folder),
+            Filter::Extension(vec!["pdf".to_string(), "md".to_string()]),
+            Filter::Size {
+                min: 1024,
+                max: u64::MAX,
+            },
+            Filter::Modified {
+                from: 0,
+                to: i64::MAX,
+            },
+        ];
+        assert_eq!(
+            where_restrictions(Some("C:\\Users\\O'Brien"), &filters),
+            "AND SCOPE='file:C:\\Users\\O''Brien' AND System.Kind = 'folder' \
+             AND System.FileExtension IN ('.pdf', '.md') AND System.Size >= 1024 \
+             AND System.DateModified >= '1970-01-01 00:00:00'"
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Session Module
+//!
+//! The open tabs (folder, selection, sort order and view mode) and the active
+//! tab, persisted to `session.json` in the app data folder so the next start
+//! reopens where the user left off. The UI reports every change through
+//! `save_session`; writes are debounced on a background thread, and `flush`
+//! saves whatever is still pending when the window closes. `get_last_session`
+//! checks the saved paths: tabs whose folder is gone open its nearest
+//! surviving parent instead.
+
+use crate::settings::SortPreference;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use ts_rs::TS;
+
+/// Quiet period after the last change before the session is written
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug, Default, PartialEq)]
+#[serde(default)]
+#[ts(export)]
+pub struct SessionTab {
+    pub id: String,
+    /// Folder shown in the tab; "" is This PC, "shell:" paths are virtual folders
+    pub path: String,
+    /// Full paths of the selected items
+    pub selected_files: Vec<String>,
+    /// None uses the default sort
+    pub sort: Option<SortPreference>,
+    /// "list" or "grid"; None uses the list
+    pub view_mode: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug, Default, PartialEq)]
+#[serde(default)]
+#[ts(export)]
+pub struct Session {
+    pub tabs: Vec<SessionTab>,
+    pub active_tab_id: Option<String>,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct RestoredTab {
+    pub id: String,
+    pub path: String,
+    /// Selected items that still exist
+    pub selected_files: Vec<String>,
+    /// The saved folder when it no longer exists and `path` is its nearest parent
+    pub missing_path: Option<String>,
+    pub sort: Option<SortPreference>,
+    pub view_mode: Option<String>,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct RestoredSession {
+    pub tabs: Vec<RestoredTab>,
+    pub active_tab_id: Option<String>,
+}
+
+struct SessionWriter {
+    /// Latest session not yet on disk
+    pending: Mutex<Option<Session>>,
+    /// Wakes the writer thread on every change
+    changed: Mutex<Sender<()>>,
+}
+
+static WRITER: OnceLock<SessionWriter> = OnceLock::new();
+
+fn session_file() -> PathBuf {
+    crate::paths::app_data_dir().join("session.json")
+}
+
+fn writer() -> &'static SessionWriter {
+    WRITER.get_or_init(|| {
+        let (tx, rx) = channel::<()>();
+        std::thread::spawn(move || {
+            while rx.recv().is_ok() {
+                // Keep waiting while changes keep coming
+                loop {
+                    match rx.recv_timeout(SAVE_DELAY) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                flush();
+            }
+        });
+        SessionWriter {
+            pending: Mutex::new(None),
+            changed: Mutex::new(tx),
+        }
+    })
+}
+
+fn write_session(session: &Session) -> Result<(), String> {
+    let file = session_file();
+    let data = serde_json::to_vec_pretty(session).map_err(|e| e.to_string())?;
+    // Write then rename so a crash never leaves a truncated session behind
+    let tmp_file = file.with_extension("json.tmp");
+    std::fs::write(&tmp_file, data)
+        .and_then(|_| std::fs::rename(&tmp_file, &file))
+        .map_err(|e| format!("Failed to save session: {}", e))
+}
+
+/// Writes the pending session, if any, right away; called when the window closes
+pub fn flush() {
+    let Some(writer) = WRITER.get() else {
+        return;
+    };
+    let pending = writer.pending.lock().ok().and_then(|mut p| p.take());
+    if let Some(session) = pending {
+        if let Err(e) = write_session(&session) {
+            log::error!("[SESSION] {}", e);
+        }
+    }
+}
+
+/// The saved session file as is, for backups
+pub fn to_json() -> Option<Vec<u8>> {
+    flush();
+    std::fs::read(session_file()).ok()
+}
+
+/// Replaces the saved session with a backup; takes effect on the next start
+pub fn replace_from_json(data: &[u8]) -> Result<(), String> {
+    let session: Session =
+        serde_json::from_slice(data).map_err(|e| format!("Invalid session backup: {}", e))?;
+    // Drop any pending save so it can't overwrite the restored session
+    if let Some(writer) = WRITER.get() {
+        if let Ok(mut pending) = writer.pending.lock() {
+            *pending = None;
+        }
+    }
+    write_session(&session)
+}
+
+fn is_virtual(path: &str) -> bool {
+    path.is_empty() || path.starts_with("shell:")
+}
+
+/// `path` itself when it exists, otherwise its closest existing parent ("" when the drive is gone)
+fn nearest_existing(path: &str) -> String {
+    Path::new(&crate::expand_env_vars(path))
+        .ancestors()
+        .find(|p| p.is_dir())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn restore_tab(tab: SessionTab) -> RestoredTab {
+    if is_virtual(&tab.path) || Path::new(&crate::expand_env_vars(&tab.path)).is_dir() {
+        let selected_files = tab
+            .selected_files
+            .into_iter()
+            .filter(|f| Path::new(f).exists())
+            .collect();
+        return RestoredTab {
+            id: tab.id,
+            path: tab.path,
+            selected_files,
+            missing_path: None,
+            sort: tab.sort,
+            view_mode: tab.view_mode,
+        };
+    }
+    log::warn!("[SESSION] Saved folder no longer exists: {}", tab.path);
+    RestoredTab {
+        id: tab.id,
+        path: nearest_existing(&tab.path),
+        selected_files: Vec::new(),
+        missing_path: Some(tab.path),
+        sort: tab.sort,
+        view_mode: tab.view_mode,
+    }
+}
+
+/// Records the current tabs; they are written to disk shortly after changes stop
+#[tauri::command]
+pub fn save_session(session: Session) -> Result<(), String> {
+    let writer = writer();
+    *writer
+        .pending
+        .lock()
+        .map_err(|e| format!("Session lock poisoned: {}", e))? = Some(session);
+    let _ = writer.changed.lock().map(|tx| tx.send(()));
+    Ok(())
+}
+
+/// Tabs saved by the previous run, with vanished folders replaced by their nearest parent
+#[tauri::command]
+pub fn get_last_session() -> Option<RestoredSession> {
+    let data = std::fs::read(session_file()).ok()?;
+    let session: Session = match serde_json::from_slice(&data) {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("[SESSION] Ignoring unreadable session file: {}", e);
+            return None;
+        }
+    };
+    if session.tabs.is_empty() {
+        return None;
+    }
+    Some(RestoredSession {
+        tabs: session.tabs.into_iter().map(restore_tab).collect(),
+        active_tab_id: session.active_tab_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_missing_folder_falls_back_to_parent() {
+        let gone = std::env::temp_dir().join("quick-explorer-session-test-missing");
+        // temp_dir() may end with a separator; ancestors() never do
+        let parent = gone.parent().unwrap();
+        let tab = SessionTab {
+            id: "1".to_string(),
+            path: gone.to_string_lossy().to_string(),
+            selected_files: vec![gone.join("a.txt").to_string_lossy().to_string()],
+            view_mode: Some("grid".to_string()),
+            ..Default::default()
+        };
+        let restored = restore_tab(tab);
+        assert_eq!(restored.path, parent.to_string_lossy());
+        assert_eq!(
+            restored.missing_path.as_deref(),
+            Some(&*gone.to_string_lossy())
+        );
+        assert!(restored.selected_files.is_empty());
+        // The tab keeps its view even when its folder moves up
+        assert_eq!(restored.view_mode.as_deref(), Some("grid"));
+
+        let this_pc = restore_tab(SessionTab::default());
+        assert_eq!(this_pc.path, "");
+        assert!(this_pc.missing_path.is_none());
+    }
+}
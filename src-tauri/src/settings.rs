@@ -0,0 +1,417 @@
+//! Settings Module
+//!
+//! Backend-owned key/value store persisted as `settings.json` in the app data
+//! folder. Values are arbitrary JSON so each feature can keep its own typed
+//! structure under a key of its own (e.g. `saved_searches`).
+//!
+//! General preferences live under `preferences` as one typed `Preferences`
+//! value: the UI reads and writes it through `get_settings`/`set_settings`
+//! and hears about changes through `settings-changed`, while backend modules
+//! read the fields they need with `preferences()`. `get_setting`/`set_setting`
+//! read and change a single preference by its field name.
+//!
+//! The file records the schema version it was written with; older files are
+//! upgraded by `MIGRATIONS` when loaded (or restored from a backup), and files
+//! from a newer version are used as they are.
+
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, OnceLock};
+use tauri::Emitter;
+use ts_rs::TS;
+
+const PREFERENCES_KEY: &str = "preferences";
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+/// Keys that never leave this machine in a backup
+const SECRET_SETTINGS: [&str; 1] = [crate::reputation::API_KEY_SETTING];
+
+/// Upgrades from each schema version to the next, indexed by the version they upgrade from
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[
+    // 0 -> 1: files from before versioning; every key already has its current shape
+    |_| {},
+];
+const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// `size_units` as last read: 0 when unknown, else the variant's index + 1.
+/// Sizes are formatted for every listed file, too often to parse the preferences each time.
+static SIZE_UNITS: AtomicU8 = AtomicU8::new(0);
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug, PartialEq)]
+#[serde(default)]
+#[ts(export)]
+pub struct SortPreference {
+    /// Column id, e.g. "name", "size", "modified"
+    pub column: String,
+    /// "asc" or "desc"
+    pub direction: String,
+}
+
+impl Default for SortPreference {
+    fn default() -> Self {
+        Self {
+            column: "name".to_string(),
+            direction: "asc".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, TS, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SizeUnits {
+    /// 1 KB = 1024 bytes, as Explorer counts
+    #[default]
+    Binary,
+    /// 1 KB = 1000 bytes, as drive makers count
+    Decimal,
+}
+
+impl SizeUnits {
+    /// "512 B", "1.5 KB", "12.0 MB", "1.25 GB"
+    pub fn format(self, size: u64) -> String {
+        let unit = match self {
+            SizeUnits::Binary => 1024.0,
+            SizeUnits::Decimal => 1000.0,
+        };
+        let bytes = size as f64;
+        if bytes < unit {
+            format!("{} B", size)
+        } else if bytes < unit * unit {
+            format!("{:.1} KB", bytes / unit)
+        } else if bytes < unit * unit * unit {
+            format!("{:.1} MB", bytes / (unit * unit))
+        } else {
+            format!("{:.2} GB", bytes / (unit * unit * unit))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug, PartialEq)]
+#[serde(default)]
+#[ts(export)]
+pub struct Preferences {
+    pub show_hidden: bool,
+    pub default_sort: SortPreference,
+    /// Appended to a name that is already taken; `{n}` becomes 2, 3, ...
+    pub duplicate_suffix: String,
+    /// Thumbnails kept in memory; applied on the next start
+    pub thumbnail_cache_entries: usize,
+    /// Thumbnails and ffmpeg probes generated at once; 0 uses half the CPU cores.
+    /// Applied on the next start
+    pub thumbnail_workers: usize,
+    /// Custom ffmpeg binary; None uses the bundled one, or the one on PATH
+    pub ffmpeg_path: Option<String>,
+    /// Closing the window hides it to the tray icon instead of exiting
+    pub close_to_tray: bool,
+    /// Set through `set_window_backdrop`, which also applies it
+    pub window_backdrop: crate::appearance::BackdropKind,
+    /// "auto", "en" or "es", as chosen in the UI; names file types and generated files
+    pub language: String,
+    /// Drive roots ("C:\\") kept in the instant search index; needs the app to run elevated
+    pub instant_search_drives: Vec<String>,
+    pub size_units: SizeUnits,
+    /// Ask before moving items to the recycle bin
+    pub confirm_delete: bool,
+    /// Mirror pins to and from Explorer's Quick Access
+    pub sync_quick_access: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            show_hidden: false,
+            default_sort: SortPreference::default(),
+            duplicate_suffix: " ({n})".to_string(),
+            thumbnail_cache_entries: 500,
+            thumbnail_workers: 0,
+            ffmpeg_path: None,
+            close_to_tray: false,
+            window_backdrop: crate::appearance::BackdropKind::default(),
+            language: "auto".to_string(),
+            instant_search_drives: Vec::new(),
+            size_units: SizeUnits::default(),
+            confirm_delete: true,
+            sync_quick_access: false,
+        }
+    }
+}
+
+impl Preferences {
+    /// `name` with the duplicate suffix for the `n`th copy
+    pub fn duplicate_name(&self, name: &str, n: u32) -> String {
+        format!(
+            "{}{}",
+            name,
+            self.duplicate_suffix.replace("{n}", &n.to_string())
+        )
+    }
+
+    /// The user's ffmpeg binary, if one is set
+    pub fn custom_ffmpeg(&self) -> Option<&str> {
+        self.ffmpeg_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+    }
+
+    /// Size of the shared thumbnail / ffmpeg worker pool
+    pub fn thumbnail_worker_count(&self) -> usize {
+        match self.thumbnail_workers {
+            0 => std::thread::available_parallelism()
+                .map(|n| n.get() / 2)
+                .unwrap_or(2)
+                .max(1),
+            n => n,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.duplicate_suffix.contains("{n}") {
+            return Err("The duplicate suffix must contain {n}".to_string());
+        }
+        if self.thumbnail_cache_entries == 0 {
+            return Err("The thumbnail cache needs room for at least one entry".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Current preferences, with defaults for anything never saved
+pub fn preferences() -> Preferences {
+    SettingsStore::global()
+        .get::<Preferences>(PREFERENCES_KEY)
+        .unwrap_or_default()
+}
+
+/// File size in the units the user picked
+pub fn format_size(size: u64) -> String {
+    let units = match SIZE_UNITS.load(Ordering::Relaxed) {
+        0 => {
+            let units = preferences().size_units;
+            SIZE_UNITS.store(units as u8 + 1, Ordering::Relaxed);
+            units
+        }
+        1 => SizeUnits::Binary,
+        _ => SizeUnits::Decimal,
+    };
+    units.format(size)
+}
+
+/// Brings `values` up to the current schema; true when anything was upgraded
+fn migrate(values: &mut Map<String, Value>) -> bool {
+    let version = values
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if version >= SCHEMA_VERSION {
+        if version > SCHEMA_VERSION {
+            log::warn!(
+                "[SETTINGS] Settings were saved by a newer version (schema {}); unknown keys are kept",
+                version
+            );
+        }
+        return false;
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(values);
+    }
+    values.insert(SCHEMA_VERSION_KEY.to_string(), SCHEMA_VERSION.into());
+    log::info!(
+        "[SETTINGS] Migrated settings from schema {} to {}",
+        version,
+        SCHEMA_VERSION
+    );
+    true
+}
+
+/// `preferences` with the field `key` set to `value`; errors on unknown keys and mistyped values
+fn with_setting(preferences: &Preferences, key: &str, value: Value) -> Result<Preferences, String> {
+    let mut fields = serde_json::to_value(preferences).map_err(|e| e.to_string())?;
+    let field = fields
+        .get_mut(key)
+        .ok_or_else(|| format!("Unknown setting: {}", key))?;
+    *field = value;
+    serde_json::from_value(fields).map_err(|e| format!("Invalid value for {}: {}", key, e))
+}
+
+pub struct SettingsStore {
+    values: RwLock<Map<String, Value>>,
+    storage_file: PathBuf,
+}
+
+static INSTANCE: OnceLock<Arc<SettingsStore>> = OnceLock::new();
+
+impl SettingsStore {
+    pub fn global() -> Arc<Self> {
+        INSTANCE
+            .get_or_init(|| {
+                let storage_file = crate::paths::app_data_dir().join("settings.json");
+                let mut values = match std::fs::read(&storage_file) {
+                    Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                        log::error!("[SETTINGS] Ignoring unreadable settings file: {}", e);
+                        Map::new()
+                    }),
+                    Err(_) => Map::new(),
+                };
+                let migrated = migrate(&mut values);
+                let store = SettingsStore {
+                    values: RwLock::new(values),
+                    storage_file,
+                };
+                if migrated {
+                    if let Err(e) = store.save() {
+                        log::error!("[SETTINGS] {}", e);
+                    }
+                }
+                Arc::new(store)
+            })
+            .clone()
+    }
+
+    /// Returns the value stored under `key`, or None if it is missing or has another shape
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.values.read().get(key).cloned()?;
+        match serde_json::from_value(value) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                log::warn!(
+                    "[SETTINGS] Value for '{}' has an unexpected shape: {}",
+                    key,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), String> {
+        let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+        self.values.write().insert(key.to_string(), value);
+        self.save()
+    }
+
+    /// The whole store as JSON, for backups; `SECRET_SETTINGS` are left out
+    pub fn to_json(&self) -> Result<Vec<u8>, String> {
+        let mut values = self.values.read().clone();
+        for key in SECRET_SETTINGS {
+            values.remove(key);
+        }
+        serde_json::to_vec_pretty(&values).map_err(|e| e.to_string())
+    }
+
+    /// Replaces every key with the contents of a backup made by `to_json`. This
+    /// machine's `SECRET_SETTINGS` stay as they are, whatever the backup holds
+    pub fn replace_from_json(&self, data: &[u8]) -> Result<(), String> {
+        let mut values: Map<String, Value> =
+            serde_json::from_slice(data).map_err(|e| format!("Invalid settings backup: {}", e))?;
+        migrate(&mut values);
+        {
+            let mut current = self.values.write();
+            for key in SECRET_SETTINGS {
+                values.remove(key);
+                if let Some(secret) = current.remove(key) {
+                    values.insert(key.to_string(), secret);
+                }
+            }
+            *current = values;
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let data = serde_json::to_vec_pretty(&*self.values.read()).map_err(|e| e.to_string())?;
+        // Write then rename so a crash never leaves a truncated settings file behind
+        let tmp_file = self.storage_file.with_extension("json.tmp");
+        std::fs::write(&tmp_file, data)
+            .and_then(|_| std::fs::rename(&tmp_file, &self.storage_file))
+            .map_err(|e| format!("Failed to save settings: {}", e))
+    }
+}
+
+#[tauri::command]
+pub fn get_settings() -> Preferences {
+    preferences()
+}
+
+/// Saves `settings` and broadcasts them as `settings-changed` to every window
+#[tauri::command]
+pub fn set_settings(settings: Preferences) -> Result<(), String> {
+    settings.validate()?;
+    SettingsStore::global().set(PREFERENCES_KEY, &settings)?;
+    log::info!("[SETTINGS] Preferences updated");
+    notify_changed();
+    Ok(())
+}
+
+/// One preference by its field name, e.g. `show_hidden`
+#[tauri::command]
+pub fn get_setting(key: String) -> Result<Value, String> {
+    let fields = serde_json::to_value(preferences()).map_err(|e| e.to_string())?;
+    fields
+        .get(&key)
+        .cloned()
+        .ok_or_else(|| format!("Unknown setting: {}", key))
+}
+
+/// Changes one preference and broadcasts `settings-changed`
+#[tauri::command]
+pub fn set_setting(key: String, value: Value) -> Result<(), String> {
+    set_settings(with_setting(&preferences(), &key, value)?)
+}
+
+/// Broadcasts the current preferences as `settings-changed`
+pub fn notify_changed() {
+    crate::i18n::reset();
+    SIZE_UNITS.store(0, Ordering::Relaxed);
+    if let Some(app) = crate::APP_HANDLE.get() {
+        let _ = app.emit("settings-changed", preferences());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferences_defaults_fill_missing_fields() {
+        let prefs: Preferences = serde_json::from_str(r#"{"show_hidden": true}"#).unwrap();
+        assert!(prefs.show_hidden);
+        assert_eq!(prefs.default_sort, SortPreference::default());
+        assert_eq!(prefs.thumbnail_cache_entries, 500);
+        assert_eq!(prefs.custom_ffmpeg(), None);
+    }
+
+    #[test]
+    fn test_duplicate_name() {
+        let mut prefs = Preferences::default();
+        assert_eq!(prefs.duplicate_name("Photos", 2), "Photos (2)");
+        prefs.duplicate_suffix = " - Copy {n}".to_string();
+        assert_eq!(prefs.duplicate_name("Photos", 3), "Photos - Copy 3");
+        prefs.duplicate_suffix = " - Copy".to_string();
+        assert!(prefs.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_by_key() {
+        let prefs = with_setting(
+            &Preferences::default(),
+            "confirm_delete",
+            Value::Bool(false),
+        );
+        assert!(!prefs.unwrap().confirm_delete);
+        let prefs = with_setting(&Preferences::default(), "size_units", "decimal".into()).unwrap();
+        assert_eq!(prefs.size_units.format(1_500_000), "1.5 MB");
+        assert_eq!(SizeUnits::Binary.format(1536), "1.5 KB");
+        assert!(with_setting(&prefs, "no_such_setting", Value::Null).is_err());
+        assert!(with_setting(&prefs, "show_hidden", "yes".into()).is_err());
+
+        let mut values = Map::new();
+        assert!(migrate(&mut values));
+        assert_eq!(values[SCHEMA_VERSION_KEY], SCHEMA_VERSION);
+        assert!(!migrate(&mut values));
+    }
+}
@@ -0,0 +1,403 @@
+//! Shell Actions Module
+//!
+//! Thin wrappers around shell verbs and Explorer hand-offs that don't fit the
+//! IFileOperation pipeline in `sta_worker.rs`.
+
+use crate::paths::to_wide;
+use serde::Serialize;
+use ts_rs::TS;
+use windows::core::PCWSTR;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct SpecialFolder {
+    /// Stable identifier passed back to `open_special_folder`
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Shell parsing name, e.g. "shell:::{ED7BA470-8E54-465E-825C-99712043E01C}"
+    pub target: String,
+    /// Verb invoked on the target ("open" unless the entry is a property sheet)
+    pub verb: String,
+}
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct PrintResult {
+    pub path: String,
+    /// Set when the file could not be sent to its print handler
+    pub error: Option<String>,
+}
+
+/// (id, name, description, CLSID, verb)
+const SPECIAL_FOLDERS: &[(&str, &str, &str, &str, &str)] = &[
+    (
+        "god-mode",
+        "All Tasks (God Mode)",
+        "Every Control Panel setting in one list",
+        "{ED7BA470-8E54-465E-825C-99712043E01C}",
+        "open",
+    ),
+    (
+        "control-panel",
+        "Control Panel",
+        "Classic Control Panel, category view",
+        "{26EE0668-A00A-44D7-9371-BEB064C98683}",
+        "open",
+    ),
+    (
+        "network-connections",
+        "Network Connections",
+        "Network adapters and their settings",
+        "{7007ACC7-3202-11D1-AAD2-00805FC1270E}",
+        "open",
+    ),
+    (
+        "programs-and-features",
+        "Programs and Features",
+        "Uninstall or change installed programs",
+        "{7B81BE6A-CE2B-4676-A29E-EB907A5126C5}",
+        "open",
+    ),
+    (
+        "devices-and-printers",
+        "Devices and Printers",
+        "Printers, scanners and other attached devices",
+        "{A8A91A66-3A7D-4424-8D24-04E180695C7A}",
+        "open",
+    ),
+    (
+        "power-options",
+        "Power Options",
+        "Power plans and lid/button behaviour",
+        "{025A5937-A6BE-4686-A844-36FE4BEC8B6D}",
+        "open",
+    ),
+    (
+        "administrative-tools",
+        "Windows Tools",
+        "Administrative tools (Event Viewer, Services, ...)",
+        "{D20EA4E1-3957-11D2-A40B-0C5020524153}",
+        "open",
+    ),
+    (
+        "recycle-bin",
+        "Recycle Bin",
+        "Deleted files",
+        "{645FF040-5081-101B-9F08-00AA002F954E}",
+        "open",
+    ),
+    (
+        "recycle-bin-properties",
+        "Recycle Bin Properties",
+        "Maximum size and delete confirmation per drive",
+        "{645FF040-5081-101B-9F08-00AA002F954E}",
+        "properties",
+    ),
+];
+
+/// Opens classic Explorer on the parent folder with `path` selected.
+#[tauri::command]
+pub fn show_in_explorer(path: String) -> Result<(), String> {
+    use windows::Win32::UI::Shell::{ILCreateFromPathW, ILFree, SHOpenFolderAndSelectItems};
+
+    let expanded_path = crate::expand_env_vars(&path);
+    let path_wide = to_wide(&expanded_path);
+
+    unsafe {
+        let pidl = ILCreateFromPathW(PCWSTR(path_wide.as_ptr()));
+        if pidl.is_null() {
+            return Err(format!("Failed to resolve path: {}", expanded_path));
+        }
+
+        // With no child list, a fully qualified item PIDL opens its parent and selects it
+        let result = SHOpenFolderAndSelectItems(pidl, None, 0);
+        ILFree(Some(pidl));
+
+        result.map_err(|e| format!("SHOpenFolderAndSelectItems failed: {}", e))
+    }
+}
+
+/// Launches `path` through the `runas` verb so Windows shows the UAC prompt.
+#[tauri::command]
+pub async fn run_elevated(
+    window: tauri::Window,
+    path: String,
+    args: Option<String>,
+) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let expanded_path = crate::expand_env_vars(&path);
+    let path_obj = std::path::Path::new(&expanded_path);
+    if !path_obj.is_file() {
+        return Err(format!("File not found: {}", expanded_path));
+    }
+
+    // .msi has no "runas" verb registered; elevate msiexec with the package instead
+    let is_msi = path_obj
+        .extension()
+        .map(|e| e.to_string_lossy().eq_ignore_ascii_case("msi"))
+        .unwrap_or(false);
+    let (file, params) = if is_msi {
+        (
+            "msiexec.exe".to_string(),
+            format!("/i \"{}\" {}", expanded_path, args.as_deref().unwrap_or("")),
+        )
+    } else {
+        (expanded_path.clone(), args.clone().unwrap_or_default())
+    };
+
+    let dir = path_obj
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    log::info!("[SHELL] Running elevated: {} {:?}", expanded_path, args);
+
+    // ShellExecuteEx wants a COM thread (the helper sets it up) and blocks for the
+    // whole UAC prompt, so keep it off the async runtime. `HWND` wraps a raw
+    // pointer; pass it to the blocking task as a number
+    let hwnd = crate::get_root_hwnd(&window).0 as isize;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::elevation::shell_execute_runas(
+            HWND(hwnd as *mut _),
+            &file,
+            params.trim_end(),
+            Some(&dir),
+            SW_SHOWNORMAL,
+            false,
+        )
+        .map(|_| ())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Command line for `open_file_with`: `%1` in `args` becomes the quoted path, otherwise
+/// the quoted path is appended
+fn open_with_params(path: &str, args: Option<&str>) -> String {
+    let quoted = format!("\"{}\"", path);
+    match args.map(str::trim).filter(|a| !a.is_empty()) {
+        Some(args) if args.contains("\"%1\"") => args.replace("\"%1\"", &quoted),
+        Some(args) if args.contains("%1") => args.replace("%1", &quoted),
+        Some(args) => format!("{} {}", args, quoted),
+        None => quoted,
+    }
+}
+
+/// Opens `path` in a specific program, for the "Open with" list of recently used apps.
+#[tauri::command]
+pub async fn open_file_with(
+    window: tauri::Window,
+    path: String,
+    app_exe: String,
+    args: Option<String>,
+) -> Result<(), String> {
+    use windows::Win32::UI::Shell::{ShellExecuteExW, SEE_MASK_NOASYNC, SHELLEXECUTEINFOW};
+
+    let expanded_path = crate::expand_env_vars(&path);
+    let expanded_exe = crate::expand_env_vars(&app_exe);
+    if !std::path::Path::new(&expanded_path).exists() {
+        return Err(format!("File not found: {}", expanded_path));
+    }
+    if !std::path::Path::new(&expanded_exe).is_file() {
+        return Err(format!("Application not found: {}", expanded_exe));
+    }
+
+    let verb_wide = to_wide("open");
+    let file_wide = to_wide(&expanded_exe);
+    let params_wide = to_wide(&open_with_params(&expanded_path, args.as_deref()));
+    // Run in the file's folder so programs resolve relative references the way Explorer does
+    let dir_wide = to_wide(
+        &std::path::Path::new(&expanded_path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    );
+
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_NOASYNC,
+        hwnd: crate::get_root_hwnd(&window),
+        lpVerb: PCWSTR(verb_wide.as_ptr()),
+        lpFile: PCWSTR(file_wide.as_ptr()),
+        lpParameters: PCWSTR(params_wide.as_ptr()),
+        lpDirectory: PCWSTR(dir_wide.as_ptr()),
+        nShow: 1,
+        ..Default::default()
+    };
+
+    log::info!("[SHELL] Opening {} with {}", expanded_path, expanded_exe);
+
+    unsafe {
+        ShellExecuteExW(&mut info).map_err(|e| {
+            format!(
+                "Failed to open {} with {}: {}",
+                expanded_path, expanded_exe, e
+            )
+        })
+    }
+}
+
+/// Sends each file to the print handler of its type (the "print" verb), one result per file
+#[tauri::command]
+pub async fn print_files(window: tauri::Window, paths: Vec<String>) -> Vec<PrintResult> {
+    use windows::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_FLAG_NO_UI, SEE_MASK_NOASYNC, SHELLEXECUTEINFOW,
+    };
+
+    let root_hwnd = crate::get_root_hwnd(&window);
+    let verb_wide = to_wide("print");
+    paths
+        .into_iter()
+        .map(|path| {
+            let expanded_path = crate::expand_env_vars(&path);
+            let file_wide = to_wide(&expanded_path);
+            // NO_UI: a type without a print handler is reported back instead of a shell dialog
+            let mut info = SHELLEXECUTEINFOW {
+                cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+                fMask: SEE_MASK_NOASYNC | SEE_MASK_FLAG_NO_UI,
+                hwnd: root_hwnd,
+                lpVerb: PCWSTR(verb_wide.as_ptr()),
+                lpFile: PCWSTR(file_wide.as_ptr()),
+                nShow: 0,
+                ..Default::default()
+            };
+            let result = unsafe { ShellExecuteExW(&mut info) };
+            let error = result.err().map(|e| {
+                // ERROR_NO_ASSOCIATION: nothing registered the verb for this type
+                if e.code().0 as u32 == 0x80070483 {
+                    let ext = std::path::Path::new(&expanded_path)
+                        .extension()
+                        .map(|e| format!(".{}", e.to_string_lossy()))
+                        .unwrap_or_else(|| "this type of".to_string());
+                    format!("No program is set up to print {} files", ext)
+                } else {
+                    format!("Failed to print: {}", e)
+                }
+            });
+            match &error {
+                Some(e) => log::warn!("[SHELL] Print {}: {}", expanded_path, e),
+                None => log::info!("[SHELL] Printing {}", expanded_path),
+            }
+            PrintResult {
+                path: expanded_path,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Curated `shell:::{CLSID}` destinations for the command palette.
+#[tauri::command]
+pub fn list_special_folders() -> Vec<SpecialFolder> {
+    SPECIAL_FOLDERS
+        .iter()
+        .map(|(id, name, description, clsid, verb)| SpecialFolder {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            target: format!("shell:::{}", clsid),
+            verb: verb.to_string(),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn open_special_folder(window: tauri::Window, id: String) -> Result<(), String> {
+    use windows::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_INVOKEIDLIST, SEE_MASK_NOASYNC, SHELLEXECUTEINFOW,
+    };
+
+    let entry = list_special_folders()
+        .into_iter()
+        .find(|f| f.id == id)
+        .ok_or_else(|| format!("Unknown special folder: {}", id))?;
+
+    let verb_wide = to_wide(&entry.verb);
+    let file_wide = to_wide(&entry.target);
+
+    // INVOKEIDLIST makes the shell resolve the parsing name, which "properties" needs
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_INVOKEIDLIST | SEE_MASK_NOASYNC,
+        hwnd: crate::get_root_hwnd(&window),
+        lpVerb: PCWSTR(verb_wide.as_ptr()),
+        lpFile: PCWSTR(file_wide.as_ptr()),
+        nShow: 1,
+        ..Default::default()
+    };
+
+    log::info!(
+        "[SHELL] Opening special folder {} ({})",
+        entry.id,
+        entry.target
+    );
+
+    unsafe {
+        ShellExecuteExW(&mut info).map_err(|e| format!("Failed to open {}: {}", entry.name, e))
+    }
+}
+
+/// Shows the native Explorer context menu for `paths`, with every installed shell
+/// extension, at client point (x, y) in CSS pixels. Runs the chosen command and
+/// returns its verb, or None when the menu was dismissed. "rename" is returned
+/// without running so the caller can start its own inline rename.
+#[tauri::command]
+pub async fn show_shell_context_menu(
+    window: tauri::Window,
+    paths: Vec<String>,
+    x: f64,
+    y: f64,
+) -> Result<Option<String>, String> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+
+    if paths.is_empty() {
+        return Ok(None);
+    }
+    let paths: Vec<String> = paths.iter().map(|p| crate::expand_env_vars(p)).collect();
+    let scale = window.scale_factor().unwrap_or(1.0);
+    // HWND isn't Send; only its value crosses the await
+    let (hwnd, point) = {
+        let root_hwnd = crate::get_root_hwnd(&window);
+        let mut point = POINT {
+            x: (x * scale).round() as i32,
+            y: (y * scale).round() as i32,
+        };
+        unsafe {
+            let _ = ClientToScreen(root_hwnd, &mut point);
+        }
+        (root_hwnd.0 as isize, point)
+    };
+
+    tokio::task::spawn_blocking(move || {
+        crate::sta_worker::StaWorker::global().show_context_menu(
+            paths,
+            point.x,
+            point.y,
+            Some(hwnd),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_with_params() {
+        assert_eq!(open_with_params("C:\\a b.txt", None), "\"C:\\a b.txt\"");
+        assert_eq!(
+            open_with_params("C:\\a.txt", Some("-n")),
+            "-n \"C:\\a.txt\""
+        );
+        assert_eq!(
+            open_with_params("C:\\a.txt", Some("--file=\"%1\" -r")),
+            "--file=\"C:\\a.txt\" -r"
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Shortcuts Module
+//!
+//! Reads and writes Shell Link (.lnk) properties through IShellLinkW / IPersistFile
+//! so the frontend can show and edit everything Explorer's "Shortcut" tab exposes,
+//! and creates/parses .url internet shortcuts.
+
+use crate::paths::to_wide;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use windows::core::{Interface, PCWSTR};
+use windows::Win32::System::Com::{CoCreateInstance, IPersistFile, CLSCTX_INPROC_SERVER, STGM};
+use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+use windows::Win32::UI::WindowsAndMessaging::SHOW_WINDOW_CMD;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct ShortcutInfo {
+    pub target_path: String,
+    pub arguments: String,
+    pub working_directory: String,
+    pub description: String,
+    pub icon_location: String,
+    pub icon_index: i32,
+    /// Raw IShellLink hotkey: low byte = virtual key, high byte = HOTKEYF_* modifiers
+    pub hotkey: u16,
+    /// Human readable hotkey, e.g. "Ctrl+Alt+T" (empty when unset)
+    pub hotkey_display: String,
+    /// "normal", "minimized" or "maximized"
+    pub window_style: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ShortcutUpdate {
+    pub target_path: Option<String>,
+    pub arguments: Option<String>,
+    pub working_directory: Option<String>,
+    pub description: Option<String>,
+    pub icon_location: Option<String>,
+    pub icon_index: Option<i32>,
+    pub hotkey: Option<u16>,
+    pub window_style: Option<String>,
+}
+
+const HOTKEYF_SHIFT: u16 = 0x01;
+const HOTKEYF_CONTROL: u16 = 0x02;
+const HOTKEYF_ALT: u16 = 0x04;
+
+fn wide_buf_to_string(buf: &[u16]) -> String {
+    let end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..end])
+}
+
+fn format_hotkey(hotkey: u16) -> String {
+    let vk = (hotkey & 0xFF) as u8;
+    if vk == 0 {
+        return String::new();
+    }
+    let modifiers = hotkey >> 8;
+
+    let mut parts = Vec::new();
+    if modifiers & HOTKEYF_CONTROL != 0 {
+        parts.push("Ctrl".to_string());
+    }
+    if modifiers & HOTKEYF_ALT != 0 {
+        parts.push("Alt".to_string());
+    }
+    if modifiers & HOTKEYF_SHIFT != 0 {
+        parts.push("Shift".to_string());
+    }
+
+    let key = match vk {
+        b'0'..=b'9' | b'A'..=b'Z' => (vk as char).to_string(),
+        0x70..=0x87 => format!("F{}", vk - 0x6F),
+        0x60..=0x69 => format!("Num {}", vk - 0x60),
+        _ => format!("0x{:02X}", vk),
+    };
+    parts.push(key);
+    parts.join("+")
+}
+
+fn show_cmd_to_style(show_cmd: i32) -> String {
+    match show_cmd {
+        3 => "maximized".to_string(),         // SW_SHOWMAXIMIZED
+        2 | 6 | 7 => "minimized".to_string(), // SW_SHOWMINIMIZED / SW_MINIMIZE / SW_SHOWMINNOACTIVE
+        _ => "normal".to_string(),
+    }
+}
+
+fn style_to_show_cmd(style: &str) -> Result<SHOW_WINDOW_CMD, String> {
+    match style {
+        "normal" => Ok(SHOW_WINDOW_CMD(1)),    // SW_SHOWNORMAL
+        "maximized" => Ok(SHOW_WINDOW_CMD(3)), // SW_SHOWMAXIMIZED
+        "minimized" => Ok(SHOW_WINDOW_CMD(7)), // SW_SHOWMINNOACTIVE (what Explorer writes)
+        other => Err(format!("Unknown window style: {}", other)),
+    }
+}
+
+/// Creates an IShellLinkW and loads `path` into it with the given storage mode
+unsafe fn load_shell_link(path: &str, mode: STGM) -> Result<(IShellLinkW, IPersistFile), String> {
+    let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+        .map_err(|e| format!("CoCreateInstance failed: {}", e))?;
+
+    let persist_file: IPersistFile = shell_link
+        .cast()
+        .map_err(|e| format!("QueryInterface(IPersistFile) failed: {}", e))?;
+
+    let path_wide = to_wide(path);
+    persist_file
+        .Load(PCWSTR(path_wide.as_ptr()), mode)
+        .map_err(|e| format!("IPersistFile::Load failed: {}", e))?;
+
+    Ok((shell_link, persist_file))
+}
+
+#[tauri::command]
+pub fn resolve_shortcut(path: String) -> Result<ShortcutInfo, String> {
+    let path = crate::expand_env_vars(&path);
+
+    // Internet shortcuts are plain INI files; their "target" is the URL itself
+    if let Some(url) = read_url_target(std::path::Path::new(&path)) {
+        return Ok(ShortcutInfo {
+            target_path: url,
+            arguments: String::new(),
+            working_directory: String::new(),
+            description: String::new(),
+            icon_location: String::new(),
+            icon_index: 0,
+            hotkey: 0,
+            hotkey_display: String::new(),
+            window_style: "normal".to_string(),
+        });
+    }
+
+    unsafe {
+        let (shell_link, _persist_file) = load_shell_link(&path, STGM(0))?;
+
+        let mut target_path = [0u16; 1024];
+        shell_link
+            .GetPath(&mut target_path, std::ptr::null_mut(), 0)
+            .map_err(|e| format!("IShellLink::GetPath failed: {}", e))?;
+
+        let mut arguments = [0u16; 1024];
+        let _ = shell_link.GetArguments(&mut arguments);
+
+        let mut working_directory = [0u16; 1024];
+        let _ = shell_link.GetWorkingDirectory(&mut working_directory);
+
+        let mut description = [0u16; 1024];
+        let _ = shell_link.GetDescription(&mut description);
+
+        let mut icon_location = [0u16; 1024];
+        let mut icon_index = 0i32;
+        let _ = shell_link.GetIconLocation(&mut icon_location, &mut icon_index);
+
+        let hotkey = shell_link.GetHotkey().unwrap_or(0);
+        let show_cmd = shell_link.GetShowCmd().map(|c| c.0).unwrap_or(1);
+
+        Ok(ShortcutInfo {
+            target_path: wide_buf_to_string(&target_path),
+            arguments: wide_buf_to_string(&arguments),
+            working_directory: wide_buf_to_string(&working_directory),
+            description: wide_buf_to_string(&description),
+            icon_location: wide_buf_to_string(&icon_location),
+            icon_index,
+            hotkey,
+            hotkey_display: format_hotkey(hotkey),
+            window_style: show_cmd_to_style(show_cmd),
+        })
+    }
+}
+
+#[tauri::command]
+pub fn update_shortcut(path: String, props: ShortcutUpdate) -> Result<ShortcutInfo, String> {
+    use windows::Win32::System::Com::STGM_READWRITE;
+
+    let path = crate::expand_env_vars(&path);
+    log::info!("[SHORTCUT] Updating {}: {:?}", path, props);
+
+    unsafe {
+        let (shell_link, persist_file) = load_shell_link(&path, STGM_READWRITE)?;
+
+        if let Some(target) = &props.target_path {
+            let wide = to_wide(target);
+            shell_link
+                .SetPath(PCWSTR(wide.as_ptr()))
+                .map_err(|e| format!("IShellLink::SetPath failed: {}", e))?;
+        }
+        if let Some(args) = &props.arguments {
+            let wide = to_wide(args);
+            shell_link
+                .SetArguments(PCWSTR(wide.as_ptr()))
+                .map_err(|e| format!("IShellLink::SetArguments failed: {}", e))?;
+        }
+        if let Some(dir) = &props.working_directory {
+            let wide = to_wide(dir);
+            shell_link
+                .SetWorkingDirectory(PCWSTR(wide.as_ptr()))
+                .map_err(|e| format!("IShellLink::SetWorkingDirectory failed: {}", e))?;
+        }
+        if let Some(desc) = &props.description {
+            let wide = to_wide(desc);
+            shell_link
+                .SetDescription(PCWSTR(wide.as_ptr()))
+                .map_err(|e| format!("IShellLink::SetDescription failed: {}", e))?;
+        }
+        if props.icon_location.is_some() || props.icon_index.is_some() {
+            // Keep whichever half of the icon location was not supplied
+            let mut current = [0u16; 1024];
+            let mut current_index = 0i32;
+            let _ = shell_link.GetIconLocation(&mut current, &mut current_index);
+
+            let location = props
+                .icon_location
+                .clone()
+                .unwrap_or_else(|| wide_buf_to_string(&current));
+            let wide = to_wide(&location);
+            shell_link
+                .SetIconLocation(
+                    PCWSTR(wide.as_ptr()),
+                    props.icon_index.unwrap_or(current_index),
+                )
+                .map_err(|e| format!("IShellLink::SetIconLocation failed: {}", e))?;
+        }
+        if let Some(hotkey) = props.hotkey {
+            shell_link
+                .SetHotkey(hotkey)
+                .map_err(|e| format!("IShellLink::SetHotkey failed: {}", e))?;
+        }
+        if let Some(style) = &props.window_style {
+            shell_link
+                .SetShowCmd(style_to_show_cmd(style)?)
+                .map_err(|e| format!("IShellLink::SetShowCmd failed: {}", e))?;
+        }
+
+        // A null file name saves back to the file that was loaded
+        persist_file
+            .Save(PCWSTR::null(), true)
+            .map_err(|e| format!("IPersistFile::Save failed: {}", e))?;
+    }
+
+    resolve_shortcut(path)
+}
+
+/// Parses the `[InternetShortcut]` section of a .url file and returns its URL
+pub fn parse_url_file_contents(contents: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case("[InternetShortcut]");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("URL") {
+                let url = value.trim();
+                if !url.is_empty() {
+                    return Some(url.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Reads the target URL of a .url internet shortcut (None for anything else)
+pub fn read_url_target(path: &std::path::Path) -> Option<String> {
+    use std::io::Read;
+
+    let is_url_file = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("url"))
+        .unwrap_or(false);
+    if !is_url_file {
+        return None;
+    }
+    // .url files are tiny INI files; read at most one byte past the cap to tell
+    // larger files apart without loading them
+    const MAX_URL_FILE_SIZE: u64 = 64 * 1024;
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(MAX_URL_FILE_SIZE + 1)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    if bytes.len() as u64 > MAX_URL_FILE_SIZE {
+        return None;
+    }
+    parse_url_file_contents(&String::from_utf8_lossy(&bytes))
+}
+
+/// Creates a .lnk to `target` in the folder `location`, named "<target> - Shortcut"
+/// like the ones Explorer makes
+#[tauri::command]
+pub fn create_shortcut(target: String, location: String) -> Result<crate::FileEntry, String> {
+    use crate::i18n::{self, Text};
+
+    let target = crate::expand_env_vars(&target);
+    let location = crate::expand_env_vars(&location);
+    let target_path = std::path::Path::new(&target);
+    if !target_path.exists() {
+        return Err(format!("Target not found: {}", target));
+    }
+
+    let name = format!(
+        "{}{}.lnk",
+        crate::links::link_name(target_path),
+        i18n::tr(Text::ShortcutSuffix)
+    );
+    let link_path =
+        std::path::Path::new(&location).join(crate::new_file::unique_name(&location, &name));
+
+    unsafe {
+        let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("CoCreateInstance failed: {}", e))?;
+        let target_wide = to_wide(&target);
+        shell_link
+            .SetPath(PCWSTR(target_wide.as_ptr()))
+            .map_err(|e| format!("IShellLink::SetPath failed: {}", e))?;
+        // Programs usually expect to start in their own folder
+        if target_path.is_file() {
+            if let Some(parent) = target_path.parent() {
+                let dir_wide = to_wide(parent);
+                let _ = shell_link.SetWorkingDirectory(PCWSTR(dir_wide.as_ptr()));
+            }
+        }
+
+        let persist_file: IPersistFile = shell_link
+            .cast()
+            .map_err(|e| format!("QueryInterface(IPersistFile) failed: {}", e))?;
+        let link_wide = to_wide(&link_path);
+        persist_file
+            .Save(PCWSTR(link_wide.as_ptr()), true)
+            .map_err(|e| format!("Failed to save shortcut: {}", e))?;
+    }
+
+    log::info!("[SHORTCUT] Created {} -> {}", link_path.display(), target);
+    crate::get_file_entry(&link_path)
+}
+
+/// Schemes an internet shortcut may point to
+const URL_SCHEMES: [&str; 4] = ["http", "https", "ftp", "mailto"];
+
+/// `url` if it can go in a .url file as is: a line break would let it add keys
+/// of its own (an `IconFile` on a remote share leaks credentials on sight)
+fn valid_url(url: &str) -> Result<&str, String> {
+    let url = url.trim();
+    if url.chars().any(char::is_control) {
+        return Err("The URL contains control characters".to_string());
+    }
+    let scheme = url.split_once(':').map(|(scheme, _)| scheme);
+    match scheme {
+        Some(scheme) if URL_SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(scheme)) => Ok(url),
+        _ => Err(format!(
+            "Invalid URL; it must start with {}:",
+            URL_SCHEMES.join(":, ")
+        )),
+    }
+}
+
+#[tauri::command]
+pub fn create_url_file(dir: String, name: String, url: String) -> Result<crate::FileEntry, String> {
+    use std::io::Write;
+
+    let url = valid_url(&url)?;
+
+    // Strip characters Windows does not allow in file names
+    let clean_name: String = name
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect();
+    let has_extension = clean_name
+        .len()
+        .checked_sub(4)
+        .and_then(|start| clean_name.get(start..))
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(".url"));
+    let stem = if has_extension {
+        &clean_name[..clean_name.len() - 4]
+    } else {
+        &clean_name
+    }
+    .trim();
+    let stem = if stem.is_empty() {
+        "New Internet Shortcut"
+    } else {
+        stem
+    };
+
+    let dir = crate::expand_env_vars(&dir);
+    let (target, mut file) = crate::new_file::open_unique(&dir, &format!("{}.url", stem))?;
+    let contents = format!("[InternetShortcut]\r\nURL={}\r\n", url);
+    file.write_all(contents.as_bytes())
+        .map_err(|e| format!("Failed to create internet shortcut: {}", e))?;
+
+    log::info!("[SHORTCUT] Created {} -> {}", target.display(), url);
+    crate::get_file_entry(&target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_url() {
+        assert_eq!(
+            valid_url(" https://example.com/a b "),
+            Ok("https://example.com/a b")
+        );
+        assert!(valid_url("MAILTO:someone@example.com").is_ok());
+        assert!(valid_url("https://example.com\r\nIconFile=\\\\host\\share\\x.ico").is_err());
+        assert!(valid_url("file://host/share").is_err());
+        assert!(valid_url("javascript:alert(1)").is_err());
+        assert!(valid_url("example.com").is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Single Instance Module
+//!
+//! Keeps one process per user session. The first instance owns a named mutex
+//! and listens on a named pipe; a later launch finds the mutex, writes its
+//! arguments and working directory to the pipe and exits, and the running
+//! instance opens the requested folder in a new tab and comes to the front.
+//! `--new-instance` skips the check, for when a separate process is wanted.
+//!
+//! Only the current user may open or add instances to the pipe, and the
+//! server always keeps one instance open so the name can't be taken over
+//! between connections. A client gets `READ_TIMEOUT` to send its message.
+
+use crate::paths::to_wide;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::os::windows::io::FromRawHandle;
+use std::sync::mpsc;
+use std::time::Duration;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{
+    GetLastError, LocalFree, ERROR_ALREADY_EXISTS, ERROR_PIPE_CONNECTED, HANDLE, HLOCAL,
+};
+use windows::Win32::Security::Authorization::{
+    ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+};
+use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+use windows::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_INBOUND};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+    PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+};
+use windows::Win32::System::Threading::CreateMutexW;
+use windows::Win32::System::IO::CancelIoEx;
+
+const NEW_INSTANCE_FLAG: &str = "--new-instance";
+/// Attempts to reach the running instance, which may still be starting its pipe
+const FORWARD_ATTEMPTS: u32 = 10;
+const FORWARD_RETRY_DELAY: Duration = Duration::from_millis(100);
+/// Longest a connected client may take to send its message
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+/// Largest message read; a command line and working directory are far smaller
+const MAX_MESSAGE: u64 = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Forwarded {
+    args: Vec<String>,
+    cwd: String,
+}
+
+/// Pipe names are machine-wide, so each user gets their own
+fn pipe_name() -> String {
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    format!("\\\\.\\pipe\\QuickExplorer-{}", user)
+}
+
+fn forward(message: &Forwarded) -> Result<(), String> {
+    let data = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+    let mut last_error = String::new();
+    for _ in 0..FORWARD_ATTEMPTS {
+        match std::fs::OpenOptions::new().write(true).open(pipe_name()) {
+            Ok(mut pipe) => return pipe.write_all(&data).map_err(|e| e.to_string()),
+            Err(e) => last_error = e.to_string(),
+        }
+        std::thread::sleep(FORWARD_RETRY_DELAY);
+    }
+    Err(last_error)
+}
+
+/// Hands this launch to an already running instance. Returns true when it did, in which
+/// case this process should exit; call first thing in `main`.
+pub fn forward_to_running_instance() -> bool {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == NEW_INSTANCE_FLAG) {
+        return false;
+    }
+
+    let name = to_wide("Local\\QuickExplorer.SingleInstance");
+    // Held for the life of the process; Windows releases it on exit
+    let already_running = match unsafe { CreateMutexW(None, false, PCWSTR(name.as_ptr())) } {
+        Ok(_) => (unsafe { GetLastError() }) == ERROR_ALREADY_EXISTS,
+        Err(_) => false,
+    };
+    if !already_running {
+        return false;
+    }
+
+    let message = Forwarded {
+        args,
+        cwd: std::env::current_dir()
+            .map(|d| d.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    // Lets the running instance bring its window to the front
+    unsafe {
+        let _ = windows::Win32::UI::WindowsAndMessaging::AllowSetForegroundWindow(u32::MAX);
+    }
+    // Nothing is logged here: the logger isn't set up yet
+    forward(&message).is_ok()
+}
+
+fn handle(app: &tauri::AppHandle, message: Forwarded) {
+    crate::tray::show_main_window(app);
+    crate::launch::open(app, &message.args, &message.cwd);
+}
+
+/// Grants the current user, and no one else, full access to the pipe
+struct PipeSecurity(PSECURITY_DESCRIPTOR);
+
+impl PipeSecurity {
+    fn new() -> Result<Self, String> {
+        let sid = crate::permissions::current_user_sid()?;
+        let sddl = to_wide(&format!("D:P(A;;GA;;;{})", sid));
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                PCWSTR(sddl.as_ptr()),
+                SDDL_REVISION_1,
+                &mut descriptor,
+                None,
+            )
+        }
+        .map_err(|e| format!("Failed to build the pipe's security descriptor: {}", e))?;
+        Ok(Self(descriptor))
+    }
+
+    fn attributes(&self) -> SECURITY_ATTRIBUTES {
+        SECURITY_ATTRIBUTES {
+            nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+            lpSecurityDescriptor: self.0 .0,
+            bInheritHandle: false.into(),
+        }
+    }
+}
+
+impl Drop for PipeSecurity {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = LocalFree(Some(HLOCAL(self.0 .0)));
+        }
+    }
+}
+
+/// A new instance of the pipe; `first` fails when another process already owns the name
+fn create_pipe(name: &[u16], security: &PipeSecurity, first: bool) -> Result<HANDLE, String> {
+    let mut open_mode = PIPE_ACCESS_INBOUND;
+    if first {
+        open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+    }
+    let attributes = security.attributes();
+    let pipe = unsafe {
+        CreateNamedPipeW(
+            PCWSTR(name.as_ptr()),
+            open_mode,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            0,
+            64 * 1024,
+            0,
+            Some(&attributes),
+        )
+    };
+    if pipe.is_invalid() {
+        return Err(format!("Failed to create pipe: {:?}", unsafe {
+            GetLastError()
+        }));
+    }
+    Ok(pipe)
+}
+
+/// Reads a connected client's message, giving up after `READ_TIMEOUT` or `MAX_MESSAGE` bytes
+fn read_message(pipe: HANDLE) -> Option<Vec<u8>> {
+    // Owns the handle from here on and closes it when dropped
+    let file = unsafe { std::fs::File::from_raw_handle(pipe.0) };
+    let (finished, read_done) = mpsc::channel::<()>();
+    let handle = pipe.0 as isize;
+    let watchdog = std::thread::spawn(move || {
+        if read_done.recv_timeout(READ_TIMEOUT) == Err(mpsc::RecvTimeoutError::Timeout) {
+            // Wakes the blocked read below
+            let _ = unsafe { CancelIoEx(HANDLE(handle as *mut _), None) };
+        }
+    });
+    let mut data = Vec::new();
+    let result = file.take(MAX_MESSAGE).read_to_end(&mut data);
+    drop(finished);
+    let _ = watchdog.join();
+    match result {
+        Ok(_) => Some(data),
+        Err(e) => {
+            log::warn!("[INSTANCE] Dropped a client: {}", e);
+            None
+        }
+    }
+}
+
+/// Receives launches forwarded by later instances; call once from setup
+pub fn start_server(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let name = to_wide(&pipe_name());
+        let security = match PipeSecurity::new() {
+            Ok(security) => security,
+            Err(e) => {
+                log::error!("[INSTANCE] {}", e);
+                return;
+            }
+        };
+        let mut pipe = match create_pipe(&name, &security, true) {
+            Ok(pipe) => pipe,
+            Err(e) => {
+                log::error!("[INSTANCE] {}", e);
+                return;
+            }
+        };
+        loop {
+            let connected = unsafe { ConnectNamedPipe(pipe, None) };
+            // The client may connect between creation and ConnectNamedPipe
+            let connected = match connected {
+                Ok(()) => true,
+                Err(e) => e.code() == ERROR_PIPE_CONNECTED.to_hresult(),
+            };
+            // The next instance exists before this one closes, so the name is never free
+            let next = match create_pipe(&name, &security, false) {
+                Ok(next) => next,
+                Err(e) => {
+                    log::error!("[INSTANCE] {}", e);
+                    return;
+                }
+            };
+            let current = std::mem::replace(&mut pipe, next);
+            if !connected {
+                drop(unsafe { std::fs::File::from_raw_handle(current.0) });
+                continue;
+            }
+            let Some(data) = read_message(current) else {
+                continue;
+            };
+            match serde_json::from_slice::<Forwarded>(&data) {
+                Ok(message) => handle(&app, message),
+                Err(e) => log::warn!("[INSTANCE] Ignoring malformed message: {}", e),
+            }
+        }
+    });
+}
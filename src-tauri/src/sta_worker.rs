@@ -1,18 +1,29 @@
-use crate::{DiskInfo, FileEntry};
+use crate::FileEntry;
+use crate::conflicts::{self, Resolutions};
+use crate::history::HistoryOperation;
+use crate::operations::OperationControl;
+use serde::Serialize;
 use base64::{engine::general_purpose, Engine as _};
 use chrono::{DateTime, Local};
 use rayon::prelude::*;
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
 use std::sync::{
+    atomic::{AtomicUsize, Ordering},
     mpsc::{channel, Sender},
     Arc, OnceLock,
 };
 use std::thread;
 use std::time::SystemTime;
 use tauri::Emitter;
-use windows::core::{PCWSTR, PWSTR};
-use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+use ts_rs::TS;
+use windows::core::{Interface, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{ERROR_FILE_NOT_FOUND, FILETIME, PROPERTYKEY};
+use windows::Win32::Storage::FileSystem::{
+    FindClose, FindExInfoBasic, FindExSearchNameMatch, FindFirstFileExW, FindNextFileW,
+    FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_REPARSE_POINT,
+    FIND_FIRST_EX_LARGE_FETCH, WIN32_FIND_DATAW,
+};
 use windows::Win32::System::Com::{CoCreateInstance, CoTaskMemFree, CLSCTX_ALL};
 use windows::Win32::System::Ole::{OleInitialize, OleUninitialize};
 use windows::Win32::System::SystemServices::{SFGAO_FLAGS, SFGAO_FOLDER};
@@ -23,7 +34,7 @@ use windows::Win32::UI::Input::KeyboardAndMouse::{
 use windows::Win32::UI::Shell::Common::ITEMIDLIST;
 use windows::Win32::UI::Shell::{
     BHID_EnumItems, FOLDERID_RecycleBinFolder, FileOperation, IEnumShellItems, IFileOperation,
-    ILFree, ILGetSize, IShellItem, SHCreateItemFromIDList, SHCreateItemFromParsingName,
+    ILFree, ILGetSize, IShellItem, IShellItem2, SHCreateItemFromIDList, SHCreateItemFromParsingName,
     SHGetIDListFromObject, SHGetKnownFolderItem, FOF_ALLOWUNDO, FOF_NOCONFIRMMKDIR,
     FOF_RENAMEONCOLLISION, KF_FLAG_DEFAULT, SIGDN_FILESYSPATH, SIGDN_NORMALDISPLAY,
 };
@@ -142,11 +153,91 @@ fn synchronize_handshake(hwnd: windows::Win32::Foundation::HWND) {
     }
 }
 
-fn notify_refresh() {
+/// Quiet period before queued refreshes go out, so a burst of operations
+/// refreshes each folder once
+const REFRESH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Payload of `refresh-tab`
+#[derive(Serialize, Clone, TS, Debug, Default)]
+#[ts(export)]
+pub struct RefreshEvent {
+    /// Folders whose contents changed; empty when any folder may have
+    pub paths: Vec<String>,
+}
+
+#[derive(Default)]
+struct PendingRefresh {
+    /// Set when an operation couldn't tell which folders it touched
+    all: bool,
+    paths: std::collections::BTreeSet<String>,
+}
+
+struct RefreshQueue {
+    pending: std::sync::Mutex<PendingRefresh>,
+    changed: std::sync::Mutex<Sender<()>>,
+}
+
+static REFRESH_QUEUE: OnceLock<RefreshQueue> = OnceLock::new();
+
+fn refresh_queue() -> &'static RefreshQueue {
+    REFRESH_QUEUE.get_or_init(|| {
+        let (tx, rx) = channel::<()>();
+        thread::spawn(move || {
+            while rx.recv().is_ok() {
+                // Keep collecting while notifications keep coming
+                while rx.recv_timeout(REFRESH_DEBOUNCE).is_ok() {}
+                emit_refresh();
+            }
+        });
+        RefreshQueue {
+            pending: std::sync::Mutex::new(PendingRefresh::default()),
+            changed: std::sync::Mutex::new(tx),
+        }
+    })
+}
+
+fn emit_refresh() {
+    let pending = match refresh_queue().pending.lock() {
+        Ok(mut pending) => std::mem::take(&mut *pending),
+        Err(_) => return,
+    };
+    let event = RefreshEvent {
+        paths: if pending.all {
+            Vec::new()
+        } else {
+            pending.paths.into_iter().collect()
+        },
+    };
     if let Some(app) = crate::APP_HANDLE.get() {
-        let _ = app.emit("refresh-tab", ());
-        log::debug!("[STA-WORKER] Event 'refresh-tab' emitted.");
+        log::debug!("[STA-WORKER] Event 'refresh-tab' emitted for {:?}", event.paths);
+        let _ = app.emit("refresh-tab", event);
+    }
+}
+
+/// Folders containing `paths`
+fn parent_dirs<'a>(paths: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+    paths
+        .into_iter()
+        .filter_map(|p| std::path::Path::new(p).parent())
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Queues a `refresh-tab` for the folders in `dirs` (every folder when empty).
+/// Cached listings are dropped right away.
+fn notify_refresh(dirs: Vec<String>) {
+    crate::listing::invalidate_cache();
+    let queue = refresh_queue();
+    if let Ok(mut pending) = queue.pending.lock() {
+        if dirs.is_empty() {
+            pending.all = true;
+        }
+        pending.paths.extend(
+            dirs.into_iter()
+                .map(|d| d.trim_end_matches(['\\', '/']).to_string()),
+        );
     }
+    let _ = queue.changed.lock().map(|tx| tx.send(()));
 }
 
 fn log_sta_diagnostic(label: &str, target_hwnd: windows::Win32::Foundation::HWND) {
@@ -186,23 +277,7 @@ pub enum StaCommand {
         response: Sender<Result<Vec<FileEntry>, String>>,
     },
     EmptyRecycleBin {
-        response: Sender<Result<(), String>>,
-    },
-    DropItems {
-        files: Vec<String>,
-        target_path: String,
-        hwnd: Option<isize>,
-        response: Sender<Result<Vec<String>, String>>,
-    },
-    MoveItems {
-        paths: Vec<String>,
-        target_path: String,
-        hwnd: Option<isize>,
-        response: Sender<Result<(), String>>,
-    },
-    DeleteItems {
-        paths: Vec<String>,
-        hwnd: Option<isize>,
+        root: Option<String>,
         response: Sender<Result<(), String>>,
     },
     RenameItem {
@@ -211,13 +286,6 @@ pub enum StaCommand {
         hwnd: Option<isize>,
         response: Sender<Result<(), String>>,
     },
-    PasteItems {
-        paths: Vec<String>,
-        target_path: String,
-        is_move: bool,
-        hwnd: Option<isize>,
-        response: Sender<Result<Vec<String>, String>>,
-    },
     RestoreItems {
         paths: Vec<String>,
         response: Sender<Result<(), String>>,
@@ -229,6 +297,22 @@ pub enum StaCommand {
         window: tauri::Window,
         response: Sender<Result<(), String>>,
     },
+    WatchDirectory {
+        tab_id: String,
+        path: String,
+        response: Sender<Result<bool, String>>,
+    },
+    UnwatchDirectory {
+        tab_id: String,
+        response: Sender<Result<(), String>>,
+    },
+    ShowContextMenu {
+        paths: Vec<String>,
+        x: i32,
+        y: i32,
+        hwnd: Option<isize>,
+        response: Sender<Result<Option<String>, String>>,
+    },
 }
 
 pub struct StaWorker {
@@ -236,6 +320,13 @@ pub struct StaWorker {
 }
 
 static WORKER: OnceLock<StaWorker> = OnceLock::new();
+/// Commands sent to the worker and not yet picked up
+static QUEUED_COMMANDS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of commands waiting for the worker thread
+pub fn queue_depth() -> usize {
+    QUEUED_COMMANDS.load(Ordering::Relaxed)
+}
 
 impl StaWorker {
     pub fn global() -> &'static StaWorker {
@@ -257,6 +348,24 @@ impl StaWorker {
 
             // Process commands
             while let Ok(cmd) = rx.recv() {
+                QUEUED_COMMANDS.fetch_sub(1, Ordering::Relaxed);
+                // Cached listings keep directory handles open; release them before
+                // file operations so they can't block renaming or deleting a folder
+                if !matches!(cmd, StaCommand::ListFiles { .. }) {
+                    crate::listing::invalidate_cache();
+                }
+                // Tab watchers hold directory handles as well; they watch again once the
+                // operation is done
+                let is_file_operation = !matches!(
+                    cmd,
+                    StaCommand::ListFiles { .. }
+                        | StaCommand::RecursiveSearch { .. }
+                        | StaCommand::WatchDirectory { .. }
+                        | StaCommand::UnwatchDirectory { .. }
+                );
+                if is_file_operation {
+                    crate::watcher::suspend_tab_watches();
+                }
                 match cmd {
                     StaCommand::ListFiles {
                         path,
@@ -267,34 +376,8 @@ impl StaWorker {
                         let result = list_items_impl(&path, show_hidden, nav_id);
                         let _ = response.send(result);
                     }
-                    StaCommand::EmptyRecycleBin { response } => {
-                        let result = empty_recycle_bin_impl();
-                        let _ = response.send(result);
-                    }
-                    StaCommand::DropItems {
-                        files,
-                        target_path,
-                        hwnd,
-                        response,
-                    } => {
-                        let result = drop_items_impl(files, target_path, hwnd);
-                        let _ = response.send(result);
-                    }
-                    StaCommand::MoveItems {
-                        paths,
-                        target_path,
-                        hwnd,
-                        response,
-                    } => {
-                        let result = move_items_impl(paths, target_path, hwnd);
-                        let _ = response.send(result);
-                    }
-                    StaCommand::DeleteItems {
-                        paths,
-                        hwnd,
-                        response,
-                    } => {
-                        let result = delete_items_impl(paths, hwnd);
+                    StaCommand::EmptyRecycleBin { root, response } => {
+                        let result = empty_recycle_bin_impl(root.as_deref());
                         let _ = response.send(result);
                     }
                     StaCommand::RenameItem {
@@ -306,16 +389,6 @@ impl StaWorker {
                         let result = rename_item_impl(path, new_name, hwnd);
                         let _ = response.send(result);
                     }
-                    StaCommand::PasteItems {
-                        paths,
-                        target_path,
-                        is_move,
-                        hwnd,
-                        response,
-                    } => {
-                        let result = paste_items_impl(paths, target_path, is_move, hwnd);
-                        let _ = response.send(result);
-                    }
                     StaCommand::RestoreItems { paths, response } => {
                         let result = restore_items_impl(paths);
                         let _ = response.send(result);
@@ -350,6 +423,30 @@ impl StaWorker {
                             let _ = recursive_search_impl(path, query, nav_id, window);
                         });
                     }
+                    StaCommand::WatchDirectory {
+                        tab_id,
+                        path,
+                        response,
+                    } => {
+                        let _ = response.send(crate::watcher::watch_tab(&tab_id, &path));
+                    }
+                    StaCommand::UnwatchDirectory { tab_id, response } => {
+                        crate::watcher::unwatch_tab(&tab_id);
+                        let _ = response.send(Ok(()));
+                    }
+                    StaCommand::ShowContextMenu {
+                        paths,
+                        x,
+                        y,
+                        hwnd,
+                        response,
+                    } => {
+                        let result = show_context_menu_impl(paths, x, y, hwnd);
+                        let _ = response.send(result);
+                    }
+                }
+                if is_file_operation {
+                    crate::watcher::resume_tab_watches();
                 }
             }
 
@@ -361,6 +458,14 @@ impl StaWorker {
         StaWorker { sender: tx }
     }
 
+    fn submit(&self, cmd: StaCommand) -> Result<(), String> {
+        QUEUED_COMMANDS.fetch_add(1, Ordering::Relaxed);
+        self.sender.send(cmd).map_err(|e| {
+            QUEUED_COMMANDS.fetch_sub(1, Ordering::Relaxed);
+            e.to_string()
+        })
+    }
+
     pub fn list_files(
         &self,
         path: String,
@@ -368,8 +473,7 @@ impl StaWorker {
         nav_id: Option<String>,
     ) -> Result<Vec<FileEntry>, String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::ListFiles {
+        self.submit(StaCommand::ListFiles {
                 path,
                 show_hidden,
                 nav_id,
@@ -381,123 +485,92 @@ impl StaWorker {
             .map_err(|e| format!("Failed to receive response from STA worker: {}", e))?
     }
 
-    pub fn empty_recycle_bin(&self) -> Result<(), String> {
+    pub fn empty_recycle_bin(&self, root: Option<String>) -> Result<(), String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::EmptyRecycleBin { response: tx })
+        self.submit(StaCommand::EmptyRecycleBin { root, response: tx })
             .map_err(|e| format!("Failed to send command to STA worker: {}", e))?;
 
         rx.recv()
             .map_err(|e| format!("Failed to receive response from STA worker: {}", e))?
     }
 
-    pub fn drop_items(
+    pub fn rename_item(
         &self,
-        files: Vec<String>,
-        target_path: String,
+        path: String,
+        new_name: String,
         hwnd: Option<isize>,
-    ) -> Result<Vec<String>, String> {
+    ) -> Result<(), String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::DropItems {
-                files,
-                target_path,
+        self.submit(StaCommand::RenameItem {
+                path,
+                new_name,
                 hwnd,
                 response: tx,
             })
-            .map_err(|e| format!("Failed to send drop command to STA worker: {}", e))?;
+            .map_err(|e| format!("Failed to send rename command to STA worker: {}", e))?;
 
         rx.recv()
-            .map_err(|e| format!("Failed to receive drop response from STA worker: {}", e))?
+            .map_err(|e| format!("Failed to receive rename response from STA worker: {}", e))?
     }
 
-    pub fn move_items(
-        &self,
-        paths: Vec<String>,
-        target_path: String,
-        hwnd: Option<isize>,
-    ) -> Result<(), String> {
+    pub fn restore_items(&self, paths: Vec<String>) -> Result<(), String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::MoveItems {
+        self.submit(StaCommand::RestoreItems {
                 paths,
-                target_path,
-                hwnd,
                 response: tx,
             })
-            .map_err(|e| format!("Failed to send move command to STA worker: {}", e))?;
+            .map_err(|e| format!("Failed to send restore command to STA worker: {}", e))?;
 
         rx.recv()
-            .map_err(|e| format!("Failed to receive move response from STA worker: {}", e))?
+            .map_err(|e| format!("Failed to receive restore response from STA worker: {}", e))?
     }
 
-    pub fn delete_items(&self, paths: Vec<String>, hwnd: Option<isize>) -> Result<(), String> {
+    pub fn watch_directory(&self, tab_id: String, path: String) -> Result<bool, String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::DeleteItems {
-                paths,
-                hwnd,
-                response: tx,
-            })
-            .map_err(|e| format!("Failed to send delete command to STA worker: {}", e))?;
+        self.submit(StaCommand::WatchDirectory {
+            tab_id,
+            path,
+            response: tx,
+        })
+        .map_err(|e| format!("Failed to send watch command to STA worker: {}", e))?;
 
         rx.recv()
-            .map_err(|e| format!("Failed to receive delete response from STA worker: {}", e))?
+            .map_err(|e| format!("Failed to receive watch response from STA worker: {}", e))?
     }
 
-    pub fn rename_item(
-        &self,
-        path: String,
-        new_name: String,
-        hwnd: Option<isize>,
-    ) -> Result<(), String> {
+    pub fn unwatch_directory(&self, tab_id: String) -> Result<(), String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::RenameItem {
-                path,
-                new_name,
-                hwnd,
-                response: tx,
-            })
-            .map_err(|e| format!("Failed to send rename command to STA worker: {}", e))?;
+        self.submit(StaCommand::UnwatchDirectory {
+            tab_id,
+            response: tx,
+        })
+        .map_err(|e| format!("Failed to send unwatch command to STA worker: {}", e))?;
 
         rx.recv()
-            .map_err(|e| format!("Failed to receive rename response from STA worker: {}", e))?
+            .map_err(|e| format!("Failed to receive unwatch response from STA worker: {}", e))?
     }
 
-    pub fn paste_items(
+    /// Shows the Explorer context menu for `paths` at screen point (x, y) and runs the
+    /// chosen command. Returns its verb, or None when the menu was dismissed.
+    pub fn show_context_menu(
         &self,
         paths: Vec<String>,
-        target_path: String,
-        is_move: bool,
+        x: i32,
+        y: i32,
         hwnd: Option<isize>,
-    ) -> Result<Vec<String>, String> {
+    ) -> Result<Option<String>, String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::PasteItems {
-                paths,
-                target_path,
-                is_move,
-                hwnd,
-                response: tx,
-            })
-            .map_err(|e| format!("Failed to send paste command to STA worker: {}", e))?;
-
-        rx.recv()
-            .map_err(|e| format!("Failed to receive paste response from STA worker: {}", e))?
-    }
-
-    pub fn restore_items(&self, paths: Vec<String>) -> Result<(), String> {
-        let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::RestoreItems {
-                paths,
-                response: tx,
-            })
-            .map_err(|e| format!("Failed to send restore command to STA worker: {}", e))?;
+        self.submit(StaCommand::ShowContextMenu {
+            paths,
+            x,
+            y,
+            hwnd,
+            response: tx,
+        })
+        .map_err(|e| format!("Failed to send context menu command to STA worker: {}", e))?;
 
         rx.recv()
-            .map_err(|e| format!("Failed to receive restore response from STA worker: {}", e))?
+            .map_err(|e| format!("Failed to receive context menu response from STA worker: {}", e))?
     }
 
     pub async fn recursive_search(
@@ -508,8 +581,7 @@ impl StaWorker {
         window: tauri::Window,
     ) -> Result<(), String> {
         let (tx, rx) = channel();
-        self.sender
-            .send(StaCommand::RecursiveSearch {
+        self.submit(StaCommand::RecursiveSearch {
                 path,
                 query,
                 nav_id,
@@ -523,12 +595,14 @@ impl StaWorker {
     }
 }
 
-fn empty_recycle_bin_impl() -> Result<(), String> {
+fn empty_recycle_bin_impl(root: Option<&str>) -> Result<(), String> {
     use windows::Win32::UI::Shell::{SHEmptyRecycleBinW, SHERB_NOCONFIRMATION, SHERB_NOSOUND};
+    // None empties the bins of all drives
+    let root = root.map(windows::core::HSTRING::from);
     unsafe {
         let result = SHEmptyRecycleBinW(
             Some(windows::Win32::Foundation::HWND(std::ptr::null_mut())),
-            None,
+            root.as_ref().map(|r| PCWSTR(r.as_ptr())).unwrap_or(PCWSTR::null()),
             SHERB_NOCONFIRMATION | SHERB_NOSOUND,
         );
 
@@ -548,11 +622,27 @@ fn create_shell_item(path: &str) -> Result<IShellItem, windows::core::Error> {
             }
         }
 
-        let path_wide: Vec<u16> = OsStr::new(path)
+        // The shell namespace doesn't parse the \\?\ prefix
+        let path = crate::paths::display_path(path);
+        let path_wide: Vec<u16> = OsStr::new(&path)
             .encode_wide()
             .chain(std::iter::once(0))
             .collect();
-        SHCreateItemFromParsingName(PCWSTR(path_wide.as_ptr()), None)
+        let result = SHCreateItemFromParsingName(PCWSTR(path_wide.as_ptr()), None);
+        if result.is_ok() || path.len() < windows::Win32::Foundation::MAX_PATH as usize {
+            return result;
+        }
+        // Past MAX_PATH the shell may refuse the path; the 8.3 form often fits
+        match crate::paths::short_path(&path) {
+            Some(short) => {
+                let short_wide: Vec<u16> = OsStr::new(&short)
+                    .encode_wide()
+                    .chain(std::iter::once(0))
+                    .collect();
+                SHCreateItemFromParsingName(PCWSTR(short_wide.as_ptr()), None)
+            }
+            None => result,
+        }
     }
 }
 
@@ -600,10 +690,246 @@ fn restore_items_impl(paths: Vec<String>) -> Result<(), String> {
         }
     }
 
-    notify_refresh();
+    // Restored items go back to wherever they were deleted from
+    notify_refresh(Vec::new());
     Ok(())
 }
 
+/// Command ids handed to `QueryContextMenu`; 0 is what TrackPopupMenuEx returns on dismiss
+const CONTEXT_MENU_FIRST_ID: u32 = 1;
+const CONTEXT_MENU_LAST_ID: u32 = 0x7FFF;
+const CONTEXT_MENU_CLASS_NAME: &str = "QuickExplorerContextMenuHost";
+
+thread_local! {
+    /// Menu being tracked. Owner-drawn items and submenus filled on demand (Send to,
+    /// Open with) need the host window to forward their messages to it.
+    static ACTIVE_CONTEXT_MENU: std::cell::RefCell<Option<windows::Win32::UI::Shell::IContextMenu>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+unsafe extern "system" fn context_menu_wnd_proc(
+    hwnd: windows::Win32::Foundation::HWND,
+    msg: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::Foundation::LRESULT;
+    use windows::Win32::UI::Shell::{IContextMenu2, IContextMenu3};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DefWindowProcW, WM_DRAWITEM, WM_INITMENUPOPUP, WM_MEASUREITEM, WM_MENUCHAR,
+    };
+
+    if matches!(
+        msg,
+        WM_INITMENUPOPUP | WM_DRAWITEM | WM_MEASUREITEM | WM_MENUCHAR
+    ) {
+        let handled = ACTIVE_CONTEXT_MENU.with(|menu| unsafe {
+            let menu = menu.borrow();
+            let menu = menu.as_ref()?;
+            if let Ok(menu3) = menu.cast::<IContextMenu3>() {
+                let mut result = LRESULT(0);
+                menu3
+                    .HandleMenuMsg2(msg, wparam, lparam, Some(&mut result))
+                    .ok()?;
+                return Some(result);
+            }
+            // IContextMenu2 has no result; drawing and measuring messages want TRUE
+            menu.cast::<IContextMenu2>()
+                .ok()?
+                .HandleMenuMsg(msg, wparam, lparam)
+                .ok()?;
+            Some(LRESULT((msg != WM_INITMENUPOPUP) as isize))
+        });
+        if let Some(result) = handled {
+            return result;
+        }
+    }
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+/// Hidden window owning the tracked menu, so its messages reach `context_menu_wnd_proc`
+unsafe fn create_context_menu_host(
+    owner: windows::Win32::Foundation::HWND,
+) -> Result<windows::Win32::Foundation::HWND, String> {
+    use windows::Win32::Foundation::HINSTANCE;
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, RegisterClassW, WINDOW_EX_STYLE, WNDCLASSW, WS_POPUP,
+    };
+
+    let instance = GetModuleHandleW(None).map_err(|e| format!("GetModuleHandleW failed: {}", e))?;
+    let class_name: Vec<u16> = OsStr::new(CONTEXT_MENU_CLASS_NAME)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let wnd_class = WNDCLASSW {
+        lpfnWndProc: Some(context_menu_wnd_proc),
+        hInstance: HINSTANCE(instance.0),
+        lpszClassName: PCWSTR(class_name.as_ptr()),
+        ..Default::default()
+    };
+    // Fails harmlessly once the class is registered
+    RegisterClassW(&wnd_class);
+
+    CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        PCWSTR(class_name.as_ptr()),
+        PCWSTR::null(),
+        WS_POPUP,
+        0,
+        0,
+        0,
+        0,
+        (!owner.0.is_null()).then_some(owner),
+        None,
+        Some(HINSTANCE(instance.0)),
+        None,
+    )
+    .map_err(|e| format!("Failed to create context menu window: {}", e))
+}
+
+/// Canonical verb of the menu command at `offset`, when the handler names one
+unsafe fn context_menu_verb(
+    menu: &windows::Win32::UI::Shell::IContextMenu,
+    offset: usize,
+) -> Option<String> {
+    use windows::core::PSTR;
+    use windows::Win32::UI::Shell::GCS_VERBW;
+
+    let mut buffer = [0u16; 256];
+    menu.GetCommandString(
+        offset,
+        GCS_VERBW,
+        None,
+        PSTR(buffer.as_mut_ptr() as *mut u8),
+        buffer.len() as u32,
+    )
+    .ok()?;
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len])).filter(|v| !v.is_empty())
+}
+
+/// The full Explorer context menu for `paths`, shell extensions included, tracked at
+/// screen point (x, y). The chosen command is invoked here, except "rename", which
+/// is left to the caller's inline editor.
+fn show_context_menu_impl(
+    paths: Vec<String>,
+    x: i32,
+    y: i32,
+    hwnd: Option<isize>,
+) -> Result<Option<String>, String> {
+    use windows::core::PCSTR;
+    use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetKeyState, VK_SHIFT};
+    use windows::Win32::UI::Shell::{
+        BHID_SFUIObject, IContextMenu, SHCreateShellItemArrayFromIDLists, CMF_EXTENDEDVERBS,
+        CMF_NORMAL, CMINVOKECOMMANDINFO,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreatePopupMenu, DestroyMenu, DestroyWindow, PostMessageW, TrackPopupMenuEx,
+        SW_SHOWNORMAL, TPM_RETURNCMD, TPM_RIGHTBUTTON,
+    };
+
+    let owner = HWND(hwnd.unwrap_or(0) as *mut _);
+
+    let verb = unsafe {
+        let pidls: Vec<*mut ITEMIDLIST> = paths
+            .iter()
+            .filter_map(|path| {
+                let item = create_shell_item(path)
+                    .map_err(|e| log::warn!("[STA-WORKER] Context menu skips {}: {}", path, e))
+                    .ok()?;
+                SHGetIDListFromObject(&item).ok()
+            })
+            .collect();
+        if pidls.is_empty() {
+            return Err("None of the items could be found".to_string());
+        }
+        let array = SHCreateShellItemArrayFromIDLists(
+            &pidls.iter().map(|p| *p as *const ITEMIDLIST).collect::<Vec<_>>(),
+        );
+        for pidl in pidls {
+            ILFree(Some(pidl as *const ITEMIDLIST));
+        }
+        // Binding the array fails when the items don't share a folder
+        let menu: IContextMenu = array
+            .and_then(|array| array.BindToHandler(None, &BHID_SFUIObject))
+            .map_err(|e| format!("Failed to get the context menu: {}", e))?;
+
+        let hmenu = CreatePopupMenu().map_err(|e| format!("CreatePopupMenu failed: {}", e))?;
+        let host = match create_context_menu_host(owner) {
+            Ok(host) => host,
+            Err(e) => {
+                let _ = DestroyMenu(hmenu);
+                return Err(e);
+            }
+        };
+
+        // Shift adds the extended verbs, as in Explorer
+        let mut flags = CMF_NORMAL;
+        if GetKeyState(VK_SHIFT.0 as i32) < 0 {
+            flags |= CMF_EXTENDEDVERBS;
+        }
+        let command = if menu
+            .QueryContextMenu(hmenu, 0, CONTEXT_MENU_FIRST_ID, CONTEXT_MENU_LAST_ID, flags)
+            .is_ok()
+        {
+            ACTIVE_CONTEXT_MENU.with(|active| *active.borrow_mut() = Some(menu.clone()));
+            let _input = ThreadInputGuard::new(owner);
+            // The menu only closes on outside clicks while its owner is in the foreground
+            let _ = SetForegroundWindow(host);
+            let command = TrackPopupMenuEx(
+                hmenu,
+                (TPM_RETURNCMD | TPM_RIGHTBUTTON).0,
+                x,
+                y,
+                host,
+                None,
+            )
+            .0 as u32;
+            let _ = PostMessageW(Some(host), WM_NULL, WPARAM(0), LPARAM(0));
+            ACTIVE_CONTEXT_MENU.with(|active| *active.borrow_mut() = None);
+            Ok(command)
+        } else {
+            Err("Failed to fill the context menu".to_string())
+        };
+        let _ = DestroyMenu(hmenu);
+        let _ = DestroyWindow(host);
+
+        let command = command?;
+        if command < CONTEXT_MENU_FIRST_ID {
+            return Ok(None);
+        }
+        let offset = (command - CONTEXT_MENU_FIRST_ID) as usize;
+        let verb = context_menu_verb(&menu, offset).unwrap_or_default();
+        log::info!(
+            "[STA-WORKER] Context menu command {} ('{}') on {} items",
+            offset,
+            verb,
+            paths.len()
+        );
+        if verb.eq_ignore_ascii_case("rename") {
+            return Ok(Some(verb));
+        }
+
+        let _input = ThreadInputGuard::new(owner);
+        let info = CMINVOKECOMMANDINFO {
+            cbSize: std::mem::size_of::<CMINVOKECOMMANDINFO>() as u32,
+            hwnd: owner,
+            // MAKEINTRESOURCE: the offset stands in for a verb string
+            lpVerb: PCSTR(offset as *const u8),
+            nShow: SW_SHOWNORMAL.0,
+            ..Default::default()
+        };
+        menu.InvokeCommand(&info)
+            .map_err(|e| format!("Failed to run '{}': {}", verb, e))?;
+        verb
+    };
+
+    notify_refresh(parent_dirs(&paths));
+    Ok(Some(verb))
+}
+
 // ==================================================================================
 // MOVED IMPLEMENTATION (Private, running on STA thread)
 // ==================================================================================
@@ -656,31 +982,44 @@ fn list_recycle_bin() -> Result<Vec<FileEntry>, String> {
                     name.clone()
                 };
 
-                let mut is_dir = false;
-                let mut file_type = "Deleted Item".to_string();
+                // Real size and times from the item's cached data; placeholders when the
+                // shell has none for it
+                let mut entry = match shell_item_find_data(&item) {
+                    Some(found) => build_entry(path, name, &found),
+                    None => FileEntry {
+                        name,
+                        path,
+                        is_dir: false,
+                        size: 0,
+                        formatted_size: String::new(),
+                        file_type: String::new(),
+                        created_at: now_str.clone(),
+                        modified_at: now_str.clone(),
+                        is_shortcut: false,
+                        disk_info: None,
+                        modified_timestamp: 0,
+                        created_timestamp: 0,
+                        dimensions: None,
+                        url_target: None,
+                        recycle_info: None,
+                        is_symlink: false,
+                        is_junction: false,
+                        link_target: None,
+                        attributes: Default::default(),
+                        cloud_status: None,
+                    },
+                };
 
                 if let Ok(attr) = item.GetAttributes(SFGAO_FOLDER) {
-                    if (attr.0 & SFGAO_FOLDER.0) != 0 {
-                        is_dir = true;
-                        file_type = "Deleted Folder".to_string();
-                    }
+                    entry.is_dir = (attr.0 & SFGAO_FOLDER.0) != 0;
                 }
-
-                files.push(FileEntry {
-                    name,
-                    path,
-                    is_dir,
-                    size: 0,
-                    formatted_size: String::new(),
-                    file_type,
-                    created_at: now_str.clone(),
-                    modified_at: now_str.clone(),
-                    is_shortcut: false,
-                    disk_info: None,
-                    modified_timestamp: 0,
-                    created_timestamp: 0,
-                    dimensions: None,
-                });
+                entry.file_type = if entry.is_dir {
+                    crate::i18n::tr(crate::i18n::Text::DeletedFolder).to_string()
+                } else {
+                    crate::i18n::tr(crate::i18n::Text::DeletedItem).to_string()
+                };
+                entry.recycle_info = recycle_info(&item);
+                files.push(entry);
             }
         }
     }
@@ -688,6 +1027,42 @@ fn list_recycle_bin() -> Result<Vec<FileEntry>, String> {
     Ok(files)
 }
 
+/// FMTID_Displaced properties, set on items in the Recycle Bin
+const DISPLACED_FMTID: windows::core::GUID =
+    windows::core::GUID::from_u128(0x9b174b33_40ff_11d2_a27e_00c04fc30871);
+const PKEY_DISPLACED_FROM: PROPERTYKEY = PROPERTYKEY { fmtid: DISPLACED_FMTID, pid: 2 };
+const PKEY_DISPLACED_DATE: PROPERTYKEY = PROPERTYKEY { fmtid: DISPLACED_FMTID, pid: 3 };
+
+/// Original folder and deletion time of a Recycle Bin item
+fn recycle_info(item: &IShellItem) -> Option<crate::RecycleInfo> {
+    unsafe {
+        let item: IShellItem2 = item.cast().ok()?;
+        let original_location = item
+            .GetString(&PKEY_DISPLACED_FROM)
+            .map(|p| {
+                let s = p.to_string().unwrap_or_default();
+                CoTaskMemFree(Some(p.as_ptr() as *const _));
+                s
+            })
+            .unwrap_or_default();
+        let deleted = item
+            .GetFileTime(&PKEY_DISPLACED_DATE)
+            .map(filetime_to_system_time)
+            .ok();
+        let deleted_datetime: Option<DateTime<Local>> = deleted.map(Into::into);
+        Some(crate::RecycleInfo {
+            original_location,
+            deleted_at: deleted_datetime
+                .map(|d| d.format("%d/%m/%Y %H:%M").to_string())
+                .unwrap_or_default(),
+            deleted_timestamp: deleted
+                .and_then(|d| d.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+        })
+    }
+}
+
 fn get_localized_name(name: &str) -> String {
     match name.to_lowercase().as_str() {
         "downloads" => "Descargas".to_string(),
@@ -706,14 +1081,175 @@ fn get_localized_name(name: &str) -> String {
     }
 }
 
+/// One directory entry as reported by FindFirstFileExW
+struct FoundItem {
+    name: String,
+    attributes: u32,
+    /// Reparse tag when the enumeration reported one; None when it isn't known
+    reparse_tag: Option<u32>,
+    size: u64,
+    created: SystemTime,
+    modified: SystemTime,
+}
+
+fn filetime_to_system_time(ft: FILETIME) -> SystemTime {
+    // FILETIME counts 100ns intervals since 1601-01-01
+    const UNIX_EPOCH_AS_FILETIME: u64 = 116_444_736_000_000_000;
+    let ticks = ((ft.dwHighDateTime as u64) << 32) | ft.dwLowDateTime as u64;
+    std::time::UNIX_EPOCH
+        + std::time::Duration::from_nanos(ticks.saturating_sub(UNIX_EPOCH_AS_FILETIME) * 100)
+}
+
+/// Enumerates `path` with FindFirstFileExW. The basic info level (no 8.3 names) and
+/// large fetch buffers return size, times and attributes along with each name, so
+/// building the entries afterwards needs no per-item metadata calls.
+fn find_items(path: &str, is_cancelled: &dyn Fn() -> bool) -> Result<Vec<FoundItem>, String> {
+    let pattern: Vec<u16> = OsStr::new(&crate::paths::long_path(std::path::Path::new(path)).join("*"))
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut data = WIN32_FIND_DATAW::default();
+    let handle = unsafe {
+        FindFirstFileExW(
+            PCWSTR(pattern.as_ptr()),
+            FindExInfoBasic,
+            &mut data as *mut _ as *mut _,
+            FindExSearchNameMatch,
+            None,
+            FIND_FIRST_EX_LARGE_FETCH,
+        )
+    };
+    let handle = match handle {
+        Ok(h) => h,
+        // An empty drive root has no "." or ".." to find
+        Err(e) if e.code() == ERROR_FILE_NOT_FOUND.to_hresult() => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read directory: {}", e)),
+    };
+
+    let mut items = Vec::new();
+    loop {
+        let len = data.cFileName.iter().position(|&c| c == 0).unwrap_or(data.cFileName.len());
+        let name = String::from_utf16_lossy(&data.cFileName[..len]);
+        if name != "." && name != ".." {
+            items.push(FoundItem {
+                name,
+                attributes: data.dwFileAttributes,
+                // FindFirstFile reports the tag of reparse points in dwReserved0
+                reparse_tag: Some(data.dwReserved0),
+                size: ((data.nFileSizeHigh as u64) << 32) | data.nFileSizeLow as u64,
+                created: filetime_to_system_time(data.ftCreationTime),
+                modified: filetime_to_system_time(data.ftLastWriteTime),
+            });
+        }
+        if is_cancelled() {
+            log::debug!("[STA-WORKER] Native read cancelled by navigation change");
+            break;
+        }
+        if unsafe { FindNextFileW(handle, &mut data) }.is_err() {
+            break;
+        }
+    }
+    unsafe {
+        let _ = FindClose(handle);
+    }
+    Ok(items)
+}
+
+/// Builds the entry for `full_path` from what the enumeration already returned, so
+/// no further metadata calls hit the disk; `.url` files and links are the only ones opened
+fn build_entry(full_path: String, name: String, item: &FoundItem) -> FileEntry {
+    let path_obj = std::path::Path::new(&full_path);
+    let is_dir = item.attributes & FILE_ATTRIBUTE_DIRECTORY.0 != 0;
+    let link = if item.attributes & FILE_ATTRIBUTE_REPARSE_POINT.0 == 0 {
+        None
+    } else {
+        match item.reparse_tag {
+            Some(tag) => crate::links::read_link_tagged(path_obj, tag),
+            None => crate::links::read_link(path_obj),
+        }
+    };
+    let size = if is_dir { 0 } else { item.size };
+
+    let formatted_size = if is_dir {
+        String::new()
+    } else {
+        crate::settings::format_size(size)
+    };
+
+    let extension = path_obj
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let is_shortcut = extension == "lnk";
+    // Reading an online-only file would download it
+    let url_target = if extension == "url"
+        && crate::cloud::cloud_status(item.attributes, None) != Some(crate::cloud::CloudStatus::OnlineOnly)
+    {
+        crate::shortcuts::read_url_target(path_obj)
+    } else {
+        None
+    };
+
+    let file_type = if is_shortcut {
+        crate::i18n::tr(crate::i18n::Text::Shortcut).to_string()
+    } else if url_target.is_some() {
+        crate::i18n::tr(crate::i18n::Text::InternetShortcut).to_string()
+    } else if is_dir {
+        crate::i18n::tr(crate::i18n::Text::Folder).to_string()
+    } else {
+        crate::i18n::file_type_label(
+            path_obj
+                .extension()
+                .map(|ext| ext.to_string_lossy())
+                .as_deref(),
+        )
+    };
+
+    let created_datetime: DateTime<Local> = item.created.into();
+    let created_at_str = created_datetime.format("%d/%m/%Y %H:%M").to_string();
+
+    let modified_datetime: DateTime<Local> = item.modified.into();
+    let modified_at_str = modified_datetime.format("%d/%m/%Y %H:%M").to_string();
+
+    FileEntry {
+        name,
+        path: full_path,
+        is_dir,
+        size,
+        formatted_size,
+        file_type,
+        created_at: created_at_str,
+        modified_at: modified_at_str,
+        is_shortcut,
+        disk_info: None,
+        modified_timestamp: item
+            .modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64,
+        created_timestamp: item
+            .created
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64,
+        dimensions: None,
+        url_target,
+        recycle_info: None,
+        is_symlink: false,
+        is_junction: false,
+        link_target: None,
+        attributes: crate::attributes::FileAttributes::from_raw(item.attributes),
+        cloud_status: crate::cloud::cloud_status(item.attributes, item.reparse_tag),
+    }
+    .with_link(link)
+}
+
 fn list_files_native(
     path: &str,
     show_hidden: bool,
     nav_id: Option<String>,
 ) -> Result<Vec<FileEntry>, String> {
-    let entries =
-        std::fs::read_dir(path).map_err(|e| format!("Failed to read directory: {}", e))?;
-
     let is_cancelled = || {
         if let Some(id) = &nav_id {
             if let Some(mutex) = crate::GLOBAL_NAV_ID.get() {
@@ -725,120 +1261,27 @@ fn list_files_native(
         false
     };
 
-    let mut entries_vec = Vec::new();
-    for e in entries {
-        if is_cancelled() {
-            log::debug!("[STA-WORKER] Native read cancelled by navigation change (nav_id {:?})", nav_id);
-            break;
-        }
-        if let Ok(entry) = e {
-            entries_vec.push(entry);
-        }
-    }
+    let start = std::time::Instant::now();
+    let items = find_items(path, &is_cancelled)?;
+    let enumerated_in = start.elapsed();
 
-    let files: Vec<FileEntry> = entries_vec
+    let parent = std::path::Path::new(path);
+    let files: Vec<FileEntry> = items
         .into_par_iter()
-        .filter_map(|entry| {
-            let path_obj = entry.path();
-            let name = entry.file_name().to_string_lossy().to_string();
-            let full_path = path_obj.to_string_lossy().to_string();
-
-            if !show_hidden {
-                // On Windows, check file attributes for hidden flag
-                #[cfg(windows)]
-                {
-                    use std::os::windows::fs::MetadataExt;
-                    if let Ok(metadata) = entry.metadata() {
-                        let attrs = metadata.file_attributes();
-                        if (attrs & 0x2) != 0 {
-                            // 0x2 is FILE_ATTRIBUTE_HIDDEN
-                            return None;
-                        }
-                    }
-                }
-            }
-
-            if let Ok(metadata) = entry.metadata() {
-                let is_dir = metadata.is_dir();
-                let size = if is_dir { 0 } else { metadata.len() };
-
-                let formatted_size = if is_dir {
-                    String::new()
-                } else if size < 1024 {
-                    format!("{} B", size)
-                } else if size < 1024 * 1024 {
-                    format!("{:.1} KB", size as f64 / 1024.0)
-                } else {
-                    format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
-                };
-
-                let extension = path_obj
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .unwrap_or("")
-                    .to_lowercase();
-                let is_shortcut = extension == "lnk";
-
-                let file_type = if is_shortcut {
-                    "Shortcut".to_string()
-                } else if is_dir {
-                    "Folder".to_string()
-                } else {
-                    path_obj
-                        .extension()
-                        .map(|ext| ext.to_string_lossy().to_uppercase() + " File")
-                        .unwrap_or_else(|| "File".to_string())
-                };
-
-                let created_at = metadata.created().unwrap_or_else(|_| SystemTime::now());
-                let created_datetime: DateTime<Local> = created_at.into();
-                let created_at_str = created_datetime.format("%d/%m/%Y %H:%M").to_string();
-
-                let modified_at = metadata.modified().unwrap_or_else(|_| SystemTime::now());
-                let modified_datetime: DateTime<Local> = modified_at.into();
-                let modified_at_str = modified_datetime.format("%d/%m/%Y %H:%M").to_string();
-
-                Some(FileEntry {
-                    name: get_localized_name(&name),
-                    path: full_path,
-                    is_dir,
-                    size,
-                    formatted_size,
-                    file_type,
-                    created_at: created_at_str,
-                    modified_at: modified_at_str,
-                    is_shortcut,
-                    disk_info: None,
-                    modified_timestamp: modified_at
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs() as i64,
-                    created_timestamp: created_at
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs() as i64,
-                    dimensions: None,
-                })
-            } else {
-                Some(FileEntry {
-                    name: get_localized_name(&name),
-                    path: full_path,
-                    is_dir: false,
-                    size: 0,
-                    formatted_size: String::new(),
-                    file_type: "System Item".to_string(),
-                    created_at: "".to_string(),
-                    modified_at: "".to_string(),
-                    is_shortcut: false,
-                    disk_info: None,
-                    modified_timestamp: 0,
-                    created_timestamp: 0,
-                    dimensions: None,
-                })
-            }
+        .filter(|item| show_hidden || item.attributes & FILE_ATTRIBUTE_HIDDEN.0 == 0)
+        .map(|item| {
+            let full_path = parent.join(&item.name).to_string_lossy().to_string();
+            build_entry(full_path, get_localized_name(&item.name), &item)
         })
         .collect();
 
+    log::debug!(
+        "[STA-WORKER] Native listing of {}: {} items, enumerated in {:?}, built in {:?}",
+        path,
+        files.len(),
+        enumerated_in,
+        start.elapsed() - enumerated_in
+    );
     Ok(files)
 }
 
@@ -872,67 +1315,15 @@ fn list_items_impl(
         for b in b'C'..=b'Z' {
             let drive_letter = b as char;
             let drive_path = format!("{}:\\", drive_letter);
-            if std::path::Path::new(&drive_path).exists() {
-                let mut free_bytes_available = 0u64;
-                let mut total_number_of_bytes = 0u64;
-                let mut total_number_of_free_bytes = 0u64;
-
-                let path_wide: Vec<u16> = OsStr::new(&drive_path)
-                    .encode_wide()
-                    .chain(std::iter::once(0))
-                    .collect();
-
-                let system_drive = std::env::var("SystemDrive")
-                    .unwrap_or_else(|_| "C:".to_string())
-                    .to_uppercase();
-                let is_system = drive_path.starts_with(&system_drive);
-
-                let disk_info = unsafe {
-                    if GetDiskFreeSpaceExW(
-                        PCWSTR(path_wide.as_ptr()),
-                        Some(&mut free_bytes_available),
-                        Some(&mut total_number_of_bytes),
-                        Some(&mut total_number_of_free_bytes),
-                    )
-                    .is_ok()
-                    {
-                        Some(DiskInfo {
-                            total: total_number_of_bytes,
-                            used: total_number_of_bytes - total_number_of_free_bytes,
-                            free: total_number_of_free_bytes,
-                            is_system,
-                            is_ssd: crate::is_ssd(&drive_path),
-                        })
-                    } else {
-                        None
-                    }
+            // Locked BitLocker volumes can't be read, yet they still have a DiskInfo
+            let disk_info = crate::disk_monitor::disk_info(&drive_path);
+            if disk_info.is_some() || std::path::Path::new(&drive_path).exists() {
+                let label = match &disk_info {
+                    Some(info) if !info.label.is_empty() => info.label.clone(),
+                    Some(info) => info.drive_type.default_label().to_string(),
+                    None => crate::i18n::tr(crate::i18n::Text::LocalDisk).to_string(),
                 };
-
-                // Get Custom Volume Label
-                let mut volume_name_buffer: [u16; windows::Win32::Foundation::MAX_PATH as usize
-                    + 1] = [0; windows::Win32::Foundation::MAX_PATH as usize + 1];
-                let mut display_name = format!("Local Disk ({}:)", drive_letter);
-
-                unsafe {
-                    if windows::Win32::Storage::FileSystem::GetVolumeInformationW(
-                        PCWSTR(path_wide.as_ptr()),
-                        Some(&mut volume_name_buffer),
-                        None,
-                        None,
-                        None,
-                        None,
-                    )
-                    .is_ok()
-                    {
-                        let vol_name = String::from_utf16_lossy(&volume_name_buffer)
-                            .trim_matches(char::from(0))
-                            .to_string();
-
-                        if !vol_name.is_empty() {
-                            display_name = format!("{} ({}:)", vol_name, drive_letter);
-                        }
-                    }
-                }
+                let display_name = format!("{} ({}:)", label, drive_letter);
 
                 drives.push(FileEntry {
                     name: display_name,
@@ -940,7 +1331,7 @@ fn list_items_impl(
                     is_dir: true,
                     size: 0,
                     formatted_size: String::new(),
-                    file_type: "Drive".to_string(),
+                    file_type: crate::i18n::tr(crate::i18n::Text::Drive).to_string(),
                     created_at: created_at_str.clone(),
                     modified_at: created_at_str.clone(),
                     is_shortcut: false,
@@ -948,6 +1339,13 @@ fn list_items_impl(
                     modified_timestamp: 0,
                     created_timestamp: 0,
                     dimensions: None,
+                    url_target: None,
+                    recycle_info: None,
+                    is_symlink: false,
+                    is_junction: false,
+                    link_target: None,
+                    attributes: Default::default(),
+                    cloud_status: None,
                 });
             }
         }
@@ -958,6 +1356,11 @@ fn list_items_impl(
         // ... drives logic exists ... (already correctly handled above)
     }
 
+    if let Some(host) = crate::network::server_root(path) {
+        return crate::network::share_entries(host);
+    }
+    crate::network::ensure_connected(path);
+
     let path_obj = std::path::Path::new(path);
     let results = if path_obj.is_absolute() && path_obj.exists() {
         list_files_native(path, show_hidden, nav_id)
@@ -972,12 +1375,43 @@ fn list_items_impl(
     results
 }
 
+/// FMTID_Storage properties, present on every file system item
+const STORAGE_FMTID: windows::core::GUID =
+    windows::core::GUID::from_u128(0xb725f130_47ef_101a_a5f1_02608c9eebac);
+const PKEY_SIZE: PROPERTYKEY = PROPERTYKEY { fmtid: STORAGE_FMTID, pid: 12 };
+const PKEY_FILE_ATTRIBUTES: PROPERTYKEY = PROPERTYKEY { fmtid: STORAGE_FMTID, pid: 13 };
+const PKEY_DATE_MODIFIED: PROPERTYKEY = PROPERTYKEY { fmtid: STORAGE_FMTID, pid: 14 };
+const PKEY_DATE_CREATED: PROPERTYKEY = PROPERTYKEY { fmtid: STORAGE_FMTID, pid: 15 };
+
+/// Size, times and attributes of a shell item, from the data cached with its PIDL;
+/// None for virtual items that aren't backed by a file
+fn shell_item_find_data(item: &IShellItem) -> Option<FoundItem> {
+    unsafe {
+        let item: IShellItem2 = item.cast().ok()?;
+        let attributes = item.GetUInt32(&PKEY_FILE_ATTRIBUTES).ok()?;
+        let time = |key| {
+            item.GetFileTime(key)
+                .map(filetime_to_system_time)
+                .unwrap_or_else(|_| SystemTime::now())
+        };
+        Some(FoundItem {
+            name: String::new(),
+            attributes,
+            reparse_tag: None,
+            size: item.GetUInt64(&PKEY_SIZE).unwrap_or(0),
+            created: time(&PKEY_DATE_CREATED),
+            modified: time(&PKEY_DATE_MODIFIED),
+        })
+    }
+}
+
 fn list_items_shell_fallback(
     path: &str,
     show_hidden: bool,
     nav_id: Option<String>,
 ) -> Result<Vec<FileEntry>, String> {
     let mut files = Vec::new();
+    let start = std::time::Instant::now();
     
     let is_cancelled = || {
         if let Some(id) = &nav_id {
@@ -1037,8 +1471,6 @@ fn list_items_shell_fallback(
                     })
                     .unwrap_or_else(|_| name.clone());
 
-                let path_obj = std::path::Path::new(&full_path);
-
                 if !show_hidden {
                     let hidden_flag = 0x80000; // SFGAO_HIDDEN
                     if let Ok(attr) = child_item.GetAttributes(SFGAO_FLAGS(hidden_flag)) {
@@ -1048,69 +1480,10 @@ fn list_items_shell_fallback(
                     }
                 }
 
-                // Build FileEntry inline — avoids the redundant disk read
-                // that get_file_entry(path) would cause via path.metadata().
-                if let Ok(metadata) = path_obj.metadata() {
-                    let is_dir = metadata.is_dir();
-                    let size = if is_dir { 0 } else { metadata.len() };
-
-                    let formatted_size = if is_dir {
-                        String::new()
-                    } else if size < 1024 {
-                        format!("{} B", size)
-                    } else if size < 1024 * 1024 {
-                        format!("{:.1} KB", size as f64 / 1024.0)
-                    } else {
-                        format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
-                    };
-
-                    let extension = path_obj
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .unwrap_or("")
-                        .to_lowercase();
-                    let is_shortcut = extension == "lnk";
-
-                    let file_type = if is_shortcut {
-                        "Shortcut".to_string()
-                    } else if is_dir {
-                        "Folder".to_string()
-                    } else {
-                        path_obj
-                            .extension()
-                            .map(|ext| ext.to_string_lossy().to_uppercase() + " File")
-                            .unwrap_or_else(|| "File".to_string())
-                    };
-
-                    let created_at = metadata.created().unwrap_or_else(|_| SystemTime::now());
-                    let created_datetime: DateTime<Local> = created_at.into();
-                    let created_at_str = created_datetime.format("%d/%m/%Y %H:%M").to_string();
-
-                    let modified_at = metadata.modified().unwrap_or_else(|_| SystemTime::now());
-                    let modified_datetime: DateTime<Local> = modified_at.into();
-                    let modified_at_str = modified_datetime.format("%d/%m/%Y %H:%M").to_string();
-
-                    files.push(FileEntry {
-                        name,
-                        path: full_path,
-                        is_dir,
-                        size,
-                        formatted_size,
-                        file_type,
-                        created_at: created_at_str,
-                        modified_at: modified_at_str,
-                        is_shortcut,
-                        disk_info: None,
-                        modified_timestamp: modified_at
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs() as i64,
-                        created_timestamp: created_at
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs() as i64,
-                        dimensions: None,
-                    });
+                // File system items carry their find data in the PIDL; reading it through
+                // the property store avoids opening every file for its metadata
+                if let Some(found) = shell_item_find_data(&child_item) {
+                    files.push(build_entry(full_path, name, &found));
                 } else {
                     files.push(FileEntry {
                         name,
@@ -1126,39 +1499,84 @@ fn list_items_shell_fallback(
                         modified_timestamp: 0,
                         created_timestamp: 0,
                         dimensions: None,
+                        url_target: None,
+                        recycle_info: None,
+                        is_symlink: false,
+                        is_junction: false,
+                        link_target: None,
+                        attributes: Default::default(),
+                        cloud_status: None,
                     });
                 }
             }
         }
     }
 
-    files.par_sort_unstable_by(|a, b| {
-        if a.is_dir && !b.is_dir {
-            std::cmp::Ordering::Less
-        } else if !a.is_dir && b.is_dir {
-            std::cmp::Ordering::Greater
-        } else {
-            let a_chars = a.name.chars();
-            let b_chars = b.name.chars();
-
-            for (ac, bc) in a_chars.zip(b_chars) {
-                let alc = ac.to_lowercase().next().unwrap();
-                let blc = bc.to_lowercase().next().unwrap();
-                if alc != blc {
-                    return alc.cmp(&blc);
-                }
-            }
-            a.name.len().cmp(&b.name.len())
-        }
-    });
+    crate::listing::sort_entries(&mut files, crate::listing::SortBy::Name, crate::listing::SortDir::Asc);
 
+    log::debug!(
+        "[STA-WORKER] Shell listing of {}: {} items in {:?}",
+        path,
+        files.len(),
+        start.elapsed()
+    );
     Ok(files)
 }
 
-fn drop_items_impl(
+/// Runs a copy/move/delete from the operation queue on the calling thread, which
+/// gets its own apartment so a paused operation doesn't hold up the STA worker
+pub(crate) fn run_file_operation<T>(
+    control: &OperationControl,
+    operation: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    if control.is_cancelled() {
+        return Err("Operation cancelled".to_string());
+    }
+    unsafe {
+        OleInitialize(None).map_err(|e| format!("OleInitialize failed: {}", e))?;
+    }
+    // Same handle hygiene as the worker loop: cached listings and tab watchers
+    // must not keep the folders involved open
+    crate::listing::invalidate_cache();
+    crate::watcher::suspend_tab_watches();
+    let result = operation();
+    crate::watcher::resume_tab_watches();
+    unsafe { OleUninitialize() };
+    result
+}
+
+/// Queues `item` for a copy or move into `target`, under the name the plan gave it
+unsafe fn queue_planned_item(
+    file_op: &IFileOperation,
+    item: &conflicts::PlannedItem,
+    target: &IShellItem,
+    is_move: bool,
+) {
+    let Ok(shell_item) = create_shell_item(&item.source) else {
+        return;
+    };
+    let new_name: Option<Vec<u16>> = item.new_name.as_ref().map(|name| {
+        OsStr::new(name)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect()
+    });
+    let name = new_name
+        .as_ref()
+        .map_or(PCWSTR(std::ptr::null()), |w| PCWSTR(w.as_ptr()));
+    let _ = if is_move {
+        file_op.MoveItem(&shell_item, target, name, None)
+    } else {
+        file_op.CopyItem(&shell_item, target, name, None)
+    };
+}
+
+pub(crate) fn drop_items_impl(
     files: Vec<String>,
     target_path: String,
+    resolutions: Option<Resolutions>,
     hwnd: Option<isize>,
+    control: &Arc<OperationControl>,
 ) -> Result<Vec<String>, String> {
     log::debug!(
         "[STA-WORKER] drop_items_impl (IFileOperation) called with {} files to {}",
@@ -1170,8 +1588,13 @@ fn drop_items_impl(
         let file_op: IFileOperation = CoCreateInstance(&FileOperation, None, CLSCTX_ALL)
             .map_err(|e| format!("Failed to create IFileOperation: {}", e))?;
 
-        let _ =
-            file_op.SetOperationFlags(FOF_ALLOWUNDO | FOF_RENAMEONCOLLISION | FOF_NOCONFIRMMKDIR);
+        // With the frontend's answers each collision is handled as asked;
+        // without them the shell renames every collision
+        let plan = match &resolutions {
+            Some(resolutions) => conflicts::plan(&files, &target_path, false, resolutions),
+            None => conflicts::Plan::shell_renames(&files),
+        };
+        let _ = file_op.SetOperationFlags(plan.operation_flags());
 
         // --- LIFETIME EXTENSION (v8.1) ---
         // Declare the guard at the function level so it lives through PerformOperations()
@@ -1188,10 +1611,8 @@ fn drop_items_impl(
         let dest_item: IShellItem = create_shell_item(&target_path)
             .map_err(|e| format!("Failed to create destination item: {}", e))?;
 
-        for f in &files {
-            if let Ok(item) = create_shell_item(f) {
-                let _ = file_op.CopyItem(&item, &dest_item, PCWSTR(std::ptr::null()), None);
-            }
+        for item in &plan.items {
+            queue_planned_item(&file_op, item, &dest_item, false);
         }
 
         // HANDSHAKE v11.0 (STA Sync)
@@ -1199,19 +1620,22 @@ fn drop_items_impl(
             synchronize_handshake(hwnd_win);
         }
 
+        crate::file_op_progress::attach(&file_op, HistoryOperation::Copy, &files, control);
+
         file_op
             .PerformOperations()
             .map_err(|e| format!("PerformOperations failed: {}", e))?;
-        notify_refresh();
+        notify_refresh(vec![target_path.clone()]);
     }
 
     Ok(Vec::new())
 }
 
-fn move_items_impl(
+pub(crate) fn move_items_impl(
     paths: Vec<String>,
     target_path: String,
     hwnd: Option<isize>,
+    control: &Arc<OperationControl>,
 ) -> Result<(), String> {
     log::debug!(
         "[STA-WORKER] move_items_impl (IFileOperation) called with {} files to {}",
@@ -1251,15 +1675,23 @@ fn move_items_impl(
             synchronize_handshake(hwnd_win);
         }
 
+        crate::file_op_progress::attach(&file_op, HistoryOperation::Move, &paths, control);
+
         file_op
             .PerformOperations()
             .map_err(|e| format!("PerformOperations failed: {}", e))?;
-        notify_refresh();
+        let mut dirs = parent_dirs(&paths);
+        dirs.push(target_path.clone());
+        notify_refresh(dirs);
     }
     Ok(())
 }
 
-fn delete_items_impl(paths: Vec<String>, hwnd: Option<isize>) -> Result<(), String> {
+pub(crate) fn delete_items_impl(
+    paths: Vec<String>,
+    hwnd: Option<isize>,
+    control: &Arc<OperationControl>,
+) -> Result<(), String> {
     unsafe {
         let file_op: IFileOperation = CoCreateInstance(&FileOperation, None, CLSCTX_ALL)
             .map_err(|e| format!("Failed to create IFileOperation: {}", e))?;
@@ -1278,6 +1710,8 @@ fn delete_items_impl(paths: Vec<String>, hwnd: Option<isize>) -> Result<(), Stri
             let _ = file_op.SetOwnerWindow(hwnd_win);
         }
 
+        // Folder links go through the shell like everything else: it recycles (or deletes)
+        // the link itself and never descends into the folder it points to.
         for f in &paths {
             if let Ok(item) = create_shell_item(f) {
                 let _ = file_op.DeleteItem(&item, None);
@@ -1289,10 +1723,12 @@ fn delete_items_impl(paths: Vec<String>, hwnd: Option<isize>) -> Result<(), Stri
             synchronize_handshake(hwnd_win);
         }
 
+        crate::file_op_progress::attach(&file_op, HistoryOperation::Delete, &paths, control);
+
         file_op
             .PerformOperations()
             .map_err(|e| format!("PerformOperations failed: {}", e))?;
-        notify_refresh();
+        notify_refresh(parent_dirs(&paths));
     }
     Ok(())
 }
@@ -1334,16 +1770,71 @@ fn rename_item_impl(path: String, new_name: String, hwnd: Option<isize>) -> Resu
         file_op
             .PerformOperations()
             .map_err(|e| format!("PerformOperations failed: {}", e))?;
-        notify_refresh();
+        notify_refresh(parent_dirs([&path]));
     }
     Ok(())
 }
 
-fn paste_items_impl(
+/// Renames each `(path, new_name)` pair in one shell operation, so the batch is one undo step
+pub(crate) fn rename_items_impl(
+    renames: Vec<(String, String)>,
+    hwnd: Option<isize>,
+    control: &Arc<OperationControl>,
+) -> Result<(), String> {
+    unsafe {
+        let file_op: IFileOperation = CoCreateInstance(&FileOperation, None, CLSCTX_ALL)
+            .map_err(|e| format!("Failed to create IFileOperation: {}", e))?;
+
+        // Names were checked beforehand; a collision now is a race, not something to rename around
+        let _ = file_op.SetOperationFlags(FOF_ALLOWUNDO | FOF_NOCONFIRMMKDIR);
+
+        let mut _input_guard: Option<ThreadInputGuard> = None;
+        let mut hwnd_win = windows::Win32::Foundation::HWND::default();
+
+        if let Some(h) = hwnd {
+            hwnd_win = windows::Win32::Foundation::HWND(h as *mut _);
+            log_sta_diagnostic("BEFORE PerformOperations (Batch Rename)", hwnd_win);
+            _input_guard = Some(ThreadInputGuard::new(hwnd_win));
+            let _ = file_op.SetOwnerWindow(hwnd_win);
+        }
+
+        for (path, new_name) in &renames {
+            let item: IShellItem = create_shell_item(path)
+                .map_err(|e| format!("Failed to create item for {}: {}", path, e))?;
+            let name_wide: Vec<u16> = OsStr::new(new_name)
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect();
+            file_op
+                .RenameItem(&item, PCWSTR(name_wide.as_ptr()), None)
+                .map_err(|e| format!("Failed to queue rename of {}: {}", path, e))?;
+        }
+
+        let paths: Vec<String> = renames.iter().map(|(path, _)| path.clone()).collect();
+        crate::file_op_progress::attach(&file_op, HistoryOperation::Rename, &paths, control);
+
+        if !hwnd_win.0.is_null() {
+            synchronize_handshake(hwnd_win);
+        }
+
+        file_op
+            .PerformOperations()
+            .map_err(|e| format!("PerformOperations failed: {}", e))?;
+        if file_op.GetAnyOperationsAborted().map(|b| b.as_bool()).unwrap_or(false) {
+            return Err("Rename cancelled".to_string());
+        }
+        notify_refresh(parent_dirs(&paths));
+    }
+    Ok(())
+}
+
+pub(crate) fn paste_items_impl(
     paths: Vec<String>,
     target_path: String,
     is_move: bool,
+    resolutions: Option<Resolutions>,
     hwnd: Option<isize>,
+    control: &Arc<OperationControl>,
 ) -> Result<Vec<String>, String> {
     log::debug!(
         "[STA-WORKER] paste_items_impl called with {} files to {} (is_move: {})",
@@ -1356,8 +1847,11 @@ fn paste_items_impl(
         let file_op: IFileOperation = CoCreateInstance(&FileOperation, None, CLSCTX_ALL)
             .map_err(|e| format!("Failed to create IFileOperation: {}", e))?;
 
-        let _ =
-            file_op.SetOperationFlags(FOF_ALLOWUNDO | FOF_RENAMEONCOLLISION | FOF_NOCONFIRMMKDIR);
+        let plan = match &resolutions {
+            Some(resolutions) => conflicts::plan(&paths, &target_path, is_move, resolutions),
+            None => conflicts::Plan::shell_renames(&paths),
+        };
+        let _ = file_op.SetOperationFlags(plan.operation_flags());
 
         // --- LIFETIME EXTENSION (v8.1) ---
         let mut _input_guard: Option<ThreadInputGuard> = None;
@@ -1373,14 +1867,8 @@ fn paste_items_impl(
         let dest_item: IShellItem = create_shell_item(&target_path)
             .map_err(|e| format!("Failed to create destination item: {}", e))?;
 
-        for f in &paths {
-            if let Ok(item) = create_shell_item(f) {
-                if is_move {
-                    let _ = file_op.MoveItem(&item, &dest_item, PCWSTR(std::ptr::null()), None);
-                } else {
-                    let _ = file_op.CopyItem(&item, &dest_item, PCWSTR(std::ptr::null()), None);
-                }
-            }
+        for item in &plan.items {
+            queue_planned_item(&file_op, item, &dest_item, is_move);
         }
 
         // HANDSHAKE v11.0 (STA Sync)
@@ -1388,10 +1876,23 @@ fn paste_items_impl(
             synchronize_handshake(hwnd_win);
         }
 
+        let operation = if is_move {
+            HistoryOperation::Move
+        } else {
+            HistoryOperation::Copy
+        };
+        crate::file_op_progress::attach(&file_op, operation, &paths, control);
+
         file_op
             .PerformOperations()
             .map_err(|e| format!("PerformOperations failed: {}", e))?;
-        notify_refresh();
+        let mut dirs = if is_move {
+            parent_dirs(&paths)
+        } else {
+            Vec::new()
+        };
+        dirs.push(target_path.clone());
+        notify_refresh(dirs);
     }
 
     Ok(paths)
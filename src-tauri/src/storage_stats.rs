@@ -0,0 +1,186 @@
+//! Storage Stats Module
+//!
+//! "Size" vs "Size on disk" for the properties panel, computed the way
+//! Explorer does: plain files are rounded up to whole clusters, while NTFS
+//! compressed and sparse files report what is actually allocated
+//! (`GetCompressedFileSizeW`). The walk runs on jwalk's worker threads, with
+//! each file's numbers gathered where it is read.
+
+use serde::Serialize;
+use std::os::windows::fs::MetadataExt;
+use std::path::Path;
+use ts_rs::TS;
+use windows::core::HSTRING;
+use windows::Win32::Storage::FileSystem::{
+    GetCompressedFileSizeW, GetDiskFreeSpaceW, GetVolumePathNameW, FILE_ATTRIBUTE_COMPRESSED,
+    FILE_ATTRIBUTE_SPARSE_FILE, INVALID_FILE_SIZE,
+};
+
+/// Used when the volume does not report its geometry
+const DEFAULT_CLUSTER_SIZE: u64 = 4096;
+
+#[derive(Serialize, Clone, TS, Debug, Default)]
+#[ts(export)]
+pub struct FolderStorageStats {
+    #[ts(type = "number")]
+    pub logical_size: u64,
+    #[ts(type = "number")]
+    pub size_on_disk: u64,
+    #[ts(type = "number")]
+    pub file_count: u64,
+    #[ts(type = "number")]
+    pub folder_count: u64,
+    #[ts(type = "number")]
+    pub compressed_files: u64,
+    /// Logical bytes of the compressed files, to show how much compression saved
+    #[ts(type = "number")]
+    pub compressed_logical_size: u64,
+    #[ts(type = "number")]
+    pub sparse_files: u64,
+    #[ts(type = "number")]
+    pub cluster_size: u64,
+}
+
+/// Per-file numbers gathered on the walker threads
+#[derive(Debug, Default, Clone, Copy)]
+struct FileStats {
+    logical: u64,
+    on_disk: u64,
+    compressed: bool,
+    sparse: bool,
+}
+
+fn round_to_cluster(len: u64, cluster_size: u64) -> u64 {
+    len.div_ceil(cluster_size) * cluster_size
+}
+
+/// Cluster size of the volume holding `path`
+fn cluster_size(path: &Path) -> u64 {
+    let mut root = [0u16; 261];
+    unsafe {
+        if GetVolumePathNameW(&HSTRING::from(path), &mut root).is_err() {
+            return DEFAULT_CLUSTER_SIZE;
+        }
+        let mut sectors_per_cluster = 0u32;
+        let mut bytes_per_sector = 0u32;
+        let ok = GetDiskFreeSpaceW(
+            windows::core::PCWSTR(root.as_ptr()),
+            Some(&mut sectors_per_cluster),
+            Some(&mut bytes_per_sector),
+            None,
+            None,
+        )
+        .is_ok();
+        let size = sectors_per_cluster as u64 * bytes_per_sector as u64;
+        if ok && size > 0 {
+            size
+        } else {
+            DEFAULT_CLUSTER_SIZE
+        }
+    }
+}
+
+/// Bytes actually allocated to a compressed or sparse file
+fn allocated_size(path: &Path) -> Option<u64> {
+    let mut high = 0u32;
+    let low = unsafe { GetCompressedFileSizeW(&HSTRING::from(path), Some(&mut high)) };
+    // INVALID_FILE_SIZE is also a valid low word, so the last error decides
+    if low == INVALID_FILE_SIZE && std::io::Error::last_os_error().raw_os_error() != Some(0) {
+        return None;
+    }
+    Some(((high as u64) << 32) | low as u64)
+}
+
+fn file_stats(path: &Path, metadata: &std::fs::Metadata, cluster_size: u64) -> FileStats {
+    let attributes = metadata.file_attributes();
+    let compressed = attributes & FILE_ATTRIBUTE_COMPRESSED.0 != 0;
+    let sparse = attributes & FILE_ATTRIBUTE_SPARSE_FILE.0 != 0;
+    let logical = metadata.len();
+    let on_disk = if compressed || sparse {
+        allocated_size(path)
+            .map(|a| round_to_cluster(a, cluster_size))
+            .unwrap_or_else(|| round_to_cluster(logical, cluster_size))
+    } else {
+        round_to_cluster(logical, cluster_size)
+    };
+    FileStats {
+        logical,
+        on_disk,
+        compressed,
+        sparse,
+    }
+}
+
+fn add_file(stats: &mut FolderStorageStats, file: FileStats) {
+    stats.logical_size += file.logical;
+    stats.size_on_disk += file.on_disk;
+    stats.file_count += 1;
+    if file.compressed {
+        stats.compressed_files += 1;
+        stats.compressed_logical_size += file.logical;
+    }
+    if file.sparse {
+        stats.sparse_files += 1;
+    }
+}
+
+fn storage_stats(path: &Path) -> Result<FolderStorageStats, String> {
+    let metadata = std::fs::symlink_metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let cluster = cluster_size(path);
+    let mut stats = FolderStorageStats {
+        cluster_size: cluster,
+        ..Default::default()
+    };
+    if !metadata.is_dir() {
+        add_file(&mut stats, file_stats(path, &metadata, cluster));
+        return Ok(stats);
+    }
+
+    let walker = jwalk::WalkDirGeneric::<((), Option<FileStats>)>::new(path)
+        .skip_hidden(false)
+        .follow_links(false)
+        .process_read_dir(move |_, _, _, children| {
+            for child in children.iter_mut().flatten() {
+                if child.file_type().is_file() {
+                    if let Ok(m) = child.metadata() {
+                        child.client_state = Some(file_stats(&child.path(), &m, cluster));
+                    }
+                }
+            }
+        });
+    for entry in walker.into_iter().filter_map(Result::ok) {
+        if entry.depth() == 0 {
+            continue;
+        }
+        if entry.file_type().is_dir() {
+            stats.folder_count += 1;
+        } else if let Some(file) = entry.client_state {
+            add_file(&mut stats, file);
+        }
+    }
+    Ok(stats)
+}
+
+/// Logical size vs size on disk of a file or folder, including compression and sparse totals
+#[tauri::command]
+pub async fn get_folder_storage_stats(path: String) -> Result<FolderStorageStats, String> {
+    let expanded = crate::expand_env_vars(&path);
+    tauri::async_runtime::spawn_blocking(move || storage_stats(Path::new(&expanded)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_to_cluster() {
+        assert_eq!(round_to_cluster(0, 4096), 0);
+        assert_eq!(round_to_cluster(1, 4096), 4096);
+        assert_eq!(round_to_cluster(4096, 4096), 4096);
+        assert_eq!(round_to_cluster(4097, 4096), 8192);
+        assert_eq!(round_to_cluster(100, 65536), 65536);
+    }
+}
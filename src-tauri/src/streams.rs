@@ -0,0 +1,112 @@
+//! Streams Module
+//!
+//! NTFS alternate data streams of an item, which Explorer never shows. The one
+//! most users meet is `Zone.Identifier`, the "mark of the web" browsers attach
+//! to downloads; `remove_zone_identifier` is Explorer's Unblock checkbox.
+
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::path::Path;
+use ts_rs::TS;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::ERROR_HANDLE_EOF;
+use windows::Win32::Storage::FileSystem::{
+    FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard, WIN32_FIND_STREAM_DATA,
+};
+
+const ZONE_IDENTIFIER: &str = "Zone.Identifier";
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct AlternateStream {
+    pub name: String,
+    #[ts(type = "number")]
+    pub size: u64,
+}
+
+/// The name in FindFirstStreamW's `:name:$DATA` form; None for the unnamed main stream
+fn stream_name(raw: &str) -> Option<String> {
+    let name = raw.strip_prefix(':')?;
+    let name = name.strip_suffix(":$DATA").unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn push_stream(streams: &mut Vec<AlternateStream>, data: &WIN32_FIND_STREAM_DATA) {
+    let len = data
+        .cStreamName
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(data.cStreamName.len());
+    if let Some(name) = stream_name(&String::from_utf16_lossy(&data.cStreamName[..len])) {
+        streams.push(AlternateStream {
+            name,
+            size: data.StreamSize.max(0) as u64,
+        });
+    }
+}
+
+/// Named data streams of `path`, in the order NTFS stores them
+#[tauri::command]
+pub fn list_alternate_streams(path: String) -> Result<Vec<AlternateStream>, String> {
+    let path = crate::expand_env_vars(&path);
+    let mut streams = Vec::new();
+    unsafe {
+        let mut data = WIN32_FIND_STREAM_DATA::default();
+        let handle = match FindFirstStreamW(
+            &HSTRING::from(crate::paths::long_path(Path::new(&path)).as_path()),
+            FindStreamInfoStandard,
+            &mut data as *mut _ as *mut _,
+            None,
+        ) {
+            Ok(handle) => handle,
+            // Folders without streams, and file systems that have none
+            Err(e) if e.code() == ERROR_HANDLE_EOF.to_hresult() => return Ok(streams),
+            Err(e) => return Err(format!("Failed to list streams of {}: {}", path, e)),
+        };
+        push_stream(&mut streams, &data);
+        while FindNextStreamW(handle, &mut data as *mut _ as *mut _).is_ok() {
+            push_stream(&mut streams, &data);
+        }
+        let _ = FindClose(handle);
+    }
+    Ok(streams)
+}
+
+/// Removes the download mark from `paths`, like Explorer's Unblock checkbox.
+/// Files that aren't marked are left alone.
+#[tauri::command]
+pub fn remove_zone_identifier(paths: Vec<String>) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for path in paths {
+        let path = crate::expand_env_vars(&path);
+        match std::fs::remove_file(format!("{}:{}", path, ZONE_IDENTIFIER)) {
+            Ok(()) => log::info!("[STREAMS] Unblocked {}", path),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => errors.push(format!("{}: {}", path, e)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Failed to unblock {}", errors.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_name() {
+        assert_eq!(stream_name("::$DATA"), None);
+        assert_eq!(
+            stream_name(":Zone.Identifier:$DATA").as_deref(),
+            Some("Zone.Identifier")
+        );
+        assert_eq!(
+            stream_name(":Quick Explorer.Tags:$DATA").as_deref(),
+            Some("Quick Explorer.Tags")
+        );
+        assert_eq!(stream_name("no-colon"), None);
+    }
+}
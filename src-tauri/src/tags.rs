@@ -0,0 +1,404 @@
+//! Tags Module
+//!
+//! User-defined colored labels on files and folders, kept in `tags.db`
+//! (SQLite) in the app data folder. Paths are compared case-insensitively,
+//! like NTFS does.
+//!
+//! With the `tags.mirror_to_ads` setting on, each tagged item also carries
+//! its tag names in a `Quick Explorer.Tags` alternate data stream, so the
+//! tags survive renames, moves and copies between NTFS volumes. Items the
+//! database knows nothing about pick their tags up from that stream the
+//! first time they are asked for.
+
+use crate::settings::SettingsStore;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use ts_rs::TS;
+
+const MIRROR_TO_ADS_KEY: &str = "tags.mirror_to_ads";
+const TAGS_STREAM: &str = ":Quick Explorer.Tags";
+/// Color given to tags created implicitly by `tag_files`
+const DEFAULT_COLOR: &str = "#3b82f6";
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct Tag {
+    pub name: String,
+    /// CSS color, e.g. "#ef4444"
+    pub color: String,
+    #[ts(type = "number")]
+    pub file_count: u64,
+}
+
+static DB: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+fn open_db() -> Result<Connection, String> {
+    let path = crate::paths::app_data_dir().join("tags.db");
+    let conn =
+        Connection::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    conn.execute_batch(
+        "PRAGMA foreign_keys = ON;
+         CREATE TABLE IF NOT EXISTS tags (
+             name TEXT PRIMARY KEY COLLATE NOCASE,
+             color TEXT NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS file_tags (
+             path TEXT NOT NULL COLLATE NOCASE,
+             tag TEXT NOT NULL COLLATE NOCASE
+                 REFERENCES tags(name) ON DELETE CASCADE ON UPDATE CASCADE,
+             PRIMARY KEY (path, tag)
+         );
+         CREATE INDEX IF NOT EXISTS file_tags_by_tag ON file_tags(tag);",
+    )
+    .map_err(|e| format!("Failed to initialize tag database: {}", e))?;
+    Ok(conn)
+}
+
+fn db() -> Result<MutexGuard<'static, Connection>, String> {
+    if DB.get().is_none() {
+        let conn = open_db()?;
+        let _ = DB.set(Mutex::new(conn));
+    }
+    DB.get()
+        .ok_or("Tag database unavailable")?
+        .lock()
+        .map_err(|e| format!("Tag database lock poisoned: {}", e))
+}
+
+fn sql_err(e: rusqlite::Error) -> String {
+    format!("Tag database error: {}", e)
+}
+
+fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+    // Newlines separate tags in the mirrored stream
+    if name.contains(['\r', '\n']) {
+        return Err("Tag name cannot span lines".to_string());
+    }
+    Ok(name.to_string())
+}
+
+fn mirror_enabled() -> bool {
+    SettingsStore::global()
+        .get::<bool>(MIRROR_TO_ADS_KEY)
+        .unwrap_or(false)
+}
+
+fn tags_of(conn: &Connection, path: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT tag FROM file_tags WHERE path = ?1 ORDER BY tag")
+        .map_err(sql_err)?;
+    let rows = stmt
+        .query_map(params![path], |row| row.get::<_, String>(0))
+        .map_err(sql_err)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(sql_err)
+}
+
+fn parse_stream(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn read_stream(path: &str) -> Vec<String> {
+    std::fs::read_to_string(format!("{}{}", path, TAGS_STREAM))
+        .map(|c| parse_stream(&c))
+        .unwrap_or_default()
+}
+
+/// Rewrites the item's tag stream, keeping its modified time (writing a stream bumps it)
+fn write_stream(path: &str, tags: &[String]) {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let stream = format!("{}{}", path, TAGS_STREAM);
+    let result = if tags.is_empty() {
+        match std::fs::remove_file(&stream) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    } else {
+        std::fs::write(&stream, tags.join("\n"))
+    };
+    if let Err(e) = result {
+        log::warn!("[TAGS] Failed to update tag stream of {}: {}", path, e);
+        return;
+    }
+    if let Some(modified) = modified {
+        // Folders can't be opened for writing this way; their time is left as is
+        if let Ok(file) = std::fs::File::options().write(true).open(path) {
+            let _ = file.set_modified(modified);
+        }
+    }
+}
+
+fn sync_stream(conn: &Connection, path: &str) -> Result<(), String> {
+    if mirror_enabled() {
+        write_stream(path, &tags_of(conn, path)?);
+    }
+    Ok(())
+}
+
+fn ensure_tag(conn: &Connection, name: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO tags (name, color) VALUES (?1, ?2)",
+        params![name, DEFAULT_COLOR],
+    )
+    .map_err(sql_err)?;
+    Ok(())
+}
+
+/// Writes a consistent copy of the tag database to `target`, for backups
+pub fn export_db(target: &Path) -> Result<(), String> {
+    let _ = std::fs::remove_file(target);
+    db()?
+        .execute(
+            "VACUUM INTO ?1",
+            params![target.to_string_lossy().to_string()],
+        )
+        .map_err(|e| format!("Failed to copy tag database: {}", e))?;
+    Ok(())
+}
+
+/// Replaces all tags with those in a database written by `export_db`
+pub fn import_db(source: &Path) -> Result<(), String> {
+    let mut conn = db()?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS backup",
+        params![source.to_string_lossy().to_string()],
+    )
+    .map_err(|e| format!("Failed to open tag backup: {}", e))?;
+    let result = conn.transaction().and_then(|tx| {
+        tx.execute_batch(
+            "DELETE FROM file_tags;
+             DELETE FROM tags;
+             INSERT INTO tags (name, color) SELECT name, color FROM backup.tags;
+             INSERT INTO file_tags (path, tag) SELECT path, tag FROM backup.file_tags;",
+        )?;
+        tx.commit()
+    });
+    let _ = conn.execute("DETACH DATABASE backup", []);
+    result.map_err(|e| format!("Failed to restore tags: {}", e))
+}
+
+/// Every tag with how many items carry it
+#[tauri::command]
+pub fn list_tags() -> Result<Vec<Tag>, String> {
+    let conn = db()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.name, t.color, COUNT(f.path) FROM tags t
+             LEFT JOIN file_tags f ON f.tag = t.name
+             GROUP BY t.name ORDER BY t.name",
+        )
+        .map_err(sql_err)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(Tag {
+                name: row.get(0)?,
+                color: row.get(1)?,
+                file_count: row.get::<_, i64>(2)? as u64,
+            })
+        })
+        .map_err(sql_err)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(sql_err)
+}
+
+/// Creates a tag or changes its color
+#[tauri::command]
+pub fn set_tag(name: String, color: String) -> Result<(), String> {
+    let name = normalize_name(&name)?;
+    db()?
+        .execute(
+            "INSERT INTO tags (name, color) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET color = excluded.color",
+            params![name, color],
+        )
+        .map_err(sql_err)?;
+    Ok(())
+}
+
+/// Renames a tag on every item that carries it
+#[tauri::command]
+pub fn rename_tag(name: String, new_name: String) -> Result<(), String> {
+    let new_name = normalize_name(&new_name)?;
+    let conn = db()?;
+    let changed = conn
+        .execute(
+            "UPDATE tags SET name = ?2 WHERE name = ?1",
+            params![name, new_name],
+        )
+        .map_err(|e| format!("Failed to rename tag '{}': {}", name, e))?;
+    if changed == 0 {
+        return Err(format!("Tag '{}' does not exist", name));
+    }
+    if mirror_enabled() {
+        for path in paths_with_tag(&conn, &new_name)? {
+            sync_stream(&conn, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes a tag and removes it from every item
+#[tauri::command]
+pub fn delete_tag(name: String) -> Result<(), String> {
+    let conn = db()?;
+    let paths = paths_with_tag(&conn, &name)?;
+    conn.execute("DELETE FROM tags WHERE name = ?1", params![name])
+        .map_err(sql_err)?;
+    for path in paths {
+        sync_stream(&conn, &path)?;
+    }
+    Ok(())
+}
+
+/// Adds `tag` to each of `paths`, creating the tag if needed
+#[tauri::command]
+pub fn tag_files(paths: Vec<String>, tag: String) -> Result<(), String> {
+    let tag = normalize_name(&tag)?;
+    let mut conn = db()?;
+    let tx = conn.transaction().map_err(sql_err)?;
+    ensure_tag(&tx, &tag)?;
+    for path in &paths {
+        tx.execute(
+            "INSERT OR IGNORE INTO file_tags (path, tag) VALUES (?1, ?2)",
+            params![path, tag],
+        )
+        .map_err(sql_err)?;
+    }
+    tx.commit().map_err(sql_err)?;
+    for path in &paths {
+        sync_stream(&conn, path)?;
+    }
+    log::info!("[TAGS] Tagged {} item(s) with '{}'", paths.len(), tag);
+    Ok(())
+}
+
+/// Removes `tag` from each of `paths`
+#[tauri::command]
+pub fn untag_files(paths: Vec<String>, tag: String) -> Result<(), String> {
+    let mut conn = db()?;
+    let tx = conn.transaction().map_err(sql_err)?;
+    for path in &paths {
+        tx.execute(
+            "DELETE FROM file_tags WHERE path = ?1 AND tag = ?2",
+            params![path, tag],
+        )
+        .map_err(sql_err)?;
+    }
+    tx.commit().map_err(sql_err)?;
+    for path in &paths {
+        sync_stream(&conn, path)?;
+    }
+    Ok(())
+}
+
+/// Tags of one item; tags carried in its stream are imported if the database has none
+#[tauri::command]
+pub fn get_file_tags(path: String) -> Result<Vec<String>, String> {
+    let conn = db()?;
+    let tags = tags_of(&conn, &path)?;
+    if !tags.is_empty() || !mirror_enabled() {
+        return Ok(tags);
+    }
+    let carried = read_stream(&path);
+    for tag in &carried {
+        ensure_tag(&conn, tag)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO file_tags (path, tag) VALUES (?1, ?2)",
+            params![path, tag],
+        )
+        .map_err(sql_err)?;
+    }
+    Ok(carried)
+}
+
+/// Tags of many items at once (e.g. a folder listing), keyed by path; untagged items are left out
+#[tauri::command]
+pub fn get_tags_for_paths(paths: Vec<String>) -> Result<HashMap<String, Vec<String>>, String> {
+    let conn = db()?;
+    let mut result = HashMap::new();
+    for path in paths {
+        let tags = tags_of(&conn, &path)?;
+        if !tags.is_empty() {
+            result.insert(path, tags);
+        }
+    }
+    Ok(result)
+}
+
+fn paths_with_tag(conn: &Connection, tag: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT path FROM file_tags WHERE tag = ?1 ORDER BY path")
+        .map_err(sql_err)?;
+    let rows = stmt
+        .query_map(params![tag], |row| row.get::<_, String>(0))
+        .map_err(sql_err)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(sql_err)
+}
+
+/// Items carrying `tag`, optionally only those inside `folder`. Items that no longer exist are skipped.
+#[tauri::command]
+pub fn get_files_by_tag(
+    tag: String,
+    folder: Option<String>,
+) -> Result<Vec<crate::FileEntry>, String> {
+    let paths = {
+        let conn = db()?;
+        let exists: Option<String> = conn
+            .query_row(
+                "SELECT name FROM tags WHERE name = ?1",
+                params![tag],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(sql_err)?;
+        if exists.is_none() {
+            return Err(format!("Tag '{}' does not exist", tag));
+        }
+        paths_with_tag(&conn, &tag)?
+    };
+    // Compare with a trailing separator so "C:\Work" does not match "C:\Workshop"
+    let prefix = folder.map(|f| {
+        let key = crate::frecency::folder_key(&f);
+        if key.ends_with('\\') {
+            key
+        } else {
+            format!("{}\\", key)
+        }
+    });
+    Ok(paths
+        .iter()
+        .filter(|p| {
+            prefix
+                .as_ref()
+                .is_none_or(|f| crate::frecency::folder_key(p).starts_with(f.as_str()))
+        })
+        .filter_map(|p| crate::get_file_entry(Path::new(p)).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream() {
+        assert_eq!(
+            parse_stream("Work\r\n  Urgent \n\nWork"),
+            vec!["Work", "Urgent", "Work"]
+        );
+        assert!(parse_stream("").is_empty());
+        assert!(normalize_name("  ").is_err());
+        assert!(normalize_name("a\nb").is_err());
+        assert_eq!(normalize_name(" Red ").unwrap(), "Red");
+    }
+}
@@ -0,0 +1,353 @@
+//! Terminal Module
+//!
+//! Discovers the terminals installed on the machine (Windows Terminal profiles,
+//! PowerShell 7, Windows PowerShell and cmd) and launches them in a folder,
+//! optionally elevated through the `runas` verb.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use ts_rs::TS;
+
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct TerminalProfile {
+    /// Opaque identifier passed back to `open_terminal` (e.g. "wt:{guid}", "pwsh", "cmd")
+    pub id: String,
+    pub name: String,
+    /// "windows-terminal", "pwsh", "powershell" or "cmd"
+    pub kind: String,
+    pub is_default: bool,
+}
+
+/// Candidate locations of the Windows Terminal settings file (Store, Preview and unpackaged builds)
+fn windows_terminal_settings_paths() -> Vec<PathBuf> {
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+    if local_app_data.is_empty() {
+        return Vec::new();
+    }
+    let base = PathBuf::from(local_app_data);
+    vec![
+        base.join("Packages")
+            .join("Microsoft.WindowsTerminal_8wekyb3d8bbwe")
+            .join("LocalState")
+            .join("settings.json"),
+        base.join("Packages")
+            .join("Microsoft.WindowsTerminalPreview_8wekyb3d8bbwe")
+            .join("LocalState")
+            .join("settings.json"),
+        base.join("Microsoft")
+            .join("Windows Terminal")
+            .join("settings.json"),
+    ]
+}
+
+/// Windows Terminal writes JSONC; strip `//` and `/* */` comments (outside strings)
+/// plus trailing commas so serde_json can parse it.
+fn strip_json_comments(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while let Some(&n) = chars.peek() {
+                    if n == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for n in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+            }
+            ',' => {
+                // Drop trailing commas before a closing bracket/brace
+                let rest: String = chars.clone().take_while(|n| n.is_whitespace()).collect();
+                let next = chars.clone().nth(rest.chars().count());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn read_windows_terminal_profiles() -> Vec<TerminalProfile> {
+    for settings_path in windows_terminal_settings_paths() {
+        let Ok(raw) = std::fs::read_to_string(&settings_path) else {
+            continue;
+        };
+
+        let json: serde_json::Value = match serde_json::from_str(&strip_json_comments(&raw)) {
+            Ok(v) => v,
+            Err(e) => {
+                log::warn!(
+                    "[TERMINAL] Failed to parse {}: {}",
+                    settings_path.display(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        let default_guid = json
+            .get("defaultProfile")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        // "profiles" is either a plain array or an object with a "list" array
+        let list = match json.get("profiles") {
+            Some(serde_json::Value::Array(arr)) => arr.clone(),
+            Some(obj) => obj
+                .get("list")
+                .and_then(|l| l.as_array())
+                .cloned()
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        let profiles: Vec<TerminalProfile> = list
+            .iter()
+            .filter(|p| !p.get("hidden").and_then(|h| h.as_bool()).unwrap_or(false))
+            .filter_map(|p| {
+                let guid = p.get("guid")?.as_str()?.to_string();
+                let name = p
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or("Windows Terminal")
+                    .to_string();
+                Some(TerminalProfile {
+                    is_default: guid.to_lowercase() == default_guid,
+                    id: format!("wt:{}", guid),
+                    name,
+                    kind: "windows-terminal".to_string(),
+                })
+            })
+            .collect();
+
+        if !profiles.is_empty() {
+            return profiles;
+        }
+    }
+    Vec::new()
+}
+
+/// Returns true if `exe` can be found in one of the PATH directories
+fn is_in_path(exe: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(exe).is_file()))
+        .unwrap_or(false)
+}
+
+fn is_windows_terminal_installed() -> bool {
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+    is_in_path("wt.exe")
+        || PathBuf::from(local_app_data)
+            .join("Microsoft")
+            .join("WindowsApps")
+            .join("wt.exe")
+            .exists()
+}
+
+#[tauri::command]
+pub fn list_terminal_profiles() -> Vec<TerminalProfile> {
+    let mut profiles = Vec::new();
+
+    if is_windows_terminal_installed() {
+        profiles.extend(read_windows_terminal_profiles());
+        if profiles.is_empty() {
+            profiles.push(TerminalProfile {
+                id: "wt".to_string(),
+                name: "Windows Terminal".to_string(),
+                kind: "windows-terminal".to_string(),
+                is_default: true,
+            });
+        }
+    }
+
+    if is_in_path("pwsh.exe") {
+        profiles.push(TerminalProfile {
+            id: "pwsh".to_string(),
+            name: "PowerShell 7".to_string(),
+            kind: "pwsh".to_string(),
+            is_default: false,
+        });
+    }
+
+    profiles.push(TerminalProfile {
+        id: "powershell".to_string(),
+        name: "Windows PowerShell".to_string(),
+        kind: "powershell".to_string(),
+        is_default: false,
+    });
+    profiles.push(TerminalProfile {
+        id: "cmd".to_string(),
+        name: "Command Prompt".to_string(),
+        kind: "cmd".to_string(),
+        is_default: false,
+    });
+
+    if !profiles.iter().any(|p| p.is_default) {
+        if let Some(first) = profiles.first_mut() {
+            first.is_default = true;
+        }
+    }
+
+    profiles
+}
+
+/// `arg` in double quotes for programs that split their command line like
+/// CommandLineToArgvW, where backslashes before a quote escape it: `"C:\"` would
+/// read as `C:"`, so a trailing backslash is doubled
+fn quote_arg(arg: &str) -> String {
+    let trailing = arg.len() - arg.trim_end_matches('\\').len();
+    format!("\"{}{}\"", arg, "\\".repeat(trailing))
+}
+
+/// Builds the executable + parameters needed to open `profile` in `dir`
+fn build_terminal_command(profile: &str, dir: &str) -> (String, String) {
+    let dir = dir.trim_end_matches('\\');
+    // A bare drive ("C:") must keep its trailing slash to mean the root
+    let dir = if dir.ends_with(':') {
+        format!("{}\\", dir)
+    } else {
+        dir.to_string()
+    };
+
+    // wt splits its arguments into several commands at `;` unless it is escaped
+    let wt_dir = || quote_arg(&dir.replace(';', "\\;"));
+    if let Some(guid) = profile.strip_prefix("wt:") {
+        return (
+            "wt.exe".to_string(),
+            format!("-d {} -p {}", wt_dir(), quote_arg(guid)),
+        );
+    }
+
+    match profile {
+        "pwsh" | "powershell" => (
+            format!("{}.exe", profile),
+            format!(
+                "-NoExit -Command \"Set-Location -LiteralPath '{}'\"",
+                dir.replace('\'', "''")
+            ),
+        ),
+        // cmd reads its own command line and takes `"C:\"` as is
+        "cmd" => ("cmd.exe".to_string(), format!("/K cd /d \"{}\"", dir)),
+        _ => ("wt.exe".to_string(), format!("-d {}", wt_dir())),
+    }
+}
+
+fn open_terminal_elevated(file: &str, params: &str, dir: &str) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    crate::elevation::shell_execute_runas(
+        HWND::default(),
+        file,
+        params,
+        Some(dir),
+        SW_SHOWNORMAL,
+        false,
+    )
+    .map(|_| ())
+}
+
+#[tauri::command]
+pub fn open_terminal(
+    path: String,
+    profile: Option<String>,
+    elevated: Option<bool>,
+) -> Result<(), String> {
+    let expanded_path = crate::expand_env_vars(&path);
+    let profile = profile.unwrap_or_else(|| {
+        list_terminal_profiles()
+            .into_iter()
+            .find(|p| p.is_default)
+            .map(|p| p.id)
+            .unwrap_or_else(|| "cmd".to_string())
+    });
+    let (file, params) = build_terminal_command(&profile, &expanded_path);
+
+    log::info!(
+        "[TERMINAL] Opening {} in {} (elevated: {})",
+        profile,
+        expanded_path,
+        elevated.unwrap_or(false)
+    );
+
+    if elevated.unwrap_or(false) {
+        return open_terminal_elevated(&file, &params, &expanded_path);
+    }
+
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    // `start` gives console programs their own window instead of inheriting ours
+    Command::new("cmd")
+        .raw_arg(format!("/c start \"\" {} {}", file, params))
+        .current_dir(&expanded_path)
+        .creation_flags(0x08000000)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_terminal_command() {
+        assert_eq!(
+            build_terminal_command("wt:{guid}", "C:\\"),
+            (
+                "wt.exe".to_string(),
+                "-d \"C:\\\\\" -p \"{guid}\"".to_string()
+            )
+        );
+        assert_eq!(build_terminal_command("wt", "D:").1, "-d \"D:\\\\\"");
+        assert_eq!(
+            build_terminal_command("wt", "C:\\My Files\\a;b").1,
+            "-d \"C:\\My Files\\a\\;b\""
+        );
+        assert_eq!(
+            build_terminal_command("pwsh", "C:\\Bob's Files\\").1,
+            "-NoExit -Command \"Set-Location -LiteralPath 'C:\\Bob''s Files'\""
+        );
+        assert_eq!(
+            build_terminal_command("powershell", "C:\\").1,
+            "-NoExit -Command \"Set-Location -LiteralPath 'C:\\'\""
+        );
+        assert_eq!(
+            build_terminal_command("cmd", "C:\\Program Files").1,
+            "/K cd /d \"C:\\Program Files\""
+        );
+    }
+}
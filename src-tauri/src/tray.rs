@@ -0,0 +1,189 @@
+//! Tray Module
+//!
+//! A notification area icon that keeps the app one click away. Its menu
+//! opens the pinned folders (sent by the UI with `set_tray_folders`) and the
+//! most frequent ones, or a new window. With the `close_to_tray` preference
+//! closing the window only hides it, so the next folder opens instantly.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+use ts_rs::TS;
+
+const TRAY_ID: &str = "main";
+const PINNED_KEY: &str = "tray_pinned_folders";
+const FREQUENT_COUNT: usize = 5;
+const FOLDER_PREFIX: &str = "folder:";
+
+#[derive(Serialize, Deserialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct TrayFolder {
+    pub name: String,
+    pub path: String,
+}
+
+/// Paths of the folder items in the current menu, by their index in the item id
+static MENU_FOLDERS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn pinned_folders() -> Vec<TrayFolder> {
+    crate::settings::SettingsStore::global()
+        .get(PINNED_KEY)
+        .unwrap_or_default()
+}
+
+fn folder_label(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "show",
+        "Open Quick Explorer",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "new-window",
+        "New window",
+        true,
+        None::<&str>,
+    )?)?;
+
+    let pinned = pinned_folders();
+    let frequent: Vec<TrayFolder> = crate::frecency::get_frequent_folders(FREQUENT_COUNT * 2, None)
+        .into_iter()
+        .filter(|f| {
+            let key = crate::frecency::folder_key(&f.path);
+            !pinned
+                .iter()
+                .any(|p| crate::frecency::folder_key(&p.path) == key)
+        })
+        .take(FREQUENT_COUNT)
+        .map(|f| TrayFolder {
+            name: folder_label(&f.path),
+            path: f.path,
+        })
+        .collect();
+
+    let mut paths = Vec::new();
+    for group in [pinned, frequent] {
+        if group.is_empty() {
+            continue;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        for folder in group {
+            let id = format!("{}{}", FOLDER_PREFIX, paths.len());
+            menu.append(&MenuItem::with_id(
+                app,
+                id,
+                &folder.name,
+                true,
+                None::<&str>,
+            )?)?;
+            paths.push(folder.path);
+        }
+    }
+    if let Ok(mut folders) = MENU_FOLDERS.lock() {
+        *folders = paths;
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Exit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::warn!("[TRAY] Failed to build menu: {}", e),
+    }
+}
+
+/// Restores and focuses the main window
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        "show" => show_main_window(app),
+        "new-window" => {
+            let spawned = std::env::current_exe().and_then(|exe| {
+                std::process::Command::new(exe)
+                    .arg("--new-instance")
+                    .spawn()
+            });
+            if let Err(e) = spawned {
+                log::error!("[TRAY] Failed to open a new window: {}", e);
+            }
+        }
+        "quit" => {
+            crate::session::flush();
+            crate::frecency::flush();
+            app.exit(0);
+        }
+        id => {
+            let path = id
+                .strip_prefix(FOLDER_PREFIX)
+                .and_then(|i| i.parse::<usize>().ok())
+                .and_then(|i| MENU_FOLDERS.lock().ok()?.get(i).cloned());
+            if let Some(path) = path {
+                show_main_window(app);
+                crate::launch::open(app, &[path], "");
+            }
+        }
+    }
+}
+
+fn on_tray_event(tray: &TrayIcon, event: TrayIconEvent) {
+    match event {
+        TrayIconEvent::Click {
+            button: MouseButton::Left,
+            button_state: MouseButtonState::Up,
+            ..
+        } => show_main_window(tray.app_handle()),
+        // Frequent folders change as the user browses; rebuild before the menu can open
+        TrayIconEvent::Enter { .. } => refresh_menu(tray.app_handle()),
+        _ => {}
+    }
+}
+
+/// Creates the tray icon; call once from setup
+pub fn start(app: &AppHandle) -> tauri::Result<()> {
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Quick Explorer")
+        .menu(&build_menu(app)?)
+        .show_menu_on_left_click(false)
+        .on_menu_event(on_menu_event)
+        .on_tray_icon_event(on_tray_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Replaces the pinned folders listed in the tray menu
+#[tauri::command]
+pub fn set_tray_folders(app: AppHandle, folders: Vec<TrayFolder>) -> Result<(), String> {
+    crate::settings::SettingsStore::global().set(PINNED_KEY, &folders)?;
+    refresh_menu(&app);
+    Ok(())
+}
@@ -0,0 +1,402 @@
+//! Directory Watcher Module
+//!
+//! Thin wrapper around `ReadDirectoryChangesW`. Each watcher owns one directory
+//! handle and a thread that blocks on it, handing batches of changes to a
+//! callback. Dropping the watcher cancels the pending read and joins the thread.
+//!
+//! Tabs register the folder they show with `watch_directory` and hear about
+//! its changes as `fs-change` events. Registrations go through the STA
+//! worker, which drops the watchers' handles while it runs a file operation
+//! (an open handle would keep a parent folder from being renamed or deleted)
+//! and watches again afterwards. The same watchers keep the listing cache
+//! honest: a change drops the folder's cached listing (see `listing`).
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use tauri::Emitter;
+use ts_rs::TS;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, ReadDirectoryChangesW, FILE_ACTION_ADDED, FILE_ACTION_REMOVED,
+    FILE_ACTION_RENAMED_NEW_NAME, FILE_ACTION_RENAMED_OLD_NAME, FILE_FLAG_BACKUP_SEMANTICS,
+    FILE_LIST_DIRECTORY, FILE_NOTIFY_CHANGE_ATTRIBUTES, FILE_NOTIFY_CHANGE_DIR_NAME,
+    FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_LAST_WRITE, FILE_NOTIFY_CHANGE_SIZE,
+    FILE_NOTIFY_INFORMATION, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+};
+use windows::Win32::System::IO::CancelIoEx;
+
+/// 64 KB is the largest buffer ReadDirectoryChangesW accepts for network shares
+const BUFFER_WORDS: usize = 16 * 1024;
+
+#[derive(Serialize, Clone, Copy, TS, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ChangeKind {
+    Created,
+    Removed,
+    Modified,
+    /// `old_path` on the change holds the previous name
+    Renamed,
+    /// The kernel buffer overflowed; anything under the root may have changed
+    Rescan,
+}
+
+#[derive(Debug, Clone)]
+pub struct FsChange {
+    pub kind: ChangeKind,
+    pub path: PathBuf,
+    pub old_path: Option<PathBuf>,
+}
+
+/// `HANDLE` wraps a raw pointer; the watcher only hands it to thread-safe kernel calls
+#[derive(Clone, Copy)]
+struct SendHandle(HANDLE);
+unsafe impl Send for SendHandle {}
+
+pub struct DirectoryWatcher {
+    root: PathBuf,
+    handle: SendHandle,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DirectoryWatcher {
+    /// Starts watching `root` (and its subtree when `recursive`). `on_changes` runs on the
+    /// watcher thread once per batch returned by the kernel.
+    pub fn start<F>(root: &Path, recursive: bool, on_changes: F) -> Result<Self, String>
+    where
+        F: Fn(Vec<FsChange>) + Send + 'static,
+    {
+        let wide: Vec<u16> = OsStr::new(root)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        let handle = unsafe {
+            CreateFileW(
+                PCWSTR(wide.as_ptr()),
+                FILE_LIST_DIRECTORY.0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                None,
+                OPEN_EXISTING,
+                // Required to open a directory handle
+                FILE_FLAG_BACKUP_SEMANTICS,
+                None,
+            )
+        }
+        .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+
+        let handle = SendHandle(handle);
+        let stopping = Arc::new(AtomicBool::new(false));
+        let thread_stopping = stopping.clone();
+        let thread_root = root.to_path_buf();
+
+        let thread = std::thread::Builder::new()
+            .name("dir-watcher".to_string())
+            .spawn(move || watch_loop(handle, thread_root, recursive, thread_stopping, on_changes))
+            .map_err(|e| {
+                unsafe {
+                    let _ = CloseHandle(handle.0);
+                }
+                format!("Failed to start watcher thread: {}", e)
+            })?;
+
+        log::info!("[WATCHER] Watching {}", root.display());
+        Ok(DirectoryWatcher {
+            root: root.to_path_buf(),
+            handle,
+            stopping,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for DirectoryWatcher {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        unsafe {
+            // Wakes the thread out of its blocking read
+            let _ = CancelIoEx(self.handle.0, None);
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        unsafe {
+            let _ = CloseHandle(self.handle.0);
+        }
+        log::info!("[WATCHER] Stopped watching {}", self.root.display());
+    }
+}
+
+fn watch_loop<F>(
+    handle: SendHandle,
+    root: PathBuf,
+    recursive: bool,
+    stopping: Arc<AtomicBool>,
+    on_changes: F,
+) where
+    F: Fn(Vec<FsChange>),
+{
+    // u32 storage keeps the buffer DWORD-aligned as the API requires
+    let mut buffer = vec![0u32; BUFFER_WORDS];
+    let filter = FILE_NOTIFY_CHANGE_FILE_NAME
+        | FILE_NOTIFY_CHANGE_DIR_NAME
+        | FILE_NOTIFY_CHANGE_ATTRIBUTES
+        | FILE_NOTIFY_CHANGE_SIZE
+        | FILE_NOTIFY_CHANGE_LAST_WRITE;
+
+    while !stopping.load(Ordering::SeqCst) {
+        let mut bytes_returned = 0u32;
+        let read = unsafe {
+            ReadDirectoryChangesW(
+                handle.0,
+                buffer.as_mut_ptr() as *mut _,
+                (buffer.len() * 4) as u32,
+                recursive,
+                filter,
+                Some(&mut bytes_returned),
+                None,
+                None,
+            )
+        };
+
+        if let Err(e) = read {
+            if !stopping.load(Ordering::SeqCst) {
+                log::warn!("[WATCHER] Watch on {} ended: {}", root.display(), e);
+            }
+            break;
+        }
+        if stopping.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let changes = if bytes_returned == 0 {
+            vec![FsChange {
+                kind: ChangeKind::Rescan,
+                path: root.clone(),
+                old_path: None,
+            }]
+        } else {
+            unsafe { parse_notifications(&buffer, bytes_returned as usize, &root) }
+        };
+        if !changes.is_empty() {
+            on_changes(changes);
+        }
+    }
+}
+
+/// Walks the FILE_NOTIFY_INFORMATION chain written by the kernel
+unsafe fn parse_notifications(buffer: &[u32], len: usize, root: &Path) -> Vec<FsChange> {
+    let base = buffer.as_ptr() as *const u8;
+    let mut changes = Vec::new();
+    let mut pending_old_name: Option<PathBuf> = None;
+    let mut offset = 0usize;
+
+    while offset < len {
+        let info = &*(base.add(offset) as *const FILE_NOTIFY_INFORMATION);
+        let name =
+            std::slice::from_raw_parts(info.FileName.as_ptr(), info.FileNameLength as usize / 2);
+        let path = root.join(std::ffi::OsString::from_wide(name));
+
+        match info.Action {
+            FILE_ACTION_RENAMED_OLD_NAME => pending_old_name = Some(path),
+            FILE_ACTION_RENAMED_NEW_NAME => changes.push(FsChange {
+                kind: ChangeKind::Renamed,
+                path,
+                old_path: pending_old_name.take(),
+            }),
+            action => {
+                let kind = if action == FILE_ACTION_ADDED {
+                    ChangeKind::Created
+                } else if action == FILE_ACTION_REMOVED {
+                    ChangeKind::Removed
+                } else {
+                    ChangeKind::Modified
+                };
+                changes.push(FsChange {
+                    kind,
+                    path,
+                    old_path: None,
+                });
+            }
+        }
+
+        if info.NextEntryOffset == 0 {
+            break;
+        }
+        offset += info.NextEntryOffset as usize;
+    }
+    changes
+}
+
+/// One entry of an `fs-change` event
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct FsChangeEntry {
+    pub kind: ChangeKind,
+    pub path: String,
+    /// Previous path of a renamed entry
+    pub old_path: Option<String>,
+}
+
+/// Payload of `fs-change`
+#[derive(Serialize, Clone, TS, Debug)]
+#[ts(export)]
+pub struct FsChangeEvent {
+    pub tab_id: String,
+    /// The watched folder
+    pub folder: String,
+    pub changes: Vec<FsChangeEntry>,
+}
+
+struct TabWatch {
+    folder: PathBuf,
+    /// None while suspended for a file operation, or when watching again failed
+    watcher: Option<DirectoryWatcher>,
+}
+
+static TAB_WATCHES: OnceLock<Mutex<HashMap<String, TabWatch>>> = OnceLock::new();
+/// File operations in progress; tab watchers stay closed until the last one ends
+static SUSPENDED: AtomicUsize = AtomicUsize::new(0);
+
+fn tab_watches() -> &'static Mutex<HashMap<String, TabWatch>> {
+    TAB_WATCHES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn start_tab_watcher(tab_id: &str, folder: &Path) -> Result<DirectoryWatcher, String> {
+    let tab_id = tab_id.to_string();
+    let folder_name = folder.to_string_lossy().to_string();
+    let watcher = DirectoryWatcher::start(folder, false, move |changes| {
+        crate::listing::forget_folder(&folder_name);
+        let event = FsChangeEvent {
+            tab_id: tab_id.clone(),
+            folder: folder_name.clone(),
+            changes: changes
+                .into_iter()
+                .map(|c| FsChangeEntry {
+                    kind: c.kind,
+                    path: c.path.to_string_lossy().to_string(),
+                    old_path: c.old_path.map(|p| p.to_string_lossy().to_string()),
+                })
+                .collect(),
+        };
+        if let Some(app) = crate::APP_HANDLE.get() {
+            let _ = app.emit("fs-change", event);
+        }
+    })?;
+    crate::listing::set_watched(&folder.to_string_lossy(), true);
+    Ok(watcher)
+}
+
+/// Tells the listing cache that `folder` lost a watcher, unless another tab still watches it
+fn stopped_watching(watches: &HashMap<String, TabWatch>, folder: &Path) {
+    if !watches
+        .values()
+        .any(|w| w.folder == folder && w.watcher.is_some())
+    {
+        crate::listing::set_watched(&folder.to_string_lossy(), false);
+    }
+}
+
+/// Whether a tab currently reports the changes of `folder`
+pub fn is_folder_watched(folder: &str) -> bool {
+    let key = crate::frecency::folder_key(&crate::expand_env_vars(folder));
+    tab_watches().lock().is_ok_and(|watches| {
+        watches.values().any(|w| {
+            w.watcher.is_some() && crate::frecency::folder_key(&w.folder.to_string_lossy()) == key
+        })
+    })
+}
+
+/// Watches `folder` for `tab_id`, replacing what the tab watched before. Returns false,
+/// watching nothing, when `folder` isn't a directory (e.g. "This PC" or a shell folder).
+/// Called on the STA worker.
+pub fn watch_tab(tab_id: &str, folder: &str) -> Result<bool, String> {
+    let folder = PathBuf::from(crate::expand_env_vars(folder));
+    let mut watches = tab_watches().lock().map_err(|e| e.to_string())?;
+    if let Some(watch) = watches.get(tab_id) {
+        if watch.folder == folder && watch.watcher.is_some() {
+            return Ok(true);
+        }
+    }
+    // Stops the previous watcher before starting the new one
+    if let Some(previous) = watches.remove(tab_id) {
+        drop(previous.watcher);
+        stopped_watching(&watches, &previous.folder);
+    }
+    if !folder.is_dir() {
+        return Ok(false);
+    }
+    // During a file operation only register; `resume_tab_watches` starts it
+    let watcher = if SUSPENDED.load(Ordering::SeqCst) > 0 {
+        None
+    } else {
+        Some(start_tab_watcher(tab_id, &folder)?)
+    };
+    watches.insert(tab_id.to_string(), TabWatch { folder, watcher });
+    Ok(true)
+}
+
+/// Called on the STA worker
+pub fn unwatch_tab(tab_id: &str) {
+    if let Ok(mut watches) = tab_watches().lock() {
+        if let Some(previous) = watches.remove(tab_id) {
+            drop(previous.watcher);
+            stopped_watching(&watches, &previous.folder);
+        }
+    }
+}
+
+/// Closes every tab watcher's handle, keeping the registrations. Calls nest: each
+/// one is paired with a `resume_tab_watches`.
+pub fn suspend_tab_watches() {
+    let Ok(mut watches) = tab_watches().lock() else {
+        return;
+    };
+    // Counted under the lock, so a concurrent resume can't restart what this closes
+    SUSPENDED.fetch_add(1, Ordering::SeqCst);
+    let suspended: Vec<DirectoryWatcher> = watches
+        .values_mut()
+        .filter_map(|w| w.watcher.take())
+        .collect();
+    for watch in watches.values() {
+        crate::listing::set_watched(&watch.folder.to_string_lossy(), false);
+    }
+    drop(watches);
+    drop(suspended);
+}
+
+/// Watches again the folders of `suspend_tab_watches` once no file operation is left.
+/// Folders that no longer exist stay unwatched until the tab registers again.
+pub fn resume_tab_watches() {
+    let Ok(mut watches) = tab_watches().lock() else {
+        return;
+    };
+    if SUSPENDED.fetch_sub(1, Ordering::SeqCst) > 1 {
+        return;
+    }
+    for (tab_id, watch) in watches.iter_mut().filter(|(_, w)| w.watcher.is_none()) {
+        match start_tab_watcher(tab_id, &watch.folder) {
+            Ok(watcher) => watch.watcher = Some(watcher),
+            Err(e) => log::warn!("[WATCHER] Not watching tab {} again: {}", tab_id, e),
+        }
+    }
+}
+
+/// Starts sending `fs-change` events for `path`, shown in `tab_id`. Returns whether the
+/// folder is watched; shell locations aren't.
+#[tauri::command]
+pub fn watch_directory(tab_id: String, path: String) -> Result<bool, String> {
+    crate::sta_worker::StaWorker::global().watch_directory(tab_id, path)
+}
+
+#[tauri::command]
+pub fn unwatch_directory(tab_id: String) -> Result<(), String> {
+    crate::sta_worker::StaWorker::global().unwatch_directory(tab_id)
+}
@@ -26,6 +26,7 @@ vi.mock('@tauri-apps/api/window', () => ({
 
 vi.mock('@tauri-apps/plugin-dialog', () => ({
     ask: vi.fn(),
+    message: vi.fn(),
 }));
 
 // Mock ResizeObserver
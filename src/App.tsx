@@ -7,7 +7,7 @@ import ThisPCView from './components/ThisPCView';
 
 // Global singleton for extreme frontend performance
 const collator = new Intl.Collator(undefined, { numeric: true, sensitivity: 'base' });
-import { ask } from '@tauri-apps/plugin-dialog';
+import { ask, message } from '@tauri-apps/plugin-dialog';
 import {
   Search,
   RotateCw,
@@ -46,6 +46,9 @@ import InfoPanel from './components/InfoPanel';
 import ContextMenu from './components/ContextMenu';
 import SettingsPanel from './components/SettingsPanel';
 import { invalidateCachedSize } from './utils/folderSizeCache';
+import { isDrive } from './utils/drives';
+import { resolveConflicts } from './utils/conflicts';
+import { extractArchive, activeExtractions, cancelExtraction } from './utils/extraction';
 import TabBar from './components/TabBar';
 import QuickPreview from './components/QuickPreview';
 import InputContextMenu from './components/InputContextMenu';
@@ -53,12 +56,13 @@ import { useDebouncedValue } from './hooks/useDebouncedValue';
 import './App.css';
 
 import { useTabs } from './hooks/useTabs';
-import { /* Tab, */ SortConfig, SortColumn, FileEntry, QuickAccessConfig, ClipboardInfo, RecycleBinStatus, ToolbarMode } from './types';
+import { /* Tab, */ SortConfig, SortColumn, FileEntry, QuickAccessConfig, PinnedFolder, ClipboardInfo, RecycleBinStatus, ToolbarMode, SizeUnits, BackendPreferences } from './types';
 import SplashScreen from './components/SplashScreen';
 import { useTranslation } from './i18n/useTranslation';
 import { DeepSearchButton } from './components/DeepSearchButton';
 import { cn } from './lib/utils';
 import { SearchStatusIndicator } from './components/SearchStatusIndicator';
+import { FileOpProgressBar } from './components/FileOpProgressBar';
 
 export default function App() {
   const { t } = useTranslation();
@@ -119,26 +123,11 @@ export default function App() {
     };
   });
 
-  const [defaultSortConfig, setDefaultSortConfig] = useState<SortConfig>(() => {
-    try {
-      const saved = localStorage.getItem('speedexplorer-sort');
-      if (saved) {
-        const parsed = JSON.parse(saved);
-        if (parsed && typeof parsed === 'object' && parsed.column && parsed.direction) {
-          return parsed;
-        }
-      }
-    } catch (e) { }
-    return { column: 'name', direction: 'asc' };
-  });
-
-  const [showHiddenFiles, setShowHiddenFiles] = useState<boolean>(() => {
-    try {
-      const saved = localStorage.getItem('speedexplorer-hidden');
-      if (saved) return JSON.parse(saved);
-    } catch (e) { }
-    return false;
-  });
+  // Kept by the backend settings store, which broadcasts `settings-changed`
+  const [defaultSortConfig, setDefaultSortConfig] = useState<SortConfig>({ column: 'name', direction: 'asc' });
+  const [showHiddenFiles, setShowHiddenFiles] = useState(false);
+  const [confirmDelete, setConfirmDelete] = useState(true);
+  const [sizeUnits, setSizeUnits] = useState<SizeUnits>('binary');
 
   const [autoSearchOnKey, setAutoSearchOnKey] = useState<boolean>(() => {
     try {
@@ -162,6 +151,10 @@ export default function App() {
 
   const [isToolbarCompact, setIsToolbarCompact] = useState(false);
 
+  const saveBackendSetting = useCallback((key: string, value: unknown) => {
+    invoke('set_setting', { key, value }).catch(e => console.error(`Failed to save ${key}:`, e));
+  }, []);
+
 
   const searchInputRef = useRef<HTMLInputElement>(null);
   const centralPanelRef = useRef<HTMLDivElement>(null);
@@ -171,6 +164,7 @@ export default function App() {
     tabs,
     activeTabId,
     currentTab,
+    missingSessionPaths,
     addTab,
     closeTab,
     switchTab,
@@ -193,6 +187,54 @@ export default function App() {
     // reorderTabs
   } = useTabs(defaultSortConfig, showHiddenFiles, quickAccessConfig);
 
+  useEffect(() => {
+    if (missingSessionPaths.length === 0) return;
+    message(t('session.missing_folders', { paths: missingSessionPaths.join('\n') }), {
+      title: 'Quick Explorer',
+      kind: 'info',
+    }).catch(console.error);
+  }, [missingSessionPaths]);
+
+  // Hidden files and size units change what the backend lists, so open tabs are listed again
+  const reloadAllTabsRef = useRef<(showHidden: boolean) => void>(() => { });
+  reloadAllTabsRef.current = (showHidden) => tabs.forEach(tab => loadFilesForTab(tab.id, tab.path, showHidden));
+
+  useEffect(() => {
+    let current: BackendPreferences | null = null;
+    const applyPreferences = (prefs: BackendPreferences) => {
+      setDefaultSortConfig(prefs.default_sort);
+      setShowHiddenFiles(prefs.show_hidden);
+      setConfirmDelete(prefs.confirm_delete);
+      setSizeUnits(prefs.size_units);
+      const listingChanged = current
+        ? current.show_hidden !== prefs.show_hidden || current.size_units !== prefs.size_units
+        : prefs.show_hidden;
+      if (listingChanged) reloadAllTabsRef.current(prefs.show_hidden);
+      current = prefs;
+    };
+
+    // Older versions only kept these in localStorage
+    const migrateLocalSettings = async () => {
+      for (const [storageKey, key] of [['speedexplorer-sort', 'default_sort'], ['speedexplorer-hidden', 'show_hidden']]) {
+        const saved = localStorage.getItem(storageKey);
+        if (saved === null) continue;
+        try {
+          await invoke('set_setting', { key, value: JSON.parse(saved) });
+        } catch (e) {
+          console.error(`Failed to migrate ${key}:`, e);
+        }
+        localStorage.removeItem(storageKey);
+      }
+    };
+
+    migrateLocalSettings()
+      .then(() => invoke<BackendPreferences>('get_settings'))
+      .then(applyPreferences)
+      .catch(e => console.error('Failed to read settings:', e));
+    const unlisten = listen<BackendPreferences>('settings-changed', (event) => applyPreferences(event.payload));
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
   // === Stable callbacks to prevent infinite render loops ===
   const handleVisibleFilesChange = useCallback((indices: number[]) => {
     if (currentTab) {
@@ -210,6 +252,7 @@ export default function App() {
   const currentTabRef = useRef(currentTab);
   const currentPathRef = useRef(currentTab?.path);
   const refreshCurrentTabRef = useRef(refreshCurrentTab);
+  const tRef = useRef(t);
   const dragCounterRef = useRef(0);
   const lastProcessedDropRef = useRef(0);
   const lastShowOverlayRef = useRef(0);
@@ -274,9 +317,20 @@ export default function App() {
     refreshCurrentTabRef.current = refreshCurrentTab;
   }, [refreshCurrentTab]);
 
+  useEffect(() => {
+    tRef.current = t;
+  }, [t]);
+
   // === Async Notification Listener (v12.0) ===
   useEffect(() => {
-    const unlisten = listen('refresh-tab', () => {
+    // Paths are the folders that changed; an empty list means any folder may have
+    const normalize = (p: string) => p.replace(/[\\/]+$/, '').toLowerCase();
+    const unlisten = listen<{ paths: string[] }>('refresh-tab', (event) => {
+      const current = currentPathRef.current;
+      const { paths } = event.payload;
+      if (paths.length > 0 && (current === undefined || !paths.some(p => normalize(p) === normalize(current)))) {
+        return;
+      }
       console.log("[v12.0] Received 'refresh-tab' event. Refreshing...");
       if (refreshCurrentTabRef.current) {
         refreshCurrentTabRef.current();
@@ -288,6 +342,52 @@ export default function App() {
     };
   }, []);
 
+  // Changes made outside the app to the folder on screen
+  useEffect(() => {
+    const path = currentTab?.path;
+    if (!activeTabId || !path) return;
+    invoke<boolean>('watch_directory', { tabId: activeTabId, path })
+      .catch(err => console.error('Failed to watch folder:', err));
+    return () => {
+      invoke('unwatch_directory', { tabId: activeTabId })
+        .catch(err => console.error('Failed to stop watching folder:', err));
+    };
+  }, [activeTabId, currentTab?.path]);
+
+  useEffect(() => {
+    // A copy or an extraction sends many batches; refresh once they settle
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const unlisten = listen<{ tab_id: string }>('fs-change', (event) => {
+      if (event.payload.tab_id !== currentTabRef.current?.id) return;
+      clearTimeout(timer);
+      timer = setTimeout(() => refreshCurrentTabRef.current?.(), 200);
+    });
+    return () => {
+      clearTimeout(timer);
+      unlisten.then(f => f());
+    };
+  }, []);
+
+  // Folder (or file to select) passed on the command line
+  useEffect(() => {
+    invoke<{ path: string; select: string | null } | null>('take_launch_target')
+      .then(target => {
+        if (target?.path) addTab(target.path, true, target.select ? [target.select] : undefined);
+      })
+      .catch(err => console.error('Failed to read launch target:', err));
+  }, []);
+
+  // Launches forwarded by a second instance of the app
+  useEffect(() => {
+    const unlisten = listen<{ path: string; select: string | null }>('open-path', (event) => {
+      const { path, select } = event.payload;
+      addTab(path, true, select ? [select] : undefined);
+    });
+    return () => {
+      unlisten.then(f => f());
+    };
+  }, [addTab]);
+
   useEffect(() => {
     const unlistenStatus = listen<string>('deep-search-detail-status', (event) => {
       setDeepSearchDetailStatus(event.payload);
@@ -466,9 +566,11 @@ export default function App() {
           // before we hit the backend which will disable the window for modality.
           setTimeout(async () => {
             try {
+              const resolutions = await resolveConflicts(paths, targetPath, tRef.current);
               await invoke('drop_items', {
                 files: paths,
-                targetPath: targetPath
+                targetPath: targetPath,
+                resolutions
               });
               // Invalidate cache for destination
               invalidateCachedSize(targetPath);
@@ -527,9 +629,11 @@ export default function App() {
         if (paths.length > 0 && targetPath && targetPath !== '' && targetPath !== 'shell:RecycleBin') {
           try {
             console.log(`[APP] [${now}] Invoking drop_items for ${paths.length} files to: ${targetPath}`);
+            const resolutions = await resolveConflicts(paths, targetPath, tRef.current);
             const result = await invoke('drop_items', {
               files: paths,
-              targetPath: targetPath
+              targetPath: targetPath,
+              resolutions
             });
             // Invalidate cache for destination
             invalidateCachedSize(targetPath);
@@ -618,6 +722,43 @@ export default function App() {
     }
   }, []);
 
+  // Pinned folders also appear in the tray icon's menu
+  useEffect(() => {
+    const folders = quickAccessConfig.pinnedFolders
+      .filter(f => f.path && f.enabled !== false)
+      .map(f => ({ name: f.name, path: f.path }));
+    invoke('set_tray_folders', { folders })
+      .catch(err => console.error('Failed to update tray folders:', err));
+  }, [quickAccessConfig]);
+
+  // The backend keeps the pinned folders; localStorage only seeds it on the first run
+  const [pinnedFoldersLoaded, setPinnedFoldersLoaded] = useState(false);
+  useEffect(() => {
+    invoke<PinnedFolder[] | null>('get_pinned_folders')
+      .then(folders => {
+        if (folders) {
+          setQuickAccessConfig(prev => ({ ...prev, pinnedFolders: folders }));
+          fetchSystemPaths();
+        }
+      })
+      .catch(err => console.error('Failed to load pinned folders:', err))
+      .finally(() => setPinnedFoldersLoaded(true));
+  }, [fetchSystemPaths]);
+
+  useEffect(() => {
+    if (!pinnedFoldersLoaded) return;
+    invoke('set_pinned_folders', { folders: quickAccessConfig.pinnedFolders })
+      .catch(err => console.error('Failed to save pinned folders:', err));
+  }, [quickAccessConfig, pinnedFoldersLoaded]);
+
+  const applyPinnedFolders = useCallback((folders: PinnedFolder[]) => {
+    setQuickAccessConfig(prev => {
+      const newConfig = { ...prev, pinnedFolders: folders };
+      localStorage.setItem('speedexplorer-config', JSON.stringify(newConfig));
+      return newConfig;
+    });
+  }, []);
+
   useEffect(() => {
     fetchSystemPaths();
     // Show window after a short delay to ensure black background is rendered
@@ -873,9 +1014,8 @@ export default function App() {
       if (!a.is_dir && b.is_dir) return 1;
 
       // SPECIFIC DRIVE SORTING (v12.5): Ensure ThisPC matches Sidebar (C: first, then D, E...)
-      // Drives are uniquely identified by file_type === 'Drive'
-      const isADrive = a.file_type === 'Drive';
-      const isBDrive = b.file_type === 'Drive';
+      const isADrive = isDrive(a);
+      const isBDrive = isDrive(b);
       
       if (isADrive && isBDrive) {
         // 1. System Drive always first
@@ -896,6 +1036,10 @@ export default function App() {
       } else if (column === 'created_at') {
         // @ts-ignore - Field was added to backend and TS bindings
         comparison = (a.created_timestamp || 0) - (b.created_timestamp || 0);
+      } else if (column === 'deleted_at') {
+        comparison = (a.recycle_info?.deleted_timestamp || 0) - (b.recycle_info?.deleted_timestamp || 0);
+      } else if (column === 'original_location') {
+        comparison = collator.compare(a.recycle_info?.original_location || '', b.recycle_info?.original_location || '');
       } else {
         // O(1) instant locale comparison via Intl singleton
         comparison = collator.compare(String(a[column] || ''), String(b[column] || ''));
@@ -935,9 +1079,9 @@ export default function App() {
     const newSortConfig = hookHandleSort(column);
     if (newSortConfig) {
       setDefaultSortConfig(newSortConfig);
-      localStorage.setItem('speedexplorer-sort', JSON.stringify(newSortConfig));
+      saveBackendSetting('default_sort', newSortConfig);
     }
-  }, [hookHandleSort]);
+  }, [hookHandleSort, saveBackendSetting]);
 
   const handleSelectAll = useCallback(() => {
     hookHandleSelectAll(sortedFiles);
@@ -976,8 +1120,8 @@ export default function App() {
   };
 
   const handleRename = useCallback((file: FileEntry) => {
-    const isDrive = file.file_type === 'Drive' || (file.path.length <= 3 && file.path.endsWith(':\\'));
-    if (isDrive) return; // Disallow drive rename
+    const isDriveRoot = isDrive(file);
+    if (isDriveRoot) return; // Disallow drive rename
 
     if (currentTab) {
       updateTab(currentTab.id, { renamingPath: file.path });
@@ -997,7 +1141,7 @@ export default function App() {
     }
 
     try {
-      const isDrive = file.file_type === 'Drive' || (file.path.length <= 3 && file.path.endsWith(':\\'));
+      const isDriveRoot = isDrive(file);
 
       await invoke('rename_item', { oldPath: file.path, newName });
 
@@ -1006,7 +1150,7 @@ export default function App() {
 
       updateTab(currentTab.id, { renamingPath: null });
 
-      if (isDrive) {
+      if (isDriveRoot) {
         refreshCurrentTab();
       } else {
         const lastSlash = file.path.lastIndexOf('\\');
@@ -1103,7 +1247,10 @@ export default function App() {
     }
 
     try {
-      await invoke('paste_items', { targetPath });
+      const resolutions = clipboardInfo?.has_files
+        ? await resolveConflicts(clipboardInfo.paths, targetPath, t)
+        : undefined;
+      await invoke('paste_items', { targetPath, resolutions });
 
       // Invalidate destination and sources
       invalidateCachedSize(targetPath);
@@ -1123,44 +1270,19 @@ export default function App() {
     } catch (err: any) {
       updateTab(currentTab.id, { error: String(err) });
     }
-  }, [currentTab, updateTab, refreshTabsViewing, lastCutPaths, clipboardInfo, checkClipboard]);
+  }, [currentTab, updateTab, refreshTabsViewing, lastCutPaths, clipboardInfo, checkClipboard, t]);
 
   const handlePinFolder = useCallback((folder: FileEntry) => {
-    setQuickAccessConfig(prev => {
-      const newConfig = {
-        ...prev,
-        pinnedFolders: [
-          ...prev.pinnedFolders,
-          { id: crypto.randomUUID(), name: folder.name, path: folder.path }
-        ]
-      };
-      localStorage.setItem('speedexplorer-config', JSON.stringify(newConfig));
-      return newConfig;
-    });
-  }, []);
+    invoke<PinnedFolder[]>('pin_folder', { path: folder.path, name: folder.name })
+      .then(applyPinnedFolders)
+      .catch(err => console.error('Failed to pin folder:', err));
+  }, [applyPinnedFolders]);
 
   const handleUnpinFolder = useCallback((path: string) => {
-    setQuickAccessConfig(prev => {
-      const folder = prev.pinnedFolders.find(f => f.path === path || (f.id === 'home' && path === ''));
-      const isSystemFolder = folder && ['desktop', 'downloads', 'documents', 'pictures', 'recycle-bin', 'home'].includes(folder.id);
-
-      let newPinnedFolders;
-      if (isSystemFolder) {
-        newPinnedFolders = prev.pinnedFolders.map(f =>
-          f.id === folder.id ? { ...f, enabled: false } : f
-        );
-      } else {
-        newPinnedFolders = prev.pinnedFolders.filter(f => f.path !== path);
-      }
-
-      const newConfig = {
-        ...prev,
-        pinnedFolders: newPinnedFolders
-      };
-      localStorage.setItem('speedexplorer-config', JSON.stringify(newConfig));
-      return newConfig;
-    });
-  }, []);
+    invoke<PinnedFolder[]>('unpin_folder', { path })
+      .then(applyPinnedFolders)
+      .catch(err => console.error('Failed to unpin folder:', err));
+  }, [applyPinnedFolders]);
 
   const handleToggleColumn = useCallback((column: SortColumn) => {
     setVisibleColumns(prev => {
@@ -1177,8 +1299,8 @@ export default function App() {
   const handleDelete = async (files: FileEntry[], silent: boolean = false) => {
     if (files.length === 0) return;
 
-    let confirmed = silent;
-    if (!silent) {
+    let confirmed = silent || !confirmDelete;
+    if (!confirmed) {
       const message = files.length === 1
         ? t('preview.delete_conf_msg').replace('{name}', files[0].name)
         : t('preview.delete_conf_msg').replace('{name}', `${files.length} ${t('files.items')}`);
@@ -1282,6 +1404,20 @@ export default function App() {
     } else if (action === 'restore') {
       handleRestore(selectedFiles.map(f => f.path));
       return;
+    } else if (action === 'more-options') {
+      if (!contextMenu || selectedFiles.length === 0) return;
+      try {
+        // The shell runs the chosen command itself; only rename comes back to us
+        const verb = await invoke<string | null>('show_shell_context_menu', {
+          paths: selectedFiles.map(f => f.path),
+          x: contextMenu.x,
+          y: contextMenu.y,
+        });
+        if (verb === 'rename' && file) handleRename(file);
+      } catch (err: any) {
+        updateTab(currentTab.id, { error: String(err) });
+      }
+      return;
     }
 
     if (!file) {
@@ -1291,6 +1427,27 @@ export default function App() {
         if (currentTab) invoke('show_item_properties', { path: currentTab.path });
       } else if (action === 'open-terminal') {
         invoke('open_terminal', { path: currentTab.path });
+      } else if (action === 'new-folder') {
+        try {
+          const folderName = await invoke<string>('create_folder', { parentPath: currentTab.path });
+          const newPath = currentTab.path.endsWith('\\') ? currentTab.path + folderName : currentTab.path + '\\' + folderName;
+          await loadFilesForTab(currentTab.id, currentTab.path, undefined, [newPath]);
+          updateTab(currentTab.id, { renamingPath: newPath });
+        } catch (err: any) {
+          updateTab(currentTab.id, { error: String(err) });
+        }
+      } else if (action === 'new-item' && data) {
+        try {
+          const created = await invoke<FileEntry>('create_from_shell_new', {
+            parentPath: currentTab.path,
+            extension: data.extension,
+            template: data.template,
+          });
+          await loadFilesForTab(currentTab.id, currentTab.path, undefined, [created.path]);
+          updateTab(currentTab.id, { renamingPath: created.path });
+        } catch (err: any) {
+          updateTab(currentTab.id, { error: String(err) });
+        }
       }
       return;
     }
@@ -1301,8 +1458,17 @@ export default function App() {
     } else if (action === 'open-with' && file) {
       invoke('open_with', { path: file.path });
     } else if (action === 'open-location') {
-      if (file.is_shortcut) {
-        invoke<string>('resolve_shortcut', { path: file.path }).then(targetPath => {
+      if (file.link_target) {
+        // Folder links open where they point; file links show their target in its folder
+        const target = file.link_target;
+        if (file.is_dir) {
+          navigateTo(target);
+        } else {
+          const parent = target.substring(0, target.lastIndexOf('\\'));
+          if (parent) addTab(parent, true, [target]);
+        }
+      } else if (file.is_shortcut) {
+        invoke<{ target_path: string }>('resolve_shortcut', { path: file.path }).then(({ target_path: targetPath }) => {
           const parent = targetPath.substring(0, targetPath.lastIndexOf('\\'));
           if (parent) navigateTo(parent);
         }).catch((err: any) => {
@@ -1337,7 +1503,28 @@ export default function App() {
     } else if (action === 'extract-here' && file) {
       try {
         const parentDir = file.path.substring(0, file.path.lastIndexOf('\\'));
-        await invoke('extract_archive', { archivePath: file.path, targetDir: parentDir || currentTab.path });
+        await extractArchive(file.path, parentDir || currentTab.path);
+        refreshCurrentTab();
+      } catch (err: any) {
+        updateTab(currentTab.id, { error: String(err) });
+      }
+    } else if (action === 'pin-cloud' && file) {
+      try {
+        await invoke('pin_file', { paths: [file.path] });
+        refreshCurrentTab();
+      } catch (err: any) {
+        updateTab(currentTab.id, { error: String(err) });
+      }
+    } else if (action === 'free-up-space' && file) {
+      try {
+        await invoke('free_up_space', { paths: [file.path] });
+        refreshCurrentTab();
+      } catch (err: any) {
+        updateTab(currentTab.id, { error: String(err) });
+      }
+    } else if (action === 'eject' && file) {
+      try {
+        await invoke('eject_drive', { letter: file.path });
         refreshCurrentTab();
       } catch (err: any) {
         updateTab(currentTab.id, { error: String(err) });
@@ -1402,6 +1589,10 @@ export default function App() {
           // If we are showing a "Finished" status but results are not active anymore (or we are in a normal folder)
           setDeepSearchDetailStatus("");
           handled = true;
+        } else if (activeExtractions.size > 0) {
+          // Stop running "Extract here" jobs; the backend removes their partial output
+          activeExtractions.forEach(jobId => cancelExtraction(jobId));
+          handled = true;
         } else {
           if (currentTab?.selectedFiles && currentTab.selectedFiles.length > 0) {
             handleClearSelection();
@@ -1508,7 +1699,14 @@ export default function App() {
       disk_info: null,
       modified_timestamp: 0,
       created_timestamp: 0,
-      dimensions: null
+      dimensions: null,
+      url_target: null,
+      recycle_info: null,
+      is_symlink: false,
+      is_junction: false,
+      link_target: null,
+      attributes: { hidden: false, system: false, readonly: false, archive: false, compressed: false, encrypted: false, offline: false, cloud: false },
+      cloud_status: null
     };
     setContextMenu({ x: e.clientX, y: e.clientY, file: mockFile, fromSidebar: true });
 
@@ -1525,13 +1723,11 @@ export default function App() {
     localStorage.setItem('speedexplorer-config', JSON.stringify(newConfig));
     if (newSortConfig) {
       setDefaultSortConfig(newSortConfig);
-      localStorage.setItem('speedexplorer-sort', JSON.stringify(newSortConfig));
+      saveBackendSetting('default_sort', newSortConfig);
     }
     if (newShowHidden !== undefined) {
-      setShowHiddenFiles(newShowHidden);
-      localStorage.setItem('speedexplorer-hidden', JSON.stringify(newShowHidden));
-      // Refresh all tabs with new hidden setting
-      tabs.forEach(tab => loadFilesForTab(tab.id, tab.path, newShowHidden));
+      // Tabs are listed again when `settings-changed` arrives
+      saveBackendSetting('show_hidden', newShowHidden);
     }
     if (newAutoSearch !== undefined) {
       setAutoSearchOnKey(newAutoSearch);
@@ -1558,15 +1754,16 @@ export default function App() {
 
     if (confirmed) {
       localStorage.removeItem('speedexplorer-config');
-      localStorage.removeItem('speedexplorer-sort');
-      localStorage.removeItem('speedexplorer-hidden');
       localStorage.removeItem('speedexplorer-autosearch');
       localStorage.removeItem('speedexplorer-theme');
       localStorage.removeItem('speedexplorer-focus-new-tab');
       localStorage.removeItem('speedexplorer-toolbar-mode');
 
       setDefaultSortConfig({ column: 'name', direction: 'asc' });
-      setShowHiddenFiles(false);
+      saveBackendSetting('default_sort', { column: 'name', direction: 'asc' });
+      saveBackendSetting('show_hidden', false);
+      saveBackendSetting('confirm_delete', true);
+      saveBackendSetting('size_units', 'binary');
       setAutoSearchOnKey(true);
       setFocusNewTabOnMiddleClick(false);
       setToolbarMode('dynamic');
@@ -1593,6 +1790,9 @@ export default function App() {
     parts.forEach((part, index) => {
       if (index === 0 && part.endsWith(':')) {
         currentBuildPath = part + '\\';
+      } else if (index === 0 && currentTab.path.startsWith('\\\\')) {
+        // UNC paths start at the server: \\server\share
+        currentBuildPath = '\\\\' + part;
       } else {
         currentBuildPath = currentBuildPath.endsWith('\\') ? currentBuildPath + part : currentBuildPath + '\\' + part;
       }
@@ -1818,10 +2018,13 @@ export default function App() {
           config={quickAccessConfig}
           sortConfig={defaultSortConfig}
           showHiddenFiles={showHiddenFiles}
+          confirmDelete={confirmDelete}
+          sizeUnits={sizeUnits}
           autoSearchOnKey={autoSearchOnKey}
           focusNewTabOnMiddleClick={focusNewTabOnMiddleClick}
           toolbarMode={toolbarMode}
           onSave={saveConfig}
+          onSavePreference={saveBackendSetting}
           onReset={handleResetSettings}
           onCancel={() => setShowSettings(false)}
         />
@@ -2598,6 +2801,8 @@ export default function App() {
         />
       )}
 
+      <FileOpProgressBar />
+
       {/* Custom Input Context Menu */}
       {inputContextMenu && (
         <InputContextMenu
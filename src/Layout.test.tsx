@@ -7,6 +7,7 @@ vi.mock('@tauri-apps/api/core', () => ({
     invoke: vi.fn(async (cmd) => {
         if (cmd === 'get_system_default_paths') return {};
         if (cmd === 'get_recycle_bin_status') return { is_empty: true, item_count: 0, total_size: 0 };
+        if (cmd === 'get_settings') return { show_hidden: false, default_sort: { column: 'name', direction: 'asc' }, size_units: 'binary', confirm_delete: true };
         return [];
     }),
 }));
@@ -26,6 +27,7 @@ vi.mock('@tauri-apps/api/window', () => ({
 
 vi.mock('@tauri-apps/plugin-dialog', () => ({
     ask: vi.fn(),
+    message: vi.fn(),
 }));
 
 // Mock ResizeObserver
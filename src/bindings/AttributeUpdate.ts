@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AttributeUpdate = { hidden: boolean | null, system: boolean | null, readonly: boolean | null, archive: boolean | null, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BitLockerState = "off" | 
+/**
+ * Encrypted and readable
+ */
+"unlocked" | 
+/**
+ * Encrypted and waiting for its password or recovery key
+ */
+"locked";
\ No newline at end of file
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CloudStatus = 
+/**
+ * Only a placeholder; opening it downloads it
+ */
+"online_only" | 
+/**
+ * Downloaded, but may be freed up again
+ */
+"locally_available" | 
+/**
+ * "Always keep on this device"
+ */
+"pinned";
\ No newline at end of file
@@ -1,3 +1,17 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BitLockerState } from "./BitLockerState";
+import type { DriveType } from "./DriveType";
 
-export type DiskInfo = { total_space: number, used_space: number, available_space: number, is_system: boolean, is_ssd: boolean, };
\ No newline at end of file
+export type DiskInfo = { total_space: number, used_space: number, available_space: number, is_system: boolean, is_ssd: boolean, 
+/**
+ * Volume label; empty when the volume has none
+ */
+label: string, 
+/**
+ * "NTFS", "FAT32", "exFAT"...; empty when the volume can't be read
+ */
+file_system: string, drive_type: DriveType, 
+/**
+ * None for drives BitLocker can't protect
+ */
+bitlocker: BitLockerState | null, };
\ No newline at end of file
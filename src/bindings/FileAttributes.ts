@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FileAttributes = { hidden: boolean, system: boolean, readonly: boolean, archive: boolean, compressed: boolean, encrypted: boolean, 
+/**
+ * Data moved to offline storage
+ */
+offline: boolean, 
+/**
+ * Cloud placeholder whose contents are downloaded when opened
+ */
+cloud: boolean, };
\ No newline at end of file
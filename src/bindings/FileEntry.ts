@@ -1,4 +1,31 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CloudStatus } from "./CloudStatus";
 import type { DiskInfo } from "./DiskInfo";
+import type { FileAttributes } from "./FileAttributes";
+import type { RecycleInfo } from "./RecycleInfo";
 
-export type FileEntry = { name: string, path: string, is_dir: boolean, size: number, formatted_size: string, file_type: string, created_at: string, modified_at: string, is_shortcut: boolean, disk_info: DiskInfo | null, modified_timestamp: number, created_timestamp: number, dimensions: string | null, };
\ No newline at end of file
+export type FileEntry = { name: string, path: string, is_dir: boolean, size: number, formatted_size: string, file_type: string, created_at: string, modified_at: string, is_shortcut: boolean, disk_info: DiskInfo | null, modified_timestamp: number, created_timestamp: number, dimensions: string | null, 
+/**
+ * Target URL for .url internet shortcuts
+ */
+url_target: string | null, 
+/**
+ * Where and when a Recycle Bin item was deleted
+ */
+recycle_info: RecycleInfo | null, 
+/**
+ * Symbolic link, to a file or a folder
+ */
+is_symlink: boolean, 
+/**
+ * NTFS junction to a folder
+ */
+is_junction: boolean, 
+/**
+ * Where a symbolic link or junction points
+ */
+link_target: string | null, attributes: FileAttributes, 
+/**
+ * OneDrive and other sync engines' placeholders; None for ordinary files
+ */
+cloud_status: CloudStatus | null, };
\ No newline at end of file
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ListingGroup = { 
+/**
+ * Stable id of the group, e.g. "today" or "text document"
+ */
+key: string, 
+/**
+ * Header in the app's language
+ */
+label: string, 
+/**
+ * Index of the group's first entry in the whole (unpaged) listing
+ */
+start: number, count: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type NewMenuItem = { 
+/**
+ * With the dot, e.g. ".docx"
+ */
+extension: string, 
+/**
+ * Type name shown in the menu, e.g. "Microsoft Word Document"
+ */
+name: string, 
+/**
+ * Set for documents from a Templates folder; pass it back to `create_from_shell_new`
+ */
+template: string | null, };
\ No newline at end of file
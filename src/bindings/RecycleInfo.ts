@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RecycleInfo = { 
+/**
+ * Folder the item was deleted from
+ */
+original_location: string, deleted_at: string, deleted_timestamp: number, };
\ No newline at end of file
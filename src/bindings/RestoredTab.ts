@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SortPreference } from "./SortPreference";
+
+export type RestoredTab = { id: string, path: string, 
+/**
+ * Selected items that still exist
+ */
+selected_files: Array<string>, 
+/**
+ * The saved folder when it no longer exists and `path` is its nearest parent
+ */
+missing_path: string | null, sort: SortPreference | null, view_mode: string | null, };
\ No newline at end of file
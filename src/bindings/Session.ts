@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionTab } from "./SessionTab";
+
+export type Session = { tabs: Array<SessionTab>, active_tab_id: string | null, };
\ No newline at end of file
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SortPreference } from "./SortPreference";
+
+export type SessionTab = { id: string, 
+/**
+ * Folder shown in the tab; "" is This PC, "shell:" paths are virtual folders
+ */
+path: string, 
+/**
+ * Full paths of the selected items
+ */
+selected_files: Array<string>, 
+/**
+ * None uses the default sort
+ */
+sort: SortPreference | null, 
+/**
+ * "list" or "grid"; None uses the list
+ */
+view_mode: string | null, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SortPreference = { 
+/**
+ * Column id, e.g. "name", "size", "modified"
+ */
+column: string, 
+/**
+ * "asc" or "desc"
+ */
+direction: string, };
\ No newline at end of file
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TabHistory = { 
+/**
+ * Visited folders, oldest first; "" is This PC
+ */
+entries: Array<string>, 
+/**
+ * Position of the current folder in `entries`
+ */
+index: number, };
@@ -1,8 +1,9 @@
 import { useEffect, useRef, useState, useLayoutEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { ExternalLink, Copy, Trash, FileSearch, Scissors, Clipboard, Pin, PinOff, Pencil, FolderOpen, ArrowRight, Archive, RotateCcw } from 'lucide-react';
+import { ExternalLink, Copy, Trash, FileSearch, Scissors, Clipboard, Pin, PinOff, Pencil, FolderOpen, ArrowRight, Archive, RotateCcw, MoreHorizontal, FilePlus, Folder, File, ArrowUpFromLine, CloudDownload, CloudOff } from 'lucide-react';
 import { useTranslation } from '../i18n/useTranslation';
-import { RecycleBinStatus, Tab } from '../types';
+import { NewMenuItem, RecycleBinStatus, Tab } from '../types';
+import { isDrive as isDriveEntry } from '../utils/drives';
 
 interface ContextMenuProps {
     x: number;
@@ -35,12 +36,14 @@ export default function ContextMenu({ x, y, selectedFiles, pinnedFolders, onClos
     const [canPaste, setCanPaste] = useState(false);
     const [pos, setPos] = useState({ left: x, top: y, opacity: 0 });
     const [activeSubmenu, setActiveSubmenu] = useState<string | null>(null);
+    const [newMenuItems, setNewMenuItems] = useState<NewMenuItem[]>([]);
 
     const file = selectedFiles.length === 1 ? selectedFiles[0] : null;
     const isMultiple = selectedFiles.length > 1;
     const isSystemFolder = file && pinnedFolders.some(f => f.path === file.path && ['desktop', 'downloads', 'documents', 'pictures', 'recycle-bin', 'home'].includes(f.id));
-    const isDrive = file?.file_type === 'Drive';
-    const isArchive = file && !file.is_dir && /\.(zip|7z)$/i.test(file.name);
+    const isDrive = !!file && isDriveEntry(file);
+    const isEjectable = isDrive && ['removable', 'cdrom'].includes(file.disk_info?.drive_type);
+    const isArchive = file && !file.is_dir && /\.(zip|7z|rar|tar|tgz|txz|gz|xz)$/i.test(file.name);
     const isRecycleBin = tabs.find(t => t.id === activeTabId)?.path === 'shell:RecycleBin';
 
     const normalizePath = (p: string) => p.replace(/[\\/]+$/, '').toLowerCase();
@@ -79,6 +82,15 @@ export default function ContextMenu({ x, y, selectedFiles, pinnedFolders, onClos
         checkClipboard();
     }, [checkClipboard]);
 
+    // Document types of Explorer's "New" menu, for the background menu only
+    const showNewMenu = selectedFiles.length === 0 && !isRecycleBin && !isDeepSearch && !!tabs.find(t => t.id === activeTabId)?.path;
+    useEffect(() => {
+        if (!showNewMenu) return;
+        invoke<NewMenuItem[]>('get_new_menu_items')
+            .then(setNewMenuItems)
+            .catch(() => setNewMenuItems([]));
+    }, [showNewMenu]);
+
     useLayoutEffect(() => {
         if (menuRef.current) {
             const rect = menuRef.current.getBoundingClientRect();
@@ -124,7 +136,7 @@ export default function ContextMenu({ x, y, selectedFiles, pinnedFolders, onClos
     ] : (file ? [
         { id: 'open', label: t('context_menu.open'), icon: <ExternalLink size={20} />, hidden: (fromSidebar && isSystemFolder) || isRecycleBin },
         { id: 'open-with', label: t('context_menu.open_with'), icon: <ExternalLink size={20} />, hidden: (fromSidebar && isSystemFolder) || file.is_dir || isDrive || isRecycleBin },
-        { id: 'open-location', label: t('context_menu.open_location'), icon: <FolderOpen size={20} />, hidden: (fromSidebar && isSystemFolder) || (!file.is_shortcut && !isDeepSearch) || isDrive },
+        { id: 'open-location', label: t('context_menu.open_location'), icon: <FolderOpen size={20} />, hidden: (fromSidebar && isSystemFolder) || (!file.is_shortcut && !file.link_target && !isDeepSearch) || isDrive },
         { id: 'rename', label: `${t('context_menu.rename')} (F2)`, icon: <Pencil size={20} />, hidden: fromSidebar || !allowRename || isDrive || isRecycleBin || isDeepSearch },
         { id: 'restore', label: t('context_menu.restore'), icon: <RotateCcw size={20} />, hidden: !isRecycleBin || fromSidebar },
         { id: 'separator-0', type: 'separator', hidden: (fromSidebar && isSystemFolder) || isDrive },
@@ -169,9 +181,14 @@ export default function ContextMenu({ x, y, selectedFiles, pinnedFolders, onClos
                 return pinned && ['downloads', 'documents', 'pictures', 'desktop', 'recycle-bin'].includes(pinned.id);
             })())
         },
+        { id: 'pin-cloud', label: t('context_menu.keep_on_device'), icon: <CloudDownload size={20} />, hidden: !file.cloud_status || file.cloud_status === 'pinned' || fromSidebar },
+        { id: 'free-up-space', label: t('context_menu.free_up_space'), icon: <CloudOff size={20} />, hidden: !file.cloud_status || file.cloud_status === 'online_only' || fromSidebar },
+        { id: 'eject', label: t('context_menu.eject'), icon: <ArrowUpFromLine size={20} />, hidden: !isEjectable },
+        { id: 'more-options', label: t('context_menu.more_options'), icon: <MoreHorizontal size={20} /> },
         { id: 'separator-2', type: 'separator', hidden: fromSidebar && isSystemFolder },
         { id: 'properties', label: t('context_menu.properties'), icon: <FileSearch size={20} />, hidden: (fromSidebar && isSystemFolder) || isRecycleBin },
     ] : [
+        { id: 'new', label: t('context_menu.new'), icon: <FilePlus size={20} />, hasSubmenu: true, hidden: !showNewMenu },
         { id: 'paste', label: t('context_menu.paste'), icon: <Clipboard size={20} />, disabled: !canPaste },
         { id: 'separator-2', type: 'separator' },
         { id: 'properties', label: t('context_menu.properties'), icon: <FileSearch size={20} />, hidden: isRecycleBin },
@@ -225,7 +242,38 @@ export default function ContextMenu({ x, y, selectedFiles, pinnedFolders, onClos
                         </button>
 
                         {/* Submenu */}
-                        {item.hasSubmenu && activeSubmenu === item.id && (
+                        {item.hasSubmenu && activeSubmenu === item.id && item.id === 'new' && (
+                            <div
+                                className="absolute left-[calc(100%-4px)] top-0 w-64 max-h-[60vh] overflow-y-auto bg-[#05060f]/98 backdrop-blur-3xl rounded-xl py-1.5 shadow-[0_20px_50px_rgba(0,0,0,0.9),0_0_20px_var(--accent-glow)] animate-in fade-in slide-in-from-left-2 duration-100"
+                            >
+                                <button
+                                    onClick={() => {
+                                        onAction('new-folder');
+                                        onClose();
+                                    }}
+                                    className="w-full flex items-center gap-2.5 px-3 py-2.5 text-sm text-zinc-300 hover:bg-[var(--accent-primary)]/20 hover:text-white transition-all rounded-lg mx-1 w-[calc(100%-8px)] group/sub"
+                                >
+                                    <Folder size={16} className="opacity-60 group-hover/sub:opacity-100 group-hover/sub:text-[var(--accent-primary)]" />
+                                    <span className="font-semibold tracking-tight truncate">{t('context_menu.folder')}</span>
+                                </button>
+                                {newMenuItems.length > 0 && <div className="h-px bg-white/[0.03] my-1 mx-4" />}
+                                {newMenuItems.map(newItem => (
+                                    <button
+                                        key={newItem.template || newItem.extension}
+                                        onClick={() => {
+                                            onAction('new-item', newItem);
+                                            onClose();
+                                        }}
+                                        title={newItem.extension}
+                                        className="w-full flex items-center gap-2.5 px-3 py-2.5 text-sm text-zinc-300 hover:bg-[var(--accent-primary)]/20 hover:text-white transition-all rounded-lg mx-1 w-[calc(100%-8px)] group/sub"
+                                    >
+                                        <File size={16} className="opacity-60 group-hover/sub:opacity-100 group-hover/sub:text-[var(--accent-primary)]" />
+                                        <span className="font-semibold tracking-tight truncate">{newItem.name}</span>
+                                    </button>
+                                ))}
+                            </div>
+                        )}
+                        {item.hasSubmenu && activeSubmenu === item.id && item.id === 'move-to' && (
                             <div
                                 className="absolute left-[calc(100%-4px)] top-0 w-64 bg-[#05060f]/98 backdrop-blur-3xl rounded-xl py-1.5 shadow-[0_20px_50px_rgba(0,0,0,0.9),0_0_20px_var(--accent-glow)] animate-in fade-in slide-in-from-left-2 duration-100"
                             >
@@ -8,6 +8,7 @@ import {
     Search
 } from 'lucide-react';
 import { getIconComponent } from '../utils/fileIcons';
+import { CloudBadge } from './ui/CloudBadge';
 import { useTranslation } from '../i18n/useTranslation';
 
 import { FileEntry, ClipboardInfo } from '../types';
@@ -231,12 +232,18 @@ const GridItem = memo(({ file, isSelected, onMouseDown, onClick, onOpen, onOpenI
                         </div>
                     )}
 
-                    {file.is_shortcut && (
+                    {(file.is_shortcut || file.is_symlink || file.is_junction) && (
                         <div className="absolute -bottom-1 -right-1 w-5 h-5 bg-[#000105] rounded-full flex items-center justify-center">
                             <LinkIcon size={10} className="text-blue-400" />
                         </div>
                     )}
 
+                    {file.cloud_status && (
+                        <div className="absolute -bottom-1 -left-1 w-5 h-5 bg-[#000105] rounded-full flex items-center justify-center">
+                            <CloudBadge status={file.cloud_status} size={12} />
+                        </div>
+                    )}
+
                     {/* Video Indicator */}
                     {!file.is_dir && VIDEO_EXTS.includes(file.name.split('.').pop()?.toLowerCase() || '') && (
                         <div className="absolute bottom-1 right-1 bg-black/60 text-white p-1 rounded-full flex items-center justify-center backdrop-blur-sm">
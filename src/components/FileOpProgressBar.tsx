@@ -0,0 +1,112 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { motion, AnimatePresence } from 'framer-motion';
+import { Pause, Play, X } from 'lucide-react';
+import { useTranslation } from '../i18n/useTranslation';
+
+interface FileOpProgress {
+  operation_id: string;
+  operation: 'copy' | 'move' | 'delete' | 'rename' | 'create';
+  bytes_done: number;
+  bytes_total: number;
+  percentage: number;
+  current_item: string | null;
+  bytes_per_second: number;
+  finished: boolean;
+}
+
+const formatSize = (bytes: number) => {
+  if (bytes <= 0) return '0 B';
+  const k = 1024;
+  const sizes = ['B', 'KB', 'MB', 'GB', 'TB'];
+  const i = Math.min(Math.floor(Math.log(bytes) / Math.log(k)), sizes.length - 1);
+  return (bytes / Math.pow(k, i)).toFixed(i === 0 ? 0 : 1) + ' ' + sizes[i];
+};
+
+// In-app progress for copy/move/delete, fed by the backend's file-op-progress events
+export const FileOpProgressBar: React.FC = () => {
+  const { t } = useTranslation();
+  const [progress, setProgress] = useState<FileOpProgress | null>(null);
+  const [paused, setPaused] = useState(false);
+
+  useEffect(() => {
+    let hideTimer: ReturnType<typeof setTimeout> | undefined;
+    const unlisten = listen<FileOpProgress>('file-op-progress', (event) => {
+      clearTimeout(hideTimer);
+      setProgress(event.payload);
+      if (event.payload.finished) {
+        setPaused(false);
+        hideTimer = setTimeout(() => setProgress(null), 1500);
+      }
+    });
+    return () => {
+      clearTimeout(hideTimer);
+      unlisten.then(f => f());
+    };
+  }, []);
+
+  const label = progress?.finished
+    ? t('file_ops.done')
+    : progress?.operation === 'move'
+      ? t('file_ops.moving')
+      : progress?.operation === 'delete'
+        ? t('file_ops.deleting')
+        : progress?.operation === 'rename'
+          ? t('file_ops.renaming')
+          : t('file_ops.copying');
+  const itemName = progress?.current_item?.split('\\').pop() ?? '';
+
+  // Pausing and cancelling go through the operation queue, which holds the shell operation
+  const togglePause = () => {
+    if (!progress) return;
+    invoke(paused ? 'resume_operation' : 'pause_operation', { id: progress.operation_id })
+      .then(() => setPaused(!paused))
+      .catch(console.error);
+  };
+  const cancel = () => {
+    if (!progress) return;
+    setPaused(false);
+    invoke('cancel_operation', { id: progress.operation_id }).catch(console.error);
+  };
+
+  return (
+    <AnimatePresence>
+      {progress && (
+        <motion.div
+          initial={{ opacity: 0, y: 10 }}
+          animate={{ opacity: 1, y: 0 }}
+          exit={{ opacity: 0, y: 10 }}
+          className="fixed bottom-10 right-4 z-50 w-72 rounded-lg border border-white/10 bg-zinc-900/95 p-3 shadow-xl"
+        >
+          <div className="flex items-center justify-between text-xs font-bold text-zinc-100">
+            <span className="truncate">{label} {itemName}</span>
+            <span className="ml-2 shrink-0">{Math.round(progress.percentage)}%</span>
+            {!progress.finished && (
+              <div className="ml-2 flex shrink-0 items-center gap-1">
+                <button onClick={togglePause} className="rounded p-0.5 text-zinc-400 hover:bg-white/10 hover:text-white">
+                  {paused ? <Play size={12} /> : <Pause size={12} />}
+                </button>
+                <button onClick={cancel} className="rounded p-0.5 text-zinc-400 hover:bg-white/10 hover:text-white">
+                  <X size={12} />
+                </button>
+              </div>
+            )}
+          </div>
+          <div className="mt-2 h-1.5 w-full overflow-hidden rounded-full bg-white/10">
+            <div
+              className="h-full bg-[var(--accent-primary)] transition-[width] duration-100"
+              style={{ width: `${progress.percentage}%` }}
+            />
+          </div>
+          {progress.bytes_total > 0 && (
+            <div className="mt-1.5 flex justify-between text-[11px] text-zinc-400">
+              <span>{formatSize(progress.bytes_done)} / {formatSize(progress.bytes_total)}</span>
+              {!progress.finished && <span>{formatSize(progress.bytes_per_second)}/s</span>}
+            </div>
+          )}
+        </motion.div>
+      )}
+    </AnimatePresence>
+  );
+};
@@ -1,7 +1,8 @@
 import { useState, useRef, useEffect, useMemo, memo } from 'react';
 import { useVirtualizer } from '@tanstack/react-virtual';
-import { ChevronUp, ChevronDown, Check, SearchX, Search } from 'lucide-react';
+import { ChevronUp, ChevronDown, Check, SearchX, Search, Link as LinkIcon } from 'lucide-react';
 import { getIconComponent } from '../utils/fileIcons';
+import { CloudBadge } from './ui/CloudBadge';
 import { useTranslation } from '../i18n/useTranslation';
 import { isPreviewable } from '../utils/previewUtils';
 
@@ -9,7 +10,7 @@ import { FileEntry, ClipboardInfo } from '../types';
 import { startDrag } from '@crabnebula/tauri-plugin-drag';
 import { DRAG_ICON_BASE64 } from '../utils/dragIcon';
 
-type SortColumn = 'name' | 'modified_at' | 'created_at' | 'file_type' | 'size';
+type SortColumn = 'name' | 'modified_at' | 'created_at' | 'file_type' | 'size' | 'original_location' | 'deleted_at';
 type SortDirection = 'asc' | 'desc';
 
 interface SortConfig {
@@ -58,9 +59,15 @@ const COLUMN_CONFIG: Record<SortColumn, { key: string, width: string, minWidth?:
     modified_at: { key: 'files.date_modified', width: '110px' },
     created_at: { key: 'files.date_created', width: '110px' },
     file_type: { key: 'files.type', width: '80px' },
-    size: { key: 'files.size', width: '90px', align: 'right' }
+    size: { key: 'files.size', width: '90px', align: 'right' },
+    original_location: { key: 'files.original_location', width: '200px' },
+    deleted_at: { key: 'files.date_deleted', width: '110px' }
 };
 
+// The Recycle Bin shows where and when items were deleted instead of the folder columns
+const RECYCLE_BIN_COLUMNS: SortColumn[] = ['name', 'original_location', 'deleted_at', 'file_type', 'size'];
+const FOLDER_COLUMNS: SortColumn[] = ['name', 'modified_at', 'created_at', 'file_type', 'size'];
+
 interface ColumnMenuProps {
     x: number;
     y: number;
@@ -90,7 +97,7 @@ const ColumnMenu = ({ x, y, visibleColumns, onToggle, onClose }: ColumnMenuProps
             style={{ left: x, top: y }}
         >
             <div className="px-3 mb-2 text-[11px] font-black text-[var(--text-muted)] uppercase tracking-widest">{t('settings.visible_columns')}</div>
-            {(Object.keys(COLUMN_CONFIG) as SortColumn[]).map(col => (
+            {FOLDER_COLUMNS.map(col => (
                 <button
                     key={col}
                     onClick={() => onToggle(col)}
@@ -119,7 +126,7 @@ const FileTable = memo(({
     renamingPath,
     onRenameSubmit,
     onRenameCancel,
-    visibleColumns,
+    visibleColumns: folderColumns,
     onToggleColumn,
     columnWidths,
     onColumnsResize,
@@ -144,6 +151,8 @@ const FileTable = memo(({
     const autoFocusRef = useRef(false);
     const submittingRef = useRef(false);
     const [headerMenu, setHeaderMenu] = useState<{ x: number, y: number } | null>(null);
+    const isRecycleBin = currentPath === 'shell:RecycleBin';
+    const visibleColumns = isRecycleBin ? RECYCLE_BIN_COLUMNS : folderColumns;
 
     // Native drag support
     const dragThresholdRef = useRef<{ x: number, y: number, paths: string[], element: HTMLElement } | null>(null);
@@ -482,6 +491,7 @@ const FileTable = memo(({
 
     const handleHeaderContextMenu = (e: React.MouseEvent) => {
         e.preventDefault();
+        if (isRecycleBin) return;
         setHeaderMenu({ x: e.clientX, y: e.clientY });
     };
 
@@ -792,11 +802,19 @@ const FileTable = memo(({
                                 <div key={col} className={`min-w-0 truncate ${COLUMN_CONFIG[col].align === 'right' ? 'text-right font-mono font-bold' : ''}`}>
                                     {col === 'name' ? (
                                         <div className="flex items-center gap-3">
-                                            <div className="flex-shrink-0 w-[18px] h-[18px] flex items-center justify-center">
+                                            <div className="relative flex-shrink-0 w-[18px] h-[18px] flex items-center justify-center" title={file.link_target || undefined}>
                                                 {(() => {
                                                     const IconComponent = getIconComponent(file);
                                                     return <IconComponent size={16} className={`${file.is_dir ? 'text-[var(--accent-primary)]' : 'text-[var(--text-muted)]'} ${isSelected ? 'text-white' : 'group-hover:text-white'} transition-colors duration-200`} />;
                                                 })()}
+                                                {(file.is_symlink || file.is_junction) && (
+                                                    <LinkIcon size={8} className="absolute -bottom-0.5 -right-0.5 text-blue-400" />
+                                                )}
+                                                {file.cloud_status && (
+                                                    <div className="absolute -top-0.5 -right-1 bg-[#000105] rounded-full">
+                                                        <CloudBadge status={file.cloud_status} size={9} />
+                                                    </div>
+                                                )}
                                             </div>
                                             {renamingPath === file.path ? (
                                                 <input
@@ -841,6 +859,8 @@ const FileTable = memo(({
                                         <span className={`text-xs ${col === 'size' ? 'text-[var(--text-muted)] font-mono' : 'text-[var(--text-muted)] font-light'}`}>
                                             {col === 'modified_at' && file.modified_at}
                                             {col === 'created_at' && file.created_at}
+                                            {col === 'original_location' && file.recycle_info?.original_location}
+                                            {col === 'deleted_at' && file.recycle_info?.deleted_at}
                                             {col === 'file_type' && (
                                                 file.is_dir
                                                     ? t('files.folder')
@@ -876,7 +896,7 @@ const FileTable = memo(({
                     <ColumnMenu
                         x={headerMenu.x}
                         y={headerMenu.y}
-                        visibleColumns={visibleColumns}
+                        visibleColumns={folderColumns}
                         onToggle={onToggleColumn}
                         onClose={() => setHeaderMenu(null)}
                     />
@@ -1,4 +1,6 @@
-import { useMemo, memo } from 'react';
+import { useMemo, memo, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { File, Folder, Info, Eye, PlayCircle, Loader2 } from 'lucide-react';
 import { useTranslation } from '../i18n/useTranslation';
 
@@ -6,6 +8,26 @@ import { useFilePreview } from '../hooks/useFilePreview';
 
 import { FileEntry } from '../types';
 
+interface FolderSize {
+    path: string;
+    size: number;
+    file_count: number;
+    folder_count: number;
+    finished: boolean;
+}
+
+interface AlternateStream {
+    name: string;
+    size: number;
+}
+
+const formatSize = (s: number) => {
+    if (s < 1024) return `${s} B`;
+    if (s < 1024 * 1024) return `${(s / 1024).toFixed(1)} KB`;
+    if (s < 1024 * 1024 * 1024) return `${(s / (1024 * 1024)).toFixed(1)} MB`;
+    return `${(s / (1024 * 1024 * 1024)).toFixed(2)} GB`;
+};
+
 interface InfoPanelProps {
     selectedFiles: FileEntry[];
     width: number;
@@ -32,6 +54,63 @@ const InfoPanel = memo(({ selectedFiles, width }: InfoPanelProps) => {
         return 'none';
     }, [firstSelected]);
 
+    // Folders report size 0; walk them in the background, showing running totals
+    const folderPath = firstSelected?.is_dir ? firstSelected.path : null;
+    const [folderSize, setFolderSize] = useState<FolderSize | null>(null);
+    useEffect(() => {
+        setFolderSize(null);
+        if (!folderPath) return;
+        let active = true;
+        const unlisten = listen<FolderSize>('folder-size-progress', (event) => {
+            if (active && event.payload.path === folderPath) {
+                setFolderSize(prev => prev?.finished ? prev : event.payload);
+            }
+        });
+        invoke<FolderSize>('get_folder_size', { path: folderPath })
+            .then(size => { if (active) setFolderSize(size); })
+            .catch(err => console.error('[InfoPanel] get_folder_size failed', err));
+        return () => {
+            active = false;
+            unlisten.then(f => f());
+        };
+    }, [folderPath]);
+
+    // Hidden / Read-only, as Explorer's properties dialog offers them
+    const [attributes, setAttributes] = useState(firstSelected?.attributes ?? null);
+    useEffect(() => {
+        setAttributes(firstSelected?.attributes ?? null);
+    }, [firstSelected]);
+    const canEditAttributes = !!firstSelected && !firstSelected.disk_info && !firstSelected.recycle_info;
+    const toggleAttribute = (name: 'hidden' | 'readonly') => {
+        if (!firstSelected || !attributes) return;
+        invoke<FileEntry>('set_file_attributes', {
+            path: firstSelected.path,
+            attrs: { [name]: !attributes[name] },
+        })
+            .then(entry => setAttributes(entry.attributes))
+            .catch(err => console.error('[InfoPanel] set_file_attributes failed', err));
+    };
+
+    // NTFS alternate streams; Zone.Identifier marks a download Explorer would offer to unblock
+    const streamsPath = canEditAttributes && !firstSelected?.is_dir ? firstSelected?.path ?? null : null;
+    const [streams, setStreams] = useState<AlternateStream[]>([]);
+    useEffect(() => {
+        setStreams([]);
+        if (!streamsPath) return;
+        let active = true;
+        invoke<AlternateStream[]>('list_alternate_streams', { path: streamsPath })
+            .then(list => { if (active) setStreams(list); })
+            .catch(err => console.error('[InfoPanel] list_alternate_streams failed', err));
+        return () => { active = false; };
+    }, [streamsPath]);
+    const isBlocked = streams.some(s => s.name === 'Zone.Identifier');
+    const unblock = () => {
+        if (!streamsPath) return;
+        invoke('remove_zone_identifier', { paths: [streamsPath] })
+            .then(() => setStreams(prev => prev.filter(s => s.name !== 'Zone.Identifier')))
+            .catch(err => console.error('[InfoPanel] remove_zone_identifier failed', err));
+    };
+
     const { previewUrl, isLoading, source, dimensions } = useFilePreview(
         firstSelected?.path || null,
         fileType,
@@ -53,11 +132,6 @@ const InfoPanel = memo(({ selectedFiles, width }: InfoPanelProps) => {
 
     if (selectedFiles.length > 1) {
         const totalSize = selectedFiles.reduce((acc, f) => acc + f.size, 0);
-        const formatSize = (s: number) => {
-            if (s < 1024) return `${s} B`;
-            if (s < 1024 * 1024) return `${(s / 1024).toFixed(1)} KB`;
-            return `${(s / (1024 * 1024)).toFixed(1)} MB`;
-        };
 
         return (
             <aside
@@ -135,7 +209,11 @@ const InfoPanel = memo(({ selectedFiles, width }: InfoPanelProps) => {
                             <div className="space-y-4">
                                 <div className="space-y-1">
                                     <label className="text-[10px] font-black text-[var(--accent-secondary)] uppercase tracking-[0.2em]">{t('preview.size')}</label>
-                                    <div className="text-xs text-white font-mono font-black">{selectedFile.formatted_size || '-'}</div>
+                                    <div className="text-xs text-white font-mono font-black">{selectedFile.is_dir
+                                        ? folderSize
+                                            ? `${formatSize(folderSize.size)}${folderSize.finished ? '' : '…'}`
+                                            : t('preview.calculating')
+                                        : selectedFile.formatted_size || '-'}</div>
                                 </div>
                                 {displayDimensions && (
                                     <div className="space-y-1">
@@ -146,6 +224,46 @@ const InfoPanel = memo(({ selectedFiles, width }: InfoPanelProps) => {
                             </div>
                         </div>
                     </div>
+                    {canEditAttributes && attributes && (
+                        <div className="space-y-2">
+                            <label className="text-[10px] font-black text-[var(--accent-secondary)] uppercase tracking-[0.2em]">{t('preview.attributes')}</label>
+                            <div className="flex items-center gap-6">
+                                {(['readonly', 'hidden'] as const).map(name => (
+                                    <label key={name} className="flex items-center gap-2 text-xs text-zinc-100 font-bold cursor-pointer">
+                                        <input
+                                            type="checkbox"
+                                            checked={attributes[name]}
+                                            onChange={() => toggleAttribute(name)}
+                                            className="accent-[var(--accent-primary)]"
+                                        />
+                                        {t(name === 'readonly' ? 'preview.read_only' : 'preview.hidden')}
+                                    </label>
+                                ))}
+                            </div>
+                            {isBlocked && (
+                                <label className="flex items-center gap-2 text-xs text-zinc-100 font-bold cursor-pointer" title={t('preview.blocked_hint')}>
+                                    <input
+                                        type="checkbox"
+                                        checked={false}
+                                        onChange={unblock}
+                                        className="accent-[var(--accent-primary)]"
+                                    />
+                                    {t('preview.unblock')}
+                                </label>
+                            )}
+                        </div>
+                    )}
+                    {streams.length > 0 && (
+                        <div className="space-y-2">
+                            <label className="text-[10px] font-black text-[var(--accent-secondary)] uppercase tracking-[0.2em]">{t('preview.streams')}</label>
+                            {streams.map(stream => (
+                                <div key={stream.name} className="flex justify-between gap-4 text-xs text-zinc-100 font-medium">
+                                    <span className="truncate">{stream.name}</span>
+                                    <span className="text-[var(--text-muted)] shrink-0">{formatSize(stream.size)}</span>
+                                </div>
+                            ))}
+                        </div>
+                    )}
                 </div>
 
                 {/* Preview Section */}
@@ -414,7 +414,7 @@ const QuickPreview: React.FC<QuickPreviewProps> = ({ file, onClose, onNavigate,
         }
 
         if (isVideo) {
-            const src = convertFileSrc(file.path);
+            const src = convertFileSrc(file.path, 'stream');
 
             const handleTimeUpdate = () => {
                 if (mediaRef.current instanceof HTMLVideoElement) {
@@ -593,7 +593,7 @@ const QuickPreview: React.FC<QuickPreviewProps> = ({ file, onClose, onNavigate,
         }
 
         if (isAudio) {
-            const src = convertFileSrc(file.path);
+            const src = convertFileSrc(file.path, 'stream');
             return (
                 <div className="bg-white/[0.04] p-8 rounded-xl backdrop-blur-xl flex flex-col items-center gap-6 min-w-[300px]">
                     <div className="text-6xl">🎵</div>
@@ -3,7 +3,7 @@ import { Settings, Folder, Check, X, ChevronRight, SlidersHorizontal, Monitor, D
 import { motion, AnimatePresence } from 'framer-motion';
 import { useTranslation } from '../i18n/useTranslation';
 import { Language } from '../i18n/types';
-import { ToolbarMode } from '../types';
+import { ToolbarMode, SizeUnits } from '../types';
 
 interface PinnedFolder {
     id: string;
@@ -28,17 +28,20 @@ interface SettingsPanelProps {
     config: QuickAccessConfig;
     sortConfig: SortConfig;
     showHiddenFiles: boolean;
+    confirmDelete: boolean;
+    sizeUnits: SizeUnits;
     autoSearchOnKey: boolean;
     focusNewTabOnMiddleClick: boolean;
     toolbarMode: ToolbarMode;
     onSave: (newConfig: QuickAccessConfig, newSortConfig?: SortConfig, showHiddenFiles?: boolean, autoSearchOnKey?: boolean, focusNewTabOnMiddleClick?: boolean, toolbarMode?: ToolbarMode, closePanel?: boolean) => void;
+    onSavePreference: (key: string, value: unknown) => void;
     onReset: () => void;
     onCancel: () => void;
 }
 
 const SYSTEM_FOLDER_IDS = ['desktop', 'downloads', 'documents', 'pictures', 'recycle-bin', 'home'];
 
-export default function SettingsPanel({ config, sortConfig, showHiddenFiles, autoSearchOnKey, focusNewTabOnMiddleClick, toolbarMode, onSave, onReset, onCancel }: SettingsPanelProps) {
+export default function SettingsPanel({ config, sortConfig, showHiddenFiles, confirmDelete, sizeUnits, autoSearchOnKey, focusNewTabOnMiddleClick, toolbarMode, onSave, onSavePreference, onReset, onCancel }: SettingsPanelProps) {
     const { t, language, setLanguage } = useTranslation();
     const [localConfig, setLocalConfig] = useState<QuickAccessConfig>(() => ({
         pinnedFolders: config?.pinnedFolders || []
@@ -48,6 +51,8 @@ export default function SettingsPanel({ config, sortConfig, showHiddenFiles, aut
         direction: sortConfig?.direction || 'asc'
     }));
     const [localShowHidden, setLocalShowHidden] = useState(!!showHiddenFiles);
+    const [localConfirmDelete, setLocalConfirmDelete] = useState(!!confirmDelete);
+    const [localSizeUnits, setLocalSizeUnits] = useState<SizeUnits>(sizeUnits || 'binary');
     const [localAutoSearch, setLocalAutoSearch] = useState(!!autoSearchOnKey);
     const [localFocusNewTab, setLocalFocusNewTab] = useState(!!focusNewTabOnMiddleClick);
     const [localToolbarMode, setLocalToolbarMode] = useState<ToolbarMode>(toolbarMode || 'dynamic');
@@ -56,6 +61,8 @@ export default function SettingsPanel({ config, sortConfig, showHiddenFiles, aut
 
     const handleSave = (closePanel = true) => {
         setLanguage(localLanguage);
+        if (localConfirmDelete !== confirmDelete) onSavePreference('confirm_delete', localConfirmDelete);
+        if (localSizeUnits !== sizeUnits) onSavePreference('size_units', localSizeUnits);
         onSave(localConfig, localSortConfig, localShowHidden, localAutoSearch, localFocusNewTab, localToolbarMode, closePanel);
     };
 
@@ -250,6 +257,32 @@ export default function SettingsPanel({ config, sortConfig, showHiddenFiles, aut
                                         </label>
                                     </div>
 
+                                    <div className="flex items-center gap-3 py-2">
+                                        <input
+                                            type="checkbox"
+                                            id="confirmDelete"
+                                            checked={localConfirmDelete}
+                                            onChange={(e) => setLocalConfirmDelete(e.target.checked)}
+                                            style={{ accentColor: 'var(--accent-primary)' }}
+                                            className="w-5 h-5 rounded-md bg-white/[0.03] border border-white/10 cursor-pointer"
+                                        />
+                                        <label htmlFor="confirmDelete" className="text-sm text-zinc-300 cursor-pointer">
+                                            {t('settings.confirm_delete')}
+                                        </label>
+                                    </div>
+
+                                    <div className="grid gap-2 pt-2">
+                                        <label className="text-xs font-bold text-[var(--text-dim)] uppercase tracking-widest pl-1">{t('settings.size_units')}</label>
+                                        <select
+                                            value={localSizeUnits}
+                                            onChange={(e) => setLocalSizeUnits(e.target.value as SizeUnits)}
+                                            className="w-full bg-white/[0.03] rounded-xl px-4 py-3 text-sm text-zinc-200 focus:outline-none focus:bg-white/[0.06] transition-all cursor-pointer"
+                                        >
+                                            <option value="binary" className="bg-zinc-900">{t('settings.size_units_binary')}</option>
+                                            <option value="decimal" className="bg-zinc-900">{t('settings.size_units_decimal')}</option>
+                                        </select>
+                                    </div>
+
                                     <div className="grid gap-2 pt-2">
                                         <label className="text-xs font-bold text-[var(--text-dim)] uppercase tracking-widest pl-1">{t('settings.toolbar_style')}</label>
                                         <p className="text-[10px] text-[var(--text-muted)] pl-1 mb-1">{t('settings.toolbar_style_desc')}</p>
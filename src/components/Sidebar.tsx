@@ -2,6 +2,7 @@ import { useState, useEffect, memo, useMemo } from 'react';
 import { Download, FileText, Image, HardDrive, ChevronRight, Monitor, Trash2, Trash, Layout } from 'lucide-react';
 import { WindowsIcon } from './ui/WindowsIcon';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { useTranslation } from '../i18n/useTranslation';
 
 import { RecycleBinStatus, DiskInfo } from '../types';
@@ -63,6 +64,27 @@ const Sidebar = memo(({ onNavigate, onOpenInNewTab, onContextMenu, currentPath,
         refreshDrives();
     }, []);
 
+    // USB sticks and discs inserted or removed
+    useEffect(() => {
+        const unlisteners = ['drive-added', 'drive-removed'].map(event => listen(event, () => refreshDrives()));
+        return () => {
+            unlisteners.forEach(unlisten => unlisten.then(f => f()));
+        };
+    }, []);
+
+    // Free space pushed by the backend when it moves noticeably
+    useEffect(() => {
+        const unlisten = listen<{ volume: string; disk_info: DiskInfo }>('disk-info-changed', (event) => {
+            const { volume, disk_info } = event.payload;
+            setDrives(prev => prev.map(d =>
+                d.path.toUpperCase() === volume.toUpperCase() ? { ...d, disk_info } : d
+            ));
+        });
+        return () => {
+            unlisten.then(f => f());
+        };
+    }, []);
+
     useEffect(() => {
         // Refresh drives when a rename might have happened (via custom event from App.tsx or just when renamingPath clears)
         if (!renamingPath) {
@@ -120,9 +142,7 @@ const Sidebar = memo(({ onNavigate, onOpenInNewTab, onContextMenu, currentPath,
             label: t('sidebar.drives'),
             items: drives.map((drive): SidebarItem => ({
                 id: drive.path,
-                label: drive.name.includes('Local Disk')
-                    ? drive.name.replace('Local Disk', t('sidebar.local_disk'))
-                    : drive.name,
+                label: drive.name,
                 icon: <HardDrive size={18} />,
                 path: drive.path,
                 disk_info: drive.disk_info || undefined
@@ -244,7 +264,14 @@ const Sidebar = memo(({ onNavigate, onOpenInNewTab, onContextMenu, currentPath,
                                                                     disk_info: item.disk_info || null,
                                                                     modified_timestamp: 0,
                                                                     created_timestamp: 0,
-                                                                    dimensions: null
+                                                                    dimensions: null,
+                                                                    url_target: null,
+                                                                    recycle_info: null,
+                                                                    is_symlink: false,
+                                                                    is_junction: false,
+                                                                    link_target: null,
+                                                                    attributes: { hidden: false, system: false, readonly: false, archive: false, compressed: false, encrypted: false, offline: false, cloud: false },
+                                                                    cloud_status: null
                                                                 };
                                                                 onRenameSubmit?.(mockFile, newName);
                                                             } else {
@@ -256,7 +283,7 @@ const Sidebar = memo(({ onNavigate, onOpenInNewTab, onContextMenu, currentPath,
                                                     }}
                                                 />
                                             </div>
-                                        ) : item.disk_info ? (
+                                        ) : item.disk_info && item.disk_info.total_space > 0 ? (
                                             <div className="flex items-center gap-3 w-full min-w-0">
                                                 <span className={`relative shrink-0 transition-all duration-300 ${currentPath.startsWith(item.path) && (item.path !== '' || currentPath === '') ? 'text-[var(--accent-primary)] scale-110 drop-shadow-[0_0_8px_var(--accent-primary)]' : 'group-hover:text-[var(--text-main)] opacity-70 group-hover:opacity-100 group-hover:scale-110'}`}>
                                                     {item.disk_info?.is_system ? (
@@ -1,8 +1,9 @@
 import { useMemo } from 'react';
-import { HardDrive } from 'lucide-react';
+import { HardDrive, Usb, Disc, Network, Lock } from 'lucide-react';
 import { FileEntry } from '../types';
 import { WindowsIcon } from './ui/WindowsIcon';
 import { useTranslation } from '../i18n/useTranslation';
+import { isDrive } from '../utils/drives';
 
 interface ThisPCViewProps {
     files: FileEntry[];
@@ -40,7 +41,7 @@ export default function ThisPCView({
 }: ThisPCViewProps) {
     const { t } = useTranslation();
     const drives = useMemo(() => {
-        return files.filter(f => f.file_type === 'Drive');
+        return files.filter(isDrive);
     }, [files]);
 
     const isSelected = (path: string) => selectedFiles.some(f => f.path === path);
@@ -61,6 +62,12 @@ export default function ThisPCView({
                             const selected = isSelected(drive.path);
                             const usagePercent = info ? (Number(info.total_space - info.available_space) / Number(info.total_space)) * 100 : 0;
                             const isLowSpace = usagePercent > 90;
+                            const isLocked = info?.bitlocker === 'locked';
+                            const DriveIcon = isLocked ? Lock
+                                : info?.drive_type === 'removable' ? Usb
+                                : info?.drive_type === 'cdrom' ? Disc
+                                : info?.drive_type === 'network' ? Network
+                                : HardDrive;
 
                             return (
                                 <div
@@ -100,16 +107,17 @@ export default function ThisPCView({
                                             {info?.is_system ? (
                                                 <WindowsIcon size={36} className="text-[#00a4ef]" />
                                             ) : (
-                                                <HardDrive size={32} className={isLowSpace ? 'text-red-400' : 'text-[var(--text-muted)]'} />
+                                                <DriveIcon size={32} className={isLowSpace ? 'text-red-400' : 'text-[var(--text-muted)]'} />
                                             )}
                                         </div>
                                         <div className="relative z-10 flex-1 min-w-0 space-y-1.5">
                                             <div className={`text-sm truncate font-bold ${selected ? 'text-white' : 'text-zinc-100'}`}>
-                                                {drive.name.includes('Local Disk')
-                                                    ? drive.name.replace('Local Disk', t('sidebar.local_disk'))
-                                                    : drive.name}
+                                                {drive.name}
                                             </div>
-                                            {info && (
+                                            {isLocked && (
+                                                <div className="text-xs text-[var(--text-muted)] font-medium">{t('sidebar.bitlocker_locked')}</div>
+                                            )}
+                                            {info && !isLocked && (
                                                 <>
                                                     <div className="h-1.5 w-full bg-white/[0.05] rounded-full overflow-hidden">
                                                         <div
@@ -119,7 +127,7 @@ export default function ThisPCView({
                                                         />
                                                     </div>
                                                     <div className="flex justify-between items-center text-xs text-[var(--text-muted)] font-medium">
-                                                        <span>{formatSize(info.available_space)} {t('files.free_of')} {formatSize(info.total_space)}</span>
+                                                        <span>{formatSize(info.available_space)} {t('files.free_of')} {formatSize(info.total_space)}{info.file_system && ` · ${info.file_system}`}</span>
                                                         <span>{Math.floor(usagePercent)}%</span>
                                                     </div>
                                                 </>
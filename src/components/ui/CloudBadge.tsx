@@ -0,0 +1,22 @@
+import React from 'react';
+import { Cloud, CircleCheck, Check } from 'lucide-react';
+import { CloudStatus } from '../../types';
+
+interface CloudBadgeProps {
+    status: CloudStatus | null;
+    size?: number;
+}
+
+// Explorer's status column: cloud outline, green check outline, solid green check
+export const CloudBadge: React.FC<CloudBadgeProps> = ({ status, size = 10 }) => {
+    if (status === 'online_only') return <Cloud size={size} className="text-sky-400" />;
+    if (status === 'locally_available') return <CircleCheck size={size} className="text-emerald-400" />;
+    if (status === 'pinned') {
+        return (
+            <div className="rounded-full bg-emerald-500 flex items-center justify-center" style={{ width: size, height: size }}>
+                <Check size={size - 3} strokeWidth={4} className="text-white" />
+            </div>
+        );
+    }
+    return null;
+};
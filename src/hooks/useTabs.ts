@@ -1,7 +1,7 @@
 import { useState, useCallback, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { Tab, SortConfig, FileEntry, SortColumn, QuickAccessConfig, FolderSizeUpdate, ListFilesResult } from '../types';
+import { Tab, SortConfig, FileEntry, SortColumn, QuickAccessConfig, FolderSizeUpdate, ListFilesResult, Session, RestoredSession, TabHistory, ViewMode } from '../types';
 import { getCachedSize, setCachedSize, clearExpiredEntries } from '../utils/folderSizeCache';
 
 const normalizePath = (p: string) => {
@@ -34,6 +34,8 @@ const createTab = (path: string = '', defaultSort?: SortConfig): Tab => ({
 });
 
 export const useTabs = (initialSortConfig: SortConfig, showHiddenFiles: boolean, quickAccessConfig: QuickAccessConfig) => {
+    // Read before the persistence effect below writes the current tabs back
+    const [hadSavedTabs] = useState(() => localStorage.getItem('speedexplorer-tabs') !== null);
     const [tabs, setTabsState] = useState<Tab[]>(() => {
         const saved = localStorage.getItem('speedexplorer-tabs');
         try {
@@ -99,6 +101,57 @@ export const useTabs = (initialSortConfig: SortConfig, showHiddenFiles: boolean,
         localStorage.setItem('speedexplorer-active-tab', activeTabId);
     }, [activeTabId]);
 
+    // Backend session file: survives a crash or a cleared WebView profile.
+    // The backend debounces writes; only send when something it keeps changed.
+    // Nothing is sent until the saved session has been read, or the default tab would replace it.
+    const [sessionRead, setSessionRead] = useState(hadSavedTabs);
+    const [missingSessionPaths, setMissingSessionPaths] = useState<string[]>([]);
+    const lastSessionRef = useRef('');
+    useEffect(() => {
+        if (!sessionRead) return;
+        const session: Session = {
+            tabs: tabs.map(t => ({
+                id: t.id,
+                path: t.path,
+                selected_files: t.selectedFiles.map(f => f.path),
+                sort: t.sortConfig,
+                view_mode: t.viewMode,
+            })),
+            active_tab_id: activeTabId || null,
+        };
+        const json = JSON.stringify(session);
+        if (json === lastSessionRef.current) return;
+        lastSessionRef.current = json;
+        invoke('save_session', { session }).catch(e => console.error('Failed to save session:', e));
+    }, [tabs, activeTabId, sessionRead]);
+
+    // Without saved tabs in localStorage, reopen the tabs of the backend session
+    useEffect(() => {
+        if (hadSavedTabs) return;
+        invoke<RestoredSession | null>('get_last_session').then(session => {
+            if (!session || session.tabs.length === 0) return;
+            const restored = session.tabs.map(t => ({
+                ...createTab(t.path, (t.sort as SortConfig | null) || initialSortConfig),
+                id: t.id,
+                viewMode: (t.view_mode as ViewMode | null) || 'list',
+            }));
+            setTabs(restored);
+            const active = restored.find(t => t.id === session.active_tab_id) || restored[0];
+            setActiveTabId(active.id);
+            session.tabs.forEach(t => loadFilesForTab(t.id, t.path, undefined, t.selected_files));
+            setMissingSessionPaths(session.tabs.flatMap(t => t.missing_path ? [t.missing_path] : []));
+            // Back/forward survive too, as long as the saved folder is still where the tab ended
+            restored.forEach(tab => {
+                invoke<TabHistory | null>('get_history', { tabId: tab.id }).then(history => {
+                    if (!history || normalizePath(history.entries[history.index] ?? '') !== normalizePath(tab.path)) return;
+                    setTabs(prev => prev.map(t => t.id === tab.id ? { ...t, history: history.entries, historyIndex: history.index } : t));
+                }).catch(console.error);
+            });
+        }).catch(e => console.error('Failed to restore session:', e))
+            .finally(() => setSessionRead(true));
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, []);
+
     // Cleanup expired folder size cache entries on mount
     useEffect(() => {
         clearExpiredEntries();
@@ -380,6 +433,10 @@ export const useTabs = (initialSortConfig: SortConfig, showHiddenFiles: boolean,
             newHistory.push(path);
             pendingUpdates.history = newHistory;
             pendingUpdates.historyIndex = newHistory.length - 1;
+            // The first push seeds the tab's starting folder; it's a no-op once that is current
+            invoke('push_history', { tabId: currentTab.id, path: currentTab.path })
+                .then(() => invoke('push_history', { tabId: currentTab.id, path }))
+                .catch(console.error);
         }
 
         const currentNavId = String(nextGenId);
@@ -401,6 +458,7 @@ export const useTabs = (initialSortConfig: SortConfig, showHiddenFiles: boolean,
 
         const newIndex = tab.historyIndex - 1;
         const newPath = tab.history[newIndex];
+        invoke('navigate_back', { tabId: tab.id }).catch(console.error);
         const nextGenId = tab.generationId + 1;
         lastNavigationTimeRef.current = Date.now();
 
@@ -433,6 +491,7 @@ export const useTabs = (initialSortConfig: SortConfig, showHiddenFiles: boolean,
 
         const newIndex = tab.historyIndex + 1;
         const newPath = tab.history[newIndex];
+        invoke('navigate_forward', { tabId: tab.id }).catch(console.error);
         const nextGenId = tab.generationId + 1;
         lastNavigationTimeRef.current = Date.now();
 
@@ -459,7 +518,8 @@ export const useTabs = (initialSortConfig: SortConfig, showHiddenFiles: boolean,
         const parts = currentTab.path.split('\\').filter(Boolean);
         if (parts.length > 0) {
             parts.pop();
-            const parent = parts.join('\\');
+            const isUnc = currentTab.path.startsWith('\\\\');
+            const parent = (isUnc && parts.length > 0 ? '\\\\' : '') + parts.join('\\');
             if (parent === '') {
                 if (currentTab.path.length <= 3) {
                     navigateTo('');
@@ -695,6 +755,7 @@ export const useTabs = (initialSortConfig: SortConfig, showHiddenFiles: boolean,
         tabs,
         activeTabId,
         currentTab,
+        missingSessionPaths,
         addTab,
         closeTab,
         switchTab,
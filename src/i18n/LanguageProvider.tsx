@@ -1,4 +1,5 @@
-import React, { createContext, useState, useCallback, useMemo } from 'react';
+import React, { createContext, useState, useCallback, useMemo, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { en } from './en';
 import { es } from './es';
 import { Language } from './types';
@@ -29,6 +30,17 @@ export const LanguageProvider: React.FC<{ children: React.ReactNode }> = ({ chil
         return language;
     }, [language]);
 
+    // File types and generated names come from the backend; keep its language in step
+    useEffect(() => {
+        invoke<string>('get_setting', { key: 'language' })
+            .then(current => {
+                if (current !== language) {
+                    return invoke('set_setting', { key: 'language', value: language });
+                }
+            })
+            .catch(err => console.error('[i18n] Failed to sync language', err));
+    }, [language]);
+
     const setLanguage = useCallback((lang: Language) => {
         setLanguageState(lang);
         localStorage.setItem('speedexplorer-language', lang);
@@ -26,11 +26,14 @@ export const en: Translations = {
         home: 'Home',
         devices_and_drives: 'Devices and drives',
         local_disk: 'Local Disk',
+        bitlocker_locked: 'Locked by BitLocker',
     },
     files: {
         name: 'Name',
         date_modified: 'Date modified',
         date_created: 'Date created',
+        date_deleted: 'Date deleted',
+        original_location: 'Original location',
         type: 'Type',
         size: 'Size',
         items: 'items',
@@ -68,6 +71,12 @@ export const en: Translations = {
         move_to: 'Move to',
         paste_and_go: 'Paste and go',
         restore: 'Restore',
+        more_options: 'Show more options',
+        new: 'New',
+        folder: 'Folder',
+        eject: 'Eject',
+        keep_on_device: 'Always keep on this device',
+        free_up_space: 'Free up space',
     },
     toolbar: {
         new_folder: 'New Folder',
@@ -126,6 +135,12 @@ export const en: Translations = {
         rotate: 'Rotate 90°',
         access_denied_title: 'Access Restricted',
         access_denied_msg: 'This file is locked by the system or requires administrator permissions.',
+        attributes: 'Attributes',
+        read_only: 'Read-only',
+        hidden: 'Hidden',
+        unblock: 'Unblock',
+        blocked_hint: 'This file came from another computer and might be blocked to help protect this computer',
+        streams: 'Alternate streams',
     },
     settings: {
         title: 'Settings',
@@ -140,6 +155,10 @@ export const en: Translations = {
         show_hidden: 'Show hidden files',
         auto_search: 'Auto-search while typing',
         focus_new_tab: 'Focus new tab on open',
+        confirm_delete: 'Ask before deleting',
+        size_units: 'File sizes',
+        size_units_binary: 'Binary (1 KB = 1024 bytes)',
+        size_units_decimal: 'Decimal (1 KB = 1000 bytes)',
         reset_to_default: 'Reset to default',
         theme_glass: 'Glass effect',
         visible_columns: 'Visible Columns',
@@ -167,4 +186,20 @@ export const en: Translations = {
         reindexing_finished: 'Re-indexing finished',
         search_finished: 'Search finished',
     },
+    conflicts: {
+        title: 'Replace or keep both',
+        message: '{name} already exist in the destination. Replace them, or keep both with the new ones numbered?',
+        replace: 'Replace',
+        keep_both: 'Keep both',
+    },
+    file_ops: {
+        copying: 'Copying',
+        moving: 'Moving',
+        deleting: 'Deleting',
+        renaming: 'Renaming',
+        done: 'Done',
+    },
+    session: {
+        missing_folders: 'These folders from the last session no longer exist, so their tabs opened the nearest folder above them:\n{paths}',
+    },
 };
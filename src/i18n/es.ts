@@ -26,11 +26,14 @@ export const es: Translations = {
         home: 'Inicio',
         devices_and_drives: 'Dispositivos y unidades',
         local_disk: 'Disco local',
+        bitlocker_locked: 'Bloqueada por BitLocker',
     },
     files: {
         name: 'Nombre',
         date_modified: 'Fecha de modificación',
         date_created: 'Fecha de creación',
+        date_deleted: 'Fecha de eliminación',
+        original_location: 'Ubicación original',
         type: 'Tipo',
         size: 'Tamaño',
         items: 'elementos',
@@ -68,6 +71,12 @@ export const es: Translations = {
         move_to: 'Mover a',
         paste_and_go: 'Pegar e ir',
         restore: 'Restaurar',
+        more_options: 'Mostrar más opciones',
+        new: 'Nuevo',
+        folder: 'Carpeta',
+        eject: 'Expulsar',
+        keep_on_device: 'Mantener siempre en este dispositivo',
+        free_up_space: 'Liberar espacio',
     },
     toolbar: {
         new_folder: 'Nueva carpeta',
@@ -126,6 +135,12 @@ export const es: Translations = {
         rotate: 'Rotar 90°',
         access_denied_title: 'Acceso Restringido',
         access_denied_msg: 'Este archivo está bloqueado por el sistema o requiere permisos de administrador.',
+        attributes: 'Atributos',
+        read_only: 'Solo lectura',
+        hidden: 'Oculto',
+        unblock: 'Desbloquear',
+        blocked_hint: 'Este archivo procede de otro equipo y podría bloquearse para ayudar a proteger este equipo',
+        streams: 'Flujos alternativos',
     },
     settings: {
         title: 'Ajustes',
@@ -142,6 +157,10 @@ export const es: Translations = {
         show_hidden: 'Mostrar archivos ocultos',
         auto_search: 'Búsqueda automática al escribir',
         focus_new_tab: 'Enfocar pestaña nueva al abrir',
+        confirm_delete: 'Preguntar antes de eliminar',
+        size_units: 'Tamaños de archivo',
+        size_units_binary: 'Binario (1 KB = 1024 bytes)',
+        size_units_decimal: 'Decimal (1 KB = 1000 bytes)',
         reset_to_default: 'Restablecer valores predeterminados',
         ascending: 'Ascendente',
         descending: 'Descendente',
@@ -167,4 +186,20 @@ export const es: Translations = {
         reindexing_finished: 'Re-indexación finalizada',
         search_finished: 'Búsqueda finalizada',
     },
+    conflicts: {
+        title: 'Reemplazar o conservar ambos',
+        message: '{name} ya existe(n) en el destino. ¿Reemplazarlos o conservar ambos numerando los nuevos?',
+        replace: 'Reemplazar',
+        keep_both: 'Conservar ambos',
+    },
+    file_ops: {
+        copying: 'Copiando',
+        moving: 'Moviendo',
+        deleting: 'Eliminando',
+        renaming: 'Renombrando',
+        done: 'Listo',
+    },
+    session: {
+        missing_folders: 'Estas carpetas de la última sesión ya no existen, así que sus pestañas abrieron la carpeta más cercana que las contiene:\n{paths}',
+    },
 };
@@ -26,11 +26,14 @@ export interface Translations {
         home: string;
         devices_and_drives: string;
         local_disk: string;
+        bitlocker_locked: string;
     };
     files: {
         name: string;
         date_modified: string;
         date_created: string;
+        date_deleted: string;
+        original_location: string;
         type: string;
         size: string;
         items: string;
@@ -68,6 +71,12 @@ export interface Translations {
         move_to: string;
         paste_and_go: string;
         restore: string;
+        more_options: string;
+        new: string;
+        folder: string;
+        eject: string;
+        keep_on_device: string;
+        free_up_space: string;
     };
     toolbar: {
         new_folder: string;
@@ -126,6 +135,12 @@ export interface Translations {
         rotate: string;
         access_denied_title: string;
         access_denied_msg: string;
+        attributes: string;
+        read_only: string;
+        hidden: string;
+        unblock: string;
+        blocked_hint: string;
+        streams: string;
     };
     settings: {
         title: string;
@@ -140,6 +155,10 @@ export interface Translations {
         show_hidden: string;
         auto_search: string;
         focus_new_tab: string;
+        confirm_delete: string;
+        size_units: string;
+        size_units_binary: string;
+        size_units_decimal: string;
         reset_to_default: string;
         theme_glass: string;
         visible_columns: string;
@@ -167,4 +186,20 @@ export interface Translations {
         reindexing_finished: string;
         search_finished: string;
     };
+    conflicts: {
+        title: string;
+        message: string;
+        replace: string;
+        keep_both: string;
+    };
+    file_ops: {
+        copying: string;
+        moving: string;
+        deleting: string;
+        renaming: string;
+        done: string;
+    };
+    session: {
+        missing_folders: string;
+    };
 }
@@ -2,6 +2,12 @@ import type { DiskInfo as GeneratedDiskInfo } from './bindings/DiskInfo';
 import type { FileEntry as GeneratedFileEntry } from './bindings/FileEntry';
 import type { ClipboardInfo as GeneratedClipboardInfo } from './bindings/ClipboardInfo';
 import type { RecycleBinStatus as GeneratedRecycleBinStatus } from './bindings/RecycleBinStatus';
+import type { NewMenuItem as GeneratedNewMenuItem } from './bindings/NewMenuItem';
+import type { CloudStatus as GeneratedCloudStatus } from './bindings/CloudStatus';
+import type { Session as GeneratedSession } from './bindings/Session';
+import type { RestoredSession as GeneratedRestoredSession } from './bindings/RestoredSession';
+import type { TabHistory as GeneratedTabHistory } from './bindings/TabHistory';
+import type { ListingGroup as GeneratedListingGroup } from './bindings/ListingGroup';
 
 export type DiskInfo = GeneratedDiskInfo;
 export type FileEntry = GeneratedFileEntry & {
@@ -11,10 +17,18 @@ export type FileEntry = GeneratedFileEntry & {
 };
 export type ClipboardInfo = GeneratedClipboardInfo;
 export type RecycleBinStatus = GeneratedRecycleBinStatus;
+export type NewMenuItem = GeneratedNewMenuItem;
+export type CloudStatus = GeneratedCloudStatus;
+export type Session = GeneratedSession;
+export type RestoredSession = GeneratedRestoredSession;
+export type TabHistory = GeneratedTabHistory;
+export type ListingGroup = GeneratedListingGroup;
 
 export interface ListFilesResult {
     entries: FileEntry[];
     expanded_path: string;
+    total: number;
+    groups: ListingGroup[] | null;
 }
 
 declare global {
@@ -30,7 +44,7 @@ export interface FolderSizeUpdate {
     formatted_size: string;
 }
 
-export type SortColumn = 'name' | 'modified_at' | 'created_at' | 'file_type' | 'size';
+export type SortColumn = 'name' | 'modified_at' | 'created_at' | 'file_type' | 'size' | 'original_location' | 'deleted_at';
 export type SortDirection = 'asc' | 'desc';
 
 export interface SortConfig {
@@ -41,6 +55,16 @@ export interface SortConfig {
 export type ViewMode = 'list' | 'grid';
 export type ToolbarMode = 'dynamic' | 'compact';
 
+export type SizeUnits = 'binary' | 'decimal';
+
+// The preferences the UI follows from the backend settings store (`get_settings`)
+export interface BackendPreferences {
+    show_hidden: boolean;
+    default_sort: SortConfig;
+    size_units: SizeUnits;
+    confirm_delete: boolean;
+}
+
 export interface Tab {
     id: string;
     path: string;
@@ -0,0 +1,35 @@
+import { invoke } from '@tauri-apps/api/core';
+import { ask } from '@tauri-apps/plugin-dialog';
+import type { FileEntry } from '../types';
+
+export type ConflictResolution = 'overwrite' | 'skip' | 'rename';
+
+interface FileConflict {
+    source: FileEntry;
+    existing: FileEntry;
+}
+
+type Translate = (path: string, variables?: Record<string, string>) => string;
+
+// Asks what to do with the names already taken in targetPath. Resolves to the answers
+// for paste_items/drop_items, or undefined when nothing collides (the shell renames then).
+export const resolveConflicts = async (
+    paths: string[],
+    targetPath: string,
+    t: Translate
+): Promise<Record<string, ConflictResolution> | undefined> => {
+    const conflicts = await invoke<FileConflict[]>('find_conflicts', { paths, targetPath });
+    if (conflicts.length === 0) return undefined;
+
+    const name = conflicts.length === 1
+        ? conflicts[0].source.name
+        : `${conflicts.length} ${t('files.items')}`;
+    const replace = await ask(t('conflicts.message', { name }), {
+        title: t('conflicts.title'),
+        kind: 'warning',
+        okLabel: t('conflicts.replace'),
+        cancelLabel: t('conflicts.keep_both'),
+    });
+    const resolution: ConflictResolution = replace ? 'overwrite' : 'rename';
+    return Object.fromEntries(conflicts.map(c => [c.source.path, resolution]));
+};
@@ -0,0 +1,2 @@
+// Drive roots such as "C:\". file_type can't identify them: the backend localizes it
+export const isDrive = (file: { path: string }): boolean => /^[A-Za-z]:\\?$/.test(file.path);
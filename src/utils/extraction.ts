@@ -0,0 +1,44 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+interface ExtractionFinished {
+    job_id: string;
+    cancelled: boolean;
+    output: string | null;
+    error: string | null;
+}
+
+// Jobs started from this window, so cancel_extraction can find them
+export const activeExtractions = new Set<string>();
+
+// Starts an extraction and resolves once the backend reports it finished.
+// Resolves to the extracted path, or null when the job was cancelled.
+export const extractArchive = async (archivePath: string, targetDir: string): Promise<string | null> => {
+    // Listen before starting: a tiny archive can finish before invoke returns
+    const finished = new Map<string, ExtractionFinished>();
+    let notify: (() => void) | null = null;
+    const unlisten = await listen<ExtractionFinished>('extraction-finished', (event) => {
+        finished.set(event.payload.job_id, event.payload);
+        notify?.();
+    });
+
+    try {
+        const jobId = await invoke<string>('extract_archive', { archivePath, targetDir });
+        activeExtractions.add(jobId);
+        const result = await new Promise<ExtractionFinished>((resolve) => {
+            notify = () => {
+                const payload = finished.get(jobId);
+                if (payload) resolve(payload);
+            };
+            notify();
+        });
+        activeExtractions.delete(jobId);
+
+        if (result.error) throw result.error;
+        return result.output;
+    } finally {
+        unlisten();
+    }
+};
+
+export const cancelExtraction = (jobId: string) => invoke('cancel_extraction', { jobId });